@@ -5,12 +5,14 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Write as _;
 
 use crate::helpers::env_opt;
+use crate::message_format;
 
 pub fn generate(definitions: &str) -> String {
     let i18n = toml_span::parse(definitions).expect("Failed to parse i18n file");
     let root = i18n.as_table().unwrap();
     let mut languages = Vec::new();
     let mut aliases = Vec::new();
+    let mut neutral = HashSet::new();
     let mut translations: BTreeMap<String, HashMap<String, String>> = BTreeMap::new();
 
     for (k, v) in root.iter() {
@@ -24,6 +26,15 @@ pub fn generate(definitions: &str) -> String {
                         .map(|lang| lang.as_str().expect(ERROR).to_string()),
                 );
             }
+            "__neutral__" => {
+                const ERROR: &str = "i18n: __neutral__ must be [str]";
+                neutral.extend(
+                    v.as_array()
+                        .expect(ERROR)
+                        .iter()
+                        .map(|id| id.as_str().expect(ERROR).to_string()),
+                );
+            }
             "__alias__" => {
                 const ERROR: &str = "i18n: __alias__ must be str->str";
                 aliases.extend(v.as_table().expect(ERROR).iter().map(|(alias, lang)| {
@@ -44,6 +55,9 @@ pub fn generate(definitions: &str) -> String {
         }
     }
 
+    // The default languages are the ones we maintain. They must be complete.
+    let default_languages = languages.clone();
+
     // Use EDIT_CFG_LANGUAGES for the language list if it is set.
     if let cfg_languages = env_opt("EDIT_CFG_LANGUAGES")
         && !cfg_languages.is_empty()
@@ -108,6 +122,8 @@ pub fn generate(definitions: &str) -> String {
         }
     }
 
+    validate(&translations, &neutral, &default_languages, &languages);
+
     // Sort languages by:
     // - "en" first, because it'll map to `LangId::en == 0`, which is the default.
     // - then alphabetically
@@ -153,6 +169,20 @@ pub enum LocId {{",
             "\
 }}
 
+const LOC_ID_NAMES: [&str; {}] = [
+",
+            translations.len(),
+        );
+
+        for k in translations.keys() {
+            _ = writeln!(out, "    {k:?},");
+        }
+
+        _ = write!(
+            out,
+            "\
+];
+
 #[allow(non_camel_case_types)]
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum LangId {{
@@ -202,3 +232,66 @@ const TRANSLATIONS: [[&str; {}]; {}] = [
 
     out
 }
+
+/// Validates the translations:
+/// * Every translation must be a valid message with the same arguments as the English one.
+/// * The default languages must translate every string, except for the `__neutral__` ones.
+///   Other languages fall back to English with a warning, if they're part of the build.
+fn validate(
+    translations: &BTreeMap<String, HashMap<String, String>>,
+    neutral: &HashSet<String>,
+    default_languages: &[String],
+    languages: &[String],
+) {
+    let mut errors = Vec::new();
+    let mut incomplete: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+
+    for (id, v) in translations {
+        let Some(en) = v.get("en") else {
+            errors.push(format!("{id}: missing \"en\""));
+            continue;
+        };
+        let expected = match message_format::arguments(en) {
+            Ok(args) => args,
+            Err(err) => {
+                errors.push(format!("{id}.en: {err}"));
+                continue;
+            }
+        };
+
+        let mut langs: Vec<_> = v.iter().collect();
+        langs.sort_unstable();
+        for (lang, text) in langs {
+            match message_format::arguments(text) {
+                Ok(args) if args != expected => errors.push(format!(
+                    "{id}.{lang}: has the arguments {args:?} instead of {expected:?}"
+                )),
+                Ok(_) => {}
+                Err(err) => errors.push(format!("{id}.{lang}: {err}")),
+            }
+        }
+
+        if !neutral.contains(id) {
+            for lang in languages {
+                if !v.contains_key(lang) {
+                    incomplete.entry(lang).or_default().push(id);
+                }
+            }
+        }
+    }
+
+    for (lang, ids) in incomplete {
+        if default_languages.iter().any(|l| l == lang) {
+            errors.push(format!("\"{lang}\" is missing translations for {ids:?}"));
+        } else {
+            println!(
+                "cargo::warning=i18n: \"{lang}\" is missing {} translations, which will fall back to English",
+                ids.len()
+            );
+        }
+    }
+
+    if !errors.is_empty() {
+        panic!("i18n: invalid translations:\n  {}", errors.join("\n  "));
+    }
+}
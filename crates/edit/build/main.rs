@@ -7,6 +7,10 @@ use crate::helpers::env_opt;
 
 mod helpers;
 mod i18n;
+// Shared with the application, which uses it to format the translations.
+#[allow(dead_code)]
+#[path = "../src/message_format.rs"]
+mod message_format;
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum TargetOs {
@@ -25,6 +29,7 @@ fn main() {
     };
 
     compile_i18n();
+    configure_unicode();
     configure_icu(target_os);
     #[cfg(windows)]
     configure_windows_binary(target_os);
@@ -41,6 +46,41 @@ fn compile_i18n() {
 
     println!("cargo::rerun-if-env-changed=EDIT_CFG_LANGUAGES");
     println!("cargo::rerun-if-changed={i18n_path}");
+    println!("cargo::rerun-if-changed=src/message_format.rs");
+}
+
+fn configure_unicode() {
+    let tables_dir = "src/unicode/tables";
+
+    // Each file in `tables_dir` is named after the Unicode version it was generated from.
+    let mut versions: Vec<Vec<u32>> = std::fs::read_dir(tables_dir)
+        .unwrap()
+        .filter_map(|entry| {
+            let name = entry.unwrap().file_name().into_string().ok()?;
+            let version = name.strip_suffix(".rs")?;
+            version.split('.').map(|v| v.parse().ok()).collect()
+        })
+        .collect();
+    versions.sort();
+    let versions: Vec<String> = versions
+        .iter()
+        .map(|v| v.iter().map(|v| v.to_string()).collect::<Vec<_>>().join("."))
+        .collect();
+
+    // Terminals don't always use the latest Unicode version for their width calculations.
+    // Building with a matching version helps with diagnosing the resulting mismatches.
+    let version = env_opt("EDIT_CFG_UNICODE_VERSION");
+    let version = if version.is_empty() { versions.last().unwrap().clone() } else { version };
+    if !versions.contains(&version) {
+        panic!(
+            "`EDIT_CFG_UNICODE_VERSION` must be one of {versions:?}, but is \"{version}\"\n\
+            Use crates/unicode-gen to generate the tables for other versions."
+        );
+    }
+
+    println!("cargo::rerun-if-env-changed=EDIT_CFG_UNICODE_VERSION");
+    println!("cargo::rerun-if-changed={tables_dir}");
+    println!("cargo::rustc-env=EDIT_CFG_UNICODE_VERSION={version}");
 }
 
 fn configure_icu(target_os: TargetOs) {
@@ -7,6 +7,17 @@ use stdext::arena::ArenaString;
 
 const CHARSET: [u8; 64] = *b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
 
+/// Maps base64 characters back to their 6-bit values. Invalid characters are 0xff.
+const DECODE_LUT: [u8; 256] = {
+    let mut lut = [0xff; 256];
+    let mut i = 0;
+    while i < CHARSET.len() {
+        lut[CHARSET[i] as usize] = i as u8;
+        i += 1;
+    }
+    lut
+};
+
 /// One aspect of base64 is that the encoded length can be
 /// calculated accurately in advance, which is what this returns.
 #[inline]
@@ -77,11 +88,42 @@ pub fn encode(dst: &mut ArenaString, src: &[u8]) {
     }
 }
 
+/// Decodes the given base64 string and appends the result to the destination.
+///
+/// Whitespace is ignored and the trailing padding is optional.
+/// Returns false if the input contains invalid characters.
+pub fn decode(dst: &mut Vec<u8>, src: &[u8]) -> bool {
+    dst.reserve(src.len() / 4 * 3 + 3);
+
+    let mut acc = 0u32;
+    let mut bits = 0;
+
+    for &ch in src {
+        let val = DECODE_LUT[ch as usize];
+        if val == 0xff {
+            match ch {
+                b'=' => break,
+                b' ' | b'\t' | b'\r' | b'\n' => continue,
+                _ => return false,
+            }
+        }
+
+        acc = (acc << 6) | val as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            dst.push((acc >> bits) as u8);
+        }
+    }
+
+    true
+}
+
 #[cfg(test)]
 mod tests {
     use stdext::arena::{Arena, ArenaString};
 
-    use super::encode;
+    use super::{decode, encode};
 
     #[test]
     fn test_basic() {
@@ -119,4 +161,29 @@ mod tests {
         assert_eq!(enc(b"abcdefghijklmNOPQRSTUVWXY"), "YWJjZGVmZ2hpamtsbU5PUFFSU1RVVldYWQ==");
         assert_eq!(enc(b"abcdefghijklmNOPQRSTUVWXYZ"), "YWJjZGVmZ2hpamtsbU5PUFFSU1RVVldYWVo=");
     }
+
+    #[test]
+    fn test_decode() {
+        let dec = |s: &str| {
+            let mut dst = Vec::new();
+            assert!(decode(&mut dst, s.as_bytes()));
+            String::from_utf8(dst).unwrap()
+        };
+        assert_eq!(dec(""), "");
+        assert_eq!(dec("YQ=="), "a");
+        assert_eq!(dec("YQ"), "a");
+        assert_eq!(dec("YWI="), "ab");
+        assert_eq!(dec("YWJj"), "abc");
+        assert_eq!(dec("YWJj\r\nZGVm Zw=="), "abcdefg");
+
+        let arena = Arena::new(4 * 1024).unwrap();
+        let mut enc = ArenaString::new_in(&arena);
+        let input: Vec<u8> = (0..=255).collect();
+        encode(&mut enc, &input);
+        let mut dst = Vec::new();
+        assert!(decode(&mut dst, enc.as_bytes()));
+        assert_eq!(dst, input);
+
+        assert!(!decode(&mut Vec::new(), b"YW*j"));
+    }
 }
@@ -0,0 +1,171 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Non-interactive batch mode, e.g. `edit --replace foo bar --regex file.txt`.
+//!
+//! It runs the same [`TextBuffer`] code as the editor, without a TTY.
+//! Files are edited in place. Without files, stdin is filtered to stdout.
+
+use std::ffi::{OsStr, OsString};
+use std::fs::File;
+use std::io::{self, IsTerminal as _};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use edit::buffer::{SearchOptions, TextBuffer};
+use edit::{apperr, icu};
+
+use crate::documents::DocumentManager;
+use crate::state::FormatApperr;
+
+/// Exit code for files that couldn't be read, edited or written.
+const EXIT_FAILURE: u8 = 1;
+/// Exit code for invalid command line arguments.
+pub const EXIT_USAGE: u8 = 2;
+
+#[derive(Default)]
+pub struct Batch {
+    replacements: Vec<(String, String)>,
+    options: SearchOptions,
+    encoding: Option<&'static str>,
+    crlf: Option<bool>,
+    /// True if any batch mode argument was given, even if it's just `--regex`.
+    active: bool,
+}
+
+impl Batch {
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Parses `arg`, taking its values from `args`, if it's a batch mode option.
+    /// Returns `Ok(false)` if it isn't one, and a message on invalid usage.
+    pub fn parse_arg(
+        &mut self,
+        arg: &OsStr,
+        args: &mut impl Iterator<Item = OsString>,
+    ) -> Result<bool, String> {
+        let Some(name) = arg.to_str() else {
+            return Ok(false);
+        };
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value for {name}"))?
+                .into_string()
+                .map_err(|v| format!("invalid value for {name}: {}", v.display()))
+        };
+
+        match name {
+            "--replace" => {
+                let pattern = value()?;
+                let replacement = value()?;
+                if pattern.is_empty() {
+                    return Err("the --replace pattern must not be empty".to_string());
+                }
+                self.replacements.push((pattern, replacement));
+            }
+            "--regex" => self.options.use_regex = true,
+            "--match-case" => self.options.match_case = true,
+            "--whole-word" => self.options.whole_word = true,
+            "--convert-encoding" => {
+                let name = value()?;
+                self.encoding = Some(
+                    icu::find_encoding(&name).ok_or_else(|| format!("unknown encoding: {name}"))?,
+                );
+            }
+            "--normalize-eol" => {
+                let eol = value()?;
+                self.crlf = Some(match eol.to_ascii_lowercase().as_str() {
+                    "lf" => false,
+                    "crlf" => true,
+                    _ => return Err(format!("invalid line ending: {eol} (expected lf or crlf)")),
+                });
+            }
+            _ => return Ok(false),
+        }
+
+        self.active = true;
+        Ok(true)
+    }
+
+    /// Applies the edits to all `paths` or, if there are none, from stdin to stdout.
+    pub fn run(&self, paths: &[PathBuf]) -> ExitCode {
+        if self.replacements.is_empty() && self.encoding.is_none() && self.crlf.is_none() {
+            return usage_error("--regex, --match-case and --whole-word require --replace");
+        }
+        if let Some(dir) = paths.iter().find(|p| p.is_dir()) {
+            return usage_error(&format!("{} is a directory", dir.display()));
+        }
+
+        let mut code = ExitCode::SUCCESS;
+        let mut report = |path: &Path, err: apperr::Error| {
+            eprintln!("edit: {}: {}", path.display(), FormatApperr::from(err));
+            code = ExitCode::from(EXIT_FAILURE);
+        };
+
+        if paths.is_empty() {
+            if io::stdin().is_terminal() {
+                return usage_error("no files given and stdin is not redirected");
+            }
+            if let Err(err) = self.filter_stdin() {
+                report(Path::new("<stdin>"), err);
+            }
+        }
+
+        for path in paths {
+            if let Err(err) = self.edit_file(path) {
+                report(path, err);
+            }
+        }
+
+        code
+    }
+
+    fn filter_stdin(&self) -> apperr::Result<()> {
+        #[cfg(unix)]
+        let handle = std::os::fd::AsFd::as_fd(&io::stdin()).try_clone_to_owned()?;
+        #[cfg(windows)]
+        let handle =
+            std::os::windows::io::AsHandle::as_handle(&io::stdin()).try_clone_to_owned()?;
+
+        let mut tb = TextBuffer::new(false)?;
+        tb.read_file(&mut File::from(handle), None)?;
+        self.apply(&mut tb)?;
+        tb.write_file(&mut io::stdout().lock())
+    }
+
+    fn edit_file(&self, path: &Path) -> apperr::Result<()> {
+        let mut tb = TextBuffer::new(false)?;
+        {
+            let mut file = DocumentManager::open_for_reading(path)?;
+            tb.read_file(&mut file, None)?;
+        }
+
+        self.apply(&mut tb)?;
+
+        // Leave untouched files alone, so that their modification time is preserved.
+        if tb.is_dirty() {
+            let mut file = DocumentManager::open_for_writing(path)?;
+            tb.write_file(&mut file)?;
+        }
+        Ok(())
+    }
+
+    fn apply(&self, tb: &mut TextBuffer) -> apperr::Result<()> {
+        for (pattern, replacement) in &self.replacements {
+            tb.find_and_replace_all(pattern, self.options, replacement.as_bytes())?;
+        }
+        if let Some(crlf) = self.crlf {
+            tb.normalize_newlines(crlf);
+        }
+        if let Some(encoding) = self.encoding {
+            tb.set_encoding(encoding);
+        }
+        Ok(())
+    }
+}
+
+pub fn usage_error(msg: &str) -> ExitCode {
+    eprintln!("edit: {msg}\nTry 'edit --help' for more information.");
+    ExitCode::from(EXIT_USAGE)
+}
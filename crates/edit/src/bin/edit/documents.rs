@@ -71,6 +71,19 @@ impl Document {
     fn update_file_mode(&mut self) {
         let mut tb = self.buffer.borrow_mut();
         tb.set_ruler(if self.filename == "COMMIT_EDITMSG" { 72 } else { 0 });
+        tb.set_completion_dir(self.dir.as_ref().map(|dir| dir.as_path()));
+        tb.set_spell_check(self.is_prose());
+    }
+
+    /// Returns true for documents that are mostly prose, like Markdown files.
+    fn is_prose(&self) -> bool {
+        if self.filename == "COMMIT_EDITMSG" {
+            return true;
+        }
+        let ext = Path::new(&self.filename).extension().unwrap_or_default();
+        ["md", "markdown", "txt", "text", "rst", "adoc", "org"]
+            .iter()
+            .any(|e| ext.eq_ignore_ascii_case(e))
     }
 }
 
@@ -199,6 +212,19 @@ impl DocumentManager {
         Ok(self.list.front_mut().unwrap())
     }
 
+    /// Updates the paths of the documents at or below `from` after it was renamed to `to`.
+    pub fn rename_path(&mut self, from: &Path, to: &Path) {
+        for doc in &mut self.list {
+            if let Some(path) = &doc.path
+                && let Ok(rest) = path.strip_prefix(from)
+            {
+                let path =
+                    if rest.as_os_str().is_empty() { to.to_path_buf() } else { to.join(rest) };
+                doc.set_path(path);
+            }
+        }
+    }
+
     pub fn reflow_all(&self) {
         for doc in &self.list {
             let mut tb = doc.buffer.borrow_mut();
@@ -2,18 +2,21 @@
 // Licensed under the MIT License.
 
 use std::num::ParseIntError;
+use std::path::Path;
 
+use edit::buffer::links::LinkKind;
+use edit::buffer::{TextLink, hex};
 use edit::framebuffer::IndexedColor;
 use edit::helpers::*;
-use edit::icu;
 use edit::input::{kbmod, vk};
+use edit::sys;
 use edit::tui::*;
 
 use crate::localization::*;
 use crate::state::*;
 
 pub fn draw_editor(ctx: &mut Context, state: &mut State) {
-    if !matches!(state.wants_search.kind, StateSearchKind::Hidden | StateSearchKind::Disabled) {
+    if state.wants_search.kind != StateSearchKind::Hidden {
         draw_search(ctx, state);
     }
 
@@ -28,21 +31,48 @@ pub fn draw_editor(ctx: &mut Context, state: &mut State) {
     if let Some(doc) = state.documents.active() {
         ctx.textarea("textarea", doc.buffer.clone());
         ctx.inherit_focus();
+        if std::mem::take(&mut state.wants_editor_focus) {
+            ctx.steal_focus();
+        }
+
+        if let Some(link) = ctx.take_activated_link() {
+            draw_open_link(ctx, state, link);
+        }
     } else {
         ctx.block_begin("empty");
         ctx.block_end();
+        state.wants_editor_focus = false;
     }
 
     ctx.attr_intrinsic_size(Size { width: 0, height: size.height - height_reduction });
 }
 
-fn draw_search(ctx: &mut Context, state: &mut State) {
-    if let Err(err) = icu::init() {
-        error_log_add(ctx, state, err);
-        state.wants_search.kind = StateSearchKind::Disabled;
-        return;
+/// Opens a Ctrl+Clicked link: URLs in the browser and file paths in the editor.
+fn draw_open_link(ctx: &mut Context, state: &mut State, link: TextLink) {
+    let res = match link.kind {
+        LinkKind::Url => sys::open_url(&link.target),
+        LinkKind::Path { line, column } => {
+            // Relative paths are most likely relative to the file they're mentioned in.
+            let path = Path::new(&link.target);
+            let path = match state.documents.active().and_then(|doc| doc.dir.as_ref()) {
+                Some(dir) if path.is_relative() && dir.as_path().join(path).exists() => {
+                    dir.as_path().join(path)
+                }
+                _ => path.to_path_buf(),
+            };
+            state.documents.add_file_path(&path).map(|doc| {
+                let pos = Point { x: (column - 1).max(0), y: line - 1 };
+                doc.buffer.borrow_mut().cursor_move_to_logical(pos);
+            })
+        }
+    };
+    match res {
+        Ok(()) => ctx.needs_rerender(),
+        Err(err) => error_log_add(ctx, state, err),
     }
+}
 
+fn draw_search(ctx: &mut Context, state: &mut State) {
     let Some(doc) = state.documents.active() else {
         state.wants_search.kind = StateSearchKind::Hidden;
         return;
@@ -57,9 +87,17 @@ fn draw_search(ctx: &mut Context, state: &mut State) {
 
         // If the selection is empty, focus the search input field.
         // Otherwise, focus the replace input field, if it exists.
-        if let Some(selection) = doc.buffer.borrow_mut().extract_user_selection(false) {
-            state.search_needle = String::from_utf8_lossy_owned(selection);
-            focus = state.wants_search.kind;
+        // Multi-line selections are used as the search scope instead.
+        let mut tb = doc.buffer.borrow_mut();
+        if !state.search_options.in_selection
+            && let Some(selection) = tb.extract_user_selection(false)
+        {
+            if selection.contains(&b'\n') {
+                state.search_options.in_selection = tb.set_search_scope(true);
+            } else {
+                state.search_needle = String::from_utf8_lossy_owned(selection);
+                focus = state.wants_search.kind;
+            }
         }
     }
 
@@ -80,6 +118,7 @@ fn draw_search(ctx: &mut Context, state: &mut State) {
                 ctx.label("label", loc(LocId::SearchNeedleLabel));
 
                 if ctx.editline("needle", &mut state.search_needle) {
+                    state.search_needle_history.reset();
                     action = Some(SearchAction::Search);
                 }
                 if !state.search_success {
@@ -90,8 +129,16 @@ fn draw_search(ctx: &mut Context, state: &mut State) {
                 if focus == StateSearchKind::Search {
                     ctx.steal_focus();
                 }
-                if ctx.is_focused() && ctx.consume_shortcut(vk::RETURN) {
-                    action = Some(SearchAction::Search);
+                if ctx.is_focused() {
+                    if ctx.consume_shortcut(vk::RETURN) {
+                        state.search_needle_history.push(&state.search_needle);
+                        action = Some(SearchAction::Search);
+                    } else if let Some(entry) =
+                        history_shortcut(ctx, &mut state.search_needle_history)
+                    {
+                        state.search_needle = entry;
+                        action = Some(SearchAction::Search);
+                    }
                 }
             }
 
@@ -99,7 +146,9 @@ fn draw_search(ctx: &mut Context, state: &mut State) {
                 ctx.table_next_row();
                 ctx.label("label", loc(LocId::SearchReplacementLabel));
 
-                ctx.editline("replacement", &mut state.search_replacement);
+                if ctx.editline("replacement", &mut state.search_replacement) {
+                    state.search_replacement_history.reset();
+                }
                 ctx.attr_intrinsic_size(Size { width: COORD_TYPE_SAFE_MAX, height: 1 });
                 if focus == StateSearchKind::Replace {
                     ctx.steal_focus();
@@ -109,6 +158,10 @@ fn draw_search(ctx: &mut Context, state: &mut State) {
                         action = Some(SearchAction::Replace);
                     } else if ctx.consume_shortcut(kbmod::CTRL_ALT | vk::RETURN) {
                         action = Some(SearchAction::ReplaceAll);
+                    } else if let Some(entry) =
+                        history_shortcut(ctx, &mut state.search_replacement_history)
+                    {
+                        state.search_replacement = entry;
                     }
                 }
             }
@@ -138,16 +191,42 @@ fn draw_search(ctx: &mut Context, state: &mut State) {
                 loc(LocId::SearchUseRegex),
                 &mut state.search_options.use_regex,
             );
-            if state.wants_search.kind == StateSearchKind::Replace
-                && ctx.button("replace-all", loc(LocId::SearchReplaceAll), ButtonStyle::default())
-            {
+            if ctx.checkbox(
+                "in-selection",
+                loc(LocId::SearchInSelection),
+                &mut state.search_options.in_selection,
+            ) {
+                let enabled = state.search_options.in_selection;
+                state.search_options.in_selection =
+                    doc.buffer.borrow_mut().set_search_scope(enabled) && enabled;
                 change = true;
-                change_action = Some(SearchAction::ReplaceAll);
+            }
+            if state.wants_search.kind == StateSearchKind::Replace {
+                change |= ctx.checkbox(
+                    "preserve-case",
+                    loc(LocId::SearchPreserveCase),
+                    &mut state.search_options.preserve_case,
+                );
+                if ctx.button("replace-all", loc(LocId::SearchReplaceAll), ButtonStyle::default()) {
+                    change = true;
+                    change_action = Some(SearchAction::ReplaceAll);
+                }
             }
             if ctx.button("close", loc(LocId::SearchClose), ButtonStyle::default()) {
                 state.wants_search.kind = StateSearchKind::Hidden;
             }
 
+            if !state.search_needle.is_empty()
+                && let Some((current, total)) = doc.buffer.borrow_mut().find_match_count()
+            {
+                let text = loc_format(
+                    ctx.arena(),
+                    LocId::SearchMatchCount,
+                    &[("current", current.into()), ("total", total.into())],
+                );
+                ctx.label("match-count", &text);
+            }
+
             if change {
                 action = change_action;
                 state.wants_search.focus = true;
@@ -158,11 +237,28 @@ fn draw_search(ctx: &mut Context, state: &mut State) {
     }
     ctx.block_end();
 
+    if state.wants_search.kind == StateSearchKind::Hidden {
+        doc.buffer.borrow_mut().set_search_scope(false);
+        state.search_options.in_selection = false;
+    }
+
     if let Some(action) = action {
         search_execute(ctx, state, action);
     }
 }
 
+/// Handles Up/Down in a search editline by returning the recalled history entry.
+fn history_shortcut(ctx: &mut Context, history: &mut InputHistory) -> Option<String> {
+    let entry = if ctx.consume_shortcut(vk::UP) {
+        history.prev()
+    } else if ctx.consume_shortcut(vk::DOWN) {
+        history.next()
+    } else {
+        None
+    };
+    entry.map(str::to_string)
+}
+
 pub enum SearchAction {
     Search,
     Replace,
@@ -174,6 +270,39 @@ pub fn search_execute(ctx: &mut Context, state: &mut State, action: SearchAction
         return;
     };
 
+    match action {
+        SearchAction::Search => {}
+        SearchAction::Replace | SearchAction::ReplaceAll => {
+            state.search_needle_history.push(&state.search_needle);
+            state.search_replacement_history.push(&state.search_replacement);
+        }
+    }
+
+    // The hex view searches for byte patterns like "DE AD BE EF" instead of text.
+    if doc.buffer.borrow().is_hex_mode() {
+        let mut tb = doc.buffer.borrow_mut();
+        let pattern = hex::parse_pattern(&state.search_needle);
+        let replacement = if state.search_replacement.trim().is_empty() {
+            Some(Vec::new())
+        } else {
+            hex::parse_pattern(&state.search_replacement)
+        };
+
+        state.search_success = match (pattern, replacement, action) {
+            (Some(pattern), _, SearchAction::Search) => tb.hex_find_and_select(&pattern),
+            (Some(pattern), Some(replacement), SearchAction::Replace) => {
+                tb.hex_find_and_replace(&pattern, &replacement)
+            }
+            (Some(pattern), Some(replacement), SearchAction::ReplaceAll) => {
+                tb.hex_find_and_replace_all(&pattern, &replacement)
+            }
+            _ => state.search_needle.is_empty(),
+        };
+
+        ctx.needs_rerender();
+        return;
+    }
+
     state.search_success = match action {
         SearchAction::Search => {
             doc.buffer.borrow_mut().find_and_select(&state.search_needle, state.search_options)
@@ -320,10 +449,17 @@ pub fn draw_goto_menu(ctx: &mut Context, state: &mut State) {
             ctx.steal_focus();
 
             if ctx.consume_shortcut(vk::RETURN) {
-                match validate_goto_point(&state.goto_target) {
-                    Ok(point) => {
-                        let mut buf = doc.buffer.borrow_mut();
-                        buf.cursor_move_to_logical(point);
+                let mut buf = doc.buffer.borrow_mut();
+                // The hex view goes to byte offsets instead of lines.
+                let res = if buf.is_hex_mode() {
+                    validate_goto_offset(&state.goto_target)
+                        .map(|offset| buf.hex_cursor_move_to_offset(offset))
+                } else {
+                    validate_goto_point(&state.goto_target)
+                        .map(|point| buf.cursor_move_to_logical(point))
+                };
+                match res {
+                    Ok(()) => {
                         buf.make_cursor_visible();
                         done = true;
                     }
@@ -355,3 +491,12 @@ fn validate_goto_point(line: &str) -> Result<Point, ParseIntError> {
     }
     Ok(Point { x: coords[0], y: coords[1] })
 }
+
+/// Parses a byte offset, either in decimal or in hex with a "0x" prefix.
+fn validate_goto_offset(offset: &str) -> Result<usize, ParseIntError> {
+    let offset = offset.trim();
+    match offset.strip_prefix("0x").or_else(|| offset.strip_prefix("0X")) {
+        Some(hex) => usize::from_str_radix(hex, 16),
+        None => offset.parse(),
+    }
+}
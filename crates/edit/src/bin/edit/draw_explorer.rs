@@ -0,0 +1,249 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use std::env;
+
+use edit::framebuffer::IndexedColor;
+use edit::helpers::*;
+use edit::input::vk;
+use edit::tui::*;
+
+use crate::localization::*;
+use crate::state::*;
+
+pub fn draw_explorer(ctx: &mut Context, state: &mut State, height: CoordType) {
+    explorer_init_root(state);
+
+    let mut activated = None;
+    let mut toggled = None;
+
+    ctx.block_begin("explorer");
+    ctx.attr_intrinsic_size(Size { width: explorer_width(ctx), height });
+    ctx.attr_background_rgba(ctx.indexed_alpha(IndexedColor::Black, 1, 4));
+    {
+        let contains_focus = ctx.contains_focus();
+
+        ctx.table_begin("header");
+        ctx.table_set_cell_gap(Size { width: 1, height: 0 });
+        ctx.attr_padding(Rect::two(0, 1));
+        {
+            ctx.table_next_row();
+            if ctx.button("new-file", loc(LocId::FileNew), ButtonStyle::default().bracketed(false))
+            {
+                state.explorer_prompt = StateExplorerPrompt::NewFile;
+            }
+            if ctx.button(
+                "new-folder",
+                loc(LocId::ExplorerNewFolder),
+                ButtonStyle::default().bracketed(false),
+            ) {
+                state.explorer_prompt = StateExplorerPrompt::NewFolder;
+            }
+        }
+        ctx.table_end();
+
+        let root = state.explorer.root().map_or("", |r| r.as_str());
+        ctx.label("explorer_root", root);
+        ctx.attr_overflow(Overflow::TruncateHead);
+        ctx.attr_padding(Rect::two(0, 1));
+
+        ctx.scrollarea_begin("tree", Size { width: 0, height: (height - 2).max(1) });
+        {
+            ctx.next_block_id_mixin(state.explorer.revision);
+            ctx.tree_begin("entries");
+            ctx.inherit_focus();
+
+            let focus = std::mem::take(&mut state.explorer_focus);
+            let mut selected = state.explorer.selected;
+            for (idx, entry) in state.explorer.entries().iter().enumerate() {
+                let item_state = match (entry.is_dir, entry.expanded) {
+                    (false, _) => TreeItemState::Leaf,
+                    (true, false) => TreeItemState::Collapsed,
+                    (true, true) => TreeItemState::Expanded,
+                };
+                let select = idx == state.explorer.selected;
+
+                match ctx.tree_item(select, entry.depth, item_state, entry.name.as_str()) {
+                    TreeAction::Unchanged => {}
+                    TreeAction::Selected => selected = idx,
+                    TreeAction::Activated => activated = Some(idx),
+                    TreeAction::Expand | TreeAction::Collapse => toggled = Some(idx),
+                }
+                ctx.attr_overflow(Overflow::TruncateTail);
+
+                if focus && select {
+                    ctx.list_item_steal_focus();
+                }
+            }
+
+            ctx.tree_end();
+            state.explorer.selected = selected;
+        }
+        ctx.scrollarea_end();
+
+        if contains_focus && state.explorer.selected_entry().is_some() {
+            if ctx.consume_shortcut(vk::F2) {
+                state.explorer_prompt = StateExplorerPrompt::Rename;
+            } else if ctx.consume_shortcut(vk::DELETE) {
+                state.explorer_prompt = StateExplorerPrompt::Trash;
+            }
+        }
+        if contains_focus && ctx.consume_shortcut(vk::ESCAPE) {
+            state.wants_editor_focus = true;
+        }
+    }
+    ctx.block_end();
+
+    if let Some(idx) = activated {
+        state.explorer.selected = idx;
+        let entry = &state.explorer.entries()[idx];
+        if entry.is_dir {
+            toggled = Some(idx);
+        } else {
+            let path = entry.path.clone();
+            match state.documents.add_file_path(&path) {
+                Ok(_) => state.wants_editor_focus = true,
+                Err(err) => error_log_add(ctx, state, err),
+            }
+        }
+    }
+    if let Some(idx) = toggled {
+        if state.explorer.entries()[idx].expanded {
+            state.explorer.collapse(idx);
+        } else {
+            state.explorer.expand(idx);
+        }
+        ctx.needs_rerender();
+    }
+
+    if state.explorer_prompt != StateExplorerPrompt::None {
+        draw_explorer_prompt(ctx, state);
+    }
+}
+
+/// Shows or hides the explorer. Showing it moves the focus into it.
+pub fn explorer_toggle(state: &mut State) {
+    state.wants_explorer = !state.wants_explorer;
+    state.explorer_focus = state.wants_explorer;
+    state.wants_editor_focus = !state.wants_explorer;
+}
+
+/// Shows the explorer and selects the active document in it.
+pub fn explorer_reveal_active(state: &mut State) {
+    let Some(path) = state.documents.active().and_then(|doc| doc.path.clone()) else {
+        return;
+    };
+
+    state.wants_explorer = true;
+    explorer_init_root(state);
+    if !state.explorer.reveal(&path)
+        && let Some(dir) = path.parent()
+    {
+        state.explorer.set_root(dir);
+        state.explorer.reveal(&path);
+    }
+    state.explorer_focus = true;
+}
+
+// The explorer starts out in the working directory, like a workspace.
+fn explorer_init_root(state: &mut State) {
+    if state.explorer.root().is_none() {
+        state.explorer.set_root(&env::current_dir().unwrap_or_default());
+    }
+}
+
+fn explorer_width(ctx: &Context) -> CoordType {
+    (ctx.size().width / 4).clamp(20, 40)
+}
+
+fn draw_explorer_prompt(ctx: &mut Context, state: &mut State) {
+    let title = match state.explorer_prompt {
+        StateExplorerPrompt::NewFile => loc(LocId::FileNew),
+        StateExplorerPrompt::NewFolder => loc(LocId::ExplorerNewFolder),
+        StateExplorerPrompt::Rename => loc(LocId::ExplorerRename),
+        _ => loc(LocId::ExplorerMoveToTrash),
+    };
+    let mut doit = false;
+    let mut done = false;
+
+    ctx.modal_begin("explorer-prompt", title);
+    {
+        if state.explorer_prompt == StateExplorerPrompt::Trash {
+            let name = state.explorer.selected_entry().map_or("", |e| e.name.as_str());
+            ctx.label("name", name);
+            ctx.attr_overflow(Overflow::TruncateMiddle);
+            ctx.attr_padding(Rect::three(1, 2, 0));
+            ctx.label("description", loc(LocId::ExplorerMoveToTrashDescription));
+            ctx.attr_padding(Rect::three(0, 2, 1));
+        } else {
+            if state.explorer_prompt_name.is_empty()
+                && state.explorer_prompt == StateExplorerPrompt::Rename
+                && let Some(entry) = state.explorer.selected_entry()
+            {
+                state.explorer_prompt_name = entry.name.as_str().to_string();
+            }
+
+            ctx.editline("name", &mut state.explorer_prompt_name);
+            ctx.attr_intrinsic_size(Size { width: 40, height: 1 });
+            ctx.attr_padding(Rect::two(1, 2));
+            ctx.focus_on_first_present();
+            if ctx.is_focused() && ctx.consume_shortcut(vk::RETURN) {
+                doit = true;
+            }
+        }
+
+        ctx.table_begin("choices");
+        ctx.inherit_focus();
+        ctx.attr_padding(Rect::three(0, 2, 1));
+        ctx.attr_position(Position::Center);
+        ctx.table_set_cell_gap(Size { width: 2, height: 0 });
+        {
+            ctx.table_next_row();
+            ctx.inherit_focus();
+
+            if ctx.button("ok", loc(LocId::Ok), ButtonStyle::default()) {
+                doit = true;
+            }
+            if ctx.button("cancel", loc(LocId::Cancel), ButtonStyle::default()) {
+                done = true;
+            }
+            // Moving something to the trash is the only prompt without an editline.
+            // Default to the safe choice there.
+            if state.explorer_prompt == StateExplorerPrompt::Trash {
+                ctx.inherit_focus();
+            }
+        }
+        ctx.table_end();
+    }
+    done |= ctx.modal_end();
+
+    let name = state.explorer_prompt_name.trim();
+    if doit && (!name.is_empty() || state.explorer_prompt == StateExplorerPrompt::Trash) {
+        let idx = state.explorer.selected;
+        let res = match state.explorer_prompt {
+            StateExplorerPrompt::NewFile => state
+                .explorer
+                .create_file(name)
+                .and_then(|path| state.documents.add_file_path(&path).map(|_| ())),
+            StateExplorerPrompt::NewFolder => state.explorer.create_dir(name).map(|_| ()),
+            StateExplorerPrompt::Rename => state
+                .explorer
+                .rename(idx, name)
+                .map(|(from, to)| state.documents.rename_path(&from, &to)),
+            _ => state.explorer.trash(idx),
+        };
+        match res {
+            Ok(()) => {
+                state.explorer_focus = state.explorer_prompt != StateExplorerPrompt::NewFile;
+                done = true;
+            }
+            Err(err) => error_log_add(ctx, state, err),
+        }
+    }
+
+    if done {
+        state.explorer_prompt = StateExplorerPrompt::None;
+        state.explorer_prompt_name.clear();
+        ctx.needs_rerender();
+    }
+}
@@ -2,15 +2,19 @@
 // Licensed under the MIT License.
 
 use std::cmp::Ordering;
-use std::fs;
+use std::fmt::Write as _;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use std::{env, fs};
 
 use edit::framebuffer::IndexedColor;
+use edit::fuzzy::score_fuzzy;
 use edit::helpers::*;
 use edit::input::{kbmod, vk};
 use edit::tui::*;
-use edit::{icu, path};
+use edit::{icu, path, sys};
 use stdext::arena::scratch_arena;
+use stdext::arena_format;
 
 use crate::localization::*;
 use crate::state::*;
@@ -132,36 +136,71 @@ pub fn draw_file_picker(ctx: &mut Context, state: &mut State) {
         }
         ctx.table_end();
 
+        draw_file_picker_bookmarks(ctx, state);
+
         if state.file_picker_entries.is_none() {
             draw_dialog_saveas_refresh_files(state);
         }
 
+        draw_file_picker_header(ctx, state);
+
         ctx.scrollarea_begin(
             "directory",
             Size {
                 width: 0,
-                // -1 for the label (top)
-                // -1 for the label (bottom)
-                // -1 for the editline (bottom)
-                height: height - 3,
+                // -4 for the folder label and the editline (incl. padding)
+                // -2 for the bookmarks (incl. padding)
+                // -1 for the column headers
+                // -1 for the filter, if any
+                height: height - 7 - !state.file_picker_filter.is_empty() as CoordType,
             },
         );
         ctx.attr_background_rgba(ctx.indexed_alpha(IndexedColor::Black, 1, 4));
         {
+            let filter_changed =
+                ctx.contains_focus() && file_picker_handle_filter_input(ctx, state);
+            if state.file_picker_view.is_none() {
+                file_picker_update_view(state);
+            }
+
             ctx.next_block_id_mixin(state.file_picker_pending_dir_revision);
             ctx.list_begin("files");
             ctx.inherit_focus();
 
-            for entries in state.file_picker_entries.as_ref().unwrap() {
-                for entry in entries {
-                    match ctx.list_item(false, entry.as_str()) {
-                        ListSelection::Unchanged => {}
-                        ListSelection::Selected => {
-                            state.file_picker_pending_name = entry.as_path().into()
-                        }
-                        ListSelection::Activated => activated = true,
+            let entries = state.file_picker_entries.as_ref().unwrap();
+            for (i, &(group, idx)) in state.file_picker_view.as_ref().unwrap().iter().enumerate() {
+                let entry = &entries[group][idx];
+
+                ctx.list_item_begin();
+                {
+                    ctx.table_begin("columns");
+                    ctx.table_set_columns(&COLUMNS);
+                    ctx.table_set_cell_gap(Size { width: COLUMN_GAP, height: 0 });
+                    ctx.table_next_row();
+
+                    // The list puts its selection marker into the first label.
+                    ctx.styled_label_begin("permissions");
+                    ctx.styled_label_add_text("  ");
+                    ctx.styled_label_add_text(&entry.permissions);
+                    ctx.styled_label_end();
+                    ctx.label("size", &entry.formatted_size);
+                    ctx.label("modified", &entry.formatted_modified);
+                    ctx.label("name", entry.name.as_str());
+                    ctx.attr_overflow(Overflow::TruncateTail);
+
+                    ctx.table_end();
+                }
+                match ctx.list_item_end(false) {
+                    ListSelection::Unchanged => {}
+                    ListSelection::Selected => {
+                        state.file_picker_pending_name = entry.name.as_path().into()
                     }
-                    ctx.attr_overflow(Overflow::TruncateMiddle);
+                    ListSelection::Activated => activated = true,
+                }
+
+                if i == 0 && filter_changed {
+                    ctx.list_item_steal_focus();
+                    state.file_picker_pending_name = entry.name.as_path().into();
                 }
             }
 
@@ -169,6 +208,16 @@ pub fn draw_file_picker(ctx: &mut Context, state: &mut State) {
         }
         ctx.scrollarea_end();
 
+        if !state.file_picker_filter.is_empty() {
+            ctx.styled_label_begin("filter");
+            ctx.styled_label_add_text(loc(LocId::FilePickerFilterLabel));
+            ctx.styled_label_add_text(" ");
+            ctx.styled_label_add_text(&state.file_picker_filter);
+            ctx.styled_label_end();
+            ctx.attr_overflow(Overflow::TruncateHead);
+            ctx.attr_padding(Rect::two(0, 1));
+        }
+
         if contains_focus
             && (ctx.consume_shortcut(vk::BACK) || ctx.consume_shortcut(kbmod::ALT | vk::UP))
         {
@@ -240,6 +289,7 @@ pub fn draw_file_picker(ctx: &mut Context, state: &mut State) {
     }
 
     if let Some(path) = doit {
+        let dir = path.parent().map(Path::to_path_buf);
         let res = if state.wants_file_picker == StateFilePicker::Open {
             state.documents.add_file_path(&path).map(|_| ())
         } else if let Some(doc) = state.documents.active_mut() {
@@ -249,6 +299,9 @@ pub fn draw_file_picker(ctx: &mut Context, state: &mut State) {
         };
         match res {
             Ok(..) => {
+                if let Some(dir) = dir {
+                    file_picker_add_recent_dir(state, dir);
+                }
                 ctx.needs_rerender();
                 done = true;
             }
@@ -260,6 +313,8 @@ pub fn draw_file_picker(ctx: &mut Context, state: &mut State) {
         state.wants_file_picker = StateFilePicker::None;
         state.file_picker_pending_name = Default::default();
         state.file_picker_entries = Default::default();
+        state.file_picker_view = Default::default();
+        state.file_picker_filter = Default::default();
         state.file_picker_overwrite_warning = Default::default();
         state.file_picker_autocomplete = Default::default();
     }
@@ -291,16 +346,268 @@ fn draw_file_picker_update_path(state: &mut State) -> Option<PathBuf> {
         (dir, name)
     };
     if dir != state.file_picker_pending_dir.as_path() {
-        state.file_picker_pending_dir = DisplayablePathBuf::from_path(dir.to_path_buf());
-        state.file_picker_pending_dir_revision =
-            state.file_picker_pending_dir_revision.wrapping_add(1);
-        state.file_picker_entries = None;
+        file_picker_set_dir(state, dir.to_path_buf());
     }
 
     state.file_picker_pending_name = name;
     if state.file_picker_pending_name.as_os_str().is_empty() { None } else { Some(path) }
 }
 
+fn file_picker_set_dir(state: &mut State, dir: PathBuf) {
+    state.file_picker_pending_dir = DisplayablePathBuf::from_path(dir);
+    state.file_picker_pending_dir_revision = state.file_picker_pending_dir_revision.wrapping_add(1);
+    state.file_picker_entries = None;
+    state.file_picker_filter.clear();
+}
+
+fn file_picker_add_recent_dir(state: &mut State, dir: PathBuf) {
+    let recent = &mut state.file_picker_recent_dirs;
+    recent.retain(|d| d.as_path() != dir);
+    recent.insert(0, DisplayablePathBuf::from_path(dir));
+    recent.truncate(4);
+}
+
+fn draw_file_picker_bookmarks(ctx: &mut Context, state: &mut State) {
+    let mut dir = None;
+
+    ctx.table_begin("bookmarks");
+    ctx.table_set_cell_gap(Size { width: 1, height: 0 });
+    ctx.attr_padding(Rect::three(0, 1, 1));
+    {
+        ctx.table_next_row();
+
+        if let Some(home) = env::home_dir()
+            && ctx.button("home", loc(LocId::FilePickerHome), ButtonStyle::default())
+        {
+            dir = Some(home);
+        }
+        if ctx.button("cwd", loc(LocId::FilePickerWorkingDirectory), ButtonStyle::default()) {
+            dir = env::current_dir().ok();
+        }
+
+        for (idx, recent) in state.file_picker_recent_dirs.iter().enumerate() {
+            let path = recent.as_str();
+            let name = Path::new(path).file_name().and_then(|n| n.to_str()).unwrap_or(path);
+
+            ctx.next_block_id_mixin(idx as u64);
+            if ctx.button("recent", name, ButtonStyle::default()) {
+                dir = Some(recent.as_path().to_path_buf());
+            }
+        }
+
+        if ctx.checkbox(
+            "hidden",
+            loc(LocId::FilePickerShowHidden),
+            &mut state.file_picker_show_hidden,
+        ) {
+            state.file_picker_view = None;
+        }
+    }
+    ctx.table_end();
+
+    if let Some(dir) = dir {
+        file_picker_set_dir(state, dir);
+    }
+}
+
+// The widths of the columns shared by the header and the file list.
+// The first column includes the 2 columns for the list's selection marker.
+const PERMISSIONS_WIDTH: CoordType = if cfg!(unix) { 9 } else { 3 };
+const SIZE_WIDTH: CoordType = 6;
+const MODIFIED_WIDTH: CoordType = 16;
+const COLUMNS: [CoordType; 4] =
+    [2 + PERMISSIONS_WIDTH, SIZE_WIDTH, MODIFIED_WIDTH, COORD_TYPE_SAFE_MAX];
+const COLUMN_GAP: CoordType = 2;
+
+fn draw_file_picker_header(ctx: &mut Context, state: &mut State) {
+    ctx.table_begin("header");
+    ctx.table_set_columns(&COLUMNS);
+    ctx.table_set_cell_gap(Size { width: COLUMN_GAP, height: 0 });
+    // Leave room for the file list's scrollbar.
+    ctx.attr_padding(Rect { left: 0, top: 0, right: 1, bottom: 0 });
+    {
+        ctx.table_next_row();
+
+        // The permissions aren't sortable.
+        ctx.label("permissions", "");
+
+        for (classname, sort, text) in [
+            ("size", StateFilePickerSort::Size, loc(LocId::FilePickerSize)),
+            ("modified", StateFilePickerSort::Modified, loc(LocId::FilePickerModified)),
+            ("name", StateFilePickerSort::Name, loc(LocId::FilePickerName)),
+        ] {
+            let text = if state.file_picker_sort == sort {
+                let arrow = if state.file_picker_sort_descending { "▼" } else { "▲" };
+                arena_format!(ctx.arena(), "{text} {arrow}")
+            } else {
+                arena_format!(ctx.arena(), "{text}")
+            };
+
+            if ctx.button(classname, &text, ButtonStyle::default().bracketed(false)) {
+                if state.file_picker_sort == sort {
+                    state.file_picker_sort_descending = !state.file_picker_sort_descending;
+                } else {
+                    state.file_picker_sort = sort;
+                    state.file_picker_sort_descending = false;
+                }
+                state.file_picker_view = None;
+            }
+            ctx.attr_overflow(Overflow::TruncateTail);
+        }
+    }
+    ctx.table_end();
+}
+
+// Typing while the file list is focused filters it. As long as the filter isn't empty,
+// Backspace and Escape edit it instead of navigating up and closing the dialog.
+fn file_picker_handle_filter_input(ctx: &mut Context, state: &mut State) -> bool {
+    if let Some(text) = ctx.text_input()
+        && !text.contains(char::is_control)
+    {
+        state.file_picker_filter.push_str(text);
+        ctx.set_input_consumed();
+    } else if state.file_picker_filter.is_empty() {
+        return false;
+    } else if ctx.consume_shortcut(vk::BACK) {
+        state.file_picker_filter.pop();
+    } else if ctx.consume_shortcut(vk::ESCAPE) {
+        state.file_picker_filter.clear();
+    } else {
+        return false;
+    }
+
+    state.file_picker_view = None;
+    true
+}
+
+fn file_picker_update_view(state: &mut State) {
+    let Some(dirs_files) = &state.file_picker_entries else {
+        return;
+    };
+
+    let scratch = scratch_arena(None);
+    let needle = state.file_picker_filter.as_str();
+    let mut view = Vec::new();
+
+    for (group, entries) in dirs_files.iter().enumerate() {
+        // ".." is never what you're looking for.
+        if group == 0 && !needle.is_empty() {
+            continue;
+        }
+
+        let mut matches = Vec::new_in(&*scratch);
+
+        for (idx, entry) in entries.iter().enumerate() {
+            let name = entry.name.as_str();
+            if group != 0 && !state.file_picker_show_hidden && name.starts_with('.') {
+                continue;
+            }
+
+            let mut score = 0;
+            if !needle.is_empty() {
+                let local_scratch = scratch_arena(Some(&scratch));
+                (score, _) = score_fuzzy(&local_scratch, name.trim_end_matches('/'), needle, true);
+                if score <= 0 {
+                    continue;
+                }
+            }
+
+            matches.push((score, idx));
+        }
+
+        // The entries are sorted by name, so their index doubles as the name order,
+        // and the stable sort falls back to it for equal sizes and times.
+        matches.sort_by(|&(a_score, a_idx), &(b_score, b_idx)| {
+            let (a, b) = (&entries[a_idx], &entries[b_idx]);
+            let mut ord = match state.file_picker_sort {
+                StateFilePickerSort::Name => a_idx.cmp(&b_idx),
+                StateFilePickerSort::Size => a.size.cmp(&b.size),
+                StateFilePickerSort::Modified => a.modified.cmp(&b.modified),
+            };
+            if state.file_picker_sort_descending {
+                ord = ord.reverse();
+            }
+            // Fuzzy matches are ranked by their score first.
+            b_score.cmp(&a_score).then(ord)
+        });
+
+        view.extend(matches.iter().map(|&(_, idx)| (group, idx)));
+    }
+
+    state.file_picker_view = Some(view);
+}
+
+fn file_picker_entry(name: DisplayablePathBuf, metadata: Option<&fs::Metadata>) -> FilePickerEntry {
+    let mut permissions = String::new();
+    let mut formatted_size = String::new();
+    let mut formatted_modified = String::new();
+    let mut size = 0;
+    let mut modified = SystemTime::UNIX_EPOCH;
+
+    if let Some(metadata) = metadata {
+        format_permissions(&mut permissions, metadata);
+
+        if !metadata.is_dir() {
+            size = metadata.len();
+            // Right-aligned, like in `ls -l`.
+            let width = SIZE_WIDTH as usize;
+            _ = write!(formatted_size, "{:>width$}", format_size(size));
+        }
+        if let Ok(time) = metadata.modified() {
+            modified = time;
+        }
+    }
+
+    if modified != SystemTime::UNIX_EPOCH {
+        let t = sys::local_time(modified);
+        _ = write!(
+            formatted_modified,
+            "{:04}-{:02}-{:02} {:02}:{:02}",
+            t.year, t.month, t.day, t.hour, t.minute
+        );
+    }
+
+    FilePickerEntry { name, size, modified, permissions, formatted_size, formatted_modified }
+}
+
+#[cfg(unix)]
+fn format_permissions(out: &mut String, metadata: &fs::Metadata) {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    let mode = metadata.permissions().mode();
+    for shift in [6, 3, 0] {
+        let bits = mode >> shift;
+        out.push(if bits & 4 != 0 { 'r' } else { '-' });
+        out.push(if bits & 2 != 0 { 'w' } else { '-' });
+        out.push(if bits & 1 != 0 { 'x' } else { '-' });
+    }
+}
+
+#[cfg(not(unix))]
+fn format_permissions(out: &mut String, metadata: &fs::Metadata) {
+    out.push_str(if metadata.permissions().readonly() { "r--" } else { "rw-" });
+}
+
+// Formats the size like `ls -lh`, e.g. "999", "1.5K" or "12M".
+fn format_size(size: u64) -> String {
+    if size < 1024 {
+        return size.to_string();
+    }
+
+    let mut value = size as f64;
+    for unit in ["K", "M", "G", "T", "P"] {
+        value /= 1024.0;
+        if value < 1024.0 || unit == "P" {
+            return if value < 10.0 {
+                format!("{value:.1}{unit}")
+            } else {
+                format!("{value:.0}{unit}")
+            };
+        }
+    }
+    unreachable!()
+}
+
 fn draw_dialog_saveas_refresh_files(state: &mut State) {
     let dir = state.file_picker_pending_dir.as_path();
     // ["..", directories, files]
@@ -311,39 +618,48 @@ fn draw_dialog_saveas_refresh_files(state: &mut State) {
         // If the path is empty, we are at the drive picker.
         // Add all drives as entries.
         for drive in edit::sys::drives() {
-            dirs_files[1].push(DisplayablePathBuf::from_string(format!("{drive}:\\")));
+            let name = DisplayablePathBuf::from_string(format!("{drive}:\\"));
+            dirs_files[1].push(file_picker_entry(name, None));
         }
 
         state.file_picker_entries = Some(dirs_files);
+        state.file_picker_view = None;
         return;
     }
 
     if cfg!(windows) || dir.parent().is_some() {
-        dirs_files[0].push(DisplayablePathBuf::from(".."));
+        dirs_files[0].push(file_picker_entry(DisplayablePathBuf::from(".."), None));
     }
 
     if let Ok(iter) = fs::read_dir(dir) {
         for entry in iter.flatten() {
-            if let Ok(metadata) = entry.metadata() {
+            if let Ok(mut metadata) = entry.metadata() {
                 let mut name = entry.file_name();
-                let dir = metadata.is_dir()
-                    || (metadata.is_symlink()
-                        && fs::metadata(entry.path()).is_ok_and(|m| m.is_dir()));
+
+                // Show the size etc. of what a symlink points to, unless it's dangling.
+                if metadata.is_symlink()
+                    && let Ok(target) = fs::metadata(entry.path())
+                {
+                    metadata = target;
+                }
+
+                let dir = metadata.is_dir();
                 let idx = if dir { 1 } else { 2 };
 
                 if dir {
                     name.push("/");
                 }
 
-                dirs_files[idx].push(DisplayablePathBuf::from(name));
+                dirs_files[idx]
+                    .push(file_picker_entry(DisplayablePathBuf::from(name), Some(&metadata)));
             }
         }
     }
 
     for entries in &mut dirs_files[1..] {
         entries.sort_by(|a, b| {
-            let a = a.as_bytes();
-            let b = b.as_bytes();
+            let a = a.name.as_bytes();
+            let b = b.name.as_bytes();
 
             let a_is_dir = a.last() == Some(&b'/');
             let b_is_dir = b.last() == Some(&b'/');
@@ -356,6 +672,7 @@ fn draw_dialog_saveas_refresh_files(state: &mut State) {
     }
 
     state.file_picker_entries = Some(dirs_files);
+    state.file_picker_view = None;
 }
 
 #[inline(never)]
@@ -383,14 +700,14 @@ fn update_autocomplete_suggestions(state: &mut State) {
     if let Some(dirs_files) = &state.file_picker_entries {
         'outer: for entries in &dirs_files[1..] {
             let lower = entries
-                .binary_search_by(|entry| icu::compare_strings(entry.as_bytes(), needle))
+                .binary_search_by(|entry| icu::compare_strings(entry.name.as_bytes(), needle))
                 .unwrap_or_else(|i| i);
 
             for entry in &entries[lower..] {
-                let haystack = entry.as_bytes();
+                let haystack = entry.name.as_bytes();
                 match icu::compare_strings(haystack, &needle_upper_bound) {
                     Ordering::Less => {
-                        matches.push(entry.clone());
+                        matches.push(entry.name.clone());
                         if matches.len() >= 5 {
                             break 'outer; // Limit to 5 suggestions
                         }
@@ -406,3 +723,92 @@ fn update_autocomplete_suggestions(state: &mut State) {
 
     state.file_picker_autocomplete = matches;
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(0), "0");
+        assert_eq!(format_size(1023), "1023");
+        assert_eq!(format_size(1024), "1.0K");
+        assert_eq!(format_size(1536), "1.5K");
+        assert_eq!(format_size(10 * 1024), "10K");
+        assert_eq!(format_size(1023 * 1024), "1023K");
+        assert_eq!(format_size(5 << 30), "5.0G");
+        assert_eq!(format_size(u64::MAX), "16384P");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_format_permissions() {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        let path = env::temp_dir().join(format!("edit-permissions-{}", std::process::id()));
+        fs::write(&path, "").unwrap();
+
+        let mut out = String::new();
+        for (mode, expected) in [(0o640, "rw-r-----"), (0o755, "rwxr-xr-x"), (0o000, "---------")] {
+            fs::set_permissions(&path, fs::Permissions::from_mode(mode)).unwrap();
+            out.clear();
+            format_permissions(&mut out, &fs::metadata(&path).unwrap());
+            assert_eq!(out, expected);
+        }
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    fn entry(name: &str, size: u64, modified: u64) -> FilePickerEntry {
+        let mut entry = file_picker_entry(DisplayablePathBuf::from(name), None);
+        entry.size = size;
+        entry.modified = SystemTime::UNIX_EPOCH + Duration::from_secs(modified);
+        entry
+    }
+
+    fn view(state: &mut State) -> Vec<&str> {
+        file_picker_update_view(state);
+        let entries = state.file_picker_entries.as_ref().unwrap();
+        state
+            .file_picker_view
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|&(group, idx)| entries[group][idx].name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_update_view() {
+        let mut state = State::new().unwrap();
+        state.file_picker_entries = Some([
+            vec![entry("..", 0, 0)],
+            vec![entry(".git/", 0, 5), entry("src/", 0, 1)],
+            vec![entry(".hidden", 7, 9), entry("a.rs", 30, 2), entry("b.rs", 10, 3)],
+        ]);
+
+        // Directories always come first, each group is sorted on its own.
+        assert_eq!(view(&mut state), ["..", ".git/", "src/", ".hidden", "a.rs", "b.rs"]);
+
+        state.file_picker_sort = StateFilePickerSort::Size;
+        assert_eq!(view(&mut state), ["..", ".git/", "src/", ".hidden", "b.rs", "a.rs"]);
+
+        state.file_picker_sort = StateFilePickerSort::Modified;
+        state.file_picker_sort_descending = true;
+        assert_eq!(view(&mut state), ["..", ".git/", "src/", ".hidden", "b.rs", "a.rs"]);
+
+        state.file_picker_show_hidden = false;
+        assert_eq!(view(&mut state), ["..", "src/", "b.rs", "a.rs"]);
+
+        // ".." is hidden while filtering and the best match comes first.
+        state.file_picker_filter = "rs".to_string();
+        state.file_picker_sort = StateFilePickerSort::Name;
+        state.file_picker_sort_descending = false;
+        assert_eq!(view(&mut state), ["a.rs", "b.rs"]);
+
+        state.file_picker_filter = "xyz".to_string();
+        assert!(view(&mut state).is_empty());
+    }
+}
@@ -3,9 +3,11 @@
 
 use edit::helpers::*;
 use edit::input::{kbmod, vk};
+use edit::sys;
 use edit::tui::*;
 use stdext::arena_format;
 
+use crate::draw_explorer::*;
 use crate::localization::*;
 use crate::state::*;
 
@@ -85,20 +87,23 @@ fn draw_menu_edit(ctx: &mut Context, state: &mut State) {
         tb.paste(ctx.clipboard_ref());
         ctx.needs_rerender();
     }
-    if state.wants_search.kind != StateSearchKind::Disabled {
-        if ctx.menubar_menu_button(loc(LocId::EditFind), 'F', kbmod::CTRL | vk::F) {
-            state.wants_search.kind = StateSearchKind::Search;
-            state.wants_search.focus = true;
-        }
-        if ctx.menubar_menu_button(loc(LocId::EditReplace), 'L', kbmod::CTRL | vk::R) {
-            state.wants_search.kind = StateSearchKind::Replace;
-            state.wants_search.focus = true;
-        }
+    if ctx.menubar_menu_button(loc(LocId::EditFind), 'F', kbmod::CTRL | vk::F) {
+        state.wants_search.kind = StateSearchKind::Search;
+        state.wants_search.focus = true;
+    }
+    if ctx.menubar_menu_button(loc(LocId::EditReplace), 'L', kbmod::CTRL | vk::R) {
+        state.wants_search.kind = StateSearchKind::Replace;
+        state.wants_search.focus = true;
     }
     if ctx.menubar_menu_button(loc(LocId::EditSelectAll), 'A', kbmod::CTRL | vk::A) {
         tb.select_all();
         ctx.needs_rerender();
     }
+    if ctx.menubar_menu_button(loc(LocId::EditClipboardBackend), 'B', vk::NULL) {
+        state.wants_clipboard_backend_picker = true;
+        state.clipboard_backends_available =
+            sys::ClipboardBackend::ALL.into_iter().filter(|b| b.is_available()).collect();
+    }
     ctx.menubar_menu_end();
 }
 
@@ -121,6 +126,33 @@ fn draw_menu_view(ctx: &mut Context, state: &mut State) {
             tb.set_word_wrap(!word_wrap);
             ctx.needs_rerender();
         }
+        let spell_check = tb.is_spell_check_enabled();
+        if ctx.menubar_menu_checkbox(loc(LocId::ViewSpellCheck), 'C', vk::NULL, spell_check) {
+            tb.set_spell_check(!spell_check);
+            ctx.needs_rerender();
+        }
+        let hex_mode = tb.is_hex_mode();
+        if ctx.menubar_menu_checkbox(loc(LocId::ViewHexMode), 'H', vk::NULL, hex_mode) {
+            tb.set_hex_mode(!hex_mode);
+            ctx.needs_rerender();
+        }
+        if ctx.menubar_menu_button(loc(LocId::ViewTheme), 'T', vk::NULL) {
+            state.wants_theme_picker = true;
+        }
+    }
+
+    if ctx.menubar_menu_checkbox(
+        loc(LocId::ViewExplorer),
+        'E',
+        kbmod::CTRL | vk::B,
+        state.wants_explorer,
+    ) {
+        explorer_toggle(state);
+        ctx.needs_rerender();
+    }
+    if ctx.menubar_menu_button(loc(LocId::ViewRevealInExplorer), 'R', vk::NULL) {
+        explorer_reveal_active(state);
+        ctx.needs_rerender();
     }
 
     ctx.menubar_menu_end();
@@ -133,6 +165,92 @@ fn draw_menu_help(ctx: &mut Context, state: &mut State) {
     ctx.menubar_menu_end();
 }
 
+pub fn draw_dialog_clipboard_backend(ctx: &mut Context, state: &mut State) {
+    let mut selection = None;
+
+    ctx.modal_begin("clipboard-backend", loc(LocId::ClipboardBackendDialogTitle));
+    {
+        ctx.list_begin("backends");
+        ctx.inherit_focus();
+        ctx.attr_padding(Rect::three(1, 2, 1));
+        {
+            let mut item = |ctx: &mut Context, backend, text: &str| match ctx
+                .list_item(state.clipboard_backend == backend, text)
+            {
+                ListSelection::Unchanged => {}
+                ListSelection::Selected => selection = Some((backend, false)),
+                ListSelection::Activated => selection = Some((backend, true)),
+            };
+
+            item(ctx, StateClipboardBackend::Automatic, loc(LocId::ClipboardBackendAutomatic));
+            item(ctx, StateClipboardBackend::Terminal, loc(LocId::ClipboardBackendTerminal));
+            for &backend in &state.clipboard_backends_available {
+                item(ctx, StateClipboardBackend::Native(backend), backend.name());
+            }
+        }
+        ctx.list_end();
+    }
+    let mut done = ctx.modal_end();
+
+    if let Some((backend, activated)) = selection {
+        state.set_clipboard_backend(backend);
+        done |= activated;
+        ctx.needs_rerender();
+    }
+    if done {
+        state.wants_clipboard_backend_picker = false;
+    }
+}
+
+pub fn draw_dialog_theme(ctx: &mut Context, state: &mut State) {
+    let mut selection = None;
+
+    ctx.modal_begin("theme", loc(LocId::ThemeDialogTitle));
+    {
+        ctx.list_begin("themes");
+        ctx.inherit_focus();
+        ctx.attr_padding(Rect::three(1, 2, 1));
+        {
+            let mut item = |ctx: &mut Context, theme, text: &str| match ctx
+                .list_item(state.theme == theme, text)
+            {
+                ListSelection::Unchanged => {}
+                ListSelection::Selected => selection = Some((theme, false)),
+                ListSelection::Activated => selection = Some((theme, true)),
+            };
+
+            item(ctx, StateTheme::Terminal, loc(LocId::ThemeTerminal));
+            item(ctx, StateTheme::Light, loc(LocId::ThemeLight));
+            item(ctx, StateTheme::Dark, loc(LocId::ThemeDark));
+            item(ctx, StateTheme::HighContrast, loc(LocId::ThemeHighContrast));
+            if let Some((path, _)) = &state.theme_custom {
+                let name = path.as_path().file_name().unwrap_or_default().to_string_lossy();
+                item(ctx, StateTheme::Custom, &name);
+            }
+        }
+        ctx.list_end();
+    }
+    let mut done = ctx.modal_end();
+
+    if let Some((theme, activated)) = selection {
+        match state.load_theme(theme) {
+            Ok(t) => {
+                state.theme = theme;
+                ctx.set_theme(t);
+            }
+            Err(msg) => {
+                state.error_log_push(msg);
+                done = true;
+            }
+        }
+        done |= activated;
+        ctx.needs_rerender();
+    }
+    if done {
+        state.wants_theme_picker = false;
+    }
+}
+
 pub fn draw_dialog_about(ctx: &mut Context, state: &mut State) {
     ctx.modal_begin("about", loc(LocId::AboutDialogTitle));
     {
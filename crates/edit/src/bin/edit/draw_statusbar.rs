@@ -36,8 +36,28 @@ pub fn draw_statusbar(ctx: &mut Context, state: &mut State) {
             ctx.steal_focus();
         }
 
-        state.wants_encoding_picker |=
-            ctx.button("encoding", tb.encoding(), ButtonStyle::default());
+        // Offer to repair mixed line endings and invalid UTF-8, but only if there's any.
+        // In the hex view any byte goes, so there's nothing to repair.
+        let issues = if tb.is_hex_mode() { Default::default() } else { tb.content_issues() };
+        if issues.foreign_newlines != 0
+            && ctx.button("fix-newlines", loc(LocId::StatusFixNewlines), ButtonStyle::default())
+        {
+            tb.fix_newlines();
+            ctx.needs_rerender();
+        }
+        if issues.invalid_sequences != 0
+            && ctx.button("fix-invalid", loc(LocId::StatusReplaceInvalid), ButtonStyle::default())
+        {
+            tb.replace_invalid_utf8();
+            ctx.needs_rerender();
+        }
+
+        // A guessed encoding shows how sure we are. Picking it in the encoding picker confirms it.
+        let encoding = match tb.encoding_confidence() {
+            Some(confidence) => &arena_format!(ctx.arena(), "{} ({confidence}%)", tb.encoding()),
+            None => tb.encoding(),
+        };
+        state.wants_encoding_picker |= ctx.button("encoding", encoding, ButtonStyle::default());
         if state.wants_encoding_picker {
             if doc.path.is_some() {
                 ctx.block_begin("frame");
@@ -150,12 +170,16 @@ pub fn draw_statusbar(ctx: &mut Context, state: &mut State) {
 
         ctx.label(
             "location",
-            &arena_format!(
-                ctx.arena(),
-                "{}:{}",
-                tb.cursor_logical_pos().y + 1,
-                tb.cursor_logical_pos().x + 1
-            ),
+            &if tb.is_hex_mode() {
+                arena_format!(ctx.arena(), "0x{:X}", tb.hex_cursor_offset())
+            } else {
+                arena_format!(
+                    ctx.arena(),
+                    "{}:{}",
+                    tb.cursor_logical_pos().y + 1,
+                    tb.cursor_logical_pos().x + 1
+                )
+            },
         );
 
         #[cfg(feature = "debug-latency")]
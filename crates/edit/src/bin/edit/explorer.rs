@@ -0,0 +1,266 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! The model behind the file explorer sidebar.
+//!
+//! The tree is stored flattened in display order, which is what [`edit::tui::Context::tree_item`]
+//! wants. Directories are only read once they're expanded.
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use edit::{apperr, icu, sys};
+
+use crate::state::DisplayablePathBuf;
+
+pub struct ExplorerEntry {
+    pub path: PathBuf,
+    pub name: DisplayablePathBuf,
+    pub depth: usize,
+    pub is_dir: bool,
+    pub expanded: bool,
+}
+
+#[derive(Default)]
+pub struct Explorer {
+    root: Option<DisplayablePathBuf>,
+    entries: Vec<ExplorerEntry>,
+    /// Index into `entries`.
+    pub selected: usize,
+    /// Bumped whenever the entries are rebuilt from scratch, to reset the tree view.
+    pub revision: u64,
+}
+
+impl Explorer {
+    pub fn root(&self) -> Option<&DisplayablePathBuf> {
+        self.root.as_ref()
+    }
+
+    pub fn set_root(&mut self, root: &Path) {
+        self.root = Some(DisplayablePathBuf::from_path(root.to_path_buf()));
+        self.entries.clear();
+        self.selected = 0;
+        self.revision = self.revision.wrapping_add(1);
+        self.entries = read_dir_entries(root, 0);
+    }
+
+    pub fn entries(&self) -> &[ExplorerEntry] {
+        &self.entries
+    }
+
+    pub fn selected_entry(&self) -> Option<&ExplorerEntry> {
+        self.entries.get(self.selected)
+    }
+
+    /// Returns the directory new files are created in: The selected directory,
+    /// the directory of the selected file, or the root.
+    pub fn target_dir(&self) -> PathBuf {
+        match self.selected_entry() {
+            Some(e) if e.is_dir => e.path.clone(),
+            Some(e) => e.path.parent().map(Path::to_path_buf).unwrap_or_default(),
+            None => self.root.as_ref().map(|r| r.as_path().to_path_buf()).unwrap_or_default(),
+        }
+    }
+
+    /// Reads the children of the directory at `idx` and inserts them after it.
+    pub fn expand(&mut self, idx: usize) {
+        let Some(entry) = self.entries.get_mut(idx) else {
+            return;
+        };
+        if !entry.is_dir || entry.expanded {
+            return;
+        }
+        entry.expanded = true;
+        let children = read_dir_entries(&entry.path, entry.depth + 1);
+        self.entries.splice(idx + 1..idx + 1, children);
+    }
+
+    pub fn collapse(&mut self, idx: usize) {
+        let Some(entry) = self.entries.get_mut(idx) else {
+            return;
+        };
+        if !entry.expanded {
+            return;
+        }
+        entry.expanded = false;
+        let end = self.subtree_end(idx);
+        self.entries.drain(idx + 1..end);
+        if self.selected >= end {
+            self.selected -= end - idx - 1;
+        } else if self.selected > idx {
+            self.selected = idx;
+        }
+    }
+
+    /// Re-reads all expanded directories, e.g. after files were created or deleted.
+    pub fn refresh(&mut self) {
+        let Some(root) = self.root.as_ref().map(|r| r.as_path().to_path_buf()) else {
+            return;
+        };
+        let expanded: HashSet<PathBuf> =
+            self.entries.iter().filter(|e| e.expanded).map(|e| e.path.clone()).collect();
+        let selected = self.selected_entry().map(|e| e.path.clone());
+
+        self.entries = read_dir_entries(&root, 0);
+        let mut idx = 0;
+        while idx < self.entries.len() {
+            if expanded.contains(&self.entries[idx].path) {
+                self.expand(idx);
+            }
+            idx += 1;
+        }
+
+        self.selected = selected.and_then(|p| self.find(&p)).unwrap_or(0);
+    }
+
+    /// Expands all ancestors of `path` and selects it.
+    /// Returns false if it isn't located inside the root directory.
+    pub fn reveal(&mut self, path: &Path) -> bool {
+        let Some(root) = &self.root else {
+            return false;
+        };
+        let Ok(relative) = path.strip_prefix(root.as_path()) else {
+            return false;
+        };
+
+        let mut current = root.as_path().to_path_buf();
+        let mut idx = None;
+        for component in relative.components() {
+            if let Some(idx) = idx {
+                self.expand(idx);
+            }
+            current.push(component);
+            idx = self.find(&current);
+            if idx.is_none() {
+                return false;
+            }
+        }
+
+        match idx {
+            Some(idx) => {
+                self.selected = idx;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn create_file(&mut self, name: &str) -> apperr::Result<PathBuf> {
+        check_name(name)?;
+        let path = self.target_dir().join(name);
+        fs::OpenOptions::new().write(true).create_new(true).open(&path)?;
+        self.refresh_and_reveal(&path);
+        Ok(path)
+    }
+
+    pub fn create_dir(&mut self, name: &str) -> apperr::Result<PathBuf> {
+        check_name(name)?;
+        let path = self.target_dir().join(name);
+        fs::create_dir(&path)?;
+        self.refresh_and_reveal(&path);
+        Ok(path)
+    }
+
+    /// Renames the entry at `idx` and returns its old and new path.
+    pub fn rename(&mut self, idx: usize, name: &str) -> apperr::Result<(PathBuf, PathBuf)> {
+        check_name(name)?;
+
+        let from = self.entries[idx].path.clone();
+        let to = from.with_file_name(name);
+        // Unlike `create_new`, `rename` would silently replace existing files.
+        if fs::symlink_metadata(&to).is_ok() {
+            return Err(sys::apperr_already_exists());
+        }
+        fs::rename(&from, &to)?;
+        self.refresh_and_reveal(&to);
+        Ok((from, to))
+    }
+
+    pub fn trash(&mut self, idx: usize) -> apperr::Result<()> {
+        sys::move_to_trash(&self.entries[idx].path)?;
+        self.refresh();
+        Ok(())
+    }
+
+    fn refresh_and_reveal(&mut self, path: &Path) {
+        self.refresh();
+        self.reveal(path);
+    }
+
+    fn find(&self, path: &Path) -> Option<usize> {
+        self.entries.iter().position(|e| e.path == path)
+    }
+
+    /// Returns the index past the last descendant of the entry at `idx`.
+    fn subtree_end(&self, idx: usize) -> usize {
+        let depth = self.entries[idx].depth;
+        self.entries[idx + 1..]
+            .iter()
+            .position(|e| e.depth <= depth)
+            .map_or(self.entries.len(), |i| idx + 1 + i)
+    }
+}
+
+/// New and renamed entries must stay within the directory they're created in.
+fn check_name(name: &str) -> apperr::Result<()> {
+    if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\']) {
+        return Err(sys::apperr_invalid_name());
+    }
+    Ok(())
+}
+
+/// Lists the directory, directories first, sorted the same way as in the file picker.
+fn read_dir_entries(dir: &Path, depth: usize) -> Vec<ExplorerEntry> {
+    let mut entries = Vec::new();
+
+    if let Ok(iter) = fs::read_dir(dir) {
+        for entry in iter.flatten() {
+            let path = entry.path();
+            let is_dir = entry.file_type().is_ok_and(|t| t.is_dir())
+                || (entry.file_type().is_ok_and(|t| t.is_symlink())
+                    && fs::metadata(&path).is_ok_and(|m| m.is_dir()));
+            entries.push(ExplorerEntry {
+                name: DisplayablePathBuf::from(&entry.file_name()),
+                path,
+                depth,
+                is_dir,
+                expanded: false,
+            });
+        }
+    }
+
+    entries.sort_by(|a, b| match b.is_dir.cmp(&a.is_dir) {
+        Ordering::Equal => icu::compare_strings(a.name.as_bytes(), b.name.as_bytes()),
+        other => other,
+    });
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_names() {
+        let dir = std::env::temp_dir().join(format!("edit-explorer-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.txt"), "").unwrap();
+
+        let mut explorer = Explorer::default();
+        explorer.set_root(&dir);
+
+        for name in ["", ".", "..", "x/y", "x\\y", "../escape"] {
+            assert!(explorer.create_file(name).is_err(), "{name:?}");
+            assert!(explorer.create_dir(name).is_err(), "{name:?}");
+            assert!(explorer.rename(0, name).is_err(), "{name:?}");
+        }
+        assert!(!dir.parent().unwrap().join("escape").exists());
+
+        assert_eq!(explorer.create_file("b.txt").unwrap(), dir.join("b.txt"));
+        assert_eq!(explorer.create_dir("sub").unwrap(), dir.join("sub"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
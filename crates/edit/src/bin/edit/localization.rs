@@ -1,33 +1,227 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use edit::helpers::AsciiStringHelpers;
+use std::{env, fs};
+
+use edit::message_format::{self, Arg};
 use edit::sys;
-use stdext::arena::scratch_arena;
+use stdext::arena::{Arena, ArenaString, scratch_arena};
 
 include!(concat!(env!("OUT_DIR"), "/i18n_edit.rs"));
 
 static mut S_LANG: LangId = LangId::en;
+/// The language tag for the plural rules. Differs from `S_LANG` if the overrides add a language.
+static mut S_LANG_TAG: &str = "en";
+/// Translations from the file given by `EDIT_TRANSLATIONS`, indexed by `LocId`.
+static mut S_OVERRIDES: &[Option<&str>] = &[];
+
+/// A translation from the override file.
+struct Override {
+    lang: String,
+    id: usize,
+    text: String,
+}
 
-pub fn init() {
+/// Picks the language and loads the translations from the file given by the
+/// `EDIT_TRANSLATIONS` environment variable, if any. The file uses the same format as
+/// `i18n/edit.toml`, but only consists of `[LocId]` tables. Its languages take
+/// precedence over the builtin ones and may add new ones.
+///
+/// Errors are formatted for the error log. The builtin translations are used in that case.
+pub fn init() -> Result<(), String> {
     let scratch = scratch_arena(None);
     let langs = sys::preferred_languages(&scratch);
-    let mut lang = LangId::en;
+    let (overrides, res) = match load_overrides() {
+        Ok(overrides) => (overrides, Ok(())),
+        Err(err) => (Vec::new(), Err(err)),
+    };
 
-    'outer: for l in langs {
-        for (prefix, id) in LANGUAGES {
-            if l.starts_with_ignore_ascii_case(prefix) {
-                lang = *id;
-                break 'outer;
+    for l in &langs {
+        let builtin = LANGUAGES.iter().find(|(tag, _)| lang_matches(l, tag));
+        let custom = overrides.iter().find(|o| lang_matches(l, &o.lang)).map(|o| o.lang.as_str());
+        if builtin.is_none() && custom.is_none() {
+            continue;
+        }
+
+        let lang = builtin.map_or(LangId::en, |&(_, id)| id);
+        let tag = custom.or(builtin.map(|&(tag, _)| tag)).unwrap_or("en");
+
+        let mut strings = vec![None; LOC_ID_NAMES.len()];
+        for o in &overrides {
+            if o.lang == tag {
+                strings[o.id] = Some(&*o.text.clone().leak());
             }
         }
-    }
 
-    unsafe {
-        S_LANG = lang;
+        unsafe {
+            S_LANG = lang;
+            S_LANG_TAG = String::from(tag).leak();
+            S_OVERRIDES = strings.leak();
+        }
+        break;
     }
+
+    res
 }
 
 pub fn loc(id: LocId) -> &'static str {
-    TRANSLATIONS[unsafe { S_LANG as usize }][id as usize]
+    let id = id as usize;
+    let overrides = unsafe { S_OVERRIDES };
+    match overrides.get(id) {
+        Some(Some(text)) => text,
+        _ => TRANSLATIONS[unsafe { S_LANG as usize }][id],
+    }
+}
+
+/// Formats the message `id` with the given arguments.
+/// See [`edit::message_format`] for the syntax.
+pub fn loc_format<'a>(arena: &'a Arena, id: LocId, args: &[(&str, Arg)]) -> ArenaString<'a> {
+    let message = loc(id);
+    let mut out = ArenaString::new_in(arena);
+    // The builtin translations are validated at build time and the overrides when loading them.
+    if message_format::format(&mut out, message, unsafe { S_LANG_TAG }, args).is_err() {
+        out.clear();
+        out.push_str(message);
+    }
+    out
+}
+
+/// Checks if the preferred language `l` (e.g. "pt-BR") is or starts with `tag` (e.g. "pt_br").
+fn lang_matches(l: &str, tag: &str) -> bool {
+    l.len() >= tag.len()
+        && l.bytes().zip(tag.bytes()).all(|(a, b)| {
+            let norm = |c: u8| if c == b'_' { b'-' } else { c.to_ascii_lowercase() };
+            norm(a) == norm(b)
+        })
+}
+
+fn load_overrides() -> Result<Vec<Override>, String> {
+    let Some(path) = env::var_os("EDIT_TRANSLATIONS") else {
+        return Ok(Vec::new());
+    };
+    let name = path.to_string_lossy();
+    let contents = fs::read_to_string(&path).map_err(|err| format!("{name}: {err}"))?;
+    parse_overrides(&contents).map_err(|(line, msg)| format!("{name}:{line}: {msg}"))
+}
+
+/// Parses the `[LocId]` tables of an override file.
+/// Returns the 1-based line number and a description on error.
+fn parse_overrides(contents: &str) -> Result<Vec<Override>, (usize, String)> {
+    let mut overrides = Vec::new();
+    let mut id = None;
+
+    for (i, line) in contents.lines().enumerate() {
+        let err = |msg: String| (i + 1, msg);
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            let name = name.trim();
+            match LOC_ID_NAMES.iter().position(|&n| n == name) {
+                Some(i) => id = Some(i),
+                None => return Err(err(format!("unknown string \"{name}\""))),
+            }
+            continue;
+        }
+
+        let Some(id) = id else {
+            return Err(err("expected a [LocId] table".to_string()));
+        };
+        let Some((lang, value)) = line.split_once('=') else {
+            return Err(err("expected `lang = \"text\"`".to_string()));
+        };
+        let lang = lang.trim().to_ascii_lowercase().replace('-', "_");
+        let text = parse_string(value.trim()).ok_or_else(|| err("invalid string".to_string()))?;
+
+        let expected = message_format::arguments(TRANSLATIONS[LangId::en as usize][id]);
+        match message_format::arguments(&text) {
+            Ok(args) if Ok(&args) == expected.as_ref() => {}
+            Ok(args) => {
+                return Err(err(format!("has the arguments {args:?} instead of {expected:?}")));
+            }
+            Err(e) => return Err(err(e.to_string())),
+        }
+
+        overrides.push(Override { lang, id, text });
+    }
+
+    Ok(overrides)
+}
+
+/// Parses a TOML basic string, including the quotes.
+fn parse_string(s: &str) -> Option<String> {
+    let s = s.strip_prefix('"')?.strip_suffix('"')?;
+    let mut res = String::with_capacity(s.len());
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            if c == '"' {
+                return None;
+            }
+            res.push(c);
+            continue;
+        }
+
+        let c = match chars.next()? {
+            '"' => '"',
+            '\\' => '\\',
+            'n' => '\n',
+            't' => '\t',
+            c @ ('u' | 'U') => {
+                let len = if c == 'u' { 4 } else { 8 };
+                let hex: String = chars.by_ref().take(len).collect();
+                if hex.len() != len {
+                    return None;
+                }
+                char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?
+            }
+            _ => return None,
+        };
+        res.push(c);
+    }
+
+    Some(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_overrides() {
+        let contents = r#"
+# A comment
+[SearchMatchCount]
+pt-BR = "{total, plural, =0 {Nada} other {{current} de {total}}}"
+
+[LargeClipboardWarningLine2]
+pl = "Skopiowano \"{size}\"…"
+"#;
+        let overrides = parse_overrides(contents).unwrap();
+        assert_eq!(overrides.len(), 2);
+        assert_eq!(overrides[0].lang, "pt_br");
+        assert_eq!(LOC_ID_NAMES[overrides[0].id], "SearchMatchCount");
+        assert_eq!(overrides[1].text, "Skopiowano \"{size}\"…");
+
+        for (contents, line) in [
+            ("de = \"Hallo\"", 1),
+            ("[NoSuchString]", 1),
+            ("[SearchMatchCount]\n\nde = \"{current} von {count}\"", 3),
+            ("[LargeClipboardWarningLine2]\nde = \"{size\"", 2),
+            ("[LargeClipboardWarningLine2]\nde = {size}", 2),
+        ] {
+            assert_eq!(parse_overrides(contents).err().map(|(l, _)| l), Some(line), "{contents}");
+        }
+    }
+
+    #[test]
+    fn test_lang_matches() {
+        assert!(lang_matches("pt-BR", "pt_br"));
+        assert!(lang_matches("zh-Hans-CN", "zh"));
+        assert!(!lang_matches("pt", "pt_br"));
+        assert!(!lang_matches("de-DE", "en"));
+    }
 }
@@ -3,24 +3,29 @@
 
 #![feature(allocator_api, linked_list_cursors, string_from_utf8_lossy_owned)]
 
+mod batch;
 mod documents;
 mod draw_editor;
+mod draw_explorer;
 mod draw_filepicker;
 mod draw_menubar;
 mod draw_statusbar;
+mod explorer;
 mod localization;
 mod state;
 
 use std::borrow::Cow;
 use std::path::{Path, PathBuf};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::{env, process};
 
 use draw_editor::*;
+use draw_explorer::*;
 use draw_filepicker::*;
 use draw_menubar::*;
 use draw_statusbar::*;
-use edit::framebuffer::{self, IndexedColor};
+use edit::framebuffer::{self, ColorDepth, IndexedColor};
+use edit::hash::hash;
 use edit::helpers::*;
 use edit::input::{self, kbmod, vk};
 use edit::oklab::StraightRgba;
@@ -48,7 +53,7 @@ fn main() -> process::ExitCode {
     }
 
     match run() {
-        Ok(()) => process::ExitCode::SUCCESS,
+        Ok(code) => code,
         Err(err) => {
             sys::write_stdout(&format!("{}\n", FormatApperr::from(err)));
             process::ExitCode::FAILURE
@@ -56,17 +61,17 @@ fn main() -> process::ExitCode {
     }
 }
 
-fn run() -> apperr::Result<()> {
+fn run() -> apperr::Result<process::ExitCode> {
     // Init `sys` first, as everything else may depend on its functionality (IO, function pointers, etc.).
     let _sys_deinit = sys::init();
     // Next init `arena`, so that `scratch_arena` works. `loc` depends on it.
     arena::init(SCRATCH_ARENA_CAPACITY)?;
     // Init the `loc` module, so that error messages are localized.
-    localization::init();
+    let localization_res = localization::init();
 
     let mut state = State::new()?;
-    if handle_args(&mut state)? {
-        return Ok(());
+    if let Some(code) = handle_args(&mut state)? {
+        return Ok(code);
     }
 
     // This will reopen stdin if it's redirected (which may fail) and switch
@@ -102,6 +107,17 @@ fn run() -> apperr::Result<()> {
     tui.set_modal_default_bg(floater_bg);
     tui.set_modal_default_fg(floater_fg);
 
+    if let Err(msg) = localization_res {
+        state.error_log_push(msg);
+    }
+    if let Err(err) = state.init_theme() {
+        state.error_log_push(format!("{}", FormatApperr::from(err)));
+    }
+    match state.load_theme(state.theme) {
+        Ok(theme) => tui.set_theme(theme),
+        Err(msg) => state.error_log_push(msg),
+    }
+
     sys::inject_window_size_into_stdin();
 
     #[cfg(feature = "debug-latency")]
@@ -116,7 +132,10 @@ fn run() -> apperr::Result<()> {
         // Process a batch of input.
         {
             let scratch = scratch_arena(None);
-            let read_timeout = vt_parser.read_timeout().min(tui.read_timeout());
+            let read_timeout = vt_parser
+                .read_timeout()
+                .min(tui.read_timeout())
+                .min(osc_clipboard_read_timeout(&state));
             let Some(input) = sys::read_stdin(&scratch, read_timeout) else {
                 break;
             };
@@ -128,11 +147,19 @@ fn run() -> apperr::Result<()> {
             }
 
             let vt_iter = vt_parser.parse(&input);
-            let mut input_iter = input_parser.parse(vt_iter);
+            let mut input_iter = input_parser.parse(vt_iter).peekable();
 
             while {
-                let input = input_iter.next();
+                let mut input = input_iter.next();
+                // Mode 1003 reports every cell the mouse moves across. Moving without
+                // buttons only changes the hover state, so only the last one of a run matters.
+                while input.as_ref().is_some_and(is_mouse_hover)
+                    && input_iter.peek().is_some_and(is_mouse_hover)
+                {
+                    input = input_iter.next();
+                }
                 let more = input.is_some();
+                let input = clipboard_filter_input(&mut state, input);
                 let mut ctx = tui.create_context(input);
 
                 draw(&mut ctx, &mut state);
@@ -146,6 +173,18 @@ fn run() -> apperr::Result<()> {
             } {}
         }
 
+        // The terminal didn't reply to our clipboard query in time.
+        // Paste the internal clipboard instead, and don't bother asking again.
+        if let Some((key, time)) = state.osc_clipboard_read_pending
+            && time.elapsed() >= OSC_CLIPBOARD_READ_TIMEOUT
+        {
+            state.osc_clipboard_read_pending = None;
+            state.osc_clipboard_read_unsupported = true;
+
+            let mut ctx = tui.create_context(Some(input::Input::Keyboard(key)));
+            draw(&mut ctx, &mut state);
+        }
+
         // Continue rendering until the layout has settled.
         // This can take >1 frame, if the input focus is tossed between different controls.
         while tui.needs_settling() {
@@ -219,19 +258,21 @@ fn run() -> apperr::Result<()> {
         }
     }
 
-    Ok(())
+    Ok(process::ExitCode::SUCCESS)
 }
 
-// Returns true if the application should exit early.
-fn handle_args(state: &mut State) -> apperr::Result<bool> {
+// Returns the exit code if the application should exit early.
+fn handle_args(state: &mut State) -> apperr::Result<Option<process::ExitCode>> {
     let scratch = scratch_arena(None);
     let mut paths: Vec<PathBuf, &Arena> = Vec::new_in(&*scratch);
     let cwd = env::current_dir()?;
     let mut dir = None;
     let mut parse_args = true;
+    let mut batch = batch::Batch::default();
 
     // The best CLI argument parser in the world.
-    for arg in env::args_os().skip(1) {
+    let mut args = env::args_os().skip(1);
+    while let Some(arg) = args.next() {
         if parse_args {
             if arg == "--" {
                 parse_args = false;
@@ -243,17 +284,25 @@ fn handle_args(state: &mut State) -> apperr::Result<bool> {
             }
             if arg == "-h" || arg == "--help" || (cfg!(windows) && arg == "/?") {
                 print_help();
-                return Ok(true);
+                return Ok(Some(process::ExitCode::SUCCESS));
             }
             if arg == "-v" || arg == "--version" {
                 print_version();
-                return Ok(true);
+                return Ok(Some(process::ExitCode::SUCCESS));
+            }
+            match batch.parse_arg(&arg, &mut args) {
+                Ok(true) => continue,
+                Ok(false) => {}
+                Err(msg) => return Ok(Some(batch::usage_error(&msg))),
             }
         }
 
         let p = cwd.join(Path::new(&arg));
         let p = path::normalize(&p);
-        if p.is_dir() {
+        if batch.is_active() {
+            // Batch mode edits the files as they are and doesn't open the file picker.
+            paths.push(p);
+        } else if p.is_dir() {
             state.wants_file_picker = StateFilePicker::Open;
             dir = Some(p);
         } else {
@@ -261,6 +310,10 @@ fn handle_args(state: &mut State) -> apperr::Result<bool> {
         }
     }
 
+    if batch.is_active() {
+        return Ok(Some(batch.run(&paths)));
+    }
+
     for p in &paths {
         state.documents.add_file_path(p)?;
     }
@@ -282,28 +335,47 @@ fn handle_args(state: &mut State) -> apperr::Result<bool> {
     }
 
     state.file_picker_pending_dir = DisplayablePathBuf::from_path(dir.unwrap_or(cwd));
-    Ok(false)
+    Ok(None)
 }
 
 fn print_help() {
     sys::write_stdout(concat!(
         "Usage: edit [OPTIONS] [FILE[:LINE[:COLUMN]]]\n",
+        "       edit [BATCH OPTIONS] [FILE]...\n",
         "Options:\n",
         "    -h, --help       Print this help message\n",
         "    -v, --version    Print the version number\n",
         "\n",
+        "Batch options (edit the files in place, or stdin to stdout, without a UI):\n",
+        "    --replace PATTERN REPLACEMENT    Replace all occurrences of PATTERN (repeatable)\n",
+        "    --regex                          PATTERN is a regular expression\n",
+        "    --match-case                     Match PATTERN case-sensitively\n",
+        "    --whole-word                     Match PATTERN only as a whole word\n",
+        "    --convert-encoding ENCODING      Save the files in ENCODING (e.g., UTF-16LE)\n",
+        "    --normalize-eol lf|crlf          Convert all line endings\n",
+        "\n",
         "Arguments:\n",
         "    FILE[:LINE[:COLUMN]]    The file to open, optionally with line and column (e.g., foo.txt:123:45)\n",
+        "\n",
+        "Exit status: 0 on success, 1 if a file couldn't be edited, 2 on invalid arguments.\n",
     ));
 }
 
 fn print_version() {
-    sys::write_stdout(concat!("edit version ", env!("CARGO_PKG_VERSION"), "\n"));
+    sys::write_stdout(concat!(
+        "edit version ",
+        env!("CARGO_PKG_VERSION"),
+        "\n",
+        // The width of characters depends on this. Useful if the terminal disagrees.
+        "Unicode version ",
+        env!("EDIT_CFG_UNICODE_VERSION"),
+        "\n",
+    ));
 }
 
 fn draw(ctx: &mut Context, state: &mut State) {
     draw_menubar(ctx, state);
-    draw_editor(ctx, state);
+    draw_workspace(ctx, state);
     draw_statusbar(ctx, state);
 
     if state.wants_close {
@@ -330,6 +402,12 @@ fn draw(ctx: &mut Context, state: &mut State) {
     if state.wants_about {
         draw_dialog_about(ctx, state);
     }
+    if state.wants_clipboard_backend_picker {
+        draw_dialog_clipboard_backend(ctx, state);
+    }
+    if state.wants_theme_picker {
+        draw_dialog_theme(ctx, state);
+    }
     if ctx.clipboard_ref().wants_host_sync() {
         draw_handle_clipboard_change(ctx, state);
     }
@@ -356,12 +434,12 @@ fn draw(ctx: &mut Context, state: &mut State) {
             state.wants_exit = true;
         } else if key == kbmod::CTRL | vk::G {
             state.wants_goto = true;
-        } else if key == kbmod::CTRL | vk::F && state.wants_search.kind != StateSearchKind::Disabled
-        {
+        } else if key == kbmod::CTRL | vk::B {
+            explorer_toggle(state);
+        } else if key == kbmod::CTRL | vk::F {
             state.wants_search.kind = StateSearchKind::Search;
             state.wants_search.focus = true;
-        } else if key == kbmod::CTRL | vk::R && state.wants_search.kind != StateSearchKind::Disabled
-        {
+        } else if key == kbmod::CTRL | vk::R {
             state.wants_search.kind = StateSearchKind::Replace;
             state.wants_search.focus = true;
         } else if key == vk::F3 {
@@ -376,6 +454,34 @@ fn draw(ctx: &mut Context, state: &mut State) {
     }
 }
 
+// The explorer sidebar and the editor, side by side.
+fn draw_workspace(ctx: &mut Context, state: &mut State) {
+    ctx.table_begin("workspace");
+    ctx.inherit_focus();
+    if state.wants_explorer {
+        ctx.table_set_columns(&[0, COORD_TYPE_SAFE_MAX]);
+    } else {
+        ctx.table_set_columns(&[COORD_TYPE_SAFE_MAX]);
+    }
+    {
+        ctx.table_next_row();
+        ctx.inherit_focus();
+
+        if state.wants_explorer {
+            // -2 for the menubar and the statusbar.
+            draw_explorer(ctx, state, ctx.size().height - 2);
+        }
+
+        // The block ensures that the editor keeps its node IDs (and thus its state),
+        // no matter whether the explorer is shown or not.
+        ctx.block_begin("editor");
+        ctx.inherit_focus();
+        draw_editor(ctx, state);
+        ctx.block_end();
+    }
+    ctx.table_end();
+}
+
 fn draw_handle_wants_exit(_ctx: &mut Context, state: &mut State) {
     while let Some(doc) = state.documents.active() {
         if doc.buffer.borrow().is_dirty() {
@@ -419,11 +525,22 @@ fn write_terminal_title(output: &mut ArenaString, state: &mut State) {
 const LARGE_CLIPBOARD_THRESHOLD: usize = 128 * KIBI;
 
 fn draw_handle_clipboard_change(ctx: &mut Context, state: &mut State) {
+    if let Some(backend) = state.clipboard_native {
+        let data = ctx.clipboard_ref().read();
+        state.osc_clipboard_host_hash = hash(0, data);
+        if let Err(err) = sys::clipboard_write(backend, data) {
+            error_log_add(ctx, state, err);
+        }
+        ctx.clipboard_mut().mark_as_synchronized();
+        return;
+    }
+
     let data_len = ctx.clipboard_ref().read().len();
 
     if state.osc_clipboard_always_send || data_len < LARGE_CLIPBOARD_THRESHOLD {
         ctx.clipboard_mut().mark_as_synchronized();
         state.osc_clipboard_sync = true;
+        state.osc_clipboard_host_stale = false;
         return;
     }
 
@@ -441,16 +558,12 @@ fn draw_handle_clipboard_change(ctx: &mut Context, state: &mut State) {
             ctx.label("line2", loc(LocId::SuperLargeClipboardWarning));
             ctx.attr_position(Position::Center);
         } else {
-            let label2 = {
-                let template = loc(LocId::LargeClipboardWarningLine2);
-                let size = arena_format!(ctx.arena(), "{}", MetricFormatter(data_len));
-
-                let mut label =
-                    ArenaString::with_capacity_in(template.len() + size.len(), ctx.arena());
-                label.push_str(template);
-                label.replace_once_in_place("{size}", &size);
-                label
-            };
+            let size = arena_format!(ctx.arena(), "{}", MetricFormatter(data_len));
+            let label2 = loc_format(
+                ctx.arena(),
+                LocId::LargeClipboardWarningLine2,
+                &[("size", size.as_str().into())],
+            );
 
             ctx.label("line1", loc(LocId::LargeClipboardWarningLine1));
             ctx.attr_position(Position::Center);
@@ -504,11 +617,90 @@ fn draw_handle_clipboard_change(ctx: &mut Context, state: &mut State) {
 
     if let Some(sync) = done {
         state.osc_clipboard_sync = sync;
+        state.osc_clipboard_host_stale = !sync;
         ctx.clipboard_mut().mark_as_synchronized();
         ctx.needs_rerender();
     }
 }
 
+const OSC_CLIPBOARD_READ_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Whether the input is a mouse movement without any buttons held.
+fn is_mouse_hover(input: &input::Input) -> bool {
+    matches!(input, input::Input::Mouse(m) if m.state == input::InputMouseState::None)
+}
+
+/// Intercepts paste key presses to first fetch the host's clipboard contents, either from
+/// the native clipboard backend or by asking the terminal via OSC 52 (e.g. over SSH).
+/// Once the contents arrive, they're turned into a regular paste.
+fn clipboard_filter_input<'a>(
+    state: &mut State,
+    input: Option<input::Input<'a>>,
+) -> Option<input::Input<'a>> {
+    let is_paste_key = |key| key == kbmod::CTRL | vk::V || key == kbmod::SHIFT | vk::INSERT;
+
+    if let Some(backend) = state.clipboard_native {
+        return match input {
+            Some(input::Input::Keyboard(key)) if is_paste_key(key) => {
+                match sys::clipboard_read(backend) {
+                    Ok(data) => {
+                        let data_hash = hash(0, &data);
+                        let changed =
+                            !data.is_empty() && data_hash != state.osc_clipboard_host_hash;
+                        state.osc_clipboard_host_hash = data_hash;
+                        Some(if changed {
+                            input::Input::Paste(data)
+                        } else {
+                            input::Input::Keyboard(key)
+                        })
+                    }
+                    Err(_) => Some(input::Input::Keyboard(key)),
+                }
+            }
+            input => input,
+        };
+    }
+
+    match input {
+        Some(input::Input::Keyboard(key))
+            if is_paste_key(key)
+                && !state.osc_clipboard_read_unsupported
+                && !state.osc_clipboard_host_stale
+                && state.osc_clipboard_read_pending.is_none() =>
+        {
+            sys::write_stdout("\x1b]52;c;?\x1b\\");
+            state.osc_clipboard_read_pending = Some((key, Instant::now()));
+            None
+        }
+        Some(input::Input::HostClipboard(data)) => {
+            // Even late replies prove that the terminal supports it.
+            state.osc_clipboard_read_unsupported = false;
+
+            // Terminals reply with an empty string if they deny access to the clipboard.
+            // If the contents didn't change since we last saw them, our internal clipboard
+            // is at least as recent (e.g. if the terminal ignores our OSC 52 writes).
+            let data_hash = hash(0, &data);
+            let changed = !data.is_empty() && data_hash != state.osc_clipboard_host_hash;
+            state.osc_clipboard_host_hash = data_hash;
+
+            match state.osc_clipboard_read_pending.take() {
+                Some((_, _)) if changed => Some(input::Input::Paste(data)),
+                Some((key, _)) => Some(input::Input::Keyboard(key)),
+                None if changed => Some(input::Input::HostClipboard(data)),
+                None => None,
+            }
+        }
+        input => input,
+    }
+}
+
+fn osc_clipboard_read_timeout(state: &State) -> Duration {
+    match state.osc_clipboard_read_pending {
+        Some((_, time)) => OSC_CLIPBOARD_READ_TIMEOUT.saturating_sub(time.elapsed()),
+        None => Duration::MAX,
+    }
+}
+
 #[cold]
 fn write_osc_clipboard(tui: &mut Tui, state: &mut State, output: &mut ArenaString) {
     let clipboard = tui.clipboard_mut();
@@ -523,6 +715,7 @@ fn write_osc_clipboard(tui: &mut Tui, state: &mut State, output: &mut ArenaStrin
         output.push_str("\x1b]52;c;");
         base64::encode(output, data);
         output.push_str("\x1b\\");
+        state.osc_clipboard_host_hash = hash(0, data);
     }
 
     state.osc_clipboard_sync = false;
@@ -534,8 +727,12 @@ impl Drop for RestoreModes {
     fn drop(&mut self) {
         // Same as in the beginning but in the reverse order.
         // It also includes DECSCUSR 0 to reset the cursor style and DECTCEM to show the cursor.
+        // BDSM is set back to the implicit bidi mode, which is the default in terminals that support it.
         // We specifically don't reset mode 1036, because most applications expect it to be set nowadays.
-        sys::write_stdout("\x1b[0 q\x1b[?25h\x1b]0;\x07\x1b[?1002;1006;2004l\x1b[?1049l");
+        // The kitty keyboard protocol flags are popped off the stack with `CSI < u`.
+        sys::write_stdout(
+            "\x1b[<u\x1b[0 q\x1b[?25h\x1b]0;\x07\x1b[8h\x1b[?1003;1006;2004l\x1b[?1049l",
+        );
     }
 }
 
@@ -544,11 +741,18 @@ fn setup_terminal(tui: &mut Tui, state: &mut State, vt_parser: &mut vt::Parser)
         // 1049: Alternative Screen Buffer
         //   I put the ASB switch in the beginning, just in case the terminal performs
         //   some additional state tracking beyond the modes we enable/disable.
-        // 1002: Cell Motion Mouse Tracking
+        // 1003: Any Event Mouse Tracking (motion without buttons is needed to hover links)
         // 1006: SGR Mouse Mode
         // 2004: Bracketed Paste Mode
         // 1036: Xterm: "meta sends escape" (Alt keypresses should be encoded with ESC + char)
-        "\x1b[?1049h\x1b[?1002;1006;2004h\x1b[?1036h",
+        "\x1b[?1049h\x1b[?1003;1006;2004h\x1b[?1036h",
+        // BDSM: Switch to explicit bidi mode. We reorder right-to-left text ourselves and
+        //   terminals with bidi support (e.g. VTE) would otherwise reorder it a second time.
+        "\x1b[8l",
+        // Kitty keyboard protocol: Push the "disambiguate escape codes" flag onto the stack.
+        //   This gives us Ctrl+Shift+letters, Ctrl+Enter, Ctrl+Backspace vs. Ctrl+H, etc.
+        //   Terminals that don't support it ignore the sequence.
+        "\x1b[>1u",
         // OSC 4 color table requests for indices 0 through 15 (base colors).
         "\x1b]4;0;?;1;?;2;?;3;?;4;?;5;?;6;?;7;?\x07",
         "\x1b]4;8;?;9;?;10;?;11;?;12;?;13;?;14;?;15;?\x07",
@@ -571,6 +775,7 @@ fn setup_terminal(tui: &mut Tui, state: &mut State, vt_parser: &mut vt::Parser)
     let mut indexed_colors = framebuffer::DEFAULT_THEME;
     let mut color_responses = 0;
     let mut ambiguous_width = 1;
+    let mut da1_class = None;
 
     while !done {
         let scratch = scratch_arena(None);
@@ -586,7 +791,13 @@ fn setup_terminal(tui: &mut Tui, state: &mut State, vt_parser: &mut vt::Parser)
         while let Some(token) = vt_stream.next() {
             match token {
                 Token::Csi(csi) => match csi.final_byte {
-                    'c' => done = true,
+                    'c' => {
+                        // DA1 response: `CSI ? <class> ; <attributes> c`.
+                        if csi.private_byte == '?' && csi.param_count > 0 {
+                            da1_class = Some(csi.params[0]);
+                        }
+                        done = true;
+                    }
                     // CPR (Cursor Position Report) response.
                     'R' => ambiguous_width = csi.params[1] as CoordType - 1,
                     _ => {}
@@ -656,6 +867,8 @@ fn setup_terminal(tui: &mut Tui, state: &mut State, vt_parser: &mut vt::Parser)
         tui.setup_indexed_colors(indexed_colors);
     }
 
+    tui.setup_color_depth(ColorDepth::detect(da1_class));
+
     RestoreModes
 }
 
@@ -3,16 +3,20 @@
 
 use std::borrow::Cow;
 use std::ffi::{OsStr, OsString};
-use std::mem;
 use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime};
+use std::{env, fs, mem};
 
 use edit::framebuffer::IndexedColor;
 use edit::helpers::*;
+use edit::input::InputKey;
 use edit::oklab::StraightRgba;
+use edit::theme::Theme;
 use edit::tui::*;
 use edit::{apperr, buffer, icu, sys};
 
 use crate::documents::DocumentManager;
+use crate::explorer::Explorer;
 use crate::localization::*;
 
 #[repr(transparent)]
@@ -100,11 +104,75 @@ pub struct StateSearch {
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum StateSearchKind {
     Hidden,
-    Disabled,
     Search,
     Replace,
 }
 
+/// A list of previous inputs for an editline, navigated with Up/Down.
+#[derive(Default)]
+pub struct InputHistory {
+    entries: Vec<String>,
+    index: Option<usize>,
+}
+
+impl InputHistory {
+    const MAX_ENTRIES: usize = 50;
+
+    /// Adds `value` as the most recent entry and resets the navigation.
+    /// Duplicates are moved to the end instead of being added twice.
+    pub fn push(&mut self, value: &str) {
+        self.index = None;
+        if value.is_empty() {
+            return;
+        }
+        if let Some(pos) = self.entries.iter().position(|e| e == value) {
+            self.entries.remove(pos);
+        }
+        if self.entries.len() >= Self::MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+        self.entries.push(value.to_string());
+    }
+
+    /// Returns the next older entry, if any.
+    pub fn prev(&mut self) -> Option<&str> {
+        let index = match self.index {
+            None => self.entries.len().checked_sub(1)?,
+            Some(0) => 0,
+            Some(i) => i - 1,
+        };
+        self.index = Some(index);
+        Some(&self.entries[index])
+    }
+
+    /// Returns the next newer entry. Moving past the newest one returns an empty string.
+    pub fn next(&mut self) -> Option<&str> {
+        let index = self.index?;
+        if index + 1 < self.entries.len() {
+            self.index = Some(index + 1);
+            Some(&self.entries[index + 1])
+        } else {
+            self.index = None;
+            Some("")
+        }
+    }
+
+    /// Stops navigating, e.g. because the user edited the recalled entry.
+    pub fn reset(&mut self) {
+        self.index = None;
+    }
+}
+
+/// Where the clipboard contents are synchronized to.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum StateClipboardBackend {
+    /// A native backend, if one was detected, or otherwise the terminal.
+    Automatic,
+    /// Only OSC 52 sequences.
+    Terminal,
+    Native(sys::ClipboardBackend),
+}
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum StateFilePicker {
     None,
@@ -114,6 +182,32 @@ pub enum StateFilePicker {
     SaveAsShown, // Transitioned from SaveAs
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum StateFilePickerSort {
+    Name,
+    Size,
+    Modified,
+}
+
+pub struct FilePickerEntry {
+    pub name: DisplayablePathBuf, // Directories have a trailing "/".
+    pub size: u64,
+    pub modified: SystemTime,
+    // The permissions, size and modification time, formatted for their columns.
+    pub permissions: String,
+    pub formatted_size: String,
+    pub formatted_modified: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum StateExplorerPrompt {
+    None,
+    NewFile,
+    NewFolder,
+    Rename,
+    Trash,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum StateEncodingChange {
     None,
@@ -121,6 +215,16 @@ pub enum StateEncodingChange {
     Reopen,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum StateTheme {
+    Terminal,
+    Light,
+    Dark,
+    HighContrast,
+    /// The theme file given via the `EDIT_THEME` environment variable.
+    Custom,
+}
+
 #[derive(Default)]
 pub struct OscTitleFileStatus {
     pub filename: String,
@@ -142,15 +246,30 @@ pub struct State {
     pub file_picker_pending_dir: DisplayablePathBuf,
     pub file_picker_pending_dir_revision: u64, // Bumped every time `file_picker_pending_dir` changes.
     pub file_picker_pending_name: PathBuf,
-    pub file_picker_entries: Option<[Vec<DisplayablePathBuf>; 3]>, // ["..", directories, files]
-    pub file_picker_overwrite_warning: Option<PathBuf>,            // The path the warning is about.
+    pub file_picker_entries: Option<[Vec<FilePickerEntry>; 3]>, // ["..", directories, files], sorted by name.
+    pub file_picker_view: Option<Vec<(usize, usize)>>, // Indices into `file_picker_entries`, filtered & sorted.
+    pub file_picker_sort: StateFilePickerSort,
+    pub file_picker_sort_descending: bool,
+    pub file_picker_filter: String,
+    pub file_picker_show_hidden: bool,
+    pub file_picker_recent_dirs: Vec<DisplayablePathBuf>, // Most recent first.
+    pub file_picker_overwrite_warning: Option<PathBuf>,   // The path the warning is about.
     pub file_picker_autocomplete: Vec<DisplayablePathBuf>,
 
+    pub wants_explorer: bool,
+    pub explorer: Explorer,
+    pub explorer_focus: bool, // Moves the focus to the selected entry in the next frame.
+    pub explorer_prompt: StateExplorerPrompt,
+    pub explorer_prompt_name: String,
+    pub wants_editor_focus: bool,
+
     pub wants_search: StateSearch,
     pub search_needle: String,
     pub search_replacement: String,
     pub search_options: buffer::SearchOptions,
     pub search_success: bool,
+    pub search_needle_history: InputHistory,
+    pub search_replacement_history: InputHistory,
 
     pub wants_encoding_picker: bool,
     pub wants_encoding_change: StateEncodingChange,
@@ -168,9 +287,29 @@ pub struct State {
     pub goto_target: String,
     pub goto_invalid: bool,
 
+    pub wants_clipboard_backend_picker: bool,
+    pub clipboard_backend: StateClipboardBackend,
+    // The resolved `clipboard_backend`. `None` means OSC 52.
+    pub clipboard_native: Option<sys::ClipboardBackend>,
+    pub clipboard_backends_available: Vec<sys::ClipboardBackend>,
+
+    pub wants_theme_picker: bool,
+    pub theme: StateTheme,
+    pub theme_custom: Option<(DisplayablePathBuf, String)>, // The path and contents of the file.
+
     pub osc_title_file_status: OscTitleFileStatus,
     pub osc_clipboard_sync: bool,
     pub osc_clipboard_always_send: bool,
+    // True if the user declined to send the clipboard to the terminal,
+    // in which case the terminal's clipboard is outdated and we must not read it.
+    pub osc_clipboard_host_stale: bool,
+    // The paste key press that is waiting for the terminal's OSC 52 reply.
+    pub osc_clipboard_read_pending: Option<(InputKey, Instant)>,
+    // Set once the terminal failed to reply to an OSC 52 query in time.
+    pub osc_clipboard_read_unsupported: bool,
+    // Hash of the clipboard contents the terminal last had, as far as we know.
+    // If a reply matches it, nothing was copied outside the editor since.
+    pub osc_clipboard_host_hash: u64,
     pub exit: bool,
 }
 
@@ -191,14 +330,29 @@ impl State {
             file_picker_pending_dir_revision: 0,
             file_picker_pending_name: Default::default(),
             file_picker_entries: None,
+            file_picker_view: None,
+            file_picker_sort: StateFilePickerSort::Name,
+            file_picker_sort_descending: false,
+            file_picker_filter: Default::default(),
+            file_picker_show_hidden: true,
+            file_picker_recent_dirs: Vec::new(),
             file_picker_overwrite_warning: None,
             file_picker_autocomplete: Vec::new(),
 
+            wants_explorer: false,
+            explorer: Default::default(),
+            explorer_focus: false,
+            explorer_prompt: StateExplorerPrompt::None,
+            explorer_prompt_name: Default::default(),
+            wants_editor_focus: false,
+
             wants_search: StateSearch { kind: StateSearchKind::Hidden, focus: false },
             search_needle: Default::default(),
             search_replacement: Default::default(),
             search_options: Default::default(),
             search_success: true,
+            search_needle_history: Default::default(),
+            search_replacement_history: Default::default(),
 
             wants_encoding_picker: false,
             encoding_picker_needle: Default::default(),
@@ -216,12 +370,77 @@ impl State {
             goto_target: Default::default(),
             goto_invalid: false,
 
+            wants_clipboard_backend_picker: false,
+            clipboard_backend: StateClipboardBackend::Automatic,
+            clipboard_native: sys::clipboard_detect(),
+            clipboard_backends_available: Vec::new(),
+
+            wants_theme_picker: false,
+            theme: StateTheme::Terminal,
+            theme_custom: None,
+
             osc_title_file_status: Default::default(),
             osc_clipboard_sync: false,
             osc_clipboard_always_send: false,
+            osc_clipboard_host_stale: false,
+            osc_clipboard_read_pending: None,
+            osc_clipboard_read_unsupported: false,
+            osc_clipboard_host_hash: 0,
             exit: false,
         })
     }
+
+    pub fn set_clipboard_backend(&mut self, backend: StateClipboardBackend) {
+        self.clipboard_backend = backend;
+        self.clipboard_native = match backend {
+            StateClipboardBackend::Automatic => sys::clipboard_detect(),
+            StateClipboardBackend::Terminal => None,
+            StateClipboardBackend::Native(backend) => Some(backend),
+        };
+    }
+
+    /// Picks the initial theme based on the `EDIT_THEME` environment variable,
+    /// which is either the name of a builtin theme or the path to a theme file.
+    pub fn init_theme(&mut self) -> apperr::Result<()> {
+        let Some(value) = env::var_os("EDIT_THEME") else {
+            return Ok(());
+        };
+
+        self.theme = match value.to_str() {
+            Some("terminal") => StateTheme::Terminal,
+            Some("light") => StateTheme::Light,
+            Some("dark") => StateTheme::Dark,
+            Some("high-contrast") => StateTheme::HighContrast,
+            _ => {
+                let contents = fs::read_to_string(&value)?;
+                self.theme_custom = Some((DisplayablePathBuf::from(&value), contents));
+                StateTheme::Custom
+            }
+        };
+        Ok(())
+    }
+
+    /// Parses the given theme. Errors are formatted for the error log.
+    pub fn load_theme(&self, theme: StateTheme) -> Result<Theme, String> {
+        let (name, source) = match (theme, &self.theme_custom) {
+            (StateTheme::Light, _) => ("light.theme", include_str!("themes/light.theme")),
+            (StateTheme::Dark, _) => ("dark.theme", include_str!("themes/dark.theme")),
+            (StateTheme::HighContrast, _) => {
+                ("high-contrast.theme", include_str!("themes/high-contrast.theme"))
+            }
+            (StateTheme::Custom, Some((path, contents))) => (path.as_str(), contents.as_str()),
+            (StateTheme::Terminal | StateTheme::Custom, _) => return Ok(Theme::default()),
+        };
+        Theme::parse(source).map_err(|err| format!("{name}:{err}"))
+    }
+
+    pub fn error_log_push(&mut self, msg: String) {
+        if !msg.is_empty() {
+            self.error_log[self.error_log_index] = msg;
+            self.error_log_index = (self.error_log_index + 1) % self.error_log.len();
+            self.error_log_count = self.error_log.len().min(self.error_log_count + 1);
+        }
+    }
 }
 
 pub fn draw_add_untitled_document(ctx: &mut Context, state: &mut State) {
@@ -231,13 +450,8 @@ pub fn draw_add_untitled_document(ctx: &mut Context, state: &mut State) {
 }
 
 pub fn error_log_add(ctx: &mut Context, state: &mut State, err: apperr::Error) {
-    let msg = format!("{}", FormatApperr::from(err));
-    if !msg.is_empty() {
-        state.error_log[state.error_log_index] = msg;
-        state.error_log_index = (state.error_log_index + 1) % state.error_log.len();
-        state.error_log_count = state.error_log.len().min(state.error_log_count + 1);
-        ctx.needs_rerender();
-    }
+    state.error_log_push(format!("{}", FormatApperr::from(err)));
+    ctx.needs_rerender();
 }
 
 pub fn draw_error_log(ctx: &mut Context, state: &mut State) {
@@ -1,16 +1,45 @@
 use std::collections::HashSet;
 use std::cmp::Ordering;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 use crate::buffer::{TextBuffer, CursorMovement};
-use crate::helpers::Point;
+use crate::helpers::{CoordType, Point};
+use crate::unicode::MeasurementConfig;
+use crate::{icu, path, spell};
+
+/// The kind of symbol a completion item refers to.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionItemKind {
+    /// Plain text, e.g. a word found in the buffer.
+    #[default]
+    Text,
+    File,
+    Folder,
+}
+
+impl CompletionItemKind {
+    /// Single-letter icon shown in front of the label in the completion popup.
+    pub fn icon(self) -> char {
+        match self {
+            CompletionItemKind::Text => 'T',
+            CompletionItemKind::File => 'P',
+            CompletionItemKind::Folder => 'D',
+        }
+    }
+}
 
 /// Represents a single auto-completion suggestion
 #[derive(Debug, Clone, PartialEq)]
 pub struct CompletionItem {
     /// The text to insert when this completion is selected
     pub label: String,
+    /// What kind of symbol this completion refers to
+    pub kind: CompletionItemKind,
     /// Optional detail/description for the completion
     pub detail: Option<String>,
+    /// Optional long-form documentation, shown in a side panel
+    pub documentation: Option<String>,
     /// Sort text (defaults to label if not provided)
     pub sort_text: Option<String>,
 }
@@ -19,16 +48,28 @@ impl CompletionItem {
     pub fn new(label: String) -> Self {
         CompletionItem {
             label,
+            kind: CompletionItemKind::default(),
             detail: None,
+            documentation: None,
             sort_text: None,
         }
     }
 
+    pub fn with_kind(mut self, kind: CompletionItemKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
     pub fn with_detail(mut self, detail: String) -> Self {
         self.detail = Some(detail);
         self
     }
 
+    pub fn with_documentation(mut self, documentation: String) -> Self {
+        self.documentation = Some(documentation);
+        self
+    }
+
     pub fn with_sort_text(mut self, sort_text: String) -> Self {
         self.sort_text = Some(sort_text);
         self
@@ -48,6 +89,8 @@ pub struct AutoCompletionState {
     pub items: Vec<CompletionItem>,
     /// Current selected index in the items list
     pub selected_index: usize,
+    /// Vertical scroll offset of the documentation panel, in visual lines
+    pub documentation_scroll: CoordType,
     /// Whether completion is currently active
     pub is_active: bool,
     /// Whether to show the completion popup
@@ -62,6 +105,7 @@ impl AutoCompletionState {
             end_pos: Point::default(),
             items: Vec::new(),
             selected_index: 0,
+            documentation_scroll: 0,
             is_active: false,
             show_popup: false,
         }
@@ -71,6 +115,7 @@ impl AutoCompletionState {
         self.prefix.clear();
         self.items.clear();
         self.selected_index = 0;
+        self.documentation_scroll = 0;
         self.is_active = false;
         self.show_popup = false;
     }
@@ -78,6 +123,7 @@ impl AutoCompletionState {
     pub fn select_next(&mut self) {
         if !self.items.is_empty() {
             self.selected_index = (self.selected_index + 1) % self.items.len();
+            self.documentation_scroll = 0;
         }
     }
 
@@ -88,16 +134,30 @@ impl AutoCompletionState {
             } else {
                 self.selected_index -= 1;
             }
+            self.documentation_scroll = 0;
         }
     }
 
+    /// Scrolls the documentation panel of the selected item by `delta` lines.
+    /// `max` is the number of wrapped lines that don't fit into the panel.
+    pub fn scroll_documentation(&mut self, delta: CoordType, max: CoordType) {
+        self.documentation_scroll = (self.documentation_scroll + delta).min(max).max(0);
+    }
+
+    /// Returns the currently selected item, if any.
+    pub fn selected_item(&self) -> Option<&CompletionItem> {
+        self.items.get(self.selected_index)
+    }
+
     pub fn accept_current(&mut self, buffer: &mut TextBuffer) -> bool {
         if self.is_active && !self.items.is_empty() && self.selected_index < self.items.len() {
             let item = &self.items[self.selected_index];
-            
-            // Delete the current prefix
-            buffer.delete(CursorMovement::Grapheme, -(self.prefix.len() as isize));
-            
+
+            // Delete the current prefix. The deletion is in graphemes, not bytes.
+            let prefix = self.prefix.as_bytes();
+            let graphemes = MeasurementConfig::new(&prefix).goto_offset(prefix.len()).logical_pos.x;
+            buffer.delete(CursorMovement::Grapheme, -graphemes);
+
             // Insert the completion
             buffer.write_canon(item.label.as_bytes());
             
@@ -179,7 +239,7 @@ impl CompletionProvider for WordCompletionProvider {
         // Convert to CompletionItems and sort
         let mut completions: Vec<_> = words
             .into_iter()
-            .map(|word| CompletionItem::new(word))
+            .map(|word| CompletionItem::new(word).with_kind(CompletionItemKind::Text))
             .collect();
 
         // Sort completions by relevance (length, alphabetical)
@@ -195,10 +255,151 @@ impl CompletionProvider for WordCompletionProvider {
     }
 }
 
+/// Completion provider for filesystem paths like `./src/` or `../assets/`.
+///
+/// Relative paths are resolved against the directory of the document,
+/// falling back to the current working directory for untitled documents.
+#[derive(Default)]
+pub struct PathCompletionProvider {
+    /// Directory that relative paths are resolved against.
+    pub dir: Option<PathBuf>,
+}
+
+impl PathCompletionProvider {
+    /// Returns true if `prefix` looks like the start of a path
+    /// that this provider is able to complete.
+    pub fn is_path_prefix(prefix: &str) -> bool {
+        if prefix.starts_with("./") || prefix.starts_with("../") || prefix.starts_with("~/") {
+            return true;
+        }
+        // A lone "/" is far more likely to be a division or the start of a comment.
+        let bytes = prefix.as_bytes();
+        bytes.len() >= 2 && bytes[0] == b'/' && bytes[1] != b'/' && bytes[1] != b'*'
+    }
+
+    fn resolve_dir(&self, dir_part: &str) -> Option<PathBuf> {
+        if let Some(rest) = dir_part.strip_prefix("~/") {
+            let home = std::env::var_os("HOME")?;
+            return Some(Path::new(&home).join(rest));
+        }
+        if dir_part.starts_with('/') {
+            return Some(PathBuf::from(dir_part));
+        }
+        let base = match &self.dir {
+            Some(dir) => dir.clone(),
+            None => std::env::current_dir().ok()?,
+        };
+        Some(base.join(dir_part))
+    }
+}
+
+impl CompletionProvider for PathCompletionProvider {
+    fn get_completions(&self, _buffer: &TextBuffer, prefix: &str) -> Vec<CompletionItem> {
+        if !Self::is_path_prefix(prefix) {
+            return Vec::new();
+        }
+
+        // Split "../assets/ic" into the directory part "../assets/" and the name part "ic".
+        let split = prefix.rfind('/').map_or(0, |i| i + 1);
+        let (dir_part, name_part) = prefix.split_at(split);
+        let Some(dir) = self.resolve_dir(dir_part) else {
+            return Vec::new();
+        };
+        let Ok(iter) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+
+        let mut entries = Vec::new();
+
+        for entry in iter.flatten() {
+            let name = entry.file_name();
+            let Some(name) = name.to_str() else {
+                continue;
+            };
+            // Hidden files are only suggested if the user explicitly asks for them.
+            if !name.starts_with(name_part)
+                || (name.starts_with('.') && !name_part.starts_with('.'))
+            {
+                continue;
+            }
+
+            let is_dir = entry.metadata().is_ok_and(|m| {
+                m.is_dir()
+                    || (m.is_symlink() && fs::metadata(entry.path()).is_ok_and(|m| m.is_dir()))
+            });
+
+            let mut label = String::with_capacity(dir_part.len() + name.len() + 1);
+            label.push_str(dir_part);
+            label.push_str(name);
+            if is_dir {
+                label.push('/');
+            }
+
+            // The documentation shows where relative paths and `~/` actually lead.
+            let mut item = CompletionItem::new(label)
+                .with_documentation(path::normalize(&entry.path()).display().to_string());
+            item = if is_dir {
+                item.with_kind(CompletionItemKind::Folder).with_detail("directory".to_string())
+            } else {
+                item.with_kind(CompletionItemKind::File)
+            };
+            entries.push(item);
+        }
+
+        // Same order as the file picker: directories first, then files, each collated.
+        entries.sort_by(|a, b| {
+            let a_is_dir = a.kind == CompletionItemKind::Folder;
+            let b_is_dir = b.kind == CompletionItemKind::Folder;
+
+            match b_is_dir.cmp(&a_is_dir) {
+                Ordering::Equal => icu::compare_strings(a.label.as_bytes(), b.label.as_bytes()),
+                other => other,
+            }
+        });
+
+        entries
+    }
+}
+
+/// Completion provider for prose, backed by the spell checking dictionary.
+///
+/// If the prefix is misspelled, spelling suggestions are offered first,
+/// followed by dictionary words that start with the prefix.
+pub struct SpellingCompletionProvider;
+
+impl CompletionProvider for SpellingCompletionProvider {
+    fn get_completions(&self, _buffer: &TextBuffer, prefix: &str) -> Vec<CompletionItem> {
+        let Some(dictionary) = spell::dictionary() else {
+            return Vec::new();
+        };
+        if prefix.is_empty() {
+            return Vec::new();
+        }
+
+        let mut items = Vec::new();
+
+        if !dictionary.check(prefix) {
+            for suggestion in dictionary.suggest(prefix) {
+                items.push(CompletionItem::new(suggestion).with_detail("spelling".to_string()));
+            }
+        }
+
+        for word in dictionary.complete(prefix, 20) {
+            if !items.iter().any(|i| i.label == word) {
+                items.push(CompletionItem::new(word));
+            }
+        }
+
+        items
+    }
+}
+
 /// Main auto-completion controller
 pub struct AutoCompleter {
     pub state: AutoCompletionState,
     pub provider: Box<dyn CompletionProvider>,
+    pub path_provider: PathCompletionProvider,
+    pub spelling_provider: SpellingCompletionProvider,
 }
 
 impl AutoCompleter {
@@ -206,6 +407,8 @@ impl AutoCompleter {
         AutoCompleter {
             state: AutoCompletionState::new(),
             provider,
+            path_provider: PathCompletionProvider::default(),
+            spelling_provider: SpellingCompletionProvider,
         }
     }
 
@@ -224,6 +427,7 @@ impl AutoCompleter {
                 self.state.prefix = prefix;
                 self.state.items = completions;
                 self.state.selected_index = 0;
+                self.state.documentation_scroll = 0;
                 self.state.is_active = true;
                 self.state.show_popup = true;
                 
@@ -291,4 +495,76 @@ impl Default for AutoCompleter {
     fn default() -> Self {
         Self::new(Box::new(WordCompletionProvider))
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_path_prefix() {
+        assert!(PathCompletionProvider::is_path_prefix("./"));
+        assert!(PathCompletionProvider::is_path_prefix("../assets/"));
+        assert!(PathCompletionProvider::is_path_prefix("~/"));
+        assert!(PathCompletionProvider::is_path_prefix("/usr"));
+        assert!(!PathCompletionProvider::is_path_prefix("/"));
+        assert!(!PathCompletionProvider::is_path_prefix("//"));
+        assert!(!PathCompletionProvider::is_path_prefix("foo/bar"));
+    }
+
+    #[test]
+    fn test_documentation_scroll() {
+        let mut state = AutoCompletionState::new();
+        state.items = vec![
+            CompletionItem::new("foo".to_string()).with_documentation("Docs".to_string()),
+            CompletionItem::new("bar".to_string()),
+        ];
+
+        state.scroll_documentation(-5, 4);
+        assert_eq!(state.documentation_scroll, 0);
+        state.scroll_documentation(3, 4);
+        assert_eq!(state.documentation_scroll, 3);
+        state.scroll_documentation(3, 4);
+        assert_eq!(state.documentation_scroll, 4);
+        // Scrolling back up takes effect immediately, even after overshooting.
+        state.scroll_documentation(-1, 4);
+        assert_eq!(state.documentation_scroll, 3);
+        assert_eq!(state.selected_item().unwrap().documentation.as_deref(), Some("Docs"));
+
+        state.select_next();
+        assert_eq!(state.documentation_scroll, 0);
+        assert_eq!(state.selected_item().unwrap().label, "bar");
+    }
+
+    #[test]
+    fn test_path_completions() {
+        let dir = std::env::temp_dir().join(format!("edit-path-completion-{}", std::process::id()));
+        _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("main.rs"), "").unwrap();
+        fs::write(dir.join("src/lib.rs"), "").unwrap();
+        fs::write(dir.join(".hidden"), "").unwrap();
+
+        let buffer = TextBuffer::new(true).unwrap();
+        let provider = PathCompletionProvider { dir: Some(dir.clone()) };
+
+        let items = provider.get_completions(&buffer, "./");
+        let labels: Vec<_> = items.iter().map(|i| i.label.as_str()).collect();
+        assert_eq!(labels, ["./src/", "./main.rs"]);
+        assert_eq!(items[0].detail.as_deref(), Some("directory"));
+        assert_eq!(items[1].detail, None);
+        assert_eq!(items[0].kind, CompletionItemKind::Folder);
+        assert_eq!(items[1].kind, CompletionItemKind::File);
+        assert_eq!(items[1].documentation, Some(dir.join("main.rs").display().to_string()));
+
+        let items = provider.get_completions(&buffer, "./src/l");
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].label, "./src/lib.rs");
+
+        let items = provider.get_completions(&buffer, "./.h");
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].label, "./.hidden");
+
+        _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,253 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Guesses the encoding of files without a BOM.
+//!
+//! Valid UTF-8 is taken as is. Everything else is given to ICU's charset detector,
+//! which knows most legacy encodings, and to a few byte-frequency heuristics for the
+//! most widespread ones: Shift_JIS, GBK, Big5 and Windows-1252. The most confident guess wins.
+
+use std::str;
+
+use crate::icu;
+
+/// Guesses below this confidence are discarded.
+const MIN_CONFIDENCE: u8 = 10;
+
+/// The result of [`detect`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Detection {
+    pub encoding: &'static str,
+    /// How sure we are about the encoding, from 0 to 100.
+    /// 100 means that it isn't a guess, because the text is valid UTF-8.
+    pub confidence: u8,
+}
+
+/// Guesses the encoding of `text`, a sample from the start of a file.
+///
+/// Pass `truncated` if the file continues after the sample, so that a character
+/// cut in half at the end isn't mistaken for an encoding error.
+///
+/// Returns `None` if it's anyone's guess. That includes binary files,
+/// and UTF-16/32 without a BOM, which is rare and hard to tell from binary.
+pub fn detect(text: &[u8], truncated: bool) -> Option<Detection> {
+    if is_binary(text) {
+        return None;
+    }
+
+    let utf8_valid = match str::from_utf8(text) {
+        Ok(_) => true,
+        // `error_len() == None` means that the text ends in the middle of a character.
+        Err(err) => truncated && err.error_len().is_none(),
+    };
+    if utf8_valid {
+        return Some(Detection { encoding: "UTF-8", confidence: 100 });
+    }
+
+    let mut best = guess(text, truncated);
+
+    // We already know that it's not UTF-8.
+    if let Some((encoding, confidence)) = icu::detect_encoding(text)
+        && encoding != "UTF-8"
+        && best.is_none_or(|d| confidence > d.confidence)
+    {
+        best = Some(Detection { encoding, confidence: confidence.min(99) });
+    }
+
+    best.filter(|d| d.confidence >= MIN_CONFIDENCE)
+}
+
+/// Returns true if `text`, a sample from the start of a file, looks like binary data.
+/// Text files practically never contain NUL bytes, unless they're UTF-16/32 without a BOM.
+pub fn is_binary(text: &[u8]) -> bool {
+    text.contains(&0)
+}
+
+/// The built-in heuristics, which work without ICU.
+fn guess(text: &[u8], truncated: bool) -> Option<Detection> {
+    let mut best: Option<Detection> = None;
+    let mut consider = |encoding, confidence| {
+        if best.is_none_or(|d| confidence > d.confidence) {
+            best = Some(Detection { encoding, confidence });
+        }
+    };
+
+    for dbcs in &DBCS {
+        consider(dbcs.encoding, dbcs.score(text, truncated));
+    }
+    consider("windows-1252", score_windows_1252(text));
+
+    best
+}
+
+/// A double-byte character set.
+struct Dbcs {
+    encoding: &'static str,
+    /// Bytes that start a double-byte character.
+    is_lead: fn(u8) -> bool,
+    /// Bytes that may follow a lead byte.
+    is_trail: fn(u8) -> bool,
+    /// Non-ASCII bytes that are characters on their own.
+    is_single: fn(u8) -> bool,
+    /// Characters that make up the bulk of typical text.
+    is_common: fn(u8, u8) -> bool,
+}
+
+// The order matters for ties: A text that only consists of common hanzi
+// is equally valid GBK and Big5, but GBK is the more common encoding.
+const DBCS: [Dbcs; 3] = [
+    Dbcs {
+        encoding: "Shift_JIS",
+        is_lead: |b| matches!(b, 0x81..=0x9f | 0xe0..=0xfc),
+        is_trail: |b| matches!(b, 0x40..=0x7e | 0x80..=0xfc),
+        // Half-width katakana
+        is_single: |b| matches!(b, 0xa1..=0xdf),
+        // Punctuation, hiragana, katakana and the level 1 kanji
+        is_common: |lead, trail| {
+            matches!((lead, trail), (0x81, _) | (0x82, 0x9f..=0xf1) | (0x83, 0x40..=0x96))
+                || matches!(lead, 0x88..=0x98)
+        },
+    },
+    Dbcs {
+        // GBK is decoded as its superset GB18030.
+        encoding: "GB18030",
+        is_lead: |b| matches!(b, 0x81..=0xfe),
+        is_trail: |b| matches!(b, 0x40..=0x7e | 0x80..=0xfe),
+        is_single: |_| false,
+        // Punctuation and the level 1 hanzi of GB2312
+        is_common: |lead, trail| matches!((lead, trail), (0xa1..=0xa3 | 0xb0..=0xd7, 0xa1..=0xfe)),
+    },
+    Dbcs {
+        encoding: "Big5",
+        is_lead: |b| matches!(b, 0xa1..=0xf9),
+        is_trail: |b| matches!(b, 0x40..=0x7e | 0xa1..=0xfe),
+        is_single: |_| false,
+        // Punctuation and the frequently used hanzi
+        is_common: |lead, _| matches!(lead, 0xa1 | 0xa4..=0xc6),
+    },
+];
+
+impl Dbcs {
+    fn score(&self, text: &[u8], truncated: bool) -> u8 {
+        let mut chars = 0u32;
+        let mut common = 0u32;
+        let mut i = 0;
+
+        while i < text.len() {
+            let lead = text[i];
+            i += 1;
+
+            if lead < 0x80 {
+                continue;
+            }
+
+            chars += 1;
+            if (self.is_single)(lead) {
+                continue;
+            }
+            if !(self.is_lead)(lead) {
+                return 0;
+            }
+
+            match text.get(i) {
+                Some(&trail) if (self.is_trail)(trail) => {
+                    common += (self.is_common)(lead, trail) as u32;
+                    i += 1;
+                }
+                None if truncated => chars -= 1,
+                _ => return 0,
+            }
+        }
+
+        if chars == 0 {
+            return 0;
+        }
+
+        // The share of common characters, scaled down if there are too few to tell.
+        let share = common * 100 / chars;
+        let evidence = (chars * 5 + 25).min(100);
+        (share * evidence / 100).min(95) as u8
+    }
+}
+
+fn score_windows_1252(text: &[u8]) -> u8 {
+    let mut high = 0u32;
+    let mut isolated = 0u32;
+
+    for (i, &b) in text.iter().enumerate() {
+        match b {
+            0x00..=0x7f => {}
+            // Unassigned in Windows-1252
+            0x81 | 0x8d | 0x8f | 0x90 | 0x9d => return 0,
+            _ => {
+                high += 1;
+
+                // Accented letters, quotes and dashes usually sit next to ASCII,
+                // whereas the bytes of multi-byte encodings come in runs.
+                let prev_ascii = i == 0 || text[i - 1] < 0x80;
+                let next_ascii = text.get(i + 1).is_none_or(|&n| n < 0x80);
+                isolated += (prev_ascii || next_ascii) as u32;
+            }
+        }
+    }
+
+    if high == 0 {
+        return 0;
+    }
+
+    // Capped below what a convincing double-byte text scores,
+    // because any byte soup without the 5 unassigned bytes is valid Windows-1252.
+    (isolated * 60 / high) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHIFT_JIS: &[u8] = b"\x93\xfa\x96{\x8c\xea\x82\xcc\x83e\x83L\x83X\x83g\x82\xc5\x82\xb7\x81B\x82\xd0\x82\xe7\x82\xaa\x82\xc8\x82\xc6\x83J\x83^\x83J\x83i\x82\xf0\x8a\xdc\x82\xdd\x82\xdc\x82\xb7\x81B";
+    const GBK: &[u8] = b"\xd5\xe2\xca\xc7\xd2\xbb\xb8\xf6\xbc\xf2\xcc\xe5\xd6\xd0\xce\xc4\xb5\xc4\xce\xc4\xb1\xbe\xca\xbe\xc0\xfd\xa3\xac\xd3\xc3\xd3\xda\xbc\xec\xb2\xe2\xb1\xe0\xc2\xeb\xa1\xa3";
+    const BIG5: &[u8] = b"\xb3o\xacO\xa4@\xad\xd3\xc1c\xc5\xe9\xa4\xa4\xa4\xe5\xaa\xba\xa4\xe5\xa6r\xbdd\xa8\xd2\xa1A\xa5\xce\xa9\xf3\xb0\xbb\xb4\xfa\xbds\xbdX\xa1C";
+    const WINDOWS_1252: &[u8] =
+        b"Die Gr\xf6\xdfe des Caf\xe9s ist \xfcberm\xe4\xdfig \x96 \x84sch\xf6n\x93.";
+
+    #[test]
+    fn test_utf8() {
+        let expected = Some(Detection { encoding: "UTF-8", confidence: 100 });
+        assert_eq!(detect(b"hello", false), expected);
+        assert_eq!(detect("gr\u{f6}\u{df}er".as_bytes(), false), expected);
+
+        // A character cut in half is fine if the file continues...
+        assert_eq!(detect(b"caf\xc3", true), expected);
+        // ...but not if it ends there.
+        assert_ne!(detect(b"caf\xc3", false), expected);
+    }
+
+    #[test]
+    fn test_binary() {
+        assert_eq!(detect(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR", false), None);
+        assert!(is_binary(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"));
+        assert!(!is_binary(b"hello"));
+    }
+
+    #[test]
+    fn test_guess() {
+        for (text, encoding) in [
+            (SHIFT_JIS, "Shift_JIS"),
+            (GBK, "GB18030"),
+            (BIG5, "Big5"),
+            (WINDOWS_1252, "windows-1252"),
+        ] {
+            let d = guess(text, false).unwrap();
+            assert_eq!(d.encoding, encoding);
+            assert!((MIN_CONFIDENCE..100).contains(&d.confidence), "{d:?}");
+        }
+    }
+
+    #[test]
+    fn test_guess_truncated() {
+        // Cut in the middle of the last character.
+        let text = &SHIFT_JIS[..SHIFT_JIS.len() - 1];
+        assert_eq!(guess(text, true).unwrap().encoding, "Shift_JIS");
+        assert_eq!(DBCS[0].score(text, false), 0);
+    }
+}
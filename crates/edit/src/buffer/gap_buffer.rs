@@ -369,3 +369,98 @@ impl ReadableDocument for GapBuffer {
         unsafe { slice::from_raw_parts(self.text.add(beg).as_ptr(), len) }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[derive(Clone, Debug)]
+    enum Op {
+        Replace(Range<usize>, Vec<u8>),
+        Clear,
+        CopyFrom(Vec<u8>),
+    }
+
+    fn arb_bytes(max_len: usize) -> impl Strategy<Value = Vec<u8>> {
+        prop::collection::vec(prop_oneof![Just(b'a'), Just(b'\n'), any::<u8>()], 0..max_len)
+    }
+
+    fn arb_op() -> impl Strategy<Value = Op> {
+        prop_oneof![
+            8 => (0..400usize, 0..400usize, arb_bytes(64)).prop_map(|(beg, end, src)| Op::Replace(beg..end, src)),
+            1 => Just(Op::Clear),
+            1 => arb_bytes(256).prop_map(Op::CopyFrom),
+        ]
+    }
+
+    /// Applies `op` to `oracle` the same way `GapBuffer` is documented to.
+    fn apply(oracle: &mut Vec<u8>, op: &Op) {
+        match op {
+            Op::Replace(range, src) => {
+                let beg = range.start.min(oracle.len());
+                let end = beg + range.end.saturating_sub(range.start).min(oracle.len() - beg);
+                oracle.splice(beg..end, src.iter().copied());
+            }
+            Op::Clear => oracle.clear(),
+            Op::CopyFrom(src) => oracle.clone_from(src),
+        }
+    }
+
+    fn check(buf: &GapBuffer, oracle: &[u8]) -> Result<(), TestCaseError> {
+        prop_assert_eq!(buf.len(), oracle.len());
+
+        let mut extracted = Vec::new();
+        buf.extract_raw(0..usize::MAX, &mut extracted, 0);
+        prop_assert_eq!(&extracted[..], oracle);
+
+        // Reading forward/backward chunk by chunk must yield the entire text.
+        let mut forward = Vec::new();
+        while forward.len() < oracle.len() {
+            let chunk = buf.read_forward(forward.len());
+            prop_assert!(!chunk.is_empty());
+            forward.extend_from_slice(chunk);
+        }
+        prop_assert_eq!(&forward[..], oracle);
+
+        let mut backward = Vec::new();
+        let mut off = oracle.len();
+        while off > 0 {
+            let chunk = buf.read_backward(off);
+            prop_assert!(!chunk.is_empty());
+            backward.splice(0..0, chunk.iter().copied());
+            off -= chunk.len();
+        }
+        prop_assert_eq!(&backward[..], oracle);
+
+        // ...and so must reading from any offset in between.
+        for off in 0..=oracle.len() {
+            prop_assert_eq!(buf.read_forward(off).first(), oracle.get(off));
+            prop_assert_eq!(buf.read_backward(off).last(), oracle[..off].last());
+        }
+
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn prop_edits(small in any::<bool>(), ops in prop::collection::vec(arb_op(), 1..32)) {
+            let mut buf = GapBuffer::new(small).unwrap();
+            let mut oracle = Vec::new();
+
+            for op in &ops {
+                match op {
+                    Op::Replace(range, src) => buf.replace(range.clone(), src),
+                    Op::Clear => buf.clear(),
+                    Op::CopyFrom(src) => {
+                        let changed = buf.copy_from(&&src[..]);
+                        prop_assert_eq!(changed, oracle != *src);
+                    }
+                }
+                apply(&mut oracle, op);
+                check(&buf, &oracle)?;
+            }
+        }
+    }
+}
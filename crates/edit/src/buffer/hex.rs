@@ -0,0 +1,190 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Layout and search helpers for the hex view of a [`super::TextBuffer`].
+//!
+//! A row looks like this, with the offset in the margin:
+//! ```text
+//! 00000010 │ 48 65 6C 6C 6F 2C 20 77  6F 72 6C 64 21 0A 00 FF │ Hello, world!...
+//! ```
+
+use std::fmt::Write as _;
+
+use stdext::arena::ArenaString;
+
+use crate::document::ReadableDocument;
+use crate::helpers::CoordType;
+
+/// Number of bytes shown per row.
+pub const BYTES_PER_ROW: usize = 16;
+/// The column at which the ASCII representation starts.
+pub const ASCII_COLUMN: CoordType = BYTES_PER_ROW as CoordType * 3 + 3;
+/// The width of a row, excluding the margin.
+pub const ROW_WIDTH: CoordType = ASCII_COLUMN + BYTES_PER_ROW as CoordType;
+
+/// Returns the width of the margin that holds the offsets, including the trailing " │ ".
+pub fn margin_width(len: usize) -> CoordType {
+    let digits = (usize::BITS - len.leading_zeros()).div_ceil(4);
+    digits.max(8) as CoordType + 3
+}
+
+/// Returns the column of the high nibble of the `i`-th byte in a row.
+/// There's an extra space between the two halves of a row.
+pub fn byte_column(i: usize) -> CoordType {
+    (i * 3 + i / (BYTES_PER_ROW / 2)) as CoordType
+}
+
+/// The inverse of [`byte_column`]: Returns the index of the byte at column `x`
+/// and whether `x` is on its low nibble. The ASCII column maps to its bytes as well.
+pub fn byte_at_column(x: CoordType) -> (usize, bool) {
+    if x >= ASCII_COLUMN {
+        return (((x - ASCII_COLUMN) as usize).min(BYTES_PER_ROW - 1), false);
+    }
+    for i in (0..BYTES_PER_ROW).rev() {
+        let column = byte_column(i);
+        if x >= column {
+            return (i, x > column);
+        }
+    }
+    (0, false)
+}
+
+/// Appends a row of up to [`BYTES_PER_ROW`] `bytes` to `out`, without the margin.
+pub fn format_row(out: &mut ArenaString, bytes: &[u8]) {
+    for i in 0..BYTES_PER_ROW {
+        if i == BYTES_PER_ROW / 2 {
+            out.push(' ');
+        }
+        match bytes.get(i) {
+            Some(b) => _ = write!(out, "{b:02X} "),
+            None => out.push_str("   "),
+        }
+    }
+
+    out.push_str("│ ");
+    for &b in bytes {
+        out.push(if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' });
+    }
+}
+
+/// Parses a byte pattern like "DE AD be ef" or "deadbeef".
+/// Returns `None` if it's empty or contains anything but pairs of hex digits.
+pub fn parse_pattern(text: &str) -> Option<Vec<u8>> {
+    let digits: Vec<u8> = text.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    if digits.is_empty() || !digits.len().is_multiple_of(2) {
+        return None;
+    }
+    digits
+        .chunks_exact(2)
+        .map(|pair| {
+            let hi = (pair[0] as char).to_digit(16)?;
+            let lo = (pair[1] as char).to_digit(16)?;
+            Some((hi << 4 | lo) as u8)
+        })
+        .collect()
+}
+
+/// Returns the offset of the first occurrence of `pattern` in `doc` at or after `offset`.
+pub fn find(doc: &dyn ReadableDocument, pattern: &[u8], mut offset: usize) -> Option<usize> {
+    if pattern.is_empty() {
+        return None;
+    }
+
+    // The bytes right before `offset`, to find matches spanning two chunks.
+    let mut tail = Vec::new();
+
+    loop {
+        let chunk = doc.read_forward(offset);
+        if chunk.is_empty() {
+            return None;
+        }
+
+        if !tail.is_empty() {
+            let mut joined = tail.clone();
+            joined.extend_from_slice(&chunk[..chunk.len().min(pattern.len() - 1)]);
+            if let Some(i) = find_in(&joined, pattern) {
+                return Some(offset - tail.len() + i);
+            }
+        }
+
+        if let Some(i) = find_in(chunk, pattern) {
+            return Some(offset + i);
+        }
+
+        let keep = pattern.len() - 1;
+        tail.extend_from_slice(&chunk[chunk.len().saturating_sub(keep)..]);
+        tail.drain(..tail.len().saturating_sub(keep));
+        offset += chunk.len();
+    }
+}
+
+fn find_in(haystack: &[u8], pattern: &[u8]) -> Option<usize> {
+    haystack.windows(pattern.len()).position(|w| w == pattern)
+}
+
+#[cfg(test)]
+mod tests {
+    use stdext::arena::scratch_arena;
+
+    use super::*;
+
+    /// A document that returns its contents in chunks of 3 bytes.
+    struct Chunked(&'static [u8]);
+
+    impl ReadableDocument for Chunked {
+        fn read_forward(&self, off: usize) -> &[u8] {
+            let off = off.min(self.0.len());
+            &self.0[off..(off + 3).min(self.0.len())]
+        }
+
+        fn read_backward(&self, off: usize) -> &[u8] {
+            let off = off.min(self.0.len());
+            &self.0[off.saturating_sub(3)..off]
+        }
+    }
+
+    #[test]
+    fn test_layout() {
+        assert_eq!(margin_width(0), 11);
+        assert_eq!(margin_width(0xFFFF_FFFF), 11);
+        assert_eq!(margin_width(0x1_0000_0000), 12);
+
+        let scratch = scratch_arena(None);
+        let mut row = ArenaString::new_in(&scratch);
+        format_row(&mut row, b"Hello, world!\n\0\xFF");
+        assert_eq!(&*row, "48 65 6C 6C 6F 2C 20 77  6F 72 6C 64 21 0A 00 FF │ Hello, world!...");
+        assert_eq!(row.chars().count(), ROW_WIDTH as usize);
+
+        row.clear();
+        format_row(&mut row, b"ab");
+        assert_eq!(row.chars().count(), ASCII_COLUMN as usize + 2);
+
+        for i in 0..BYTES_PER_ROW {
+            let column = byte_column(i);
+            assert_eq!(row.as_bytes()[column as usize + 2], b' ');
+            assert_eq!(byte_at_column(column), (i, false));
+            assert_eq!(byte_at_column(column + 1), (i, true));
+            assert_eq!(byte_at_column(ASCII_COLUMN + i as CoordType), (i, false));
+        }
+    }
+
+    #[test]
+    fn test_parse_pattern() {
+        assert_eq!(parse_pattern("DE AD be ef"), Some(vec![0xDE, 0xAD, 0xBE, 0xEF]));
+        assert_eq!(parse_pattern("00ff"), Some(vec![0x00, 0xFF]));
+        assert_eq!(parse_pattern(""), None);
+        assert_eq!(parse_pattern("ABC"), None);
+        assert_eq!(parse_pattern("XY"), None);
+    }
+
+    #[test]
+    fn test_find() {
+        let doc = Chunked(b"abcdefabcdef");
+        assert_eq!(find(&doc, b"cd", 0), Some(2));
+        assert_eq!(find(&doc, b"cd", 3), Some(8));
+        assert_eq!(find(&doc, b"bcdefa", 0), Some(1));
+        assert_eq!(find(&doc, b"fab", 0), Some(5));
+        assert_eq!(find(&doc, b"cd", 9), None);
+        assert_eq!(find(&doc, b"x", 0), None);
+    }
+}
@@ -0,0 +1,190 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Keeps the [`ContentIssues`] of a [`GapBuffer`] up to date across edits.
+//!
+//! Scanning the entire document on every keystroke would be way too slow for large files.
+//! Instead, the counts are computed once and then adjusted by recounting a small
+//! window around every edit: Once before the change and once after it.
+//!
+//! The window must start and end at positions where UTF-8 decoding is "in sync", that is,
+//! where no (invalid) sequence can straddle the boundary. Those are the positions of bytes that
+//! aren't continuation bytes, and positions preceded by 3 continuation bytes, since no sequence
+//! is longer than 4 bytes. Additionally, a LF directly after the window belongs to it,
+//! because whether it's part of a CRLF depends on the byte in front of it.
+
+use std::ops::Range;
+use std::str;
+
+use super::ContentIssues;
+use super::gap_buffer::GapBuffer;
+use crate::document::ReadableDocument as _;
+use crate::helpers::*;
+use crate::simd::memchr2;
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+struct Counts {
+    lf: usize,
+    crlf: usize,
+    invalid: usize,
+}
+
+/// A window around an edit that needs to be recounted, returned by [`IssueTracker::unlink`].
+/// The end is stored as the distance to the end of the buffer, as that's unaffected by the edit.
+pub struct IssueWindow {
+    beg: usize,
+    distance_to_end: usize,
+}
+
+#[derive(Default)]
+pub struct IssueTracker {
+    /// `None` if the counts are unknown and the buffer must be scanned.
+    counts: Option<Counts>,
+}
+
+impl IssueTracker {
+    /// Forgets the counts, e.g. because the entire buffer was replaced.
+    pub fn invalidate(&mut self) {
+        self.counts = None;
+    }
+
+    /// Returns the issues in `buffer`, scanning it if the counts are unknown.
+    pub fn get(&mut self, buffer: &GapBuffer, crlf: bool) -> ContentIssues {
+        let counts = *self.counts.get_or_insert_with(|| count(buffer, 0..buffer.len()));
+        ContentIssues {
+            foreign_newlines: if crlf { counts.lf } else { counts.crlf },
+            invalid_sequences: counts.invalid,
+        }
+    }
+
+    /// Call this before replacing `range` in `buffer`.
+    /// Removes the issues around `range` from the counts.
+    pub fn unlink(&mut self, buffer: &GapBuffer, range: Range<usize>) -> IssueWindow {
+        // Same as the gap buffer, we clamp the range to the text.
+        let len = buffer.len();
+        let end = range.end.min(len);
+        let beg = window_beg(buffer, range.start.min(end));
+        let end = window_end(buffer, end);
+
+        if let Some(counts) = &mut self.counts {
+            let c = count(buffer, beg..end);
+            counts.lf -= c.lf;
+            counts.crlf -= c.crlf;
+            counts.invalid -= c.invalid;
+        }
+
+        IssueWindow { beg, distance_to_end: len - end }
+    }
+
+    /// Call this after the edit with the value returned by [`IssueTracker::unlink`].
+    /// Adds the issues within the changed window back to the counts.
+    pub fn relink(&mut self, buffer: &GapBuffer, window: IssueWindow) {
+        if let Some(counts) = &mut self.counts {
+            let c = count(buffer, window.beg..buffer.len() - window.distance_to_end);
+            counts.lf += c.lf;
+            counts.crlf += c.crlf;
+            counts.invalid += c.invalid;
+        }
+    }
+}
+
+fn is_continuation(b: u8) -> bool {
+    (b & 0xC0) == 0x80
+}
+
+fn byte_at(buffer: &GapBuffer, off: usize) -> u8 {
+    buffer.read_forward(off)[0]
+}
+
+fn is_sync_point(text: &[u8], i: usize) -> bool {
+    !is_continuation(text[i]) || (i >= 3 && text[i - 3..i].iter().all(|&b| is_continuation(b)))
+}
+
+/// Returns the closest synchronization point at or before `off`,
+/// without looking at the byte at `off`, as it's about to change.
+fn window_beg(buffer: &GapBuffer, off: usize) -> usize {
+    let mut beg = off;
+    let mut continuations = 0;
+
+    while beg > 0 && continuations < 3 {
+        let b = byte_at(buffer, beg - 1);
+        if !is_continuation(b) {
+            // The byte at `beg - 1` starts a sequence (or is ASCII).
+            return beg - 1;
+        }
+        continuations += 1;
+        beg -= 1;
+    }
+
+    // Either the start of the buffer, or 3 continuation bytes precede `beg + 3`.
+    if beg == 0 { 0 } else { beg + 3 }
+}
+
+/// Returns the closest synchronization point at or after `off`,
+/// without looking at the bytes before `off`, as they're about to change.
+fn window_end(buffer: &GapBuffer, off: usize) -> usize {
+    let len = buffer.len();
+    let mut end = off;
+
+    while end < len && end - off < 3 {
+        let b = byte_at(buffer, end);
+        if !is_continuation(b) {
+            // A LF may turn into a CRLF and vice versa, depending on the preceding byte.
+            return if b == b'\n' { end + 1 } else { end };
+        }
+        end += 1;
+    }
+
+    end
+}
+
+/// Counts the issues within `range`, which must start and end at synchronization points.
+fn count(buffer: &GapBuffer, range: Range<usize>) -> Counts {
+    let mut counts = Counts::default();
+    let mut prev = if range.start > 0 { byte_at(buffer, range.start - 1) } else { 0 };
+    let mut buf = Vec::new();
+    let mut off = range.start;
+
+    while off < range.end {
+        buf.clear();
+        buffer.extract_raw(off..range.end.min(off + 64 * KIBI), &mut buf, 0);
+
+        // Unless this is the last piece, cut it off at the last synchronization point.
+        let mut len = buf.len();
+        if off + len < range.end {
+            len = (1..len).rev().find(|&i| is_sync_point(&buf, i)).unwrap_or(len);
+        }
+
+        let text = &buf[..len];
+        count_slice(text, prev, &mut counts);
+        prev = text[len - 1];
+        off += len;
+    }
+
+    counts
+}
+
+fn count_slice(text: &[u8], prev: u8, counts: &mut Counts) {
+    let mut off = 0;
+    loop {
+        let i = memchr2(b'\n', b'\n', text, off);
+        if i >= text.len() {
+            break;
+        }
+        let cr = if i > 0 { text[i - 1] } else { prev } == b'\r';
+        if cr {
+            counts.crlf += 1;
+        } else {
+            counts.lf += 1;
+        }
+        off = i + 1;
+    }
+
+    let mut rest = text;
+    while let Err(err) = str::from_utf8(rest) {
+        counts.invalid += 1;
+        // The text ends at a synchronization point, so an incomplete sequence at the end is invalid.
+        let len = err.error_len().unwrap_or(rest.len() - err.valid_up_to());
+        rest = &rest[err.valid_up_to() + len..];
+    }
+}
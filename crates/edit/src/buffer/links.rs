@@ -0,0 +1,198 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Detection of URLs and `path:line:column` references in text.
+
+use std::ops::Range;
+
+use crate::helpers::CoordType;
+
+/// The URL schemes that are detected as links.
+/// They're also the only ones [`crate::sys::open_url`] passes on to the system,
+/// because `xdg-open` and `ShellExecuteW` run just about anything they're given.
+pub const SCHEMES: [&str; 5] = ["https://", "http://", "file://", "ftp://", "mailto:"];
+
+/// Returns true if the URL starts with one of the [`SCHEMES`] (case-insensitive).
+pub fn has_known_scheme(url: &str) -> bool {
+    SCHEMES.iter().any(|s| url.get(..s.len()).is_some_and(|p| p.eq_ignore_ascii_case(s)))
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LinkKind {
+    /// A URL with one of the well-known schemes.
+    Url,
+    /// A file path followed by `:line` and an optional `:column`.
+    /// Both are 1-based. `column` is 0 if it's missing.
+    Path { line: CoordType, column: CoordType },
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Link {
+    /// The byte range of the entire link.
+    pub range: Range<usize>,
+    /// The byte range of the URL or file path.
+    /// For paths this excludes the `:line:column` suffix.
+    pub target: Range<usize>,
+    pub kind: LinkKind,
+}
+
+/// Finds all links in the given text, in order.
+pub fn find_links(text: &str) -> Vec<Link> {
+    let is_separator = |c: char| c.is_whitespace() || is_delimiter(c);
+    let mut links = Vec::new();
+    let mut off = 0;
+
+    while let Some(beg) = text[off..].find(|c| !is_separator(c)).map(|i| off + i) {
+        let end = text[beg..].find(is_separator).map_or(text.len(), |i| beg + i);
+        let token = &text[beg..end];
+
+        if let Some(link) = find_url(token).or_else(|| find_path(token)) {
+            links.push(Link {
+                range: beg + link.range.start..beg + link.range.end,
+                target: beg + link.target.start..beg + link.target.end,
+                kind: link.kind,
+            });
+        }
+
+        off = end;
+    }
+
+    links
+}
+
+/// Characters that can't be part of a link, because they're commonly used to quote them.
+fn is_delimiter(c: char) -> bool {
+    matches!(c, '"' | '\'' | '`' | '<' | '>')
+}
+
+fn find_url(token: &str) -> Option<Link> {
+    let beg = SCHEMES.iter().filter_map(|s| token.find(s)).min()?;
+
+    // The scheme must not be glued to a preceding word ("xhttp://").
+    if token[..beg].ends_with(|c: char| c.is_alphanumeric()) {
+        return None;
+    }
+
+    let url = &token[beg..];
+    let scheme_len = SCHEMES.iter().find(|s| url.starts_with(**s))?.len();
+
+    // Trailing punctuation is most likely part of the surrounding prose,
+    // and so are closing parentheses that aren't balanced within the URL.
+    let mut end = url.len();
+    while let Some(last) = url[..end].chars().next_back() {
+        let unbalanced = |open, close| {
+            last == close && url[..end].matches(open).count() < url[..end].matches(close).count()
+        };
+        if matches!(last, '.' | ',' | ';' | ':' | '!' | '?')
+            || unbalanced('(', ')')
+            || unbalanced('[', ']')
+        {
+            end -= last.len_utf8();
+        } else {
+            break;
+        }
+    }
+
+    if end <= scheme_len || url[..end].chars().any(|c| c.is_control()) {
+        return None;
+    }
+
+    let range = beg..beg + end;
+    Some(Link { range: range.clone(), target: range, kind: LinkKind::Url })
+}
+
+fn find_path(token: &str) -> Option<Link> {
+    // Strip surrounding punctuation like in "(see foo.rs:12)".
+    let beg = token.len() - token.trim_start_matches(['(', '[', '{']).len();
+    let mut s = token[beg..].trim_end_matches([')', ']', '}', ',', ';', '.']);
+    // Compilers like to print "foo.rs:12:5: error".
+    s = s.strip_suffix(':').unwrap_or(s);
+
+    fn split_number(s: &str) -> Option<(&str, CoordType)> {
+        let colon = s.rfind(':')?;
+        let digits = &s[colon + 1..];
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        Some((&s[..colon], digits.parse().ok()?))
+    }
+
+    let (rest, last) = split_number(s)?;
+    let (path, line, column) = match split_number(rest) {
+        Some((path, line)) => (path, line, last),
+        None => (rest, last, 0),
+    };
+
+    // Require something path-like, so that e.g. "localhost:8080" and "12:30" aren't matched.
+    let bytes = path.as_bytes();
+    let has_drive = bytes.len() > 2
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && matches!(bytes[2], b'/' | b'\\');
+    let body = if has_drive { &path[2..] } else { path };
+    if line < 1
+        || body.is_empty()
+        || !body.contains(['.', '/', '\\'])
+        || body.contains([':', '*', '?', '|', '(', ')', '[', ']', '{', '}'])
+        || body.chars().any(|c| c.is_control())
+    {
+        return None;
+    }
+
+    Some(Link {
+        range: beg..beg + s.len(),
+        target: beg..beg + path.len(),
+        kind: LinkKind::Path { line, column },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn links(text: &str) -> Vec<(&str, &str, LinkKind)> {
+        find_links(text).into_iter().map(|l| (&text[l.range], &text[l.target], l.kind)).collect()
+    }
+
+    #[test]
+    fn test_urls() {
+        assert_eq!(
+            links("see https://example.com/a_(b)?c=d#e, or (http://x.org/y)."),
+            [
+                (
+                    "https://example.com/a_(b)?c=d#e",
+                    "https://example.com/a_(b)?c=d#e",
+                    LinkKind::Url
+                ),
+                ("http://x.org/y", "http://x.org/y", LinkKind::Url),
+            ]
+        );
+        assert_eq!(links("<mailto:a@b.c>"), [("mailto:a@b.c", "mailto:a@b.c", LinkKind::Url)]);
+        assert_eq!(
+            links("xhttp://nope https:// \"file:///tmp/a\""),
+            [("file:///tmp/a", "file:///tmp/a", LinkKind::Url)]
+        );
+    }
+
+    #[test]
+    fn test_paths() {
+        assert_eq!(
+            links("src/main.rs:12:5: error in (lib.rs:3) – C:\\a\\b.c:7"),
+            [
+                ("src/main.rs:12:5", "src/main.rs", LinkKind::Path { line: 12, column: 5 }),
+                ("lib.rs:3", "lib.rs", LinkKind::Path { line: 3, column: 0 }),
+                ("C:\\a\\b.c:7", "C:\\a\\b.c", LinkKind::Path { line: 7, column: 0 }),
+            ]
+        );
+        assert!(links("localhost:8080 12:30 foo.rs:0 a.rs:x").is_empty());
+    }
+
+    #[test]
+    fn test_known_schemes() {
+        assert!(has_known_scheme("HTTPS://example.com"));
+        assert!(has_known_scheme("mailto:a@b.c"));
+        assert!(!has_known_scheme("javascript:alert(1)"));
+        assert!(!has_known_scheme("C:\\Windows\\System32\\calc.exe"));
+        assert!(!has_known_scheme("http:"));
+    }
+}
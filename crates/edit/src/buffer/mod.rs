@@ -21,35 +21,42 @@
 //! There's no solution for the latter. However, there's a chance that the performance will still be sufficient.
 
 mod gap_buffer;
+mod issues;
 mod navigation;
 
 /// Auto-completion functionality for the text editor
 pub mod autocomplete;
+pub mod charset;
+pub mod hex;
+pub mod links;
 
 use std::borrow::Cow;
 use std::cell::UnsafeCell;
 use std::collections::LinkedList;
 use std::fmt::Write as _;
 use std::fs::File;
-use std::io::{Read as _, Write as _};
+use std::io::{self, Read as _};
 use std::mem::{self, MaybeUninit};
 use std::ops::Range;
+use std::path::Path;
 use std::rc::Rc;
 use std::str;
 
 pub use gap_buffer::GapBuffer;
 use stdext::arena::{Arena, ArenaString, scratch_arena};
 
+use crate::buffer::issues::IssueTracker;
+use crate::buffer::links::LinkKind;
 use crate::cell::SemiRefCell;
-use crate::buffer::autocomplete::AutoCompleter;
+use crate::buffer::autocomplete::{AutoCompleter, CompletionProvider as _, PathCompletionProvider};
 use crate::clipboard::Clipboard;
 use crate::document::{ReadableDocument, WriteableDocument};
-use crate::framebuffer::{Framebuffer, IndexedColor};
+use crate::framebuffer::{Attributes, Framebuffer, IndexedColor};
 use crate::helpers::*;
 use crate::oklab::StraightRgba;
 use crate::simd::memchr2;
 use crate::unicode::{self, Cursor, MeasurementConfig, Utf8Chars};
-use crate::{apperr, icu, simd};
+use crate::{apperr, encodings, icu, simd, spell};
 
 /// The margin template is used for line numbers.
 /// The max. line number we should ever expect is probably 64-bit,
@@ -63,6 +70,15 @@ const VISUAL_SPACE_PREFIX_ADD: usize = '･'.len_utf8() - 1;
 const VISUAL_TAB: &str = "￫       ";
 const VISUAL_TAB_PREFIX_ADD: usize = '￫'.len_utf8() - 1;
 
+/// The maximum number of bytes scanned for highlights during rendering.
+const HIGHLIGHT_SCAN_MAX: usize = 1024 * 1024;
+/// How far before the viewport to look for search hits that span multiple lines.
+const HIGHLIGHT_LOOKBEHIND: usize = 1024;
+/// The maximum length of the selected word whose occurrences are highlighted.
+const HIGHLIGHT_WORD_MAX: CoordType = 256;
+/// The maximum number of entries in the undo stack. The oldest ones are dropped first.
+const UNDO_LIMIT: usize = 1000;
+
 /// Stores statistics about the whole document.
 #[derive(Copy, Clone)]
 pub struct TextBufferStatistics {
@@ -109,6 +125,12 @@ struct HistoryEntry {
     deleted: Vec<u8>,
     /// Text that was added to the buffer.
     added: Vec<u8>,
+    /// Byte offset of the change relative to `cursor`. Edits in the hex view can take
+    /// place in the middle of a grapheme cluster, which a logical position can't express.
+    cursor_delta: usize,
+    /// If true, `deleted` and `added` are reinserted as-is on undo/redo.
+    /// Otherwise, their newlines are converted to [`TextBuffer::is_crlf()`].
+    verbatim: bool,
 }
 
 /// Caches an ICU search operation.
@@ -133,6 +155,15 @@ struct ActiveSearch {
     next_search_offset: usize,
     /// If we know there were no hits, we can skip searching.
     no_matches: bool,
+    /// Start offsets of all matches, for showing "3 of 41" in the UI.
+    match_starts: Vec<usize>,
+    /// [`GapBuffer::generation`] when `match_starts` was computed.
+    match_starts_generation: Option<u32>,
+    /// The visual line of each match, for the scrollbar tick marks.
+    match_lines: Vec<CoordType>,
+    /// [`GapBuffer::generation`] and [`TextBuffer::word_wrap_column`]
+    /// when `match_lines` was computed.
+    match_lines_key: Option<(u32, CoordType)>,
 }
 
 /// Options for a search operation.
@@ -144,6 +175,11 @@ pub struct SearchOptions {
     pub whole_word: bool,
     /// If true, the search uses regex.
     pub use_regex: bool,
+    /// If true, replacements adopt the casing of the text they replace.
+    pub preserve_case: bool,
+    /// If true, the search is limited to the search scope.
+    /// See [`TextBuffer::set_search_scope()`].
+    pub in_selection: bool,
 }
 
 enum RegexReplacement<'a> {
@@ -151,6 +187,25 @@ enum RegexReplacement<'a> {
     Text(Vec<u8, &'a Arena>),
 }
 
+/// Moves the search scope along with an edit that replaced `range` with `len` bytes.
+/// Text inserted at the edges of the scope isn't part of it, while replacements overlapping it are.
+fn shift_search_scope(scope: &mut Option<Range<usize>>, range: Range<usize>, len: usize) {
+    let Some(scope) = scope else {
+        return;
+    };
+    let shift = |off: usize| off - range.len() + len;
+    let start =
+        if scope.start >= range.end { shift(scope.start) } else { scope.start.min(range.start) };
+    let end = if scope.end <= range.start {
+        scope.end
+    } else if scope.end >= range.end {
+        shift(scope.end)
+    } else {
+        range.start + len
+    };
+    *scope = start..end.max(start);
+}
+
 /// Caches the start and length of the active edit line for a single edit.
 /// This helps us avoid having to remeasure the buffer after an edit.
 struct ActiveEditLineInfo {
@@ -182,6 +237,26 @@ struct ActiveEditGroupInfo {
     generation_before: u32,
 }
 
+/// The state of the hex view. See [`TextBuffer::set_hex_mode()`].
+#[derive(Default, Clone)]
+struct HexView {
+    /// Offset of the byte under the cursor. Can be equal to the text length,
+    /// in which case the cursor is on the slot for appending a byte.
+    offset: usize,
+    /// Whether the cursor is on the low nibble of the byte.
+    low_nibble: bool,
+    /// The last search hit.
+    selection: Option<Range<usize>>,
+}
+
+/// A grapheme cluster of a row of bidirectional text. See [`TextBuffer::bidi_layout()`].
+struct BidiCell {
+    /// The columns the cluster occupies when laid out in logical order.
+    columns: Range<CoordType>,
+    /// Whether to display the mirrored glyph, e.g. `)` for `(` in right-to-left text.
+    mirror: bool,
+}
+
 /// Char- or word-wise navigation? Your choice.
 pub enum CursorMovement {
     Grapheme,
@@ -194,12 +269,31 @@ pub enum MoveLineDirection {
     Down,
 }
 
+/// A link found by [`TextBuffer::link_at_visual()`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TextLink {
+    /// The byte range of the entire link in the buffer.
+    pub range: Range<usize>,
+    /// The URL or file path, without any `:line:column` suffix.
+    pub target: String,
+    pub kind: LinkKind,
+}
+
 /// The result of a call to [`TextBuffer::render()`].
 pub struct RenderResult {
     /// The maximum visual X position we encountered during rendering.
     pub visual_pos_x_max: CoordType,
 }
 
+/// The result of a call to [`TextBuffer::content_issues()`].
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ContentIssues {
+    /// Number of newlines that differ from [`TextBuffer::is_crlf()`].
+    pub foreign_newlines: usize,
+    /// Number of invalid UTF-8 sequences.
+    pub invalid_sequences: usize,
+}
+
 /// A [`TextBuffer`] with inner mutability.
 pub type TextBufferCell = SemiRefCell<TextBuffer>;
 
@@ -215,6 +309,7 @@ pub struct TextBuffer {
 
     undo_stack: LinkedList<SemiRefCell<HistoryEntry>>,
     redo_stack: LinkedList<SemiRefCell<HistoryEntry>>,
+    undo_limit: usize,
     last_history_type: HistoryType,
     last_save_generation: u32,
 
@@ -222,6 +317,10 @@ pub struct TextBuffer {
     active_edit_line_info: Option<ActiveEditLineInfo>,
     active_edit_depth: i32,
     active_edit_off: usize,
+    /// The offset at which the active edit started and how many bytes it deleted.
+    /// Together with `active_edit_off` this describes the edit as a single replacement.
+    active_edit_beg: usize,
+    active_edit_deleted: usize,
 
     stats: TextBufferStatistics,
     cursor: Cursor,
@@ -230,9 +329,13 @@ pub struct TextBuffer {
     // To avoid this, we cache the cursor position for rendering.
     // Must be cleared on every edit or reflow.
     cursor_for_rendering: Option<Cursor>,
+    // Bidirectional text is reordered per displayed row. Mapping mouse
+    // positions back requires knowing the columns the last render pass showed.
+    rendered_columns: Range<CoordType>,
     selection: Option<TextBufferSelection>,
     selection_generation: u32,
     search: Option<UnsafeCell<ActiveSearch>>,
+    search_scope: Option<Range<usize>>,
 
     width: CoordType,
     margin_width: CoordType,
@@ -244,9 +347,17 @@ pub struct TextBuffer {
     line_highlight_enabled: bool,
     ruler: CoordType,
     encoding: &'static str,
+    encoding_confidence: Option<u8>,
     newlines_are_crlf: bool,
+    content_issues: IssueTracker,
     insert_final_newline: bool,
     overtype: bool,
+    spell_check: bool,
+    link_hover: Option<Range<usize>>,
+    /// The links of the visual lines rendered since [`GapBuffer::generation`] was `link_cache_generation`.
+    link_cache: Vec<(Range<usize>, Vec<TextLink>)>,
+    link_cache_generation: u32,
+    hex: Option<HexView>,
 
     wants_cursor_visibility: bool,
 
@@ -270,6 +381,7 @@ impl TextBuffer {
 
             undo_stack: LinkedList::new(),
             redo_stack: LinkedList::new(),
+            undo_limit: UNDO_LIMIT,
             last_history_type: HistoryType::Other,
             last_save_generation: 0,
 
@@ -277,13 +389,17 @@ impl TextBuffer {
             active_edit_line_info: None,
             active_edit_depth: 0,
             active_edit_off: 0,
+            active_edit_beg: 0,
+            active_edit_deleted: 0,
 
             stats: TextBufferStatistics { logical_lines: 1, visual_lines: 1 },
             cursor: Default::default(),
             cursor_for_rendering: None,
+            rendered_columns: 0..COORD_TYPE_SAFE_MAX,
             selection: None,
             selection_generation: 0,
             search: None,
+            search_scope: None,
 
             width: 0,
             margin_width: 0,
@@ -295,9 +411,16 @@ impl TextBuffer {
             line_highlight_enabled: false,
             ruler: 0,
             encoding: "UTF-8",
+            encoding_confidence: None,
             newlines_are_crlf: cfg!(windows), // Windows users want CRLF
+            content_issues: Default::default(),
             insert_final_newline: false,
             overtype: false,
+            spell_check: false,
+            link_hover: None,
+            link_cache: Vec::new(),
+            link_cache_generation: 0,
+            hex: None,
 
             wants_cursor_visibility: false,
             auto_completer: AutoCompleter::default(),
@@ -318,6 +441,9 @@ impl TextBuffer {
     /// Number of visual lines in the document,
     /// that is, the number of lines after layout.
     pub fn visual_line_count(&self) -> CoordType {
+        if self.hex.is_some() {
+            return (self.text_length() / hex::BYTES_PER_ROW) as CoordType + 1;
+        }
         self.stats.visual_lines
     }
 
@@ -347,8 +473,16 @@ impl TextBuffer {
         self.encoding
     }
 
+    /// How confident [`charset::detect`] was about the encoding, from 0 to 99.
+    /// `None` if the encoding is certain, or was explicitly chosen.
+    pub fn encoding_confidence(&self) -> Option<u8> {
+        self.encoding_confidence
+    }
+
     /// Set the encoding used during reading/writing.
+    /// This also confirms a guessed encoding.
     pub fn set_encoding(&mut self, encoding: &'static str) {
+        self.encoding_confidence = None;
         if self.encoding != encoding {
             self.encoding = encoding;
             self.mark_as_dirty();
@@ -432,6 +566,107 @@ impl TextBuffer {
         }
 
         self.newlines_are_crlf = crlf;
+        self.content_issues.invalidate();
+        self.search_scope = None;
+    }
+
+    /// Counts the newlines that don't match [`TextBuffer::is_crlf()`]
+    /// and the invalid UTF-8 sequences in the document.
+    ///
+    /// The buffer is only scanned once. Afterwards, the counts are updated on every edit.
+    pub fn content_issues(&mut self) -> ContentIssues {
+        self.content_issues.get(&self.buffer, self.newlines_are_crlf)
+    }
+
+    /// Changes all newlines to the type given by [`TextBuffer::is_crlf()`].
+    ///
+    /// Unlike [`TextBuffer::normalize_newlines()`] this can be undone in a single step.
+    pub fn fix_newlines(&mut self) {
+        let newline: &[u8] = if self.newlines_are_crlf { b"\r\n" } else { b"\n" };
+
+        self.edit_replace_per_chunk(|text, replacement| {
+            let mut range: Option<Range<usize>> = None;
+            let mut off = 0;
+
+            loop {
+                let (end, line) = simd::lines_fwd(text, off, 0, 1);
+                if line == 0 {
+                    break;
+                }
+
+                let beg = if text[..end].ends_with(b"\r\n") { end - 2 } else { end - 1 };
+                if &text[beg..end] != newline {
+                    let range = range.get_or_insert(beg..beg);
+                    replacement.extend_from_slice(&text[range.end..beg]);
+                    replacement.extend_from_slice(newline);
+                    range.end = end;
+                }
+
+                off = end;
+            }
+
+            range
+        });
+    }
+
+    /// Replaces all invalid UTF-8 sequences with U+FFFD.
+    /// This can be undone in a single step.
+    pub fn replace_invalid_utf8(&mut self) {
+        self.edit_replace_per_chunk(|text, replacement| {
+            let mut range: Option<Range<usize>> = None;
+            let mut off = 0;
+
+            while let Err(err) = str::from_utf8(&text[off..]) {
+                let beg = off + err.valid_up_to();
+                let end = err.error_len().map_or(text.len(), |len| beg + len);
+                // A U+FFFD may join with surrounding characters into a grapheme cluster (e.g. a
+                // combining mark following it), but line starts are always cluster boundaries.
+                let range = range.get_or_insert_with(|| {
+                    let line_beg =
+                        text[..beg].iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+                    line_beg..line_beg
+                });
+                replacement.extend_from_slice(&text[range.end..beg]);
+                replacement.extend_from_slice("\u{FFFD}".as_bytes());
+                range.end = end;
+                off = end;
+            }
+
+            let mut range = range?;
+            let line_end = memchr2(b'\n', b'\n', text, range.end);
+            let line_end = (line_end + 1).min(text.len());
+            replacement.extend_from_slice(&text[range.end..line_end]);
+            range.end = line_end;
+            Some(range)
+        });
+    }
+
+    #[cfg(test)]
+    fn extract_all(&self) -> Vec<u8> {
+        let mut text = Vec::new();
+        self.buffer.extract_raw(0..self.text_length(), &mut text, 0);
+        text
+    }
+
+    /// Returns true if the line containing `offset` ends in a newline
+    /// that doesn't match [`TextBuffer::is_crlf()`].
+    fn has_foreign_newline(&self, offset: usize) -> bool {
+        let mut off = offset;
+
+        loop {
+            let chunk = self.read_forward(off);
+            if chunk.is_empty() {
+                return false;
+            }
+
+            let (delta, line) = simd::lines_fwd(chunk, 0, 0, 1);
+            off += delta;
+            if line == 1 {
+                break;
+            }
+        }
+
+        self.read_backward(off).ends_with(b"\r\n") != self.newlines_are_crlf
     }
 
     /// If enabled, automatically insert a final newline
@@ -459,6 +694,12 @@ impl TextBuffer {
     /// Gets the visual cursor position, that is,
     /// the position in laid out rows and columns.
     pub fn cursor_visual_pos(&self) -> Point {
+        if let Some(hex) = &self.hex {
+            return Point {
+                x: hex::byte_column(hex.offset % hex::BYTES_PER_ROW) + hex.low_nibble as CoordType,
+                y: (hex.offset / hex::BYTES_PER_ROW) as CoordType,
+            };
+        }
         self.cursor.visual_pos
     }
 
@@ -591,6 +832,17 @@ impl TextBuffer {
         self.ruler = column;
     }
 
+    /// Is spell checking enabled?
+    pub fn is_spell_check_enabled(&self) -> bool {
+        self.spell_check
+    }
+
+    /// Enable or disable spell checking. Misspelled words are underlined
+    /// if a dictionary could be found (see [`spell::dictionary()`]).
+    pub fn set_spell_check(&mut self, enabled: bool) {
+        self.spell_check = enabled;
+    }
+
     pub fn reflow(&mut self) {
         self.reflow_internal(true);
     }
@@ -606,7 +858,10 @@ impl TextBuffer {
             // +1 onto logical_lines, because line numbers are 1-based.
             // +1 onto log10, because we want the digit width and not the actual log10.
             // +3 onto log10, because we append " | " to the line numbers to form the margin.
-            self.margin_width = if self.margin_enabled {
+            self.margin_width = if self.hex.is_some() {
+                // The hex view always shows the offsets.
+                hex::margin_width(self.text_length())
+            } else if self.margin_enabled {
                 self.stats.logical_lines.ilog10() as CoordType + 4
             } else {
                 0
@@ -614,8 +869,13 @@ impl TextBuffer {
 
             let text_width = self.text_width();
             // 2 columns are required, because otherwise wide glyphs wouldn't ever fit.
+            // The hex view has a fixed layout and can't be wrapped.
             self.word_wrap_column =
-                if self.word_wrap_enabled && text_width >= 2 { text_width } else { 0 };
+                if self.word_wrap_enabled && text_width >= 2 && self.hex.is_none() {
+                    text_width
+                } else {
+                    0
+                };
         }
 
         self.cursor_for_rendering = None;
@@ -657,6 +917,8 @@ impl TextBuffer {
 
     fn recalc_after_content_swap(&mut self) {
         // If the buffer was changed, nothing we previously saved can be relied upon.
+        self.content_issues.invalidate();
+        self.search_scope = None;
         self.undo_stack.clear();
         self.redo_stack.clear();
         self.last_history_type = HistoryType::Other;
@@ -683,8 +945,9 @@ impl TextBuffer {
         let mut first_chunk_len = 0;
         let mut read = 0;
 
-        // Read enough bytes to detect the BOM.
-        while first_chunk_len < BOM_MAX_LEN {
+        // Read enough bytes to detect the BOM, and to guess the encoding if there's none.
+        let sample_len = if encoding.is_some() { BOM_MAX_LEN } else { buf.len() };
+        while first_chunk_len < sample_len {
             read = file_read_uninit(file, &mut buf[first_chunk_len..])?;
             if read == 0 {
                 break;
@@ -692,11 +955,28 @@ impl TextBuffer {
             first_chunk_len += read;
         }
 
+        self.encoding_confidence = None;
+        self.hex = None;
         if let Some(encoding) = encoding {
             self.encoding = encoding;
         } else {
-            let bom = detect_bom(unsafe { buf[..first_chunk_len].assume_init_ref() });
-            self.encoding = bom.unwrap_or("UTF-8");
+            let sample = unsafe { buf[..first_chunk_len].assume_init_ref() };
+            if let Some(bom) = detect_bom(sample) {
+                self.encoding = bom;
+            } else if charset::is_binary(sample) {
+                // Binary files are loaded byte-for-byte and shown in the hex view.
+                self.encoding = "UTF-8";
+                self.hex = Some(HexView::default());
+            } else {
+                // Without ICU, we can only decode the few built-in encodings.
+                let detection = charset::detect(sample, read != 0)
+                    .filter(|d| icu::init().is_ok() || encodings::is_supported(d.encoding));
+                // Detectors return labels, but the encoding picker stores canonical names.
+                // Store the latter, so that confirming the guess isn't a change.
+                self.encoding = detection
+                    .map_or("UTF-8", |d| icu::find_encoding(d.encoding).unwrap_or(d.encoding));
+                self.encoding_confidence = detection.map(|d| d.confidence).filter(|&c| c < 100);
+            }
         }
 
         // TODO: Since reading the file can fail, we should ensure that we also reset the cursor here.
@@ -939,7 +1219,8 @@ impl TextBuffer {
     }
 
     /// Writes the text buffer contents to a file, handling BOM and encoding.
-    pub fn write_file(&mut self, file: &mut File) -> apperr::Result<()> {
+    /// Any other writer works too, for instance stdout.
+    pub fn write_file(&mut self, file: &mut dyn io::Write) -> apperr::Result<()> {
         let mut offset = 0;
 
         if self.encoding.starts_with("UTF-8") {
@@ -962,7 +1243,7 @@ impl TextBuffer {
         Ok(())
     }
 
-    fn write_file_with_icu(&mut self, file: &mut File) -> apperr::Result<()> {
+    fn write_file_with_icu(&mut self, file: &mut dyn io::Write) -> apperr::Result<()> {
         let scratch = scratch_arena(None);
         let pivot_buffer = scratch.alloc_uninit_slice(4 * KIBI);
         let buf = scratch.alloc_uninit_slice(4 * KIBI);
@@ -1190,6 +1471,105 @@ impl TextBuffer {
         Ok(())
     }
 
+    /// Limits searches with [`SearchOptions::in_selection`] to the current selection.
+    /// Returns false if `enabled` is true, but there's no selection to limit the search to.
+    pub fn set_search_scope(&mut self, enabled: bool) -> bool {
+        self.search_scope = None;
+        if enabled {
+            match self.selection_range() {
+                Some((beg, end)) if beg.offset < end.offset => {
+                    self.search_scope = Some(beg.offset..end.offset);
+                }
+                _ => return false,
+            }
+        }
+        // The cached search (if any) must be rebuilt with the new scope.
+        self.search = None;
+        true
+    }
+
+    /// Returns the 1-based index of the currently selected search hit
+    /// (or 0 if no hit is selected) and the total number of hits.
+    ///
+    /// Returns `None` if there's no active search.
+    pub fn find_match_count(&mut self) -> Option<(usize, usize)> {
+        let search = self.find_update_matches()?;
+
+        let mut current = 0;
+        if let Some(TextBufferSelection { beg, end }) = self.selection
+            && self.selection_generation == search.selection_generation
+        {
+            let beg = self.cursor_move_to_logical_internal(self.cursor, beg.min(end)).offset;
+            if let Ok(idx) = search.match_starts.binary_search(&beg) {
+                current = idx + 1;
+            }
+        }
+
+        Some((current, search.match_starts.len()))
+    }
+
+    /// Returns the visual line of each search hit, in ascending order.
+    pub fn find_match_lines(&mut self) -> &[CoordType] {
+        let Some(search) = self.find_update_matches() else {
+            return &[];
+        };
+
+        let key = (self.buffer.generation(), self.word_wrap_column);
+        if search.match_lines_key != Some(key) {
+            search.match_lines_key = Some(key);
+            search.match_lines.clear();
+
+            let mut cursor = Cursor::default();
+            for &off in &search.match_starts {
+                cursor = self.cursor_move_to_offset_internal(cursor, off);
+                if search.match_lines.last() != Some(&cursor.visual_pos.y) {
+                    search.match_lines.push(cursor.visual_pos.y);
+                }
+            }
+        }
+
+        &search.match_lines
+    }
+
+    /// Refreshes [`ActiveSearch::match_starts`] if the buffer changed.
+    /// The returned reference is detached from `self`, like in [`TextBuffer::find_and_select`].
+    fn find_update_matches<'a>(&mut self) -> Option<&'a mut ActiveSearch> {
+        let search = unsafe { &mut *self.search.as_ref()?.get() };
+        let generation = self.buffer.generation();
+
+        if search.match_starts_generation != Some(generation) {
+            search.match_starts.clear();
+            search.match_starts_generation = Some(generation);
+
+            if !search.no_matches {
+                if search.buffer_generation != generation {
+                    unsafe { search.regex.set_text(&mut search.text, 0) };
+                    search.buffer_generation = generation;
+                }
+
+                let scope = self.find_scope(search);
+                search.regex.reset(scope.start);
+                for range in search.regex.by_ref() {
+                    if range.end > scope.end {
+                        break;
+                    }
+                    search.match_starts.push(range.start);
+                }
+                search.regex.reset(search.next_search_offset);
+            }
+        }
+
+        Some(search)
+    }
+
+    /// Returns the range of the document that `search` is limited to.
+    fn find_scope(&self, search: &ActiveSearch) -> Range<usize> {
+        match &self.search_scope {
+            Some(scope) if search.options.in_selection => scope.clone(),
+            _ => 0..usize::MAX,
+        }
+    }
+
     fn find_construct_search(
         &self,
         pattern: &str,
@@ -1199,26 +1579,21 @@ impl TextBuffer {
             return Err(apperr::Error::Icu(1)); // U_ILLEGAL_ARGUMENT_ERROR
         }
 
-        let sanitized_pattern = if options.whole_word && options.use_regex {
-            Cow::Owned(format!(r"\b(?:{pattern})\b"))
-        } else if options.whole_word {
+        // In regex mode `\n` matches both LF and CRLF line breaks.
+        // Plain text searches take the pattern as-is, backslashes included.
+        let literal = !options.use_regex && !options.whole_word;
+        let sanitized_pattern = if options.use_regex {
+            let p = Self::find_translate_newlines(pattern);
+            if options.whole_word { Cow::Owned(format!(r"\b(?:{p})\b")) } else { p }
+        } else if !literal {
             let mut p = String::with_capacity(pattern.len() + 16);
-            p.push_str(r"\b");
-
-            // Escape regex special characters.
-            let b = unsafe { p.as_mut_vec() };
-            for &byte in pattern.as_bytes() {
-                match byte {
-                    b'*' | b'?' | b'+' | b'[' | b'(' | b')' | b'{' | b'}' | b'^' | b'$' | b'|'
-                    | b'\\' | b'.' => {
-                        b.push(b'\\');
-                        b.push(byte);
-                    }
-                    _ => b.push(byte),
-                }
+            if options.whole_word {
+                p.push_str(r"\b");
+            }
+            Self::find_escape_literal(&mut p, pattern);
+            if options.whole_word {
+                p.push_str(r"\b");
             }
-
-            p.push_str(r"\b");
             Cow::Owned(p)
         } else {
             Cow::Borrowed(pattern)
@@ -1228,7 +1603,7 @@ impl TextBuffer {
         if !options.match_case {
             flags |= icu::Regex::CASE_INSENSITIVE;
         }
-        if !options.use_regex && !options.whole_word {
+        if literal {
             flags |= icu::Regex::LITERAL;
         }
 
@@ -1247,10 +1622,69 @@ impl TextBuffer {
             selection_generation: 0,
             next_search_offset: 0,
             no_matches: false,
+            match_starts: Vec::new(),
+            match_starts_generation: None,
+            match_lines: Vec::new(),
+            match_lines_key: None,
         })
     }
 
+    /// Escapes regex special characters in `pattern` and appends it to `dst`.
+    fn find_escape_literal(dst: &mut String, pattern: &str) {
+        for &byte in pattern.as_bytes() {
+            match byte {
+                b'*' | b'?' | b'+' | b'[' | b']' | b'(' | b')' | b'{' | b'}' | b'^' | b'$'
+                | b'|' | b'\\' | b'.' => {
+                    dst.push('\\');
+                    dst.push(byte as char);
+                }
+                // SAFETY: We only ever split the input at ASCII characters.
+                _ => unsafe { dst.as_mut_vec().push(byte) },
+            }
+        }
+    }
+
+    /// Translates `\n` in a regex pattern to also match CRLF line breaks.
+    fn find_translate_newlines(pattern: &str) -> Cow<'_, str> {
+        if !pattern.contains(r"\n") {
+            return Cow::Borrowed(pattern);
+        }
+
+        let mut res = String::with_capacity(pattern.len() + 16);
+        let mut in_class = false;
+        let mut chars = pattern.chars();
+
+        while let Some(ch) = chars.next() {
+            match ch {
+                '\\' => match chars.next() {
+                    // Inside a character class we can't use a group, but then
+                    // again `[^\n]` and similar will work regardless of CRLF.
+                    Some('n') if !in_class => res.push_str(r"(?:\r?\n)"),
+                    Some(next) => {
+                        res.push('\\');
+                        res.push(next);
+                    }
+                    None => res.push('\\'),
+                },
+                '[' => {
+                    in_class = true;
+                    res.push(ch);
+                }
+                ']' => {
+                    in_class = false;
+                    res.push(ch);
+                }
+                _ => res.push(ch),
+            }
+        }
+
+        Cow::Owned(res)
+    }
+
     fn find_select_next(&mut self, search: &mut ActiveSearch, offset: usize, wrap: bool) {
+        let scope = self.find_scope(search);
+        let offset = offset.max(scope.start);
+
         if search.buffer_generation != self.buffer.generation() {
             unsafe { search.regex.set_text(&mut search.text, offset) };
             search.buffer_generation = self.buffer.generation();
@@ -1260,14 +1694,14 @@ impl TextBuffer {
             search.regex.reset(offset);
         }
 
-        let mut hit = search.regex.next();
+        let mut hit = search.regex.next().filter(|range| range.end <= scope.end);
 
         // If we hit the end of the buffer, and we know that there's something to find,
         // start the search again from the beginning (= wrap around).
-        if wrap && hit.is_none() && search.next_search_offset != 0 {
-            search.next_search_offset = 0;
-            search.regex.reset(0);
-            hit = search.regex.next();
+        if wrap && hit.is_none() && search.next_search_offset != scope.start {
+            search.next_search_offset = scope.start;
+            search.regex.reset(scope.start);
+            hit = search.regex.next().filter(|range| range.end <= scope.end);
         }
 
         search.selection_generation = if let Some(range) = hit {
@@ -1395,7 +1829,7 @@ impl TextBuffer {
         replacement: &'a [u8],
         parsed_replacements: &[RegexReplacement],
     ) -> Cow<'a, [u8]> {
-        if !search.options.use_regex {
+        let mut res = if !search.options.use_regex {
             Cow::Borrowed(replacement)
         } else {
             let mut res = Vec::new();
@@ -1412,7 +1846,49 @@ impl TextBuffer {
             }
 
             Cow::Owned(res)
+        };
+
+        if search.options.preserve_case
+            && let Some(range) = search.regex.group(0)
+        {
+            let mut matched = Vec::new();
+            self.buffer.extract_raw(range, &mut matched, 0);
+            if let Some(preserved) = Self::find_preserve_case(&matched, &res) {
+                res = Cow::Owned(preserved);
+            }
+        }
+
+        res
+    }
+
+    /// Applies the casing of `matched` to `replacement`:
+    /// "FOO" makes it uppercase, "foo" lowercase and "Foo" capitalizes it.
+    /// Returns `None` if the casing is mixed or the inputs aren't valid UTF-8.
+    fn find_preserve_case(matched: &[u8], replacement: &[u8]) -> Option<Vec<u8>> {
+        let matched = str::from_utf8(matched).ok()?;
+        let replacement = str::from_utf8(replacement).ok()?;
+
+        let mut letters = matched.chars().filter(|c| c.is_alphabetic());
+        let first = letters.next()?;
+        let (mut upper, mut lower) = (first.is_uppercase() as usize, first.is_lowercase() as usize);
+        for c in letters {
+            upper += c.is_uppercase() as usize;
+            lower += c.is_lowercase() as usize;
         }
+
+        let res = if lower == 0 && upper > 1 {
+            replacement.to_uppercase()
+        } else if upper == 0 {
+            replacement.to_lowercase()
+        } else if upper == 1 && first.is_uppercase() {
+            let mut chars = replacement.chars();
+            let first = chars.next()?;
+            first.to_uppercase().chain(chars).collect()
+        } else {
+            return None;
+        };
+
+        Some(res.into_bytes())
     }
 
     fn measurement_config(&self) -> MeasurementConfig<'_> {
@@ -1669,6 +2145,152 @@ impl TextBuffer {
         unsafe { self.set_cursor(self.cursor_move_delta_internal(self.cursor, granularity, delta)) }
     }
 
+    /// Moves the cursor by `delta` grapheme clusters in display order.
+    ///
+    /// Unlike [`TextBuffer::cursor_move_delta()`], moving right moves
+    /// backwards in the text if the cursor is on right-to-left text.
+    pub fn cursor_move_delta_displayed(&mut self, delta: CoordType) {
+        let scratch = scratch_arena(None);
+        let cursor = self.cursor;
+        let (beg, end) = self.displayed_row(cursor, cursor.visual_pos.y);
+        let cells = match self.bidi_layout(&scratch, beg, end) {
+            Some(cells) if (beg.visual_pos.x..=end.visual_pos.x).contains(&cursor.visual_pos.x) => {
+                cells
+            }
+            _ => return self.cursor_move_delta(CursorMovement::Grapheme, delta),
+        };
+
+        // The cursor is displayed on the cluster that follows it in logical order,
+        // or after the last one if it's at the end of the row.
+        let index = cells
+            .iter()
+            .position(|c| c.columns.start == cursor.visual_pos.x)
+            .unwrap_or(cells.len());
+        let target = index as CoordType + delta;
+        let len = cells.len() as CoordType;
+
+        let cursor = if target < 0 {
+            self.cursor_move_delta_internal(beg, CursorMovement::Grapheme, target)
+        } else if target >= len {
+            self.cursor_move_delta_internal(end, CursorMovement::Grapheme, target - len)
+        } else {
+            let x = cells[target as usize].columns.start;
+            self.cursor_move_to_visual_internal(beg, Point { x, y: beg.visual_pos.y })
+        };
+        unsafe { self.set_cursor(cursor) }
+    }
+
+    /// Converts a position as displayed by [`TextBuffer::render()`] into a visual position.
+    /// They differ on rows that contain right-to-left text, which is displayed reordered.
+    /// Use this for mouse input.
+    pub fn visual_pos_from_displayed(&self, pos: Point) -> Point {
+        let scratch = scratch_arena(None);
+        let (beg, end) = self.displayed_row(self.cursor, pos.y);
+        if beg.visual_pos.y != pos.y {
+            return pos;
+        }
+        let Some(cells) = self.bidi_layout(&scratch, beg, end) else {
+            return pos;
+        };
+
+        let mut x = beg.visual_pos.x;
+        for c in cells.iter() {
+            let width = c.columns.end - c.columns.start;
+            if (x..x + width).contains(&pos.x) {
+                return Point { x: c.columns.start, y: pos.y };
+            }
+            x += width;
+        }
+        pos
+    }
+
+    /// Returns the start and end of the part of row `y` that the last call
+    /// to [`TextBuffer::render()`] displayed, skipping a glyph cut off at the left edge.
+    fn displayed_row(&self, cursor: Cursor, y: CoordType) -> (Cursor, Cursor) {
+        let columns = self.rendered_columns.clone();
+        let mut beg = self.cursor_move_to_visual_internal(cursor, Point { x: columns.start, y });
+        let end = self.cursor_move_to_visual_internal(beg, Point { x: columns.end, y });
+        if beg.visual_pos.x < columns.start && beg.offset < end.offset {
+            beg = self.cursor_move_to_logical_internal(
+                beg,
+                Point { x: beg.logical_pos.x + 1, y: beg.logical_pos.y },
+            );
+        }
+        (beg, end)
+    }
+
+    /// Lays out the row between `beg` and `end` according to the Unicode Bidirectional
+    /// Algorithm. Returns its grapheme clusters in display order, or `None` if the row
+    /// doesn't contain right-to-left text and is displayed as is.
+    fn bidi_layout<'a>(
+        &self,
+        arena: &'a Arena,
+        beg: Cursor,
+        end: Cursor,
+    ) -> Option<Vec<BidiCell, &'a Arena>> {
+        if beg.offset >= end.offset {
+            return None;
+        }
+
+        let mut text = Vec::new();
+        self.buffer.extract_raw(beg.offset..end.offset, &mut text, 0);
+        if !unicode::contains_rtl(&text) {
+            return None;
+        }
+
+        // The paragraph direction is given by the first strong character of the logical line,
+        // which may be on a previous row if the line is wrapped.
+        let para_level = {
+            let mut off = self.goto_line_start(beg, beg.logical_pos.y).offset;
+            let mut level = None;
+            while level.is_none() && off < beg.offset {
+                let chunk = self.read_forward(off);
+                let chunk = &chunk[..chunk.len().min(beg.offset - off)];
+                level = unicode::base_level(Utf8Chars::new(chunk, 0));
+                off += chunk.len();
+            }
+            level.or_else(|| unicode::base_level(Utf8Chars::new(&text, 0))).unwrap_or(0)
+        };
+
+        // Split the row into clusters and remember the index of their first character.
+        let mut chars = Vec::new_in(arena);
+        let mut clusters = Vec::new_in(arena);
+        let mut cursor = beg;
+        while cursor.offset < end.offset {
+            let next = self.cursor_move_to_logical_internal(
+                cursor,
+                Point { x: cursor.logical_pos.x + 1, y: cursor.logical_pos.y },
+            );
+            if next.offset <= cursor.offset {
+                break;
+            }
+            let next_off = next.offset.min(end.offset);
+            let right = if next_off == end.offset || next.visual_pos.y != cursor.visual_pos.y {
+                end.visual_pos.x
+            } else {
+                next.visual_pos.x
+            };
+            clusters.push((cursor.visual_pos.x..right, chars.len()));
+            chars.extend(Utf8Chars::new(
+                &text[cursor.offset - beg.offset..next_off - beg.offset],
+                0,
+            ));
+            cursor = next;
+        }
+
+        let levels = unicode::resolve_levels(arena, &chars, para_level);
+        let mut cluster_levels = Vec::with_capacity_in(clusters.len(), arena);
+        cluster_levels.extend(clusters.iter().map(|&(_, i)| levels[i]));
+
+        let mut cells = Vec::with_capacity_in(clusters.len(), arena);
+        for i in unicode::visual_order(arena, &cluster_levels) {
+            let (columns, first) = clusters[i].clone();
+            let mirror = cluster_levels[i] & 1 != 0 && unicode::bidi_mirror(chars[first]).is_some();
+            cells.push(BidiCell { columns, mirror });
+        }
+        Some(cells)
+    }
+
     /// Sets the cursor to the given position, and clears the selection.
     ///
     /// # Safety
@@ -1708,73 +2330,347 @@ impl TextBuffer {
         self.cursor = cursor;
     }
 
-    /// Extracts a rectangular region of the text buffer and writes it to the framebuffer.
-    /// The `destination` rect is framebuffer coordinates. The extracted region within this
-    /// text buffer has the given `origin` and the same size as the `destination` rect.
-    pub fn render(
-        &mut self,
-        origin: Point,
-        destination: Rect,
-        focused: bool,
-        fb: &mut Framebuffer,
-    ) -> Option<RenderResult> {
-        if destination.is_empty() {
-            return None;
+    /// Is the hex view enabled?
+    pub fn is_hex_mode(&self) -> bool {
+        self.hex.is_some()
+    }
+
+    /// Enable or disable the hex view, which shows the raw bytes of the buffer,
+    /// 16 per row, and edits them nibble by nibble. The hex cursor starts at the text cursor
+    /// and the text cursor is moved to the hex cursor when leaving the hex view.
+    ///
+    /// NOTE: It's expected that the tui code calls `set_width()` sometime after this.
+    pub fn set_hex_mode(&mut self, enabled: bool) {
+        if self.hex.is_some() == enabled {
+            return;
         }
 
-        let scratch = scratch_arena(None);
-        let width = destination.width();
-        let height = destination.height();
-        let line_number_width = self.margin_width.max(3) as usize - 3;
-        let text_width = width - self.margin_width;
-        let mut visualizer_buf = [0xE2, 0x90, 0x80]; // U+2400 in UTF8
-        let mut line = ArenaString::new_in(&scratch);
-        let mut visual_pos_x_max = 0;
+        if enabled {
+            self.set_selection(None);
+            self.hex = Some(HexView { offset: self.cursor.offset, ..Default::default() });
+        } else if let Some(hex) = self.hex.take() {
+            self.cursor_move_to_offset(hex.offset);
+        }
 
-        // Pick the cursor closer to the `origin.y`.
-        let mut cursor = {
-            let a = self.cursor;
-            let b = self.cursor_for_rendering.unwrap_or_default();
-            let da = (a.visual_pos.y - origin.y).abs();
-            let db = (b.visual_pos.y - origin.y).abs();
-            if da < db { a } else { b }
-        };
+        self.search = None;
+        self.width = 0; // Force a reflow.
+        self.make_cursor_visible();
+    }
 
-        let [selection_beg, selection_end] = match self.selection {
-            None => [Point::MIN, Point::MIN],
-            Some(TextBufferSelection { beg, end }) => minmax(beg, end),
-        };
+    /// Gets the offset of the byte under the hex cursor.
+    pub fn hex_cursor_offset(&self) -> usize {
+        self.hex.as_ref().map_or(self.cursor.offset, |hex| hex.offset)
+    }
 
-        line.reserve(width as usize * 2);
+    /// Moves the hex cursor to the high nibble of the byte at `offset`.
+    pub fn hex_cursor_move_to_offset(&mut self, offset: usize) {
+        let len = self.text_length();
+        if let Some(hex) = &mut self.hex {
+            *hex = HexView { offset: offset.min(len), ..Default::default() };
+        }
+    }
 
-        for y in 0..height {
-            line.clear();
+    /// Moves the hex cursor by `delta` nibbles. Moving by a row is a delta of 32.
+    pub fn hex_cursor_move_nibbles(&mut self, delta: isize) {
+        let len = self.text_length();
+        if let Some(hex) = &mut self.hex {
+            let nibble = (hex.offset * 2 + hex.low_nibble as usize)
+                .saturating_add_signed(delta)
+                .min(len * 2);
+            *hex = HexView {
+                offset: nibble / 2,
+                low_nibble: !nibble.is_multiple_of(2),
+                selection: None,
+            };
+        }
+    }
 
-            let visual_line = origin.y + y;
-            let mut cursor_beg =
-                self.cursor_move_to_visual_internal(cursor, Point { x: origin.x, y: visual_line });
-            let cursor_end = self.cursor_move_to_visual_internal(
-                cursor_beg,
-                Point { x: origin.x + text_width, y: visual_line },
-            );
+    /// Moves the hex cursor to the nibble at the given visual position.
+    /// Positions in the ASCII column select the high nibble of the corresponding byte.
+    pub fn hex_cursor_move_to_visual(&mut self, pos: Point) {
+        let len = self.text_length();
+        if let Some(hex) = &mut self.hex {
+            let (i, low_nibble) = hex::byte_at_column(pos.x.max(0));
+            let offset = pos.y.max(0) as usize * hex::BYTES_PER_ROW + i;
+            *hex = if offset < len {
+                HexView { offset, low_nibble, selection: None }
+            } else {
+                HexView { offset: len, ..Default::default() }
+            };
+        }
+    }
 
-            // Accelerate the next render pass by remembering where we started off.
-            if y == 0 {
-                self.cursor_for_rendering = Some(cursor_beg);
-            }
+    /// Writes the hex `digit` (0-15) at the hex cursor and advances it by one nibble.
+    ///
+    /// Low nibbles are always overwritten. Writing a high nibble inserts a new byte,
+    /// unless overtype is enabled, in which case it overwrites the existing one.
+    pub fn hex_write_nibble(&mut self, digit: u8) {
+        let Some(HexView { offset, low_nibble, .. }) = self.hex else {
+            return;
+        };
+        let digit = digit & 0x0F;
+        let existing = self.buffer.read_forward(offset).first().copied();
 
-            if line_number_width != 0 {
-                if visual_line >= self.stats.visual_lines {
-                    // Past the end of the buffer? Place "    | " in the margin.
-                    // Since we know that we won't see line numbers greater than i64::MAX (9223372036854775807)
-                    // any time soon, we can use a static string as the template (`MARGIN`) and slice it,
-                    // because `line_number_width` can't possibly be larger than 19.
-                    let off = 19 - line_number_width;
-                    unsafe { std::hint::assert_unchecked(off < MARGIN_TEMPLATE.len()) };
+        match existing {
+            Some(b) if low_nibble => self.hex_edit(offset..offset + 1, &[b & 0xF0 | digit]),
+            Some(b) if self.overtype => self.hex_edit(offset..offset + 1, &[digit << 4 | b & 0x0F]),
+            _ => self.hex_edit(offset..offset, &[digit << 4]),
+        }
+
+        if let Some(hex) = &mut self.hex {
+            *hex = if low_nibble {
+                HexView { offset: offset + 1, ..Default::default() }
+            } else {
+                HexView { offset, low_nibble: true, selection: None }
+            };
+        }
+    }
+
+    /// Deletes `delta.abs()` bytes before (negative) or after (positive) the hex cursor.
+    pub fn hex_delete(&mut self, delta: CoordType) {
+        let Some(HexView { offset, .. }) = self.hex else {
+            return;
+        };
+        let count = delta.unsigned_abs();
+        let range = if delta < 0 {
+            offset.saturating_sub(count)..offset
+        } else {
+            offset..(offset + count).min(self.text_length())
+        };
+        if range.is_empty() {
+            return;
+        }
+
+        self.hex_edit(range.clone(), b"");
+        self.hex_cursor_move_to_offset(range.start);
+    }
+
+    /// Finds the next occurrence of the byte `pattern` at or after the hex cursor,
+    /// wrapping around at the end, and selects it. Returns false if there's none.
+    pub fn hex_find_and_select(&mut self, pattern: &[u8]) -> bool {
+        let Some(hex) = &self.hex else {
+            return false;
+        };
+
+        // Skip past the current hit, so that repeated searches advance.
+        let offset = match &hex.selection {
+            Some(selection) => selection.start + 1,
+            None => hex.offset,
+        };
+        let Some(start) = hex::find(&self.buffer, pattern, offset)
+            .or_else(|| hex::find(&self.buffer, pattern, 0))
+        else {
+            return false;
+        };
+
+        if let Some(hex) = &mut self.hex {
+            *hex = HexView {
+                offset: start,
+                low_nibble: false,
+                selection: Some(start..start + pattern.len()),
+            };
+        }
+        self.make_cursor_visible();
+        true
+    }
+
+    /// Replaces the currently selected occurrence of `pattern` with `replacement`
+    /// and selects the next one. Returns false if there's none left.
+    pub fn hex_find_and_replace(&mut self, pattern: &[u8], replacement: &[u8]) -> bool {
+        if let Some(HexView { selection: Some(selection), .. }) = &self.hex
+            && hex::find(&self.buffer, pattern, selection.start) == Some(selection.start)
+        {
+            let start = selection.start;
+            self.hex_edit(start..start + pattern.len(), replacement);
+            self.hex_cursor_move_to_offset(start + replacement.len());
+        }
+
+        self.hex_find_and_select(pattern)
+    }
+
+    /// Replaces all occurrences of `pattern` with `replacement` as a single undo step.
+    /// Returns false if there were none.
+    pub fn hex_find_and_replace_all(&mut self, pattern: &[u8], replacement: &[u8]) -> bool {
+        if self.hex.is_none() {
+            return false;
+        }
+
+        let mut offset = 0;
+        let mut found = false;
+
+        self.edit_begin_grouping();
+        while let Some(start) = hex::find(&self.buffer, pattern, offset) {
+            self.hex_edit(start..start + pattern.len(), replacement);
+            offset = start + replacement.len();
+            found = true;
+        }
+        self.edit_end_grouping();
+
+        if found {
+            self.hex_cursor_move_to_offset(offset);
+        }
+        found
+    }
+
+    /// Copies the bytes of the hex selection and deletes them if `cut` is set.
+    /// Without a selection, this copies the byte under the hex cursor.
+    fn hex_cut_copy(&mut self, clipboard: &mut Clipboard, cut: bool) {
+        let Some(hex) = &self.hex else {
+            return;
+        };
+        let range = match &hex.selection {
+            Some(selection) => selection.clone(),
+            None => hex.offset..(hex.offset + 1).min(self.text_length()),
+        };
+        if range.is_empty() {
+            return;
+        }
+
+        let mut data = Vec::new();
+        self.buffer.extract_raw(range.clone(), &mut data, 0);
+        clipboard.write(data);
+
+        if cut {
+            self.hex_edit(range.clone(), b"");
+            self.hex_cursor_move_to_offset(range.start);
+        }
+    }
+
+    /// Replaces `range` with `text` byte-for-byte as a single undo step.
+    ///
+    /// Unlike other edits, `range` doesn't need to be on a grapheme cluster boundary.
+    /// The history entry is anchored at the start of the line and stores
+    /// the distance to the edit in [`HistoryEntry::cursor_delta`].
+    fn hex_edit(&mut self, range: Range<usize>, text: &[u8]) {
+        let cursor = self.cursor_move_to_offset_internal(self.cursor, range.start);
+        let line_start = self.goto_line_start(cursor, cursor.logical_pos.y);
+        let lines_deleted;
+
+        self.edit_begin(HistoryType::Other, line_start);
+        {
+            let mut entry = self.undo_stack.back_mut().unwrap().borrow_mut();
+            entry.verbatim = true;
+            entry.cursor_delta = range.start - line_start.offset;
+            self.buffer.extract_raw(range.clone(), &mut entry.deleted, 0);
+            entry.added.extend_from_slice(text);
+            lines_deleted = simd::lines_fwd(&entry.deleted, 0, 0, CoordType::MAX).1;
+        }
+
+        let window = self.content_issues.unlink(&self.buffer, range.clone());
+        self.buffer.replace(range.clone(), text);
+        self.content_issues.relink(&self.buffer, window);
+        shift_search_scope(&mut self.search_scope, range, text.len());
+        self.stats.logical_lines += simd::lines_fwd(text, 0, 0, CoordType::MAX).1 - lines_deleted;
+        self.edit_end();
+    }
+
+    /// Extracts a rectangular region of the text buffer and writes it to the framebuffer.
+    /// The `destination` rect is framebuffer coordinates. The extracted region within this
+    /// text buffer has the given `origin` and the same size as the `destination` rect.
+    pub fn render(
+        &mut self,
+        origin: Point,
+        destination: Rect,
+        focused: bool,
+        fb: &mut Framebuffer,
+    ) -> Option<RenderResult> {
+        if destination.is_empty() {
+            return None;
+        }
+        if self.hex.is_some() {
+            return self.render_hex(origin, destination, focused, fb);
+        }
+
+        let scratch = scratch_arena(None);
+        let width = destination.width();
+        let height = destination.height();
+        let line_number_width = self.margin_width.max(3) as usize - 3;
+        let text_width = width - self.margin_width;
+        let mut visualizer_buf = [0xE2, 0x90, 0x80]; // U+2400 in UTF8
+        let mut line = ArenaString::new_in(&scratch);
+        let mut visual_pos_x_max = 0;
+        // Only look for lines with foreign newlines if there are any. It's cached, so it's cheap.
+        let flag_newlines = line_number_width != 0 && self.content_issues().foreign_newlines != 0;
+
+        // Pick the cursor closer to the `origin.y`.
+        let mut cursor = {
+            let a = self.cursor;
+            let b = self.cursor_for_rendering.unwrap_or_default();
+            let da = (a.visual_pos.y - origin.y).abs();
+            let db = (b.visual_pos.y - origin.y).abs();
+            if da < db { a } else { b }
+        };
+
+        let [selection_beg, selection_end] = match self.selection {
+            None => [Point::MIN, Point::MIN],
+            Some(TextBufferSelection { beg, end }) => minmax(beg, end),
+        };
+
+        line.reserve(width as usize * 2);
+
+        // Collect the search hits and occurrences of the selected word within the viewport.
+        let (search_hits, word_hits) = {
+            let beg = self.cursor_move_to_visual_internal(cursor, Point { x: 0, y: origin.y });
+            let end = self.cursor_move_to_visual_internal(
+                beg,
+                Point { x: COORD_TYPE_SAFE_MAX, y: origin.y + height - 1 },
+            );
+            let range = beg.offset..end.offset.min(beg.offset + HIGHLIGHT_SCAN_MAX);
+            (
+                self.render_collect_search_hits(&scratch, range.clone()),
+                self.render_collect_word_hits(&scratch, range),
+            )
+        };
+        let search_hit_color = fb.indexed_alpha(IndexedColor::BrightYellow, 1, 2);
+        let word_hit_color = fb.indexed_alpha(IndexedColor::BrightBlue, 1, 4);
+        // Where the cursor is displayed if its row was reordered for bidirectional text.
+        let mut cursor_displayed_x = None;
+        self.rendered_columns = origin.x..origin.x + text_width;
+
+        for y in 0..height {
+            line.clear();
+
+            let visual_line = origin.y + y;
+            let mut cursor_beg =
+                self.cursor_move_to_visual_internal(cursor, Point { x: origin.x, y: visual_line });
+            let cursor_end = self.cursor_move_to_visual_internal(
+                cursor_beg,
+                Point { x: origin.x + text_width, y: visual_line },
+            );
+
+            // Accelerate the next render pass by remembering where we started off.
+            if y == 0 {
+                self.cursor_for_rendering = Some(cursor_beg);
+            }
+
+            if line_number_width != 0 {
+                if visual_line >= self.stats.visual_lines {
+                    // Past the end of the buffer? Place "    | " in the margin.
+                    // Since we know that we won't see line numbers greater than i64::MAX (9223372036854775807)
+                    // any time soon, we can use a static string as the template (`MARGIN`) and slice it,
+                    // because `line_number_width` can't possibly be larger than 19.
+                    let off = 19 - line_number_width;
+                    unsafe { std::hint::assert_unchecked(off < MARGIN_TEMPLATE.len()) };
                     line.push_str(&MARGIN_TEMPLATE[off..]);
                 } else if self.word_wrap_column <= 0 || cursor_beg.logical_pos.x == 0 {
                     // Regular line? Place "123 | " in the margin.
                     _ = write!(line, "{:1$} │ ", cursor_beg.logical_pos.y + 1, line_number_width);
+
+                    // Highlight the line number yellow if the line ends in a CRLF in an LF document or vice versa.
+                    if flag_newlines && self.has_foreign_newline(cursor_beg.offset) {
+                        let left = destination.left;
+                        let top = destination.top + y;
+                        let rect = Rect {
+                            left,
+                            top,
+                            right: left + line_number_width as CoordType,
+                            bottom: top + 1,
+                        };
+                        let bg = fb.indexed(IndexedColor::Yellow);
+                        let fg = fb.contrasted(bg);
+                        fb.blend_bg(rect, bg);
+                        fb.blend_fg(rect, fg);
+                    }
                 } else {
                     // Wrapped line? Place " ... | " in the margin.
                     let number_width = (cursor_beg.logical_pos.y + 1).ilog10() as usize + 1;
@@ -1800,6 +2696,12 @@ impl TextBuffer {
                 }
             }
 
+            if cursor_beg.visual_pos.y == visual_line {
+                let args = (cursor_beg, cursor_end, origin, destination);
+                self.render_highlights(&word_hits, args, word_hit_color, fb);
+                self.render_highlights(&search_hits, args, search_hit_color, fb);
+            }
+
             let mut selection_off = 0..0;
 
             // Figure out the selection range on this line, if any.
@@ -1859,7 +2761,8 @@ impl TextBuffer {
             if cursor_beg.offset != cursor_end.offset {
                 // If we couldn't reach the left edge, we may have stopped short due to a wide glyph.
                 // In that case we'll try to find the next character and then compute by how many
-                // columns it overlaps the left edge (can be anything between 1 and 7).
+                // columns it overlaps the left edge (can be anything between 1 and 7 for tabs,
+                // or up to 11 for the badges of invalid UTF-8).
                 if cursor_beg.visual_pos.x < origin.x {
                     let cursor_next = self.cursor_move_to_logical_internal(
                         cursor_beg,
@@ -1868,7 +2771,7 @@ impl TextBuffer {
 
                     if cursor_next.visual_pos.x > origin.x {
                         let overlap = cursor_next.visual_pos.x - origin.x;
-                        debug_assert!((1..=7).contains(&overlap));
+                        debug_assert!((1..=11).contains(&overlap));
                         line.push_str(&TAB_WHITESPACE[..overlap as usize]);
                         cursor_beg = cursor_next;
                     }
@@ -1960,6 +2863,31 @@ impl TextBuffer {
                             let fg = fb.contrasted(bg);
                             fb.blend_bg(visualizer_rect, bg);
                             fb.blend_fg(visualizer_rect, fg);
+                        } else if ch == '\u{FFFD}'
+                            && &chunk[chunk_off..it.offset()] != "\u{FFFD}".as_bytes()
+                        {
+                            // Append a "<XX>" badge for each invalid byte.
+                            // This must match the width given to them by `MeasurementConfig`.
+                            let bytes = &chunk[chunk_off..it.offset()];
+                            for &b in bytes {
+                                _ = write!(line, "<{b:02X}>");
+                            }
+
+                            // Highlight the badges red.
+                            cursor_line =
+                                self.cursor_move_to_offset_internal(cursor_line, global_off);
+                            let visualizer_rect = {
+                                let left =
+                                    destination.left + self.margin_width + cursor_line.visual_pos.x
+                                        - origin.x;
+                                let top = destination.top + cursor_line.visual_pos.y - origin.y;
+                                let width = bytes.len() as CoordType * 4;
+                                Rect { left, top, right: left + width, bottom: top + 1 }
+                            };
+                            let bg = fb.indexed(IndexedColor::Red);
+                            let fg = fb.contrasted(bg);
+                            fb.blend_bg(visualizer_rect, bg);
+                            fb.blend_fg(visualizer_rect, fg);
                         } else {
                             line.push(ch);
                         }
@@ -1973,6 +2901,38 @@ impl TextBuffer {
 
             fb.replace_text(destination.top + y, destination.left, destination.right, &line);
 
+            if self.spell_check
+                && cursor_beg.offset != cursor_end.offset
+                && let Some(dictionary) = spell::dictionary()
+            {
+                self.render_spelling(dictionary, cursor_beg, cursor_end, origin, destination, fb);
+            }
+
+            if cursor_beg.offset != cursor_end.offset {
+                self.render_links(cursor_beg, cursor_end, origin, destination, fb);
+            }
+
+            // Right-to-left text is reordered after everything else has been drawn,
+            // so that the highlights move along with the text.
+            if let Some(cells) = self.bidi_layout(&scratch, cursor_beg, cursor_end) {
+                let left = destination.left + self.margin_width - origin.x;
+                let mut spans = Vec::with_capacity_in(cells.len(), &*scratch);
+                spans.extend(
+                    cells.iter().map(|c| (left + c.columns.start..left + c.columns.end, c.mirror)),
+                );
+                fb.reorder(destination.top + y, &spans);
+
+                if self.cursor.visual_pos.y == visual_line {
+                    let mut x = cursor_beg.visual_pos.x;
+                    for c in cells.iter() {
+                        if c.columns.start == self.cursor.visual_pos.x {
+                            cursor_displayed_x = Some(x);
+                        }
+                        x += c.columns.end - c.columns.start;
+                    }
+                }
+            }
+
             cursor = cursor_end;
         }
 
@@ -1999,7 +2959,7 @@ impl TextBuffer {
         }
 
         if focused {
-            let mut x = self.cursor.visual_pos.x;
+            let mut x = cursor_displayed_x.unwrap_or(self.cursor.visual_pos.x);
             let mut y = self.cursor.visual_pos.y;
 
             if self.word_wrap_column > 0 && x >= self.word_wrap_column {
@@ -2041,6 +3001,122 @@ impl TextBuffer {
         Some(RenderResult { visual_pos_x_max })
     }
 
+    /// The [`TextBuffer::render()`] counterpart for the hex view.
+    fn render_hex(
+        &mut self,
+        origin: Point,
+        destination: Rect,
+        focused: bool,
+        fb: &mut Framebuffer,
+    ) -> Option<RenderResult> {
+        let hex_view = self.hex.clone()?;
+        let scratch = scratch_arena(None);
+        let len = self.text_length();
+        let offset_width = self.margin_width as usize - 3;
+        let text_left = destination.left + self.margin_width;
+        let mut line = ArenaString::new_in(&scratch);
+        let mut row = ArenaString::new_in(&scratch);
+        let mut bytes = [0; hex::BYTES_PER_ROW];
+
+        let mut selection_bg = fb.indexed(IndexedColor::Foreground).oklab_blend(fb.indexed_alpha(
+            IndexedColor::BrightBlue,
+            1,
+            2,
+        ));
+        if !focused {
+            selection_bg =
+                selection_bg.oklab_blend(fb.indexed_alpha(IndexedColor::Background, 1, 2));
+        }
+        let selection_fg = fb.contrasted(selection_bg);
+
+        // The rect of `width` columns at column `x` of the given screen row, clipped to the text area.
+        let cell_rect = |top: CoordType, x: CoordType, width: CoordType| {
+            let left = text_left + x - origin.x;
+            Rect {
+                left: left.max(text_left),
+                top,
+                right: (left + width).min(destination.right),
+                bottom: top + 1,
+            }
+        };
+
+        for y in 0..destination.height() {
+            line.clear();
+
+            let top = destination.top + y;
+            let beg = (origin.y + y) as usize * hex::BYTES_PER_ROW;
+
+            if beg > len {
+                // Past the end of the buffer? Place "    | " in the margin.
+                line.push_str(&MARGIN_TEMPLATE[19 - offset_width..]);
+            } else {
+                let end = (beg + hex::BYTES_PER_ROW).min(len);
+                let mut off = beg;
+                while off < end {
+                    let chunk = self.buffer.read_forward(off);
+                    off += slice_copy_safe(&mut bytes[off - beg..end - beg], chunk);
+                }
+
+                row.clear();
+                hex::format_row(&mut row, &bytes[..end - beg]);
+                _ = write!(line, "{beg:0offset_width$X} │ ");
+                for ch in row.chars().skip(origin.x as usize) {
+                    line.push(ch);
+                }
+
+                if let Some(selection) = &hex_view.selection {
+                    for off in selection.start.max(beg)..selection.end.min(end) {
+                        let i = off - beg;
+                        for (x, width) in
+                            [(hex::byte_column(i), 2), (hex::ASCII_COLUMN + i as CoordType, 1)]
+                        {
+                            let rect = cell_rect(top, x, width);
+                            fb.blend_bg(rect, selection_bg);
+                            fb.blend_fg(rect, selection_fg);
+                        }
+                    }
+                }
+
+                // Mirror the cursor in the ASCII column.
+                if (beg..end).contains(&hex_view.offset) {
+                    let i = (hex_view.offset - beg) as CoordType;
+                    let rect = cell_rect(top, hex::ASCII_COLUMN + i, 1);
+                    fb.blend_bg(rect, fb.indexed_alpha(IndexedColor::BrightBlue, 1, 2));
+                }
+            }
+
+            fb.replace_text(top, destination.left, destination.right, &line);
+        }
+
+        // Colorize the margin that we wrote above.
+        fb.blend_fg(
+            Rect {
+                left: destination.left,
+                top: destination.top,
+                right: text_left,
+                bottom: destination.bottom,
+            },
+            StraightRgba::from_le(0x7f7f7f7f),
+        );
+
+        if focused {
+            let pos = self.cursor_visual_pos();
+            let cursor =
+                Point { x: text_left + pos.x - origin.x, y: destination.top + pos.y - origin.y };
+            let text = Rect {
+                left: text_left,
+                top: destination.top,
+                right: destination.right,
+                bottom: destination.bottom,
+            };
+            if text.contains(cursor) {
+                fb.set_cursor(cursor, self.overtype);
+            }
+        }
+
+        Some(RenderResult { visual_pos_x_max: hex::ROW_WIDTH })
+    }
+
     pub fn cut(&mut self, clipboard: &mut Clipboard) {
         self.cut_copy(clipboard, true);
     }
@@ -2050,6 +3126,11 @@ impl TextBuffer {
     }
 
     fn cut_copy(&mut self, clipboard: &mut Clipboard, cut: bool) {
+        if self.hex.is_some() {
+            self.hex_cut_copy(clipboard, cut);
+            return;
+        }
+
         let line_copy = !self.has_selection();
         let selection = self.extract_selection(cut);
         clipboard.write(selection);
@@ -2062,6 +3143,14 @@ impl TextBuffer {
             return;
         }
 
+        if let Some(hex) = &self.hex {
+            // Pasting replaces the hex selection, the same way typing over text selections does.
+            let range = hex.selection.clone().unwrap_or(hex.offset..hex.offset);
+            self.hex_edit(range.clone(), data);
+            self.hex_cursor_move_to_offset(range.start + data.len());
+            return;
+        }
+
         let pos = self.cursor_logical_pos();
         let at = if clipboard.is_line_copy() {
             self.goto_line_start(self.cursor, pos.y)
@@ -2539,6 +3628,66 @@ impl TextBuffer {
         if beg.offset < end.offset { Some((beg, end)) } else { None }
     }
 
+    /// Passes the document in chunks of whole lines to `replace`, which returns the range
+    /// within the chunk that it wants replaced with the contents of its second argument.
+    /// Unlike [`TextBuffer::write`], the replacements are written verbatim,
+    /// without normalizing their newlines. They're undone in a single step.
+    ///
+    /// The logical cursor position is preserved, which is why this is only
+    /// suitable for replacements that don't change the grapheme count.
+    fn edit_replace_per_chunk(
+        &mut self,
+        mut replace: impl FnMut(&[u8], &mut Vec<u8>) -> Option<Range<usize>>,
+    ) {
+        // Chunks keep the history entries (and the copies made here) small. They must not be
+        // too small either, because the undo stack only holds a limited number of entries.
+        const CHUNK_SIZE: usize = MEBI;
+
+        let pos = self.cursor.logical_pos;
+        let mut text = Vec::new();
+        let mut replacement = Vec::new();
+        let mut off = 0;
+
+        self.edit_begin_grouping();
+
+        while off < self.text_length() {
+            // Extend the chunk to the end of the line.
+            let mut end = (off + CHUNK_SIZE).min(self.text_length());
+            loop {
+                let chunk = self.read_forward(end - 1);
+                let i = memchr2(b'\n', b'\n', chunk, 0);
+                if i < chunk.len() || end >= self.text_length() {
+                    end = (end + i).min(self.text_length());
+                    break;
+                }
+                end += chunk.len();
+            }
+
+            text.clear();
+            replacement.clear();
+            self.buffer.extract_raw(off..end, &mut text, 0);
+
+            let Some(range) = replace(&text, &mut replacement) else {
+                off = end;
+                continue;
+            };
+
+            let beg = self.cursor_move_to_offset_internal(self.cursor, off + range.start);
+            let end_cursor = self.cursor_move_to_offset_internal(beg, off + range.end);
+
+            self.edit_begin(HistoryType::Other, beg);
+            self.undo_stack.back_mut().unwrap().borrow_mut().verbatim = true;
+            self.edit_delete(end_cursor);
+            self.edit_write(&replacement);
+            self.edit_end();
+
+            off = end - range.len() + replacement.len();
+        }
+
+        self.edit_end_grouping();
+        self.cursor_move_to_logical(pos);
+    }
+
     fn edit_begin_grouping(&mut self) {
         self.active_edit_group = Some(ActiveEditGroupInfo {
             cursor_before: self.cursor.logical_pos,
@@ -2568,7 +3717,7 @@ impl TextBuffer {
             || !matches!(history_type, HistoryType::Write | HistoryType::Delete)
         {
             self.redo_stack.clear();
-            while self.undo_stack.len() > 1000 {
+            while self.undo_stack.len() > self.undo_limit {
                 self.undo_stack.pop_front();
             }
 
@@ -2581,6 +3730,8 @@ impl TextBuffer {
                 cursor: cursor.logical_pos,
                 deleted: Vec::new(),
                 added: Vec::new(),
+                cursor_delta: 0,
+                verbatim: false,
             }));
 
             if let Some(info) = &self.active_edit_group
@@ -2595,6 +3746,8 @@ impl TextBuffer {
         }
 
         self.active_edit_off = cursor.offset;
+        self.active_edit_beg = cursor.offset;
+        self.active_edit_deleted = 0;
 
         // If word-wrap is enabled, the visual layout of all logical lines affected by the write
         // may have changed. This includes even text before the insertion point up to the line
@@ -2626,7 +3779,10 @@ impl TextBuffer {
         }
 
         // Write!
-        self.buffer.replace(self.active_edit_off..self.active_edit_off, text);
+        let range = self.active_edit_off..self.active_edit_off;
+        let window = self.content_issues.unlink(&self.buffer, range.clone());
+        self.buffer.replace(range, text);
+        self.content_issues.relink(&self.buffer, window);
 
         // Move self.cursor to the end of the newly written text. Can't use `self.set_cursor_internal`,
         // because we're still in the progress of recalculating the line stats.
@@ -2659,7 +3815,10 @@ impl TextBuffer {
 
         // Delete the portion from the buffer by enlarging the gap.
         let count = to.offset - off;
+        let window = self.content_issues.unlink(&self.buffer, off..to.offset);
         self.buffer.allocate_gap(off, 0, count);
+        self.content_issues.relink(&self.buffer, window);
+        self.active_edit_deleted += count;
 
         self.stats.logical_lines += logical_y_before - to.logical_pos.y;
     }
@@ -2679,12 +3838,20 @@ impl TextBuffer {
             debug_assert!(!entry.deleted.is_empty() || !entry.added.is_empty());
         }
 
+        shift_search_scope(
+            &mut self.search_scope,
+            self.active_edit_beg..self.active_edit_beg + self.active_edit_deleted,
+            self.active_edit_off - self.active_edit_beg,
+        );
+
         if let Some(info) = self.active_edit_line_info.take() {
             let deleted_count = self.undo_stack.back_mut().unwrap().borrow_mut().deleted.len();
             let target = self.cursor.logical_pos;
 
             // From our safe position we can measure the actual visual position of the cursor.
-            self.set_cursor_internal(self.cursor_move_to_logical_internal(info.safe_start, target));
+            // It's only stored once `self.stats.visual_lines` is updated below,
+            // because the new position may lie beyond the previous line count.
+            let cursor = self.cursor_move_to_logical_internal(info.safe_start, target);
 
             // If content is added at the insertion position, that's not a problem:
             // We can just remeasure the height of this one line and calculate the delta.
@@ -2695,15 +3862,17 @@ impl TextBuffer {
             // the entire buffer contents until the end to compute `self.stats.visual_lines`.
             if deleted_count < info.distance_next_line_start {
                 // Now we can measure how many more visual rows this logical line spans.
-                let next_line = self
-                    .cursor_move_to_logical_internal(self.cursor, Point { x: 0, y: target.y + 1 });
+                let next_line =
+                    self.cursor_move_to_logical_internal(cursor, Point { x: 0, y: target.y + 1 });
                 let lines_before = info.line_height_in_rows;
                 let lines_after = next_line.visual_pos.y - info.safe_start.visual_pos.y;
                 self.stats.visual_lines += lines_after - lines_before;
             } else {
-                let end = self.cursor_move_to_logical_internal(self.cursor, Point::MAX);
+                let end = self.cursor_move_to_logical_internal(cursor, Point::MAX);
                 self.stats.visual_lines = end.visual_pos.y + 1;
             }
+
+            self.set_cursor_internal(cursor);
         } else {
             // If word-wrap is disabled the visual line count always matches the logical one.
             self.stats.visual_lines = self.stats.logical_lines;
@@ -2761,6 +3930,11 @@ impl TextBuffer {
                 self.cursor_move_to_logical_internal(self.cursor, change.cursor)
             };
 
+            let offset = cursor.offset + change.borrow().cursor_delta;
+            if let Some(hex) = &mut self.hex {
+                *hex = HexView { offset, ..Default::default() };
+            }
+
             let safe_cursor = if self.word_wrap_column > 0 {
                 // If word-wrap is enabled, we need to move the cursor to the beginning of the line.
                 // This is because the undo/redo operation may have changed the visual position of the cursor.
@@ -2776,14 +3950,23 @@ impl TextBuffer {
                 // Undo: Whatever was deleted is now added and vice versa.
                 mem::swap(&mut change.deleted, &mut change.added);
 
+                let len_before = self.buffer.len();
+                let window =
+                    self.content_issues.unlink(&self.buffer, offset..offset + change.deleted.len());
+
                 // Delete the inserted portion.
-                self.buffer.allocate_gap(cursor.offset, 0, change.deleted.len());
+                self.buffer.allocate_gap(offset, 0, change.deleted.len());
 
                 // Reinsert the deleted portion.
-                {
+                if change.verbatim {
+                    let added = &change.added[..];
+                    let gap = self.buffer.allocate_gap(offset, added.len(), 0);
+                    let written = slice_copy_safe(gap, added);
+                    self.buffer.commit_gap(written);
+                } else {
                     let added = &change.added[..];
                     let mut beg = 0;
-                    let mut offset = cursor.offset;
+                    let mut offset = offset;
 
                     while beg < added.len() {
                         let (end, line) = simd::lines_fwd(added, beg, 0, 1);
@@ -2815,6 +3998,14 @@ impl TextBuffer {
                     }
                 }
 
+                self.content_issues.relink(&self.buffer, window);
+                // The newlines of the added text may have been translated, changing its length.
+                shift_search_scope(
+                    &mut self.search_scope,
+                    offset..offset + change.deleted.len(),
+                    self.buffer.len() + change.deleted.len() - len_before,
+                );
+
                 // Restore the previous line statistics.
                 mem::swap(&mut self.stats, &mut change.stats_before);
 
@@ -2831,14 +4022,13 @@ impl TextBuffer {
                 change.cursor_before = self.cursor.logical_pos;
                 // Can't use `set_cursor_internal` here, because we haven't updated the line stats yet.
                 self.cursor = cursor_before;
-
-                if self.undo_stack.is_empty() {
-                    self.last_history_type = HistoryType::Other;
-                }
             }
         }
 
         if entry_buffer_generation.is_some() {
+            // The next edit must not be merged into the entry that's now on top of the undo stack,
+            // as it doesn't describe the last change anymore. It would also keep the stale redo stack.
+            self.last_history_type = HistoryType::Other;
             self.recalc_after_content_changed();
         }
     }
@@ -2853,6 +4043,333 @@ impl TextBuffer {
         self.buffer.read_forward(off)
     }
 
+    /// Returns the search hits within `range`, if there's an active search,
+    /// limited to the search scope if the search is restricted to it.
+    fn render_collect_search_hits<'a>(
+        &mut self,
+        arena: &'a Arena,
+        range: Range<usize>,
+    ) -> Vec<Range<usize>, &'a Arena> {
+        let mut hits = Vec::new_in(arena);
+        let Some(search) = self.search.as_mut().map(UnsafeCell::get_mut) else {
+            return hits;
+        };
+        if search.no_matches {
+            return hits;
+        }
+
+        let generation = self.buffer.generation();
+        if search.buffer_generation != generation {
+            unsafe { search.regex.set_text(&mut search.text, 0) };
+            search.buffer_generation = generation;
+        }
+
+        let scope = match &self.search_scope {
+            Some(scope) if search.options.in_selection => scope.clone(),
+            _ => 0..usize::MAX,
+        };
+
+        // Matches may span multiple lines (`\n`), so start a little earlier.
+        search.regex.reset(range.start.saturating_sub(HIGHLIGHT_LOOKBEHIND).max(scope.start));
+        for hit in search.regex.by_ref() {
+            if hit.start >= range.end || hit.end > scope.end {
+                break;
+            }
+            if hit.end > range.start && hit.start < hit.end {
+                hits.push(hit);
+            }
+        }
+        search.regex.reset(search.next_search_offset);
+
+        hits
+    }
+
+    /// Returns the occurrences of the selected word within `range`.
+    /// Only applies if there's no active search and the selection is a single word.
+    fn render_collect_word_hits<'a>(
+        &self,
+        arena: &'a Arena,
+        range: Range<usize>,
+    ) -> Vec<Range<usize>, &'a Arena> {
+        let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+        let mut hits = Vec::new_in(arena);
+
+        let Some(TextBufferSelection { beg, end }) = self.selection else {
+            return hits;
+        };
+        let [beg, end] = minmax(beg, end);
+        if self.search.is_some() || beg.y != end.y || end.x - beg.x > HIGHLIGHT_WORD_MAX {
+            return hits;
+        }
+
+        let beg = self.cursor_move_to_logical_internal(self.cursor, beg).offset;
+        let end = self.cursor_move_to_logical_internal(self.cursor, end).offset;
+        let mut word = Vec::new();
+        self.buffer.extract_raw(beg..end, &mut word, 0);
+        let Ok(word) = str::from_utf8(&word) else {
+            return hits;
+        };
+        if word.is_empty() || !word.chars().all(is_word_char) {
+            return hits;
+        }
+
+        let mut text = Vec::new();
+        self.buffer.extract_raw(range.clone(), &mut text, 0);
+        let text = String::from_utf8_lossy(&text);
+        // Lossy conversion could shift offsets. Just skip highlighting in that case.
+        if text.len() != range.end - range.start {
+            return hits;
+        }
+
+        for (off, _) in text.match_indices(word) {
+            let before = text[..off].chars().next_back();
+            let after = text[off + word.len()..].chars().next();
+            if !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char) {
+                hits.push(range.start + off..range.start + off + word.len());
+            }
+        }
+
+        hits
+    }
+
+    /// Blends `color` into the background of the `hits` on the visual line from `beg` to `end`.
+    fn render_highlights(
+        &self,
+        hits: &[Range<usize>],
+        (beg, end, origin, destination): (Cursor, Cursor, Point, Rect),
+        color: StraightRgba,
+        fb: &mut Framebuffer,
+    ) {
+        let first = hits.partition_point(|hit| hit.end <= beg.offset);
+        let left = destination.left + self.margin_width - origin.x;
+        let top = destination.top + beg.visual_pos.y - origin.y;
+        let mut cursor = beg;
+
+        for hit in &hits[first..] {
+            if hit.start >= end.offset {
+                break;
+            }
+
+            cursor = self.cursor_move_to_offset_internal(cursor, hit.start.max(beg.offset));
+            let x_beg = cursor.visual_pos.x;
+            cursor = self.cursor_move_to_offset_internal(cursor, hit.end.min(end.offset));
+            // Hits that span the end of the line extend to the edge of the viewport.
+            let x_end =
+                if hit.end > end.offset { COORD_TYPE_SAFE_MAX } else { cursor.visual_pos.x };
+
+            let rect = Rect {
+                left: left + x_beg.max(origin.x),
+                top,
+                right: left + x_end.min(origin.x + destination.width() - self.margin_width),
+                bottom: top + 1,
+            };
+            fb.blend_bg(rect, color);
+        }
+    }
+
+    /// Underlines misspelled words within the visual line `beg..end`.
+    fn render_spelling(
+        &self,
+        dictionary: &spell::Dictionary,
+        beg: Cursor,
+        end: Cursor,
+        origin: Point,
+        destination: Rect,
+        fb: &mut Framebuffer,
+    ) {
+        let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+
+        // Words may be cut off at the edges of the viewport, or by word wrap.
+        // Include some context on either side, so that we check whole words.
+        const CONTEXT: usize = 64;
+        let mut range_beg = beg.offset.saturating_sub(CONTEXT);
+        let range_end = (end.offset + CONTEXT).min(self.text_length());
+        let mut text = Vec::new();
+        self.buffer.extract_raw(range_beg..range_end, &mut text, 0);
+
+        // The context may have split UTF-8 sequences at either end.
+        let skip = text.iter().take_while(|&&b| (b & 0xC0) == 0x80).count();
+        range_beg += skip;
+        let text = &text[skip..];
+        let text = match str::from_utf8(text) {
+            Ok(text) => text,
+            Err(err) => unsafe { str::from_utf8_unchecked(&text[..err.valid_up_to()]) },
+        };
+        let text_end = range_beg + text.len();
+
+        let left = destination.left + self.margin_width;
+        let top = destination.top + beg.visual_pos.y - origin.y;
+        let mut cursor = beg;
+        let mut words = text.char_indices().peekable();
+
+        while let Some((word_beg, ch)) = words.next() {
+            if !is_word_char(ch) {
+                continue;
+            }
+
+            // Apostrophes are part of a word if they're followed by a letter ("don't").
+            let mut word_end = word_beg + ch.len_utf8();
+            let mut is_word = ch.is_alphabetic();
+            while let Some(&(off, ch)) = words.peek() {
+                if is_word_char(ch) {
+                    is_word &= ch.is_alphabetic();
+                } else if !(ch == '\'' || ch == '\u{2019}')
+                    || !text[off + ch.len_utf8()..].starts_with(char::is_alphabetic)
+                {
+                    break;
+                }
+                word_end = off + ch.len_utf8();
+                words.next();
+            }
+
+            // Identifiers, numbers and single letters aren't prose.
+            let word = &text[word_beg..word_end];
+            if !is_word || word.chars().nth(1).is_none() {
+                continue;
+            }
+
+            // Words that run into the edge of the context may be incomplete.
+            if (word_beg == 0 && range_beg > 0)
+                || (word_end == text.len() && text_end < self.text_length())
+            {
+                continue;
+            }
+
+            if dictionary.check(word) {
+                continue;
+            }
+
+            let word_beg = (range_beg + word_beg).max(beg.offset);
+            let word_end = (range_beg + word_end).min(end.offset);
+            if word_beg >= word_end {
+                continue;
+            }
+
+            cursor = self.cursor_move_to_offset_internal(cursor, word_beg);
+            let x_beg = cursor.visual_pos.x;
+            cursor = self.cursor_move_to_offset_internal(cursor, word_end);
+            let x_end = cursor.visual_pos.x;
+
+            let rect = Rect {
+                left: (left + x_beg - origin.x).max(left),
+                top,
+                right: (left + x_end - origin.x).min(destination.right),
+                bottom: top + 1,
+            };
+            fb.replace_attr(rect, Attributes::CurlyUnderlined, Attributes::CurlyUnderlined);
+        }
+    }
+
+    /// Returns the links that overlap the given range, including the parts outside of it.
+    fn links_around(&self, range: Range<usize>) -> Vec<TextLink> {
+        // Links may be cut off at the edges of the viewport, or by word wrap.
+        // Include some context on either side, so that we find whole links.
+        const CONTEXT: usize = 1024;
+        let mut range_beg = range.start.saturating_sub(CONTEXT);
+        let range_end = (range.end + CONTEXT).min(self.text_length());
+        let mut text = Vec::new();
+        self.buffer.extract_raw(range_beg..range_end, &mut text, 0);
+
+        // The context may have split UTF-8 sequences at either end.
+        let skip = text.iter().take_while(|&&b| (b & 0xC0) == 0x80).count();
+        range_beg += skip;
+        let text = &text[skip..];
+        let text = match str::from_utf8(text) {
+            Ok(text) => text,
+            Err(err) => unsafe { str::from_utf8_unchecked(&text[..err.valid_up_to()]) },
+        };
+
+        links::find_links(text)
+            .into_iter()
+            .map(|link| TextLink {
+                range: range_beg + link.range.start..range_beg + link.range.end,
+                target: text[link.target].to_string(),
+                kind: link.kind,
+            })
+            .filter(|link| link.range.start < range.end && link.range.end > range.start)
+            .collect()
+    }
+
+    /// Returns the link at the given visual position, if any.
+    pub fn link_at_visual(&self, pos: Point) -> Option<TextLink> {
+        if self.hex.is_some() {
+            return None;
+        }
+        let cursor = self.cursor_move_to_visual_internal(self.cursor, pos);
+        if cursor.visual_pos != pos {
+            return None;
+        }
+        self.links_around(cursor.offset..cursor.offset + 1)
+            .into_iter()
+            .find(|link| link.range.contains(&cursor.offset))
+    }
+
+    /// Sets the link that's underlined because the mouse hovers over it.
+    pub fn set_link_hover(&mut self, range: Option<Range<usize>>) {
+        self.link_hover = range;
+    }
+
+    /// Turns the links on the visual line from `beg` to `end` into OSC 8
+    /// hyperlinks and underlines the one that the mouse hovers over.
+    fn render_links(
+        &mut self,
+        beg: Cursor,
+        end: Cursor,
+        origin: Point,
+        destination: Rect,
+        fb: &mut Framebuffer,
+    ) {
+        // Scanning for links is too expensive to do for every line on every frame.
+        // The cache is small enough to be searched linearly, since it only holds
+        // the lines that were rendered since the last edit.
+        let generation = self.buffer.generation();
+        if self.link_cache_generation != generation || self.link_cache.len() >= 1024 {
+            self.link_cache.clear();
+            self.link_cache_generation = generation;
+        }
+        let range = beg.offset..end.offset;
+        let idx = match self.link_cache.iter().position(|(r, _)| *r == range) {
+            Some(idx) => idx,
+            None => {
+                let links = self.links_around(range.clone());
+                self.link_cache.push((range, links));
+                self.link_cache.len() - 1
+            }
+        };
+        let links = mem::take(&mut self.link_cache[idx].1);
+
+        let left = destination.left + self.margin_width;
+        let top = destination.top + beg.visual_pos.y - origin.y;
+        let mut cursor = beg;
+
+        for link in &links {
+            let is_hovered = self.link_hover.as_ref() == Some(&link.range);
+            if link.kind != LinkKind::Url && !is_hovered {
+                continue;
+            }
+
+            cursor = self.cursor_move_to_offset_internal(cursor, link.range.start.max(beg.offset));
+            let x_beg = cursor.visual_pos.x;
+            cursor = self.cursor_move_to_offset_internal(cursor, link.range.end.min(end.offset));
+            let x_end = cursor.visual_pos.x;
+
+            let rect = Rect {
+                left: (left + x_beg - origin.x).max(left),
+                top,
+                right: (left + x_end - origin.x).min(destination.right),
+                bottom: top + 1,
+            };
+            if link.kind == LinkKind::Url {
+                fb.set_link(rect, &link.target);
+            }
+            if is_hovered {
+                fb.replace_attr(rect, Attributes::Underlined, Attributes::Underlined);
+            }
+        }
+
+        self.link_cache[idx].1 = links;
+    }
+
     /// Trigger auto-completion at the current cursor position
     pub fn trigger_auto_completion(&mut self) {
         // We need to avoid the borrow checker issue by not passing self directly
@@ -2863,16 +4380,35 @@ impl TextBuffer {
         let current_pos = self.cursor_logical_pos();
         let current_offset = self.cursor.offset;
         
+        // Paths like `./src/` take precedence over plain words.
+        let path_prefix = self.extract_path_prefix_for_completion(current_offset);
+        let is_path = PathCompletionProvider::is_path_prefix(&path_prefix);
+
         // Extract the prefix manually (similar to what trigger_completion does)
-        let prefix = self.extract_prefix_for_completion(current_offset);
+        let prefix = if is_path { path_prefix } else { self.extract_prefix_for_completion(current_offset) };
         
         if prefix.len() >= 1 { // Changed from >= 2 to >= 1 for more responsive triggering
-            let completions = self.auto_completer.provider.get_completions(self, &prefix);
+            let completions = if is_path {
+                self.auto_completer.path_provider.get_completions(self, &prefix)
+            } else if self.spell_check {
+                // Prose gets dictionary words first, then words from the buffer.
+                let mut items =
+                    self.auto_completer.spelling_provider.get_completions(self, &prefix);
+                for item in self.auto_completer.provider.get_completions(self, &prefix) {
+                    if !items.iter().any(|i| i.label == item.label) {
+                        items.push(item);
+                    }
+                }
+                items
+            } else {
+                self.auto_completer.provider.get_completions(self, &prefix)
+            };
             
             if !completions.is_empty() {
                 self.auto_completer.state.prefix = prefix;
                 self.auto_completer.state.items = completions;
                 self.auto_completer.state.selected_index = 0;
+                self.auto_completer.state.documentation_scroll = 0;
                 self.auto_completer.state.is_active = true;
                 self.auto_completer.state.show_popup = true;
                 
@@ -2923,6 +4459,54 @@ impl TextBuffer {
         prefix
     }
     
+    // Helper method to extract a path-like prefix for completion.
+    // Paths end at whitespace, quotes and brackets, e.g. `"../assets/`.
+    fn extract_path_prefix_for_completion(&self, current_offset: usize) -> String {
+        let mut prefix = Vec::new();
+        let mut offset = current_offset;
+
+        'outer: while offset > 0 && prefix.len() < 1024 {
+            let chunk = self.read_backward(offset);
+            if chunk.is_empty() {
+                break;
+            }
+
+            for &byte in chunk.iter().rev() {
+                if byte.is_ascii_whitespace()
+                    || matches!(
+                        byte,
+                        b'"' | b'\''
+                            | b'`'
+                            | b'('
+                            | b')'
+                            | b'<'
+                            | b'>'
+                            | b'['
+                            | b']'
+                            | b'{'
+                            | b'}'
+                            | b'='
+                            | b','
+                            | b';'
+                    )
+                {
+                    break 'outer;
+                }
+                prefix.push(byte);
+            }
+
+            offset -= chunk.len();
+        }
+
+        prefix.reverse();
+        String::from_utf8(prefix).unwrap_or_default()
+    }
+
+    /// Set the directory that relative paths are completed against.
+    pub fn set_completion_dir(&mut self, dir: Option<&Path>) {
+        self.auto_completer.path_provider.dir = dir.map(Path::to_path_buf);
+    }
+
     // Helper method to convert offset to point
     fn offset_to_point(&self, offset: usize) -> Point {
         let cursor = self.cursor_move_to_offset_internal(Default::default(), offset);
@@ -2953,6 +4537,11 @@ impl TextBuffer {
         self.auto_completer.state.select_prev();
     }
 
+    /// Scroll the documentation panel of the selected completion item, up to `max` lines
+    pub fn scroll_completion_documentation(&mut self, delta: CoordType, max: CoordType) {
+        self.auto_completer.state.scroll_documentation(delta, max);
+    }
+
     /// Check if auto-completion is currently active
     pub fn is_completing(&self) -> bool {
         self.auto_completer.is_completing()
@@ -2969,6 +4558,17 @@ impl TextBuffer {
     }
 }
 
+/// For the search engine in [`crate::regex`].
+impl ReadableDocument for TextBuffer {
+    fn read_forward(&self, off: usize) -> &[u8] {
+        self.buffer.read_forward(off)
+    }
+
+    fn read_backward(&self, off: usize) -> &[u8] {
+        self.buffer.read_backward(off)
+    }
+}
+
 pub enum Bom {
     None,
     UTF8,
@@ -3006,3 +4606,729 @@ fn detect_bom(bytes: &[u8]) -> Option<&'static str> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn buffer_from(text: &str) -> TextBuffer {
+        let mut tb = TextBuffer::new(false).unwrap();
+        tb.write_raw(text.as_bytes());
+        tb.cursor_move_to_offset(0);
+        tb
+    }
+
+    fn buffer_text(tb: &mut TextBuffer) -> String {
+        let mut text = String::new();
+        tb.save_as_string(&mut text);
+        text
+    }
+
+    #[test]
+    fn test_preserve_case() {
+        let cases = [
+            ("foo", "bar", Some("bar")),
+            ("FOO", "bar", Some("BAR")),
+            ("Foo", "bar", Some("Bar")),
+            ("fOO", "bar", None),
+            ("123", "bar", None),
+        ];
+        for (matched, replacement, expected) in cases {
+            let actual = TextBuffer::find_preserve_case(matched.as_bytes(), replacement.as_bytes());
+            assert_eq!(actual.as_deref(), expected.map(str::as_bytes), "{matched}");
+        }
+    }
+
+    #[test]
+    fn test_translate_newlines() {
+        assert_eq!(TextBuffer::find_translate_newlines(r"a\nb"), r"a(?:\r?\n)b");
+        assert_eq!(TextBuffer::find_translate_newlines(r"[^\n]\\n"), r"[^\n]\\n");
+
+        let mut escaped = String::new();
+        TextBuffer::find_escape_literal(&mut escaped, r"a.b\nc");
+        assert_eq!(escaped, r"a\.b\\nc");
+    }
+
+    #[test]
+    fn test_search() {
+        // Newlines in regex patterns, and the match count.
+        let mut tb = buffer_from("foo\nbar\nfoo\nbar\n");
+        let options = SearchOptions { use_regex: true, ..Default::default() };
+        tb.find_and_select(r"foo\nbar", options).unwrap();
+        assert_eq!(tb.find_match_count(), Some((1, 2)));
+        tb.find_and_select(r"foo\nbar", options).unwrap();
+        assert_eq!(tb.find_match_count(), Some((2, 2)));
+
+        // Plain text patterns don't interpret backslashes, not even with whole words.
+        let mut tb = buffer_from("foo\nbar foo\\nbar\n");
+        for whole_word in [false, true] {
+            let options = SearchOptions { whole_word, ..Default::default() };
+            tb.find_and_select(r"foo\nbar", options).unwrap();
+            assert_eq!(tb.find_match_count(), Some((1, 1)));
+            assert_eq!(tb.selection_range().map(|(b, e)| b.offset..e.offset), Some(8..16));
+        }
+
+        // Preserve case.
+        let mut tb = buffer_from("foo Foo FOO");
+        let options = SearchOptions { preserve_case: true, ..Default::default() };
+        tb.find_and_replace_all("foo", options, b"bar").unwrap();
+        assert_eq!(buffer_text(&mut tb), "bar Bar BAR");
+
+        // Search in selection.
+        let mut tb = buffer_from("a a a a");
+        tb.cursor_move_to_offset(2);
+        tb.selection_update_offset(5);
+        assert!(tb.set_search_scope(true));
+        let options = SearchOptions { in_selection: true, ..Default::default() };
+        tb.find_and_replace_all("a", options, b"bb").unwrap();
+        assert_eq!(buffer_text(&mut tb), "a bb bb a");
+
+        // The scope moves along with edits in front of it, as well as their undo.
+        let mut tb = buffer_from("a a a a");
+        tb.cursor_move_to_offset(2);
+        tb.selection_update_offset(5);
+        assert!(tb.set_search_scope(true));
+        tb.cursor_move_to_offset(0);
+        tb.write_canon(b"xx ");
+        tb.find_and_replace_all("a", options, b"b").unwrap();
+        assert_eq!(buffer_text(&mut tb), "xx a b b a");
+        tb.undo();
+        tb.undo();
+        tb.undo();
+        assert_eq!(buffer_text(&mut tb), "a a a a");
+        tb.find_and_replace_all("a", options, b"c").unwrap();
+        assert_eq!(buffer_text(&mut tb), "a c c a");
+    }
+
+    #[test]
+    fn test_highlights() {
+        let scratch = scratch_arena(None);
+        let mut tb = buffer_from("foo foobar foo\nbar_foo foo");
+        let len = tb.text_length();
+
+        tb.cursor_move_to_offset(0);
+        tb.selection_update_offset(3);
+        let hits = tb.render_collect_word_hits(&scratch, 0..len);
+        assert_eq!(&hits[..], &[0..3, 11..14, 23..26]);
+
+        // Partial words don't highlight anything.
+        tb.selection_update_offset(2);
+        assert!(tb.render_collect_word_hits(&scratch, 0..len).is_empty());
+
+        tb.find_and_select("bar", SearchOptions::default()).unwrap();
+        let hits = tb.render_collect_search_hits(&scratch, 0..len);
+        assert_eq!(&hits[..], &[7..10, 15..18]);
+        assert_eq!(tb.find_match_lines(), &[0, 1]);
+    }
+
+    /// A single edit of an editing trace: `(offset, delete_len, insert)`.
+    type TracePatch = (usize, usize, String);
+
+    /// Loads the `rustcode` editing trace, which starts out empty.
+    fn editing_trace() -> (Vec<TracePatch>, String) {
+        let data = include_bytes!("../../../../assets/editing-traces/rustcode.json.zst");
+        let data = zstd::decode_all(std::io::Cursor::new(data)).unwrap();
+        let data = str::from_utf8(&data).unwrap();
+
+        let scratch = scratch_arena(None);
+        let root = crate::json::parse(&scratch, data).unwrap();
+        let root = root.as_object().unwrap();
+        assert_eq!(root.get_str("startContent"), Some(""));
+
+        let mut patches = Vec::new();
+        for txn in root.get_array("txns").unwrap() {
+            for patch in txn.as_object().unwrap().get_array("patches").unwrap() {
+                let patch = patch.as_array().unwrap();
+                patches.push((
+                    patch[0].as_number().unwrap() as usize,
+                    patch[1].as_number().unwrap() as usize,
+                    patch[2].as_str().unwrap().to_string(),
+                ));
+            }
+        }
+
+        (patches, root.get_str("endContent").unwrap().to_string())
+    }
+
+    /// Applies a trace patch the same way a user would: move there, delete, type.
+    fn apply_patch(tb: &mut TextBuffer, (offset, delete, insert): &TracePatch) {
+        tb.cursor_move_to_offset(*offset);
+        tb.delete(CursorMovement::Grapheme, *delete as CoordType);
+        tb.write_raw(insert.as_bytes());
+    }
+
+    fn count_newlines(text: &[u8]) -> CoordType {
+        text.iter().filter(|&&b| b == b'\n').count() as CoordType
+    }
+
+    /// Checks that the undo and redo stacks are consistent with each other and with `stats`.
+    /// `lines_before` is the line count before the oldest entry in the undo stack.
+    fn check_history(tb: &TextBuffer, lines_before: CoordType) {
+        let delta = |e: &HistoryEntry| count_newlines(&e.added) - count_newlines(&e.deleted);
+
+        // Undoing the top of the undo stack must result in its `stats_before`, and so on.
+        let mut lines = tb.stats.logical_lines;
+        let mut generation = u32::MAX;
+        for entry in tb.undo_stack.iter().rev() {
+            let entry = entry.borrow();
+            assert_eq!(entry.stats_before.logical_lines + delta(&entry), lines);
+            assert!(entry.generation_before <= generation);
+            lines = entry.stats_before.logical_lines;
+            generation = entry.generation_before;
+        }
+        assert_eq!(lines, lines_before);
+
+        // Undo swaps `added` with `deleted` and `stats_before` with `stats`, and redo swaps them back.
+        // Redoing the top of the redo stack must thus result in its `stats_before`.
+        let mut lines = tb.stats.logical_lines;
+        for entry in tb.redo_stack.iter().rev() {
+            let entry = entry.borrow();
+            lines -= delta(&entry);
+            assert_eq!(entry.stats_before.logical_lines, lines);
+        }
+    }
+
+    /// Checks that `stats` match the actual contents of the buffer.
+    fn check_stats(tb: &mut TextBuffer) -> String {
+        let text = buffer_text(tb);
+        assert_eq!(tb.logical_line_count(), count_newlines(text.as_bytes()) + 1);
+        assert_eq!(tb.visual_line_count(), tb.logical_line_count());
+        text
+    }
+
+    #[test]
+    fn test_editing_trace() {
+        let (patches, end_content) = editing_trace();
+        let mut tb = TextBuffer::new(false).unwrap();
+        tb.set_crlf(false);
+        // Keep the entire history, so that undoing all of it must result in an empty buffer.
+        tb.undo_limit = usize::MAX;
+
+        // Track the line count alongside, because comparing the full contents
+        // after each of the ~40k patches would take too long.
+        let mut expected = String::new();
+        let mut lines = 1;
+        // The line count before each entry in the undo stack, and for every 1000th also the
+        // contents, because keeping all of them would take gigabytes.
+        let mut history = Vec::new();
+        let mut last_generation = None;
+
+        for (offset, delete, insert) in &patches {
+            tb.cursor_move_to_offset(*offset);
+
+            // The deletion and the insertion may each create a separate undo entry.
+            for step in 0..2 {
+                let lines_before = lines;
+                let contents = (history.len() % 1000 == 0).then(|| expected.clone());
+                if step == 0 {
+                    let deleted = &expected.as_bytes()[*offset..offset + delete];
+                    lines -= count_newlines(deleted);
+                    expected.replace_range(offset..&(offset + delete), "");
+                    tb.delete(CursorMovement::Grapheme, *delete as CoordType);
+                } else {
+                    lines += count_newlines(insert.as_bytes());
+                    expected.insert_str(*offset, insert);
+                    tb.write_raw(insert.as_bytes());
+                }
+                assert_eq!(tb.logical_line_count(), lines);
+
+                // Consecutive entries always have distinct generations (there's no grouping here).
+                let generation = tb.undo_stack.back().map(|e| e.borrow().generation_before);
+                if generation != last_generation {
+                    last_generation = generation;
+                    history.push((lines_before, contents));
+                }
+            }
+        }
+        assert_eq!(expected, end_content);
+        assert_eq!(check_stats(&mut tb), end_content);
+        assert_eq!(tb.undo_stack.len(), history.len());
+        check_history(&tb, 1);
+
+        // Undo every step, checking the contents along the way...
+        while let Some((lines, contents)) = history.pop() {
+            tb.undo();
+            assert_eq!(tb.logical_line_count(), lines);
+            if let Some(contents) = contents {
+                assert_eq!(check_stats(&mut tb), contents);
+            }
+        }
+        assert!(tb.undo_stack.is_empty());
+        assert_eq!(check_stats(&mut tb), "");
+        check_history(&tb, 1);
+
+        // ...and redo it all again.
+        while !tb.redo_stack.is_empty() {
+            tb.redo();
+        }
+        assert_eq!(check_stats(&mut tb), end_content);
+        check_history(&tb, 1);
+    }
+
+    #[test]
+    fn test_editing_trace_undo_redo() {
+        let (patches, end_content) = editing_trace();
+
+        for seed in 1..=2u64 {
+            // xorshift64
+            let mut state = seed;
+            let mut rand = |n: usize| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state % n as u64) as usize
+            };
+
+            let mut tb = TextBuffer::new(false).unwrap();
+            tb.set_crlf(false);
+            // With the entire history around, `check_history` knows where it starts.
+            tb.undo_limit = usize::MAX;
+
+            for patch in &patches {
+                apply_patch(&mut tb, patch);
+
+                // Every now and then, randomly walk through the history and back.
+                // Checking the history and the contents is O(n), so this can't happen too often.
+                if rand(128) != 0 {
+                    continue;
+                }
+                let expected = check_stats(&mut tb);
+                for _ in 0..rand(64) {
+                    if rand(3) == 0 {
+                        tb.redo();
+                    } else {
+                        tb.undo();
+                    }
+                }
+                check_history(&tb, 1);
+                check_stats(&mut tb);
+                while !tb.redo_stack.is_empty() {
+                    tb.redo();
+                }
+                assert_eq!(check_stats(&mut tb), expected);
+            }
+
+            assert_eq!(check_stats(&mut tb), end_content);
+        }
+    }
+
+    #[test]
+    fn test_edit_after_undo() {
+        let mut tb = buffer_from("\n");
+        tb.write_raw(b"a");
+        tb.delete(CursorMovement::Grapheme, -1);
+        tb.undo();
+        assert_eq!(buffer_text(&mut tb), "a\n");
+
+        // This delete must not be merged into the "a" write, which is now on top of
+        // the undo stack, and it must clear the redo stack, since it's now stale.
+        tb.delete(CursorMovement::Grapheme, 1);
+        assert_eq!(buffer_text(&mut tb), "a");
+        tb.redo();
+        assert_eq!(buffer_text(&mut tb), "a");
+        tb.undo();
+        assert_eq!(buffer_text(&mut tb), "a\n");
+        check_stats(&mut tb);
+    }
+
+    /// Unlike [`buffer_from`] this doesn't normalize newlines and allows invalid UTF-8.
+    fn buffer_from_bytes(text: &[u8]) -> TextBuffer {
+        let mut tb = TextBuffer::new(false).unwrap();
+        tb.buffer.replace(0..0, text);
+        tb.stats.logical_lines = count_newlines(text) + 1;
+        tb.stats.visual_lines = tb.stats.logical_lines;
+        tb.recalc_after_content_swap();
+        tb
+    }
+
+    #[test]
+    fn test_fix_newlines() {
+        let text = b"a\r\nb\nc\r\nd\ne";
+        let mut tb = buffer_from_bytes(text);
+
+        tb.set_crlf(true);
+        assert_eq!(tb.content_issues().foreign_newlines, 2);
+        assert!(!tb.has_foreign_newline(0));
+        assert!(tb.has_foreign_newline(3));
+
+        tb.set_crlf(false);
+        assert_eq!(tb.content_issues().foreign_newlines, 2);
+        assert!(tb.has_foreign_newline(0));
+        assert!(!tb.has_foreign_newline(3));
+        assert!(!tb.has_foreign_newline(text.len()));
+
+        tb.cursor_move_to_logical(Point { x: 1, y: 3 });
+        tb.fix_newlines();
+        assert_eq!(tb.extract_all(), b"a\nb\nc\nd\ne");
+        assert_eq!(tb.content_issues(), ContentIssues::default());
+        assert_eq!(tb.cursor_logical_pos(), Point { x: 1, y: 3 });
+        check_stats(&mut tb);
+
+        // Fixing must be undoable in a single step.
+        tb.undo();
+        assert_eq!(tb.extract_all(), text);
+        tb.redo();
+        assert_eq!(tb.extract_all(), b"a\nb\nc\nd\ne");
+
+        // Large documents are fixed chunk by chunk, but that's still a single undo step.
+        let text = "a\r\n".repeat(MEBI / 2);
+        let mut tb = buffer_from_bytes(text.as_bytes());
+        tb.set_crlf(false);
+        tb.fix_newlines();
+        assert_eq!(tb.extract_all(), "a\n".repeat(MEBI / 2).as_bytes());
+        assert_eq!(tb.content_issues(), ContentIssues::default());
+        assert!(tb.undo_stack.len() > 1);
+        tb.undo();
+        assert_eq!(tb.extract_all(), text.as_bytes());
+        assert_eq!(tb.content_issues().foreign_newlines, MEBI / 2);
+    }
+
+    #[test]
+    fn test_replace_invalid_utf8() {
+        // The "\xFF" is followed by a combining mark (U+0301), which joins with its U+FFFD.
+        let text = b"a\xE2\x82b\n\xFF\xCC\x81c\r\nd\xEF\xBF\xBD";
+        let expected = "a\u{FFFD}b\n\u{FFFD}\u{0301}c\r\nd\u{FFFD}".as_bytes();
+        let mut tb = buffer_from_bytes(text);
+        tb.set_crlf(false);
+        assert_eq!(
+            tb.content_issues(),
+            ContentIssues { foreign_newlines: 1, invalid_sequences: 2 }
+        );
+
+        tb.cursor_move_to_logical(Point { x: 2, y: 1 });
+        tb.replace_invalid_utf8();
+        assert_eq!(tb.extract_all(), expected);
+        assert_eq!(
+            tb.content_issues(),
+            ContentIssues { foreign_newlines: 1, invalid_sequences: 0 }
+        );
+        assert_eq!(tb.cursor_logical_pos(), Point { x: 2, y: 1 });
+
+        tb.undo();
+        assert_eq!(tb.extract_all(), text);
+        tb.redo();
+        assert_eq!(tb.extract_all(), expected);
+    }
+
+    #[test]
+    fn test_render_invalid_utf8() {
+        let mut tb = buffer_from_bytes(b"a\xE2\x82b\xFF\xEF\xBF\xBDc");
+        let mut fb = Framebuffer::new();
+        let rect = Rect { left: 0, top: 0, right: 20, bottom: 1 };
+
+        // Scrolling into the middle of a badge pads it with whitespace.
+        for (origin_x, expected) in [(0, "a<E2><82>b<FF>\u{FFFD}c"), (3, "      b<FF>\u{FFFD}c")] {
+            fb.flip(Size { width: 20, height: 1 });
+            tb.render(Point { x: origin_x, y: 0 }, rect, false, &mut fb);
+            let snapshot = fb.snapshot();
+            assert_eq!(snapshot.lines().next().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_detected_encoding_is_canonical() {
+        let path = std::env::temp_dir().join(format!("edit-detect-{}", std::process::id()));
+        let text = b"Gr\xfc\xdfe aus K\xf6ln, caf\xe9 cr\xe8me br\xfbl\xe9e, na\xefve fa\xe7ade.\n";
+        std::fs::write(&path, text).unwrap();
+
+        let mut tb = TextBuffer::new(false).unwrap();
+        tb.read_file(&mut File::open(&path).unwrap(), None).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let encoding = tb.encoding();
+        assert_ne!(encoding, "UTF-8");
+        assert!(icu::get_available_encodings().all.iter().any(|e| e.canonical == encoding));
+
+        // Confirming the guess in the encoding picker must not modify the document.
+        assert!(!tb.is_dirty());
+        tb.set_encoding(icu::find_encoding(encoding).unwrap());
+        assert!(!tb.is_dirty());
+        assert_eq!(tb.encoding_confidence(), None);
+    }
+
+    #[test]
+    fn test_hex_edit() {
+        let text = "a\u{e9}\nb".as_bytes();
+        let mut tb = buffer_from_bytes(text);
+        tb.set_width(100);
+        tb.set_hex_mode(true);
+        tb.set_width(100);
+
+        // Overtype the low nibble of the first byte of "é" (C3 A9), splitting the character.
+        tb.hex_cursor_move_to_offset(1);
+        tb.hex_cursor_move_nibbles(1);
+        tb.hex_write_nibble(0xA);
+        assert_eq!(tb.extract_all(), b"a\xCA\xA9\nb");
+        assert_eq!(tb.hex_cursor_offset(), 2);
+
+        // Inserting writes a byte with the high nibble and overtypes its low nibble.
+        tb.hex_cursor_move_to_offset(0);
+        tb.hex_write_nibble(0x0);
+        tb.hex_write_nibble(0xA);
+        assert_eq!(tb.extract_all(), b"\na\xCA\xA9\nb");
+        assert_eq!(tb.logical_line_count(), 3);
+
+        // With overtype, the high nibble overwrites, too.
+        tb.set_overtype(true);
+        tb.hex_write_nibble(0x4);
+        assert_eq!(tb.extract_all(), b"\nA\xCA\xA9\nb");
+
+        tb.hex_cursor_move_to_offset(3);
+        tb.hex_delete(-2);
+        assert_eq!(tb.extract_all(), b"\n\xA9\nb");
+        assert_eq!(tb.hex_cursor_offset(), 1);
+
+        for expected in
+            [&b"\nA\xCA\xA9\nb"[..], b"\na\xCA\xA9\nb", b"\0a\xCA\xA9\nb", b"a\xCA\xA9\nb", text]
+        {
+            tb.undo();
+            assert_eq!(tb.extract_all(), expected);
+        }
+        assert_eq!(tb.logical_line_count(), 2);
+        assert_eq!(tb.hex_cursor_offset(), 1);
+
+        tb.redo();
+        assert_eq!(tb.extract_all(), b"a\xCA\xA9\nb");
+        assert_eq!(tb.hex_cursor_offset(), 1);
+
+        // Leaving the hex view moves the text cursor to the hex cursor.
+        tb.hex_cursor_move_to_offset(4);
+        tb.set_hex_mode(false);
+        tb.set_width(100);
+        assert_eq!(tb.cursor_logical_pos(), Point { x: 0, y: 1 });
+    }
+
+    #[test]
+    fn test_hex_find_and_replace() {
+        let mut tb = buffer_from_bytes(b"\x00\xDE\xAD\x00\xDE\xAD");
+        tb.set_hex_mode(true);
+        tb.set_width(100);
+
+        assert!(tb.hex_find_and_select(b"\xDE\xAD"));
+        assert_eq!(tb.hex_cursor_offset(), 1);
+        assert!(tb.hex_find_and_select(b"\xDE\xAD"));
+        assert_eq!(tb.hex_cursor_offset(), 4);
+        // Wraps around.
+        assert!(tb.hex_find_and_select(b"\xDE\xAD"));
+        assert_eq!(tb.hex_cursor_offset(), 1);
+        assert!(!tb.hex_find_and_select(b"\xBE\xEF"));
+
+        assert!(tb.hex_find_and_replace(b"\xDE\xAD", b"\xBE\xEF"));
+        assert_eq!(tb.extract_all(), b"\x00\xBE\xEF\x00\xDE\xAD");
+        assert_eq!(tb.hex_cursor_offset(), 4);
+
+        assert!(tb.hex_find_and_replace_all(b"\x00", b""));
+        assert_eq!(tb.extract_all(), b"\xBE\xEF\xDE\xAD");
+        tb.undo();
+        assert_eq!(tb.extract_all(), b"\x00\xBE\xEF\x00\xDE\xAD");
+    }
+
+    #[test]
+    fn test_hex_cut_copy_paste() {
+        let mut tb = buffer_from_bytes(b"\x00\xDE\xAD\x00");
+        tb.set_hex_mode(true);
+        tb.set_width(100);
+        let mut clipboard = Clipboard::default();
+
+        // The text cursor is still at the start and must not be what's copied.
+        assert!(tb.hex_find_and_select(b"\xDE\xAD"));
+        tb.copy(&mut clipboard);
+        assert_eq!(clipboard.read(), b"\xDE\xAD");
+        assert!(!clipboard.is_line_copy());
+
+        tb.cut(&mut clipboard);
+        assert_eq!(tb.extract_all(), b"\x00\x00");
+        assert_eq!(tb.hex_cursor_offset(), 1);
+
+        // Pasting inserts at the hex cursor...
+        tb.paste(&clipboard);
+        assert_eq!(tb.extract_all(), b"\x00\xDE\xAD\x00");
+        assert_eq!(tb.hex_cursor_offset(), 3);
+
+        // ...and without a selection, copying takes the byte under it.
+        tb.copy(&mut clipboard);
+        assert_eq!(clipboard.read(), b"\x00");
+
+        tb.undo();
+        assert_eq!(tb.extract_all(), b"\x00\x00");
+        tb.undo();
+        assert_eq!(tb.extract_all(), b"\x00\xDE\xAD\x00");
+    }
+
+    #[test]
+    fn test_render_hex() {
+        let mut tb = buffer_from_bytes(b"Hello, world!\n\x00\xFF\x7F");
+        tb.set_hex_mode(true);
+        tb.set_width(100);
+        assert_eq!(tb.visual_line_count(), 2);
+
+        let mut fb = Framebuffer::new();
+        let size = Size { width: 80, height: 3 };
+        fb.flip(size);
+        tb.render(Point::default(), Rect { left: 0, top: 0, right: 80, bottom: 3 }, false, &mut fb);
+
+        let snapshot = fb.snapshot();
+        let lines: Vec<_> = snapshot.lines().take(3).map(str::trim_end).collect();
+        assert_eq!(
+            lines,
+            [
+                "00000000 │ 48 65 6C 6C 6F 2C 20 77  6F 72 6C 64 21 0A 00 FF │ Hello, world!...",
+                "00000010 │ 7F                                               │ .",
+                "         │",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_bidi() {
+        let mut tb = buffer_from_bytes("abc (אבג) d\nשלום (x\n".as_bytes());
+        tb.set_width(80);
+
+        let mut fb = Framebuffer::new();
+        fb.flip(Size { width: 20, height: 3 });
+        tb.cursor_move_to_logical(Point { x: 5, y: 0 });
+        tb.render(Point::default(), Rect { left: 0, top: 0, right: 20, bottom: 3 }, true, &mut fb);
+
+        let snapshot = fb.snapshot();
+        let lines: Vec<_> = snapshot.lines().take(2).map(str::trim_end).collect();
+        assert_eq!(lines, ["abc (גבא) d", "x) םולש"]);
+
+        // The cursor is in front of "א", which is displayed at column 7.
+        assert_eq!(tb.visual_pos_from_displayed(Point { x: 7, y: 0 }), Point { x: 5, y: 0 });
+        assert_eq!(tb.visual_pos_from_displayed(Point { x: 5, y: 0 }), Point { x: 7, y: 0 });
+        assert_eq!(tb.visual_pos_from_displayed(Point { x: 2, y: 0 }), Point { x: 2, y: 0 });
+
+        // Moving right moves backwards through the right-to-left text and then exits it.
+        let mut positions = Vec::new();
+        for _ in 0..4 {
+            tb.cursor_move_delta_displayed(1);
+            positions.push(tb.cursor_logical_pos().x);
+        }
+        assert_eq!(positions, [8, 9, 10, 11]);
+        tb.cursor_move_to_logical(Point { x: 4, y: 0 });
+        tb.cursor_move_delta_displayed(1);
+        assert_eq!(tb.cursor_logical_pos().x, 7);
+    }
+
+    #[derive(Clone, Debug)]
+    enum EditOp {
+        Move(prop::sample::Index),
+        Write(Vec<u8>),
+        Delete(CoordType),
+        Undo,
+        Redo,
+    }
+
+    fn apply_edit_op(tb: &mut TextBuffer, op: &EditOp) {
+        match op {
+            EditOp::Move(index) => tb.cursor_move_to_offset(index.index(tb.text_length() + 1)),
+            EditOp::Write(text) => tb.write_raw(text),
+            EditOp::Delete(delta) => tb.delete(CursorMovement::Grapheme, *delta),
+            EditOp::Undo => tb.undo(),
+            EditOp::Redo => tb.redo(),
+        }
+    }
+
+    fn arb_edit_op() -> impl Strategy<Value = EditOp> {
+        const PIECES: &[&[u8]] = &[
+            b"a",
+            b"foo",
+            b"bar baz",
+            b" ",
+            b"\t",
+            b"\n",
+            b"\r\n",
+            b"\r",
+            b"-",
+            "\u{6f22}\u{5b57}".as_bytes(),
+            "e\u{301}".as_bytes(),
+            b"\xff",
+            b"\xe6\xbc",
+        ];
+        let text =
+            prop::collection::vec(prop::sample::select(PIECES), 1..8).prop_map(|p| p.concat());
+        prop_oneof![
+            3 => any::<prop::sample::Index>().prop_map(EditOp::Move),
+            4 => text.prop_map(EditOp::Write),
+            2 => (-4..=4 as CoordType).prop_map(EditOp::Delete),
+            1 => Just(EditOp::Undo),
+            1 => Just(EditOp::Redo),
+        ]
+    }
+
+    fn buffer_bytes(tb: &TextBuffer) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        loop {
+            let chunk = tb.read_forward(bytes.len());
+            if chunk.is_empty() {
+                return bytes;
+            }
+            bytes.extend_from_slice(chunk);
+        }
+    }
+
+    fn wrapped_buffer(width: CoordType, tab_size: CoordType) -> TextBuffer {
+        let mut tb = TextBuffer::new(false).unwrap();
+        tb.set_crlf(false);
+        tb.set_tab_size(tab_size);
+        tb.set_word_wrap(true);
+        tb.set_width(width);
+        tb
+    }
+
+    proptest! {
+        #[test]
+        fn prop_word_wrap_reflow(
+            // Tabs are at most 8 columns wide and are never wrapped, so they must fit on a line.
+            width in 8..24 as CoordType,
+            tab_size in 1..9 as CoordType,
+            ops in prop::collection::vec(arb_edit_op(), 1..24),
+        ) {
+            let mut tb = wrapped_buffer(width, tab_size);
+
+            for op in &ops {
+                apply_edit_op(&mut tb, op);
+
+                // The incrementally updated layout must match the one of a freshly loaded buffer.
+                let text = buffer_bytes(&tb);
+                let mut fresh = wrapped_buffer(width, tab_size);
+                fresh.write_raw(&text);
+                prop_assert_eq!(buffer_bytes(&fresh), text);
+                prop_assert_eq!(tb.logical_line_count(), fresh.logical_line_count());
+                prop_assert_eq!(tb.visual_line_count(), fresh.visual_line_count());
+
+                fresh.cursor_move_to_offset(tb.cursor.offset);
+                prop_assert_eq!(tb.cursor.offset, fresh.cursor.offset);
+                prop_assert_eq!(tb.cursor_logical_pos(), fresh.cursor_logical_pos());
+                prop_assert_eq!(tb.cursor_visual_pos(), fresh.cursor_visual_pos());
+            }
+        }
+
+        #[test]
+        fn prop_content_issues(
+            text in prop::collection::vec(
+                prop::sample::select(&[b'a', b'\r', b'\n', 0xE6, 0xBC, 0xA2, 0xF0, 0x80, 0xFF][..]),
+                0..32,
+            ),
+            ops in prop::collection::vec(arb_edit_op(), 1..24),
+        ) {
+            let mut tb = buffer_from_bytes(&text);
+            tb.content_issues();
+
+            for op in &ops {
+                apply_edit_op(&mut tb, op);
+
+                // The incrementally updated counts must match those of a full scan.
+                // Undo depends on the newline type, so it has to be LF again after the loop.
+                let mut fresh = buffer_from_bytes(&buffer_bytes(&tb));
+                for crlf in [true, false] {
+                    tb.set_crlf(crlf);
+                    fresh.set_crlf(crlf);
+                    prop_assert_eq!(tb.content_issues(), fresh.content_issues());
+                }
+            }
+        }
+    }
+}
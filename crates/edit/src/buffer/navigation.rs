@@ -103,11 +103,13 @@ impl WordNavigation for WordForward<'_> {
     fn skip_newline(&mut self) {
         // We can rely on the fact that the document does not split graphemes across chunks.
         // = If there's a newline it's wholly contained in this chunk.
-        // Unlike with `WordBackward`, we can't check for CR and LF separately as only a CR followed
-        // by a LF is a newline. A lone CR in the document is just a regular control character.
+        // Unlike with `WordBackward`, we can't check for CR and LF separately as a CR followed
+        // by a LF must be skipped as a whole. A lone CR is skipped like a newline, just like
+        // `WordBackward` does. Otherwise, the cursor would get stuck in front of it.
         self.chunk_off += match self.chunk.get(self.chunk_off) {
             Some(&b'\n') => 1,
             Some(&b'\r') if self.chunk.get(self.chunk_off + 1) == Some(&b'\n') => 2,
+            Some(&b'\r') => 1,
             _ => 0,
         }
     }
@@ -273,7 +275,10 @@ pub fn word_select(doc: &dyn ReadableDocument, offset: usize) -> Range<usize> {
 
 #[cfg(test)]
 mod test {
+    use proptest::prelude::*;
+
     use super::*;
+    use crate::buffer::gap_buffer::GapBuffer;
 
     #[test]
     fn test_word_navigation() {
@@ -281,10 +286,59 @@ mod test {
         assert_eq!(word_forward(&"Hello,World".as_bytes(), 0), 5);
         assert_eq!(word_forward(&"   Hello".as_bytes(), 0), 8);
         assert_eq!(word_forward(&"\n\nHello".as_bytes(), 0), 1);
+        assert_eq!(word_forward(&"\r\rHello".as_bytes(), 0), 1);
 
         assert_eq!(word_backward(&"Hello World".as_bytes(), 11), 6);
         assert_eq!(word_backward(&"Hello,World".as_bytes(), 10), 6);
         assert_eq!(word_backward(&"Hello   ".as_bytes(), 7), 0);
         assert_eq!(word_backward(&"Hello\n\n".as_bytes(), 7), 6);
     }
+
+    fn arb_text() -> impl Strategy<Value = Vec<u8>> {
+        const PIECES: &[&[u8]] = &[
+            b"a",
+            b"foo",
+            b" ",
+            b"\t",
+            b"\n",
+            b"\r\n",
+            b"\r",
+            b",",
+            b"-",
+            "\u{e9}".as_bytes(),
+            b"\xff",
+        ];
+        prop::collection::vec(prop::sample::select(PIECES), 0..32).prop_map(|p| p.concat())
+    }
+
+    proptest! {
+        #[test]
+        fn prop_word_navigation(text in arb_text(), gap in any::<prop::sample::Index>()) {
+            let doc = &text[..];
+
+            // Put the gap anywhere, except inside a CRLF, since documents don't split those.
+            let mut gap = gap.index(text.len() + 1);
+            if gap > 0 && text[gap - 1] == b'\r' && text.get(gap) == Some(&b'\n') {
+                gap -= 1;
+            }
+            let mut buf = GapBuffer::new(true).unwrap();
+            buf.replace(0..0, &text);
+            buf.replace(gap..gap, b"");
+
+            for off in 0..=text.len() {
+                let forward = word_forward(&doc, off);
+                prop_assert_eq!(word_forward(&buf, off), forward);
+                prop_assert!(forward <= text.len());
+                prop_assert!(forward > off || off == text.len());
+
+                let backward = word_backward(&doc, off);
+                prop_assert_eq!(word_backward(&buf, off), backward);
+                prop_assert!(backward < off || off == 0);
+
+                let select = word_select(&doc, off);
+                prop_assert_eq!(word_select(&buf, off), select.clone());
+                prop_assert!(select.start <= off && off <= select.end && select.end <= text.len());
+            }
+        }
+    }
 }
@@ -0,0 +1,338 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Built-in converters for a handful of common encodings.
+//!
+//! [`crate::icu::Converter`] falls back to these if ICU isn't installed,
+//! so that at least UTF-16/32 and Western European files can be opened.
+
+use std::mem::MaybeUninit;
+use std::str;
+
+/// The encodings we support, by the labels ICU uses for them.
+pub const LABELS: [&str; 8] = [
+    "UTF-16LE",
+    "UTF-16BE",
+    "UTF-32LE",
+    "UTF-32BE",
+    "ISO-8859-1",
+    "windows-1252",
+    "US-ASCII",
+    "UTF-8",
+];
+
+/// Returns whether `label` names one of the built-in encodings.
+pub fn is_supported(label: &str) -> bool {
+    Charset::from_label(label).is_some()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Charset {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Utf32Le,
+    Utf32Be,
+    Latin1,
+    Windows1252,
+    Ascii,
+}
+
+impl Charset {
+    fn from_label(label: &str) -> Option<Self> {
+        const ALIASES: [(&str, Charset); 12] = [
+            ("UTF-8", Charset::Utf8),
+            ("UTF-16LE", Charset::Utf16Le),
+            ("UTF-16BE", Charset::Utf16Be),
+            ("UTF-32LE", Charset::Utf32Le),
+            ("UTF-32BE", Charset::Utf32Be),
+            ("ISO-8859-1", Charset::Latin1),
+            ("latin1", Charset::Latin1),
+            ("windows-1252", Charset::Windows1252),
+            ("cp1252", Charset::Windows1252),
+            ("US-ASCII", Charset::Ascii),
+            ("ASCII", Charset::Ascii),
+            ("UTF8", Charset::Utf8),
+        ];
+        ALIASES.iter().find(|(l, _)| l.eq_ignore_ascii_case(label)).map(|&(_, c)| c)
+    }
+
+    fn decode(self, bytes: &[u8]) -> Decoded {
+        let Some(&b) = bytes.first() else {
+            return Decoded::Incomplete;
+        };
+
+        match self {
+            Self::Utf8 => {
+                let chunk = &bytes[..bytes.len().min(4)];
+                let valid = match str::from_utf8(chunk) {
+                    Ok(s) => s,
+                    Err(err) if err.valid_up_to() > 0 => unsafe {
+                        str::from_utf8_unchecked(&chunk[..err.valid_up_to()])
+                    },
+                    Err(err) => {
+                        return match err.error_len() {
+                            Some(len) => Decoded::Char(char::REPLACEMENT_CHARACTER, len),
+                            None => Decoded::Incomplete,
+                        };
+                    }
+                };
+                let ch = valid.chars().next().unwrap();
+                Decoded::Char(ch, ch.len_utf8())
+            }
+            Self::Utf16Le | Self::Utf16Be => {
+                let unit = |i: usize| {
+                    let b = [bytes[i], bytes[i + 1]];
+                    if self == Self::Utf16Le {
+                        u16::from_le_bytes(b)
+                    } else {
+                        u16::from_be_bytes(b)
+                    }
+                };
+
+                if bytes.len() < 2 {
+                    return Decoded::Incomplete;
+                }
+                let hi = unit(0);
+                if !(0xd800..0xdc00).contains(&hi) {
+                    let ch = char::from_u32(hi as u32).unwrap_or(char::REPLACEMENT_CHARACTER);
+                    return Decoded::Char(ch, 2);
+                }
+                if bytes.len() < 4 {
+                    return Decoded::Incomplete;
+                }
+                let lo = unit(2);
+                if !(0xdc00..0xe000).contains(&lo) {
+                    return Decoded::Char(char::REPLACEMENT_CHARACTER, 2);
+                }
+                let cp = 0x10000 + (((hi as u32) - 0xd800) << 10) + ((lo as u32) - 0xdc00);
+                Decoded::Char(char::from_u32(cp).unwrap(), 4)
+            }
+            Self::Utf32Le | Self::Utf32Be => {
+                let Some(&b) = bytes.first_chunk::<4>() else {
+                    return Decoded::Incomplete;
+                };
+                let cp = if self == Self::Utf32Le {
+                    u32::from_le_bytes(b)
+                } else {
+                    u32::from_be_bytes(b)
+                };
+                Decoded::Char(char::from_u32(cp).unwrap_or(char::REPLACEMENT_CHARACTER), 4)
+            }
+            Self::Latin1 => Decoded::Char(b as char, 1),
+            Self::Windows1252 => {
+                let ch = match b {
+                    0x80..=0x9f => WINDOWS_1252_C1[b as usize - 0x80],
+                    _ => b as char,
+                };
+                Decoded::Char(ch, 1)
+            }
+            Self::Ascii => {
+                let ch = if b.is_ascii() { b as char } else { char::REPLACEMENT_CHARACTER };
+                Decoded::Char(ch, 1)
+            }
+        }
+    }
+
+    /// Encodes `ch` into `out`, which must be at least 4 bytes large, and returns its length.
+    fn encode(self, ch: char, out: &mut [u8]) -> usize {
+        // Like ICU, we substitute characters the encoding can't represent.
+        let single_byte = |b: Option<u8>, out: &mut [u8]| {
+            out[0] = b.unwrap_or(b'?');
+            1
+        };
+
+        match self {
+            Self::Utf8 => ch.encode_utf8(out).len(),
+            Self::Utf16Le | Self::Utf16Be => {
+                let mut units = [0; 2];
+                let units = ch.encode_utf16(&mut units);
+                for (i, &u) in units.iter().enumerate() {
+                    let b = if self == Self::Utf16Le { u.to_le_bytes() } else { u.to_be_bytes() };
+                    out[i * 2..i * 2 + 2].copy_from_slice(&b);
+                }
+                units.len() * 2
+            }
+            Self::Utf32Le => {
+                out[..4].copy_from_slice(&(ch as u32).to_le_bytes());
+                4
+            }
+            Self::Utf32Be => {
+                out[..4].copy_from_slice(&(ch as u32).to_be_bytes());
+                4
+            }
+            Self::Latin1 => single_byte(u8::try_from(ch).ok(), out),
+            Self::Windows1252 => {
+                let b = match WINDOWS_1252_C1.iter().position(|&c| c == ch) {
+                    Some(i) => Some(0x80 + i as u8),
+                    None if ('\u{80}'..='\u{9f}').contains(&ch) => None,
+                    None => u8::try_from(ch).ok(),
+                };
+                single_byte(b, out)
+            }
+            Self::Ascii => single_byte(Some(ch as u8).filter(u8::is_ascii), out),
+        }
+    }
+}
+
+enum Decoded {
+    /// A character and the number of bytes it took up.
+    /// Invalid sequences are returned as U+FFFD.
+    Char(char, usize),
+    /// The input ends in the middle of a character.
+    Incomplete,
+}
+
+// 0x80-0x9F of Windows-1252. The 5 unassigned bytes map to the C1 controls, like on the web.
+const WINDOWS_1252_C1: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8d}', 'Ž', '\u{8f}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9d}', 'ž', 'Ÿ',
+];
+
+/// Converts between two of the built-in encodings.
+/// Works like [`crate::icu::Converter`], including buffering partial characters across calls.
+pub struct Converter {
+    source: Charset,
+    target: Charset,
+    partial: [u8; 4],
+    partial_len: usize,
+}
+
+impl Converter {
+    /// Returns `None` if either encoding isn't one of [`LABELS`].
+    pub fn new(source_encoding: &str, target_encoding: &str) -> Option<Self> {
+        Some(Self {
+            source: Charset::from_label(source_encoding)?,
+            target: Charset::from_label(target_encoding)?,
+            partial: [0; 4],
+            partial_len: 0,
+        })
+    }
+
+    /// Converts as much of `input` into `output` as fits.
+    /// An empty `input` flushes an incomplete character at the end of the text as U+FFFD.
+    ///
+    /// Returns the number of bytes read from `input` and written to `output`.
+    pub fn convert(&mut self, input: &[u8], output: &mut [MaybeUninit<u8>]) -> (usize, usize) {
+        let flush = input.is_empty();
+        let mut input_pos = 0;
+        let mut output_pos = 0;
+        let mut encoded = [0; 4];
+
+        while output.len() - output_pos >= 4 {
+            let ch = if self.partial_len > 0 {
+                // Complete the character that the previous call left unfinished.
+                match self.source.decode(&self.partial[..self.partial_len]) {
+                    Decoded::Char(ch, len) => {
+                        // An invalid sequence only spans the bytes before the one that
+                        // made it invalid. The rest stays around to be decoded next.
+                        self.partial.copy_within(len..self.partial_len, 0);
+                        self.partial_len -= len;
+                        ch
+                    }
+                    Decoded::Incomplete if flush => {
+                        self.partial_len = 0;
+                        char::REPLACEMENT_CHARACTER
+                    }
+                    Decoded::Incomplete if input_pos < input.len() => {
+                        self.partial[self.partial_len] = input[input_pos];
+                        self.partial_len += 1;
+                        input_pos += 1;
+                        continue;
+                    }
+                    Decoded::Incomplete => break,
+                }
+            } else if input_pos < input.len() {
+                match self.source.decode(&input[input_pos..]) {
+                    Decoded::Char(ch, len) => {
+                        input_pos += len;
+                        ch
+                    }
+                    Decoded::Incomplete => {
+                        let rest = &input[input_pos..];
+                        self.partial[..rest.len()].copy_from_slice(rest);
+                        self.partial_len = rest.len();
+                        input_pos = input.len();
+                        continue;
+                    }
+                }
+            } else {
+                break;
+            };
+
+            let len = self.target.encode(ch, &mut encoded);
+            for (dst, &src) in output[output_pos..output_pos + len].iter_mut().zip(&encoded) {
+                dst.write(src);
+            }
+            output_pos += len;
+        }
+
+        (input_pos, output_pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(from: &str, to: &str, input: &[u8], step: usize) -> Vec<u8> {
+        let mut c = Converter::new(from, to).unwrap();
+        let mut output = [MaybeUninit::uninit(); 64];
+        let mut result = Vec::new();
+
+        // An empty chunk flushes, so it must only be passed once at the end.
+        for mut chunk in input.chunks(step) {
+            while !chunk.is_empty() {
+                let (read, written) = c.convert(chunk, &mut output);
+                result.extend(output[..written].iter().map(|b| unsafe { b.assume_init() }));
+                chunk = &chunk[read..];
+            }
+        }
+        loop {
+            let (_, written) = c.convert(&[], &mut output);
+            if written == 0 {
+                break;
+            }
+            result.extend(output[..written].iter().map(|b| unsafe { b.assume_init() }));
+        }
+
+        result
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let text = "Grüße, 世界! 🎉 “€”";
+        for label in LABELS {
+            let charset = Charset::from_label(label).unwrap();
+            if matches!(charset, Charset::Latin1 | Charset::Windows1252 | Charset::Ascii) {
+                continue;
+            }
+            for step in [1, 3, 64] {
+                let encoded = convert("UTF-8", label, text.as_bytes(), step);
+                let decoded = convert(label, "UTF-8", &encoded, step);
+                assert_eq!(str::from_utf8(&decoded).unwrap(), text, "{label} in steps of {step}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_single_byte() {
+        let text = b"Gr\xfc\xdfe \x80\x93";
+        assert_eq!(convert("windows-1252", "UTF-8", text, 64), "Grüße €“".as_bytes());
+        assert_eq!(convert("ISO-8859-1", "UTF-8", text, 64), "Grüße \u{80}\u{93}".as_bytes());
+        assert_eq!(convert("UTF-8", "windows-1252", "Grüße €“".as_bytes(), 1), text);
+        assert_eq!(convert("UTF-8", "US-ASCII", "Grüße".as_bytes(), 64), b"Gr??e");
+    }
+
+    #[test]
+    fn test_invalid() {
+        // A truncated sequence followed by a valid character, and one at the very end.
+        assert_eq!(convert("UTF-8", "UTF-16LE", b"\xe4\xb8a\xe4", 1), b"\xfd\xffa\0\xfd\xff");
+        // A lone surrogate.
+        assert_eq!(convert("UTF-16LE", "UTF-8", b"\x00\xd8a\0", 64), "\u{fffd}a".as_bytes());
+        // An odd number of bytes.
+        assert_eq!(convert("UTF-16BE", "UTF-8", b"\0a\0", 64), "a\u{fffd}".as_bytes());
+        // A lone surrogate split across two calls.
+        assert_eq!(convert("UTF-16LE", "UTF-8", b"\x00\xd8a\0", 3), "\u{fffd}a".as_bytes());
+    }
+}
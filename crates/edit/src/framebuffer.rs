@@ -5,16 +5,16 @@
 
 use std::cell::Cell;
 use std::fmt::Write;
-use std::ops::{BitOr, BitXor};
-use std::ptr;
+use std::ops::{BitOr, BitXor, Range};
 use std::slice::ChunksExact;
+use std::{iter, ptr};
 
-use stdext::arena::{Arena, ArenaString};
+use stdext::arena::{Arena, ArenaString, scratch_arena};
 
 use crate::helpers::{CoordType, Point, Rect, Size};
-use crate::oklab::StraightRgba;
+use crate::oklab::{Oklab, StraightRgba};
 use crate::simd::{MemsetSafe, memset};
-use crate::unicode::MeasurementConfig;
+use crate::unicode::{self, MeasurementConfig};
 
 // Same constants as used in the PCG family of RNGs.
 #[cfg(target_pointer_width = "32")]
@@ -86,6 +86,82 @@ pub const DEFAULT_THEME: [StraightRgba; INDEXED_COLORS_COUNT] = [
     StraightRgba::from_be(0xbebebeff), // Foreground
 ];
 
+/// The number of colors the terminal is able to display.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColorDepth {
+    /// 24-bit colors via `CSI 38;2;<r>;<g>;<b> m`.
+    TrueColor,
+    /// The xterm 256-color palette via `CSI 38;5;<n> m`.
+    Indexed256,
+    /// The 16 standard VT colors via `CSI 30-37 m` and `CSI 90-97 m`.
+    Indexed16,
+}
+
+impl ColorDepth {
+    /// Guesses the color depth from the environment.
+    ///
+    /// `da1_class` is the first parameter of the terminal's DA1 response
+    /// (`CSI ? <class> ; ... c`), if any. The `EDIT_COLOR_DEPTH` environment
+    /// variable (`truecolor`, `256` or `16`) overrides the detection.
+    pub fn detect(da1_class: Option<u16>) -> Self {
+        let var = |key| std::env::var(key).ok();
+        Self::detect_from(
+            var("EDIT_COLOR_DEPTH").as_deref(),
+            var("COLORTERM").as_deref(),
+            var("TERM").as_deref(),
+            da1_class,
+        )
+    }
+
+    fn detect_from(
+        force: Option<&str>,
+        colorterm: Option<&str>,
+        term: Option<&str>,
+        da1_class: Option<u16>,
+    ) -> Self {
+        match force {
+            Some("truecolor" | "24bit") => return Self::TrueColor,
+            Some("256") => return Self::Indexed256,
+            Some("16") => return Self::Indexed16,
+            _ => {}
+        }
+
+        if let Some("truecolor" | "24bit") = colorterm {
+            return Self::TrueColor;
+        }
+
+        let term = term.unwrap_or("");
+        let has_256 = term.contains("256color");
+
+        if term.ends_with("-direct") {
+            return Self::TrueColor;
+        }
+        if matches!(term, "linux" | "dumb" | "ansi")
+            || term.starts_with("vt")
+            || term.starts_with("cons")
+        {
+            return Self::Indexed16;
+        }
+        // screen and tmux only pass through 24-bit colors if the user configured them to,
+        // in which case they hopefully also set COLORTERM.
+        if term.starts_with("screen") || term.starts_with("tmux") {
+            return if has_256 { Self::Indexed256 } else { Self::Indexed16 };
+        }
+
+        // Modern terminals report themselves as a VT220 or later (class 6x).
+        // The Linux console and GNU screen pretend to be a VT100/VT102.
+        if let Some(class) = da1_class
+            && class < 60
+        {
+            return if has_256 { Self::Indexed256 } else { Self::Indexed16 };
+        }
+
+        // Most terminals nowadays support 24-bit colors, even if they don't say so,
+        // for instance because COLORTERM isn't forwarded via SSH.
+        Self::TrueColor
+    }
+}
+
 /// A shoddy framebuffer for terminal applications.
 ///
 /// The idea is that you create a [`Framebuffer`], draw a bunch of text and
@@ -112,6 +188,12 @@ pub struct Framebuffer {
     /// A cache table for previously contrasted colors.
     /// See: <https://fgiesen.wordpress.com/2019/02/11/cache-tables/>
     contrast_colors: [Cell<(StraightRgba, StraightRgba)>; CACHE_TABLE_SIZE],
+    /// The color depth used by [`Framebuffer::render`].
+    color_depth: ColorDepth,
+    /// The palette colors in Oklab space we quantize to, or empty for [`ColorDepth::TrueColor`].
+    quantize_palette: Vec<Oklab>,
+    /// A cache table for previously quantized colors, mapping them to palette indices.
+    quantized_colors: [Cell<(StraightRgba, u8)>; CACHE_TABLE_SIZE],
     background_fill: StraightRgba,
     foreground_fill: StraightRgba,
 }
@@ -130,6 +212,9 @@ impl Framebuffer {
             auto_color_threshold: 0.5,
             contrast_colors: [const { Cell::new((StraightRgba::zero(), StraightRgba::zero())) };
                 CACHE_TABLE_SIZE],
+            color_depth: ColorDepth::TrueColor,
+            quantize_palette: Vec::new(),
+            quantized_colors: [const { Cell::new((StraightRgba::zero(), 0)) }; CACHE_TABLE_SIZE],
             background_fill: DEFAULT_THEME[IndexedColor::Background as usize],
             foreground_fill: DEFAULT_THEME[IndexedColor::Foreground as usize],
         }
@@ -161,6 +246,50 @@ impl Framebuffer {
         if lightness[0] > lightness[1] {
             self.auto_colors.swap(0, 1);
         }
+
+        self.update_quantize_palette();
+    }
+
+    /// Sets the color depth used for rendering.
+    ///
+    /// Colors are quantized to the closest palette color (in Oklab space)
+    /// if the terminal doesn't support 24-bit colors.
+    pub fn set_color_depth(&mut self, depth: ColorDepth) {
+        self.color_depth = depth;
+        self.update_quantize_palette();
+    }
+
+    fn update_quantize_palette(&mut self) {
+        // The first 16 colors of the 256-color palette are the same as the 16 VT colors,
+        // which are user-configurable. We skip them in favor of the standardized remainder.
+        const CUBE_LEVELS: [u32; 6] = [0, 95, 135, 175, 215, 255];
+        let rgb = |r: u32, g: u32, b: u32| {
+            StraightRgba::from_le(0xff000000 | (b << 16) | (g << 8) | r).as_oklab()
+        };
+
+        self.quantize_palette.clear();
+        match self.color_depth {
+            ColorDepth::TrueColor => {}
+            ColorDepth::Indexed256 => {
+                for r in CUBE_LEVELS {
+                    for g in CUBE_LEVELS {
+                        for b in CUBE_LEVELS {
+                            self.quantize_palette.push(rgb(r, g, b));
+                        }
+                    }
+                }
+                for i in 0..24 {
+                    let v = 8 + i * 10;
+                    self.quantize_palette.push(rgb(v, v, v));
+                }
+            }
+            ColorDepth::Indexed16 => {
+                self.quantize_palette
+                    .extend(self.indexed_colors[..16].iter().map(|c| c.as_oklab()));
+            }
+        }
+
+        self.quantized_colors.fill(Cell::new((StraightRgba::zero(), 0)));
     }
 
     /// Begins a new frame with the given `size`.
@@ -171,6 +300,7 @@ impl Framebuffer {
                 buffer.bg_bitmap = Bitmap::new(size);
                 buffer.fg_bitmap = Bitmap::new(size);
                 buffer.attributes = AttributeBuffer::new(size);
+                buffer.links = LinkBuffer::new(size);
             }
 
             let front = &mut self.buffers[self.frame_counter & 1];
@@ -188,6 +318,7 @@ impl Framebuffer {
         back.bg_bitmap.fill(self.background_fill);
         back.fg_bitmap.fill(self.foreground_fill);
         back.attributes.reset();
+        back.links.reset();
         back.cursor = Cursor::new_disabled();
     }
 
@@ -217,12 +348,14 @@ impl Framebuffer {
     ///   In absolute viewport coordinates.
     /// * `content_offset`: The current offset of the scrollarea.
     /// * `content_height`: The height of the scrollarea content.
+    /// * `ticks`: Content rows to mark on the track, e.g. search hits. Must be sorted.
     pub fn draw_scrollbar(
         &mut self,
         clip_rect: Rect,
         track: Rect,
         content_offset: CoordType,
         content_height: CoordType,
+        ticks: &[CoordType],
     ) -> CoordType {
         let track_clipped = track.intersect(clip_rect);
         if track_clipped.is_empty() {
@@ -325,6 +458,31 @@ impl Framebuffer {
             self.blend_fg(rect, self.indexed(IndexedColor::BrightBlack));
         }
 
+        // Draw the tick marks next to (but not on top of) the thumb.
+        let thumb_rows = thumb_top - (top_fract != 0) as CoordType
+            ..thumb_bottom + (bottom_fract != 0) as CoordType;
+        let mut prev_row = None;
+        for &tick in ticks {
+            let row =
+                track.top + (tick as i64 * 8 * track.height() as i64 / content_height) as CoordType;
+            if prev_row == Some(row) || thumb_rows.contains(&row) {
+                continue;
+            }
+            prev_row = Some(row);
+            if row < track_clipped.top || row >= track_clipped.bottom {
+                continue;
+            }
+
+            self.replace_text(row, track_clipped.left, track_clipped.right, "─");
+            let rect = Rect {
+                left: track_clipped.left,
+                top: row,
+                right: track_clipped.right,
+                bottom: row + 1,
+            };
+            self.blend_fg(rect, self.indexed(IndexedColor::BrightYellow));
+        }
+
         ((thumb_height + 4) / 8) as CoordType
     }
 
@@ -407,12 +565,106 @@ impl Framebuffer {
         }
     }
 
+    /// Rearranges the cells of line `y`, which is used to display bidirectional text.
+    ///
+    /// `spans` are column ranges in viewport coordinates, listed in the order in which
+    /// they should be displayed, starting at the leftmost of their columns. Together they
+    /// must form a contiguous range and they must not split any glyphs. If a span's `bool`
+    /// is true, its first character is replaced with its mirrored glyph, e.g. `(` with `)`.
+    pub fn reorder(&mut self, y: CoordType, spans: &[(Range<CoordType>, bool)]) {
+        let back = &mut self.buffers[self.frame_counter & 1];
+        let size = back.bg_bitmap.size;
+        let Some(line) = back.text.lines.get_mut(y as usize) else {
+            return;
+        };
+
+        let left = spans.iter().map(|s| s.0.start).min().unwrap_or(0);
+        let right = spans.iter().map(|s| s.0.end).max().unwrap_or(0);
+        if left < 0 || right > size.width || left >= right {
+            return;
+        }
+
+        let scratch = scratch_arena(None);
+
+        // Find the byte offset at which each column starts.
+        // Columns in the middle of a wide glyph are marked with `usize::MAX`.
+        let mut offsets = Vec::new_in(&*scratch);
+        offsets.resize(size.width as usize + 1, usize::MAX);
+        {
+            let bytes = line.as_bytes();
+            let mut cfg = MeasurementConfig::new(&bytes);
+            let mut cursor = cfg.cursor();
+            while (cursor.visual_pos.x as usize) < offsets.len() {
+                offsets[cursor.visual_pos.x as usize] = cursor.offset;
+                if cursor.offset >= bytes.len() {
+                    break;
+                }
+                cursor = cfg.goto_logical(Point { x: cursor.logical_pos.x + 1, y: 0 });
+            }
+        }
+
+        let byte_range = |r: &Range<CoordType>| offsets[r.start as usize]..offsets[r.end as usize];
+        if spans
+            .iter()
+            .any(|s| byte_range(&s.0).start == usize::MAX || byte_range(&s.0).end == usize::MAX)
+        {
+            return;
+        }
+
+        let outer = byte_range(&(left..right));
+        let mut text = String::with_capacity(line.len());
+        text.push_str(&line[..outer.start]);
+        for (columns, mirror) in spans {
+            let mut src = &line[byte_range(columns)];
+            if *mirror && let Some(ch) = src.chars().next() {
+                text.push(unicode::bidi_mirror(ch).unwrap_or(ch));
+                src = &src[ch.len_utf8()..];
+            }
+            text.push_str(src);
+        }
+        text.push_str(&line[outer.end..]);
+        *line = text;
+
+        fn permute<T: Copy>(
+            arena: &Arena,
+            row: &mut [T],
+            left: CoordType,
+            spans: &[(Range<CoordType>, bool)],
+        ) {
+            let mut old = Vec::new_in(arena);
+            old.extend_from_slice(row);
+            let mut x = left as usize;
+            for (columns, _) in spans {
+                let src = &old[columns.start as usize..columns.end as usize];
+                row[x..x + src.len()].copy_from_slice(src);
+                x += src.len();
+            }
+        }
+
+        let row = (y * size.width) as usize..((y + 1) * size.width) as usize;
+        permute(&scratch, &mut back.bg_bitmap.data[row.clone()], left, spans);
+        permute(&scratch, &mut back.fg_bitmap.data[row.clone()], left, spans);
+        permute(&scratch, &mut back.attributes.data[row.clone()], left, spans);
+        permute(&scratch, &mut back.links.data[row], left, spans);
+    }
+
     /// Replaces VT attributes in the given rectangle.
     pub fn replace_attr(&mut self, target: Rect, mask: Attributes, attr: Attributes) {
         let back = &mut self.buffers[self.frame_counter & 1];
         back.attributes.replace(target, mask, attr);
     }
 
+    /// Turns the given rectangle into an OSC 8 hyperlink to `url`,
+    /// which allows terminals to open it natively.
+    pub fn set_link(&mut self, target: Rect, url: &str) {
+        // The URL is embedded in a VT sequence and must not terminate it early.
+        if url.is_empty() || url.bytes().any(|b| b < 0x20 || b == 0x7f) {
+            return;
+        }
+        let back = &mut self.buffers[self.frame_counter & 1];
+        back.links.replace(target, url);
+    }
+
     /// Sets the current visible cursor position and type.
     ///
     /// Call this when focus is inside an editable area and you want to show the cursor.
@@ -439,16 +691,19 @@ impl Framebuffer {
         let mut front_bgs = front.bg_bitmap.iter();
         let mut front_fgs = front.fg_bitmap.iter();
         let mut front_attrs = front.attributes.iter();
+        let mut front_links = front.links.iter();
 
         let mut back_lines = back.text.lines.iter();
         let mut back_bgs = back.bg_bitmap.iter();
         let mut back_fgs = back.fg_bitmap.iter();
         let mut back_attrs = back.attributes.iter();
+        let mut back_links = back.links.iter();
 
         let mut result = ArenaString::new_in(arena);
         let mut last_bg = u64::MAX;
         let mut last_fg = u64::MAX;
         let mut last_attr = Attributes::None;
+        let mut last_link = "";
 
         for y in 0..front.text.size.height {
             // SAFETY: The only thing that changes the size of these containers,
@@ -457,11 +712,13 @@ impl Framebuffer {
             let front_bg = unsafe { front_bgs.next().unwrap_unchecked() };
             let front_fg = unsafe { front_fgs.next().unwrap_unchecked() };
             let front_attr = unsafe { front_attrs.next().unwrap_unchecked() };
+            let front_link = unsafe { front_links.next().unwrap_unchecked() };
 
             let back_line = unsafe { back_lines.next().unwrap_unchecked() };
             let back_bg = unsafe { back_bgs.next().unwrap_unchecked() };
             let back_fg = unsafe { back_fgs.next().unwrap_unchecked() };
             let back_attr = unsafe { back_attrs.next().unwrap_unchecked() };
+            let back_link = unsafe { back_links.next().unwrap_unchecked() };
 
             // TODO: Ideally, we should properly diff the contents and so if
             // only parts of a line change, we should only update those parts.
@@ -469,6 +726,8 @@ impl Framebuffer {
                 && front_bg == back_bg
                 && front_fg == back_fg
                 && front_attr == back_attr
+                && iter::zip(front_link, back_link)
+                    .all(|(&f, &b)| front.links.url(f) == back.links.url(b))
             {
                 continue;
             }
@@ -486,6 +745,7 @@ impl Framebuffer {
                 let bg = back_bg[chunk_end];
                 let fg = back_fg[chunk_end];
                 let attr = back_attr[chunk_end];
+                let link = back_link[chunk_end];
 
                 // Chunk into runs of the same color.
                 while {
@@ -494,6 +754,7 @@ impl Framebuffer {
                         && back_bg[chunk_end] == bg
                         && back_fg[chunk_end] == fg
                         && back_attr[chunk_end] == attr
+                        && back_link[chunk_end] == link
                 } {}
 
                 if last_bg != bg.to_ne() as u64 {
@@ -515,8 +776,13 @@ impl Framebuffer {
                             result.push_str("\x1b[23m");
                         }
                     }
-                    if diff.is(Attributes::Underlined) {
-                        if attr.is(Attributes::Underlined) {
+                    if diff.0 & (Attributes::Underlined | Attributes::CurlyUnderlined).0 != 0 {
+                        // A curly underline takes precedence over a straight one.
+                        // Terminals that don't support SGR 4:3 will either ignore it
+                        // or fall back to a straight underline, both of which are fine.
+                        if attr.is(Attributes::CurlyUnderlined) {
+                            result.push_str("\x1b[4:3m");
+                        } else if attr.is(Attributes::Underlined) {
                             result.push_str("\x1b[4m");
                         } else {
                             result.push_str("\x1b[24m");
@@ -525,6 +791,13 @@ impl Framebuffer {
                     last_attr = attr;
                 }
 
+                let url = back.links.url(link);
+                if last_link != url {
+                    // OSC 8 ; params ; URL ST. An empty URL ends the hyperlink.
+                    _ = write!(result, "\x1b]8;;{url}\x1b\\");
+                    last_link = url;
+                }
+
                 let beg = cfg.cursor().offset;
                 let end = cfg.goto_visual(Point { x: chunk_end as CoordType, y: 0 }).offset;
                 result.push_str(&back_line[beg..end]);
@@ -533,6 +806,11 @@ impl Framebuffer {
             } {}
         }
 
+        // Unlike SGR attributes, hyperlinks aren't reset by the `CSI m` at the start of the next frame.
+        if !last_link.is_empty() {
+            result.push_str("\x1b]8;;\x1b\\");
+        }
+
         // If the cursor has changed since the last frame we naturally need to update it,
         // but this also applies if the code above wrote to the screen,
         // as it uses CUP sequences to reposition the cursor for writing.
@@ -557,6 +835,76 @@ impl Framebuffer {
         result
     }
 
+    /// Returns a textual dump of the frame that was last rendered, for use in snapshot tests.
+    ///
+    /// It consists of the text contents, followed by a grid that assigns each cell a letter
+    /// for its combination of colors, attributes and link, a legend for those letters,
+    /// and the cursor position. Colors are written as `#rrggbbaa`.
+    pub fn snapshot(&self) -> String {
+        const STYLE_NAMES: &[u8] =
+            b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+        let back = &self.buffers[self.frame_counter & 1];
+        let size = back.text.size;
+        let mut styles = Vec::new();
+        let mut result = String::new();
+
+        for line in &back.text.lines {
+            result.push_str(line.trim_end_matches(' '));
+            result.push('\n');
+        }
+
+        result.push_str("-- styles\n");
+        for y in 0..size.height as usize {
+            for x in 0..size.width as usize {
+                let idx = y * size.width as usize + x;
+                let style = (
+                    back.bg_bitmap.data[idx],
+                    back.fg_bitmap.data[idx],
+                    back.attributes.data[idx],
+                    back.links.url(back.links.data[idx]),
+                );
+                let i = styles.iter().position(|s| *s == style).unwrap_or_else(|| {
+                    styles.push(style);
+                    styles.len() - 1
+                });
+                result.push(STYLE_NAMES.get(i).map_or('?', |&c| c as char));
+            }
+            result.push('\n');
+        }
+
+        result.push_str("-- legend\n");
+        for (&(bg, fg, attr, url), &name) in styles.iter().zip(STYLE_NAMES) {
+            _ = write!(result, "{}: bg=#{:08x} fg=#{:08x}", name as char, bg.to_be(), fg.to_be());
+            for (a, name) in [
+                (Attributes::Italic, "italic"),
+                (Attributes::Underlined, "underlined"),
+                (Attributes::CurlyUnderlined, "curly-underlined"),
+            ] {
+                if attr.is(a) {
+                    _ = write!(result, " {name}");
+                }
+            }
+            if !url.is_empty() {
+                _ = write!(result, " link={url}");
+            }
+            result.push('\n');
+        }
+
+        result.push_str("-- cursor\n");
+        if back.cursor.pos.x >= 0 && back.cursor.pos.y >= 0 {
+            _ = write!(result, "{},{}", back.cursor.pos.x, back.cursor.pos.y);
+            if back.cursor.overtype {
+                result.push_str(" overtype");
+            }
+            result.push('\n');
+        } else {
+            result.push_str("hidden\n");
+        }
+
+        result
+    }
+
     fn format_color(&self, dst: &mut ArenaString, fg: bool, mut color: StraightRgba) {
         let typ = if fg { '3' } else { '4' };
 
@@ -584,10 +932,57 @@ impl Framebuffer {
             color = dst.oklab_blend(color);
         }
 
-        let r = color.red();
-        let g = color.green();
-        let b = color.blue();
-        _ = write!(dst, "\x1b[{typ}8;2;{r};{g};{b}m");
+        match self.color_depth {
+            ColorDepth::TrueColor => {
+                let r = color.red();
+                let g = color.green();
+                let b = color.blue();
+                _ = write!(dst, "\x1b[{typ}8;2;{r};{g};{b}m");
+            }
+            ColorDepth::Indexed256 => {
+                let idx = self.quantize(color) as u32 + 16;
+                _ = write!(dst, "\x1b[{typ}8;5;{idx}m");
+            }
+            ColorDepth::Indexed16 => {
+                let idx = self.quantize(color) as u32;
+                let (typ, idx) = match (fg, idx) {
+                    (true, 0..8) => (30, idx),
+                    (true, _) => (90, idx - 8),
+                    (false, 0..8) => (40, idx),
+                    (false, _) => (100, idx - 8),
+                };
+                _ = write!(dst, "\x1b[{}m", typ + idx);
+            }
+        }
+    }
+
+    /// Returns the index of the [`Framebuffer::quantize_palette`] entry closest to `color`.
+    fn quantize(&self, color: StraightRgba) -> u8 {
+        let idx = (color.to_ne() as usize).wrapping_mul(HASH_MULTIPLIER) >> CACHE_TABLE_SHIFT;
+        let slot = self.quantized_colors[idx].get();
+        if slot.0 == color { slot.1 } else { self.quantize_slow(color) }
+    }
+
+    #[cold]
+    fn quantize_slow(&self, color: StraightRgba) -> u8 {
+        let idx = (color.to_ne() as usize).wrapping_mul(HASH_MULTIPLIER) >> CACHE_TABLE_SHIFT;
+        let lab = color.as_oklab();
+        let mut best = 0;
+        let mut best_distance = f32::INFINITY;
+
+        for (i, p) in self.quantize_palette.iter().enumerate() {
+            let dl = lab.lightness() - p.lightness();
+            let da = lab.a() - p.a();
+            let db = lab.b() - p.b();
+            let distance = dl * dl + da * da + db * db;
+            if distance < best_distance {
+                best = i as u8;
+                best_distance = distance;
+            }
+        }
+
+        self.quantized_colors[idx].set((color, best));
+        best
     }
 }
 
@@ -597,6 +992,7 @@ struct Buffer {
     bg_bitmap: Bitmap,
     fg_bitmap: Bitmap,
     attributes: AttributeBuffer,
+    links: LinkBuffer,
     cursor: Cursor,
 }
 
@@ -832,7 +1228,8 @@ impl Attributes {
     pub const None: Self = Self(0);
     pub const Italic: Self = Self(0b1);
     pub const Underlined: Self = Self(0b10);
-    pub const All: Self = Self(0b11);
+    pub const CurlyUnderlined: Self = Self(0b100);
+    pub const All: Self = Self(0b111);
 
     pub const fn is(self, attr: Self) -> bool {
         (self.0 & attr.0) == attr.0
@@ -906,6 +1303,61 @@ impl AttributeBuffer {
     }
 }
 
+/// Stores OSC 8 hyperlinks for the framebuffer.
+#[derive(Default)]
+struct LinkBuffer {
+    /// 0 for cells without a link, otherwise 1 + an index into `urls`.
+    data: Vec<u16>,
+    urls: Vec<String>,
+    size: Size,
+}
+
+impl LinkBuffer {
+    fn new(size: Size) -> Self {
+        Self { data: vec![0; (size.width * size.height) as usize], urls: Vec::new(), size }
+    }
+
+    fn reset(&mut self) {
+        memset(&mut self.data, 0);
+        self.urls.clear();
+    }
+
+    fn replace(&mut self, target: Rect, url: &str) {
+        let target = target.intersect(self.size.as_rect());
+        if target.is_empty() {
+            return;
+        }
+
+        let id = match self.urls.iter().position(|u| u == url) {
+            Some(idx) => idx + 1,
+            None if self.urls.len() < u16::MAX as usize => {
+                self.urls.push(url.to_string());
+                self.urls.len()
+            }
+            None => return,
+        } as u16;
+
+        let stride = self.size.width as usize;
+        for y in target.top as usize..target.bottom as usize {
+            let beg = y * stride + target.left as usize;
+            let end = y * stride + target.right as usize;
+            memset(&mut self.data[beg..end], id);
+        }
+    }
+
+    fn url(&self, id: u16) -> &str {
+        match id {
+            0 => "",
+            _ => &self.urls[id as usize - 1],
+        }
+    }
+
+    /// Iterates over each row in the buffer.
+    fn iter(&self) -> ChunksExact<'_, u16> {
+        self.data.chunks_exact(self.size.width as usize)
+    }
+}
+
 /// Stores cursor position and type for the framebuffer.
 #[derive(Default, PartialEq, Eq)]
 struct Cursor {
@@ -922,3 +1374,75 @@ impl Cursor {
         Self { pos: Point { x: -1, y: -1 }, overtype: false }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use stdext::arena::scratch_arena;
+
+    use super::*;
+
+    #[test]
+    fn test_detect_color_depth() {
+        let detect = ColorDepth::detect_from;
+        assert_eq!(detect(None, Some("truecolor"), Some("linux"), None), ColorDepth::TrueColor);
+        assert_eq!(detect(Some("16"), Some("truecolor"), None, None), ColorDepth::Indexed16);
+        assert_eq!(detect(None, None, Some("linux"), Some(6)), ColorDepth::Indexed16);
+        assert_eq!(detect(None, None, Some("screen"), Some(1)), ColorDepth::Indexed16);
+        assert_eq!(detect(None, None, Some("tmux-256color"), None), ColorDepth::Indexed256);
+        assert_eq!(detect(None, None, Some("xterm-256color"), Some(1)), ColorDepth::Indexed256);
+        assert_eq!(detect(None, None, Some("xterm-256color"), Some(64)), ColorDepth::TrueColor);
+        assert_eq!(detect(None, None, None, Some(61)), ColorDepth::TrueColor);
+    }
+
+    #[test]
+    fn test_quantize() {
+        let render = |fb: &mut Framebuffer, color: u32| {
+            let scratch = scratch_arena(None);
+            let mut out = ArenaString::new_in(&scratch);
+            fb.format_color(&mut out, true, StraightRgba::from_be(color));
+            fb.format_color(&mut out, false, StraightRgba::from_be(color));
+            out.to_string()
+        };
+        let mut fb = Framebuffer::new();
+
+        assert_eq!(render(&mut fb, 0x123456ff), "\x1b[38;2;18;52;86m\x1b[48;2;18;52;86m");
+
+        fb.set_color_depth(ColorDepth::Indexed256);
+        assert_eq!(render(&mut fb, 0xff0000ff), "\x1b[38;5;196m\x1b[48;5;196m");
+        assert_eq!(render(&mut fb, 0x808080ff), "\x1b[38;5;244m\x1b[48;5;244m");
+        assert_eq!(render(&mut fb, 0x5f87afff), "\x1b[38;5;67m\x1b[48;5;67m");
+
+        fb.set_color_depth(ColorDepth::Indexed16);
+        assert_eq!(render(&mut fb, 0xff3e30ff), "\x1b[91m\x1b[101m");
+        assert_eq!(render(&mut fb, 0x1a1a1aff), "\x1b[30m\x1b[40m");
+        assert_eq!(render(&mut fb, 0x3fae3aff), "\x1b[32m\x1b[42m");
+    }
+
+    #[test]
+    fn test_links() {
+        let size = Size { width: 8, height: 1 };
+        let mut fb = Framebuffer::new();
+        let render = |fb: &mut Framebuffer, link: Option<&str>| {
+            fb.flip(size);
+            fb.replace_text(0, 0, 8, "ab cd ef");
+            if let Some(url) = link {
+                fb.set_link(Rect { left: 3, top: 0, right: 5, bottom: 1 }, url);
+            }
+            let scratch = scratch_arena(None);
+            let out = fb.render(&scratch);
+            // Returns the URLs of all OSC 8 sequences in the output.
+            out.split("\x1b]8;;")
+                .skip(1)
+                .map(|s| s[..s.find("\x1b\\").unwrap()].to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(render(&mut fb, Some("https://a.b")), ["https://a.b", ""]);
+        // Unchanged links aren't re-emitted.
+        assert!(render(&mut fb, Some("https://a.b")).is_empty());
+        // Removing it rewrites the row without a link.
+        assert!(render(&mut fb, None).is_empty());
+        // Control characters could end the OSC sequence early.
+        assert!(render(&mut fb, Some("https://a\x1b.b")).is_empty());
+    }
+}
@@ -15,7 +15,7 @@ use stdext::arena_format;
 
 use crate::buffer::TextBuffer;
 use crate::unicode::Utf8Chars;
-use crate::{apperr, sys};
+use crate::{apperr, encodings, regex, sys};
 
 #[derive(Clone, Copy)]
 pub struct Encoding {
@@ -31,6 +31,7 @@ pub struct Encodings {
 static mut ENCODINGS: Encodings = Encodings { preferred: &[], all: &[] };
 
 /// Returns a list of encodings ICU supports.
+/// Without ICU, that's the few that [`crate::encodings`] has built in.
 pub fn get_available_encodings() -> &'static Encodings {
     // OnceCell for people that want to put it into a static.
     #[allow(static_mut_refs)]
@@ -75,6 +76,12 @@ pub fn get_available_encodings() -> &'static Encodings {
                         alternative.push(Encoding { label: name, canonical: name });
                     }
                 }
+            } else {
+                for label in encodings::LABELS {
+                    if label != "UTF-8" {
+                        preferred.push(Encoding { label, canonical: label });
+                    }
+                }
             }
 
             let preferred_len = preferred.len();
@@ -93,6 +100,88 @@ pub fn get_available_encodings() -> &'static Encodings {
     }
 }
 
+/// Looks up an encoding by its label, canonical name or any other alias ICU knows,
+/// ignoring case, and returns its canonical name. That's the one the encoding picker stores.
+pub fn find_encoding(name: &str) -> Option<&'static str> {
+    let encodings = get_available_encodings().all;
+    let find = |name: &[u8]| {
+        encodings
+            .iter()
+            .find(|e| {
+                e.label.as_bytes().eq_ignore_ascii_case(name)
+                    || e.canonical.as_bytes().eq_ignore_ascii_case(name)
+            })
+            .map(|e| e.canonical)
+    };
+
+    if let Some(canonical) = find(name.as_bytes()) {
+        return Some(canonical);
+    }
+
+    // Aliases like "windows-1252" don't necessarily have an entry of their own.
+    // Opening a converter resolves them to the canonical name.
+    let f = init_if_needed().ok()?;
+    let scratch = scratch_arena(None);
+    let name = Converter::append_nul(&scratch, name);
+    let mut res = None;
+
+    unsafe {
+        let mut status = icu_ffi::U_ZERO_ERROR;
+        let cnv = (f.ucnv_open)(name.as_ptr(), &mut status);
+        if status.is_success() && !cnv.is_null() {
+            let canonical = (f.ucnv_getName)(cnv, &mut status);
+            if status.is_success() && !canonical.is_null() {
+                res = find(CStr::from_ptr(canonical).to_bytes());
+            }
+        }
+        if !cnv.is_null() {
+            (f.ucnv_close)(cnv);
+        }
+    }
+
+    res
+}
+
+/// Guesses the encoding of `text` using ICU's charset detector.
+///
+/// Returns the encoding's label and ICU's confidence in it (0-100).
+/// Encodings that [`get_available_encodings`] doesn't list are ignored.
+pub fn detect_encoding(text: &[u8]) -> Option<(&'static str, u8)> {
+    let f = init_if_needed().ok()?;
+    let mut res = None;
+
+    unsafe {
+        let mut status = icu_ffi::U_ZERO_ERROR;
+        let detector = (f.ucsdet_open)(&mut status);
+        if status.is_failure() {
+            return None;
+        }
+
+        // The detector only looks at the first few KiB anyway.
+        let len = text.len().min(i32::MAX as usize) as i32;
+        (f.ucsdet_setText)(detector, text.as_ptr() as *const c_char, len, &mut status);
+
+        // The match is owned by the detector.
+        let m = (f.ucsdet_detect)(detector, &mut status);
+        if status.is_success() && !m.is_null() {
+            let name = (f.ucsdet_getName)(m, &mut status);
+            let confidence = (f.ucsdet_getConfidence)(m, &mut status);
+            if status.is_success() && !name.is_null() {
+                let name = CStr::from_ptr(name).to_bytes();
+                res = get_available_encodings()
+                    .all
+                    .iter()
+                    .find(|enc| enc.label.as_bytes().eq_ignore_ascii_case(name))
+                    .map(|enc| (enc.label, confidence.clamp(0, 100) as u8));
+            }
+        }
+
+        (f.ucsdet_close)(detector);
+    }
+
+    res
+}
+
 /// Formats the given ICU error code into a human-readable string.
 pub fn apperr_format(f: &mut std::fmt::Formatter<'_>, code: u32) -> std::fmt::Result {
     fn format(code: u32) -> &'static str {
@@ -110,7 +199,10 @@ pub fn apperr_format(f: &mut std::fmt::Formatter<'_>, code: u32) -> std::fmt::Re
         str.to_str().unwrap_or("")
     }
 
-    let msg = format(code);
+    let mut msg = format(code);
+    if msg.is_empty() {
+        msg = regex::error_name(code).unwrap_or("");
+    }
     if !msg.is_empty() {
         write!(f, "ICU Error: {msg}")
     } else {
@@ -119,7 +211,18 @@ pub fn apperr_format(f: &mut std::fmt::Formatter<'_>, code: u32) -> std::fmt::Re
 }
 
 /// Converts between two encodings using ICU.
+///
+/// If ICU isn't installed, this falls back to the converters in [`crate::encodings`].
 pub struct Converter<'pivot> {
+    backend: Backend<'pivot>,
+}
+
+enum Backend<'pivot> {
+    Icu(IcuConverter<'pivot>),
+    Builtin(encodings::Converter),
+}
+
+struct IcuConverter<'pivot> {
     source: *mut icu_ffi::UConverter,
     target: *mut icu_ffi::UConverter,
     pivot_buffer: &'pivot mut [MaybeUninit<u16>],
@@ -128,7 +231,7 @@ pub struct Converter<'pivot> {
     reset: bool,
 }
 
-impl Drop for Converter<'_> {
+impl Drop for IcuConverter<'_> {
     fn drop(&mut self) {
         let f = assume_loaded();
         unsafe { (f.ucnv_close)(self.source) };
@@ -150,7 +253,11 @@ impl<'pivot> Converter<'pivot> {
         source_encoding: &str,
         target_encoding: &str,
     ) -> apperr::Result<Self> {
-        let f = init_if_needed()?;
+        let Ok(f) = init_if_needed() else {
+            let c = encodings::Converter::new(source_encoding, target_encoding)
+                .ok_or(apperr::APP_ICU_MISSING)?;
+            return Ok(Self { backend: Backend::Builtin(c) });
+        };
 
         let arena = scratch_arena(None);
         let source_encoding = Self::append_nul(&arena, source_encoding);
@@ -172,7 +279,16 @@ impl<'pivot> Converter<'pivot> {
         let pivot_source = pivot_buffer.as_mut_ptr() as *mut u16;
         let pivot_target = unsafe { pivot_source.add(pivot_buffer.len()) };
 
-        Ok(Self { source, target, pivot_buffer, pivot_source, pivot_target, reset: true })
+        Ok(Self {
+            backend: Backend::Icu(IcuConverter {
+                source,
+                target,
+                pivot_buffer,
+                pivot_source,
+                pivot_target,
+                reset: true,
+            }),
+        })
     }
 
     fn append_nul<'a>(arena: &'a Arena, input: &str) -> ArenaString<'a> {
@@ -197,6 +313,19 @@ impl<'pivot> Converter<'pivot> {
         &mut self,
         input: &[u8],
         output: &mut [MaybeUninit<u8>],
+    ) -> apperr::Result<(usize, usize)> {
+        match &mut self.backend {
+            Backend::Icu(c) => c.convert(input, output),
+            Backend::Builtin(c) => Ok(c.convert(input, output)),
+        }
+    }
+}
+
+impl IcuConverter<'_> {
+    fn convert(
+        &mut self,
+        input: &[u8],
+        output: &mut [MaybeUninit<u8>],
     ) -> apperr::Result<(usize, usize)> {
         let f = assume_loaded();
 
@@ -287,12 +416,23 @@ struct DoubleCache {
 /// Warning! No lifetime tracking is done here.
 /// I initially did it properly with a PhantomData marker for the TextBuffer
 /// lifetime, but it was a pain so now I don't. Not a big deal in our case.
-pub struct Text(&'static mut icu_ffi::UText);
+///
+/// If ICU isn't installed, this only holds on to the [`TextBuffer`] for [`Regex`].
+pub struct Text {
+    backend: TextBackend,
+}
+
+enum TextBackend {
+    Icu(&'static mut icu_ffi::UText),
+    Builtin(*const TextBuffer),
+}
 
 impl Drop for Text {
     fn drop(&mut self) {
-        let f = assume_loaded();
-        unsafe { (f.utext_close)(self.0) };
+        if let TextBackend::Icu(ut) = &mut self.backend {
+            let f = assume_loaded();
+            unsafe { (f.utext_close)(*ut) };
+        }
     }
 }
 
@@ -304,7 +444,9 @@ impl Text {
     /// The caller must ensure that the given [`TextBuffer`]
     /// outlives the returned `Text` instance.
     pub unsafe fn new(tb: &TextBuffer) -> apperr::Result<Self> {
-        let f = init_if_needed()?;
+        let Ok(f) = init_if_needed() else {
+            return Ok(Self { backend: TextBackend::Builtin(tb) });
+        };
 
         let mut status = icu_ffi::U_ZERO_ERROR;
         let ptr =
@@ -337,7 +479,7 @@ impl Text {
         ut.context = tb as *const TextBuffer as *mut _;
         ut.a = -1;
 
-        Ok(Self(ut))
+        Ok(Self { backend: TextBackend::Icu(ut) })
     }
 }
 
@@ -595,15 +737,26 @@ extern "C" fn utext_map_native_index_to_utf16(ut: &icu_ffi::UText, native_index:
 
 /// A wrapper around ICU's `URegularExpression` struct.
 ///
+/// If ICU isn't installed, this uses the engine in [`crate::regex`] instead.
+///
 /// # Safety
 ///
 /// Warning! No lifetime tracking is done here.
-pub struct Regex(&'static mut icu_ffi::URegularExpression);
+pub struct Regex {
+    backend: RegexBackend,
+}
+
+enum RegexBackend {
+    Icu(&'static mut icu_ffi::URegularExpression),
+    Builtin(Box<regex::Regex>, *const TextBuffer),
+}
 
 impl Drop for Regex {
     fn drop(&mut self) {
-        let f = assume_loaded();
-        unsafe { (f.uregex_close)(self.0) };
+        if let RegexBackend::Icu(re) = &mut self.backend {
+            let f = assume_loaded();
+            unsafe { (f.uregex_close)(*re) };
+        }
     }
 }
 
@@ -624,7 +777,15 @@ impl Regex {
     ///
     /// The caller must ensure that the given `Text` outlives the returned `Regex` instance.
     pub unsafe fn new(pattern: &str, flags: i32, text: &Text) -> apperr::Result<Self> {
-        let f = init_if_needed()?;
+        let ut = match &text.backend {
+            TextBackend::Icu(ut) => &**ut,
+            TextBackend::Builtin(tb) => {
+                let regex = Box::new(regex::Regex::new(pattern, flags)?);
+                return Ok(Self { backend: RegexBackend::Builtin(regex, *tb) });
+            }
+        };
+
+        let f = assume_loaded();
         unsafe {
             let scratch = scratch_arena(None);
             let mut utf16 = Vec::new_in(&*scratch);
@@ -643,12 +804,12 @@ impl Regex {
             // and "typically [in] the order of milliseconds", but this claim seems
             // highly outdated. On my CPU from 2021, a limit of 4096 equals roughly 600ms.
             (f.uregex_setTimeLimit)(ptr, 4096, &mut status);
-            (f.uregex_setUText)(ptr, text.0 as *const _ as *mut _, &mut status);
+            (f.uregex_setUText)(ptr, ut as *const _ as *mut _, &mut status);
             if status.is_failure() {
                 return Err(status.as_error());
             }
 
-            Ok(Self(&mut *ptr))
+            Ok(Self { backend: RegexBackend::Icu(&mut *ptr) })
         }
     }
 
@@ -660,42 +821,64 @@ impl Regex {
     ///
     /// The caller must ensure that the given `Text` outlives the `Regex` instance.
     pub unsafe fn set_text(&mut self, text: &mut Text, offset: usize) {
-        // Get `utext_access_impl` to detect the `TextBuffer::generation` change,
-        // and refresh its contents. This ensures that ICU doesn't reuse
-        // stale `UText::chunk_contents`, as it has no way tell that it's stale.
-        utext_access(text.0, offset as i64, true);
-
-        let f = assume_loaded();
-        let mut status = icu_ffi::U_ZERO_ERROR;
-        unsafe { (f.uregex_setUText)(self.0, text.0 as *const _ as *mut _, &mut status) };
-        // `uregex_setUText` resets the regex to the start of the text.
-        // Because of this, we must also call `uregex_reset64`.
-        unsafe { (f.uregex_reset64)(self.0, offset as i64, &mut status) };
+        match (&mut self.backend, &mut text.backend) {
+            (RegexBackend::Icu(re), TextBackend::Icu(ut)) => {
+                // Get `utext_access_impl` to detect the `TextBuffer::generation` change,
+                // and refresh its contents. This ensures that ICU doesn't reuse
+                // stale `UText::chunk_contents`, as it has no way tell that it's stale.
+                utext_access(ut, offset as i64, true);
+
+                let f = assume_loaded();
+                let mut status = icu_ffi::U_ZERO_ERROR;
+                unsafe { (f.uregex_setUText)(*re, &mut **ut, &mut status) };
+                // `uregex_setUText` resets the regex to the start of the text.
+                // Because of this, we must also call `uregex_reset64`.
+                unsafe { (f.uregex_reset64)(*re, offset as i64, &mut status) };
+            }
+            (RegexBackend::Builtin(re, tb), TextBackend::Builtin(text_tb)) => {
+                *tb = *text_tb;
+                re.reset(offset);
+            }
+            _ => unreachable!(),
+        }
     }
 
     /// Sets the regex to the absolute offset in the underlying text.
     pub fn reset(&mut self, offset: usize) {
-        let f = assume_loaded();
-        let mut status = icu_ffi::U_ZERO_ERROR;
-        unsafe { (f.uregex_reset64)(self.0, offset as i64, &mut status) };
+        match &mut self.backend {
+            RegexBackend::Icu(re) => {
+                let f = assume_loaded();
+                let mut status = icu_ffi::U_ZERO_ERROR;
+                unsafe { (f.uregex_reset64)(*re, offset as i64, &mut status) };
+            }
+            RegexBackend::Builtin(re, _) => re.reset(offset),
+        }
     }
 
     /// Gets captured group count.
     pub fn group_count(&mut self) -> i32 {
-        let f = assume_loaded();
+        let re = match &mut self.backend {
+            RegexBackend::Icu(re) => re,
+            RegexBackend::Builtin(re, _) => return re.group_count(),
+        };
 
+        let f = assume_loaded();
         let mut status = icu_ffi::U_ZERO_ERROR;
-        let count = unsafe { (f.uregex_groupCount)(self.0, &mut status) };
+        let count = unsafe { (f.uregex_groupCount)(*re, &mut status) };
         if status.is_failure() { 0 } else { count }
     }
 
     /// Gets the text range of a captured group by index.
     pub fn group(&mut self, group: i32) -> Option<Range<usize>> {
-        let f = assume_loaded();
+        let re = match &mut self.backend {
+            RegexBackend::Icu(re) => re,
+            RegexBackend::Builtin(re, _) => return re.group(group),
+        };
 
+        let f = assume_loaded();
         let mut status = icu_ffi::U_ZERO_ERROR;
-        let start = unsafe { (f.uregex_start64)(self.0, group, &mut status) };
-        let end = unsafe { (f.uregex_end64)(self.0, group, &mut status) };
+        let start = unsafe { (f.uregex_start64)(*re, group, &mut status) };
+        let end = unsafe { (f.uregex_end64)(*re, group, &mut status) };
         if status.is_failure() {
             None
         } else {
@@ -710,10 +893,14 @@ impl Iterator for Regex {
     type Item = Range<usize>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let f = assume_loaded();
+        let re = match &mut self.backend {
+            RegexBackend::Icu(re) => re,
+            RegexBackend::Builtin(re, tb) => return re.find_next(unsafe { &**tb }),
+        };
 
+        let f = assume_loaded();
         let mut status = icu_ffi::U_ZERO_ERROR;
-        let ok = unsafe { (f.uregex_findNext)(self.0, &mut status) };
+        let ok = unsafe { (f.uregex_findNext)(*re, &mut status) };
         if !ok {
             return None;
         }
@@ -906,6 +1093,7 @@ struct LibraryFunctions {
     ucnv_getStandardName: icu_ffi::ucnv_getStandardName,
     ucnv_open: icu_ffi::ucnv_open,
     ucnv_close: icu_ffi::ucnv_close,
+    ucnv_getName: icu_ffi::ucnv_getName,
     ucnv_convertEx: icu_ffi::ucnv_convertEx,
     utext_setup: icu_ffi::utext_setup,
     utext_close: icu_ffi::utext_close,
@@ -922,6 +1110,12 @@ struct LibraryFunctions {
     uregex_groupCount: icu_ffi::uregex_groupCount,
     uregex_start64: icu_ffi::uregex_start64,
     uregex_end64: icu_ffi::uregex_end64,
+    ucsdet_open: icu_ffi::ucsdet_open,
+    ucsdet_close: icu_ffi::ucsdet_close,
+    ucsdet_setText: icu_ffi::ucsdet_setText,
+    ucsdet_detect: icu_ffi::ucsdet_detect,
+    ucsdet_getName: icu_ffi::ucsdet_getName,
+    ucsdet_getConfidence: icu_ffi::ucsdet_getConfidence,
 }
 
 macro_rules! proc_name {
@@ -932,7 +1126,7 @@ macro_rules! proc_name {
 }
 
 // Found in libicuuc.so on UNIX, icuuc.dll/icu.dll on Windows.
-const LIBICUUC_PROC_NAMES: [*const c_char; 10] = [
+const LIBICUUC_PROC_NAMES: [*const c_char; 11] = [
     proc_name!("u_errorName"),
     proc_name!("ucasemap_open"),
     proc_name!("ucasemap_utf8FoldCase"),
@@ -940,13 +1134,14 @@ const LIBICUUC_PROC_NAMES: [*const c_char; 10] = [
     proc_name!("ucnv_getStandardName"),
     proc_name!("ucnv_open"),
     proc_name!("ucnv_close"),
+    proc_name!("ucnv_getName"),
     proc_name!("ucnv_convertEx"),
     proc_name!("utext_setup"),
     proc_name!("utext_close"),
 ];
 
 // Found in libicui18n.so on UNIX, icuin.dll/icu.dll on Windows.
-const LIBICUI18N_PROC_NAMES: [*const c_char; 11] = [
+const LIBICUI18N_PROC_NAMES: [*const c_char; 17] = [
     proc_name!("ucol_open"),
     proc_name!("ucol_strcollUTF8"),
     proc_name!("uregex_open"),
@@ -958,8 +1153,15 @@ const LIBICUI18N_PROC_NAMES: [*const c_char; 11] = [
     proc_name!("uregex_groupCount"),
     proc_name!("uregex_start64"),
     proc_name!("uregex_end64"),
+    proc_name!("ucsdet_open"),
+    proc_name!("ucsdet_close"),
+    proc_name!("ucsdet_setText"),
+    proc_name!("ucsdet_detect"),
+    proc_name!("ucsdet_getName"),
+    proc_name!("ucsdet_getConfidence"),
 ];
 
+#[allow(clippy::large_enum_variant, reason = "it's a static; boxing it wouldn't save anything")]
 enum LibraryFunctionsState {
     Uninitialized,
     Failed,
@@ -1108,6 +1310,11 @@ mod icu_ffi {
 
     pub type ucnv_close = unsafe extern "C" fn(converter: *mut UConverter);
 
+    pub type ucnv_getName = unsafe extern "C" fn(
+        converter: *const UConverter,
+        status: &mut UErrorCode,
+    ) -> *const c_char;
+
     pub type ucnv_convertEx = unsafe extern "C" fn(
         target_cnv: *mut UConverter,
         source_cnv: *mut UConverter,
@@ -1315,6 +1522,26 @@ mod icu_ffi {
         group_num: i32,
         status: &mut UErrorCode,
     ) -> i64;
+
+    pub struct UCharsetDetector;
+    pub struct UCharsetMatch;
+
+    pub type ucsdet_open = unsafe extern "C" fn(status: &mut UErrorCode) -> *mut UCharsetDetector;
+    pub type ucsdet_close = unsafe extern "C" fn(ucsd: *mut UCharsetDetector);
+    pub type ucsdet_setText = unsafe extern "C" fn(
+        ucsd: *mut UCharsetDetector,
+        text_in: *const c_char,
+        len: i32,
+        status: &mut UErrorCode,
+    );
+    pub type ucsdet_detect = unsafe extern "C" fn(
+        ucsd: *mut UCharsetDetector,
+        status: &mut UErrorCode,
+    ) -> *const UCharsetMatch;
+    pub type ucsdet_getName =
+        unsafe extern "C" fn(ucsm: *const UCharsetMatch, status: &mut UErrorCode) -> *const c_char;
+    pub type ucsdet_getConfidence =
+        unsafe extern "C" fn(ucsm: *const UCharsetMatch, status: &mut UErrorCode) -> i32;
 }
 
 #[cfg(test)]
@@ -9,7 +9,7 @@
 use std::mem;
 
 use crate::helpers::{CoordType, Point, Size};
-use crate::vt;
+use crate::{base64, vt};
 
 /// Represents a key/modifier combination.
 ///
@@ -59,7 +59,7 @@ impl InputKey {
     }
 }
 
-/// A keyboard modifier. Ctrl/Alt/Shift.
+/// A keyboard modifier. Ctrl/Alt/Shift, and Super/Hyper if the terminal reports them.
 #[repr(transparent)]
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub struct InputKeyMod(u32);
@@ -212,6 +212,8 @@ pub mod kbmod {
     pub const CTRL: InputKeyMod = InputKeyMod::new(0x01000000);
     pub const ALT: InputKeyMod = InputKeyMod::new(0x02000000);
     pub const SHIFT: InputKeyMod = InputKeyMod::new(0x04000000);
+    pub const SUPER: InputKeyMod = InputKeyMod::new(0x08000000);
+    pub const HYPER: InputKeyMod = InputKeyMod::new(0x10000000);
 
     pub const CTRL_ALT: InputKeyMod = InputKeyMod::new(0x03000000);
     pub const CTRL_SHIFT: InputKeyMod = InputKeyMod::new(0x05000000);
@@ -257,6 +259,9 @@ pub enum Input<'input> {
     Text(&'input str),
     /// A clipboard paste.
     Paste(Vec<u8>),
+    /// The terminal's reply to an OSC 52 clipboard query (`OSC 52 ; c ; ? ST`).
+    /// Unlike [`Input::Paste`] this doesn't imply that the user wants to paste it.
+    HostClipboard(Vec<u8>),
     /// Keyboard input.
     Keyboard(InputKey),
     /// Mouse input.
@@ -270,6 +275,7 @@ pub struct Parser {
     x10_mouse_want: bool,
     x10_mouse_buf: [char; 3],
     x10_mouse_len: usize,
+    osc_buf: String,
 }
 
 impl Parser {
@@ -283,6 +289,7 @@ impl Parser {
             x10_mouse_want: false,
             x10_mouse_buf: ['\0'; 3],
             x10_mouse_len: 0,
+            osc_buf: String::new(),
         }
     }
 
@@ -333,6 +340,9 @@ impl<'input> Iterator for Stream<'_, '_, 'input> {
                 vt::Token::Ctrl(ch) => match ch {
                     '\0' | '\t' | '\r' => return Some(Input::Keyboard(InputKey::new(ch as u32))),
                     '\n' => return Some(Input::Keyboard(kbmod::CTRL | vk::RETURN)),
+                    // Ctrl+H and Ctrl+Backspace are indistinguishable without the kitty protocol.
+                    // The latter is what people are more likely to press.
+                    '\x08' => return Some(Input::Keyboard(kbmod::CTRL | vk::BACK)),
                     ..='\x1a' => {
                         // Shift control code to A-Z
                         let key = ch as u32 | 0x40;
@@ -378,7 +388,22 @@ impl<'input> Iterator for Stream<'_, '_, 'input> {
                                 ));
                             }
                         }
+                        // F1-F4 with modifiers. F3 (`CSI R`) would be ambiguous with
+                        // cursor position reports, which is why it's sent as `CSI 13 ~`.
+                        'P' | 'Q' | 'S' => {
+                            let key = match csi.final_byte {
+                                'P' => vk::F1,
+                                'Q' => vk::F2,
+                                _ => vk::F4,
+                            };
+                            return Some(Input::Keyboard(key | Self::parse_modifiers(csi)));
+                        }
                         'Z' => return Some(Input::Keyboard(kbmod::SHIFT | vk::TAB)),
+                        'u' if csi.private_byte == '\0' && csi.param_count != 0 => {
+                            if let Some(key) = Self::parse_kitty_key(csi.params[0]) {
+                                return Some(Input::Keyboard(key | Self::parse_modifiers(csi)));
+                            }
+                        }
                         '~' => {
                             const LUT: [u8; 35] = [
                                 0,
@@ -392,10 +417,10 @@ impl<'input> Iterator for Stream<'_, '_, 'input> {
                                 0,
                                 0,
                                 0,
-                                0,
-                                0,
-                                0,
-                                0,
+                                vk::F1.value() as u8, // 11
+                                vk::F2.value() as u8, // 12
+                                vk::F3.value() as u8, // 13
+                                vk::F4.value() as u8, // 14
                                 vk::F5.value() as u8, // 15
                                 0,
                                 vk::F6.value() as u8,  // 17
@@ -479,6 +504,16 @@ impl<'input> Iterator for Stream<'_, '_, 'input> {
                         _ => {}
                     }
                 }
+                vt::Token::Osc { data, partial } => {
+                    // Clipboard contents can be large and may arrive in multiple chunks.
+                    self.parser.osc_buf.push_str(data);
+                    if !partial {
+                        let osc = mem::take(&mut self.parser.osc_buf);
+                        if let Some(input) = Self::parse_osc(&osc) {
+                            return Some(input);
+                        }
+                    }
+                }
                 _ => {}
             }
         }
@@ -577,6 +612,17 @@ impl<'input> Stream<'_, '_, 'input> {
         }))
     }
 
+    /// Parses OSC replies. Currently only OSC 52 clipboard replies of the form `52;c;<base64>`.
+    fn parse_osc(osc: &str) -> Option<Input<'input>> {
+        let data = osc.strip_prefix("52;")?;
+        let (_, data) = data.split_once(';')?;
+        let mut res = Vec::new();
+        if !base64::decode(&mut res, data.as_bytes()) {
+            return None;
+        }
+        Some(Input::HostClipboard(res))
+    }
+
     fn parse_modifiers(csi: &vt::Csi) -> InputKeyMod {
         let mut modifiers = kbmod::NONE;
         let p1 = csi.params[1].saturating_sub(1);
@@ -589,6 +635,102 @@ impl<'input> Stream<'_, '_, 'input> {
         if (p1 & 0x04) != 0 {
             modifiers |= kbmod::CTRL;
         }
+        if (p1 & 0x08) != 0 {
+            modifiers |= kbmod::SUPER;
+        }
+        if (p1 & 0x10) != 0 {
+            modifiers |= kbmod::HYPER;
+        }
         modifiers
     }
+
+    /// Translates the key code of a kitty keyboard protocol `CSI code ; modifiers u` sequence.
+    ///
+    /// See: https://sw.kovidgoyal.net/kitty/keyboard-protocol/
+    fn parse_kitty_key(code: u16) -> Option<InputKey> {
+        let key = match code {
+            8 | 127 => vk::BACK,
+            9 => vk::TAB,
+            13 | 57414 => vk::RETURN, // Enter, KP_ENTER
+            27 => vk::ESCAPE,
+            0x61..=0x7a => InputKey::new(code as u32 & !0x20), // a-z to A-Z
+            0x20..=0x7e => InputKey::new(code as u32),
+            57376..=57387 => InputKey::new(vk::F13.value() + (code - 57376) as u32),
+            57399..=57408 => InputKey::new(vk::NUMPAD0.value() + (code - 57399) as u32),
+            57409 => vk::DECIMAL,
+            57410 => vk::DIVIDE,
+            57411 => vk::MULTIPLY,
+            57412 => vk::SUBTRACT,
+            57413 => vk::ADD,
+            57416 => vk::SEPARATOR,
+            57417 => vk::LEFT,
+            57418 => vk::RIGHT,
+            57419 => vk::UP,
+            57420 => vk::DOWN,
+            57421 => vk::PRIOR,
+            57422 => vk::NEXT,
+            57423 => vk::HOME,
+            57424 => vk::END,
+            57425 => vk::INSERT,
+            57426 => vk::DELETE,
+            // Other functional keys (media keys, lone modifiers, etc.) are in the private use area.
+            0xE000..=0xF8FF => return None,
+            _ => InputKey::new(code as u32),
+        };
+        Some(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_keys(input: &str) -> Vec<u32> {
+        let mut vt_parser = vt::Parser::new();
+        let mut parser = Parser::new();
+        parser
+            .parse(vt_parser.parse(input))
+            .filter_map(|input| match input {
+                Input::Keyboard(key) => Some(key.value()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_kitty_keys() {
+        let keys = parse_keys("\x1b[104;5u\x1b[127;5u\x1b[13;5u\x1b[97;6u\x1b[97;9u\x1b[97;17u");
+        assert_eq!(
+            keys,
+            [
+                (kbmod::CTRL | vk::H).value(),
+                (kbmod::CTRL | vk::BACK).value(),
+                (kbmod::CTRL | vk::RETURN).value(),
+                (kbmod::CTRL_SHIFT | vk::A).value(),
+                (kbmod::SUPER | vk::A).value(),
+                (kbmod::HYPER | vk::A).value(),
+            ]
+        );
+
+        // Sub-parameters (alternate keys, event types) are skipped.
+        assert_eq!(parse_keys("\x1b[97:65;6:1u"), [(kbmod::CTRL_SHIFT | vk::A).value()]);
+        // Keypad keys and F3.
+        assert_eq!(
+            parse_keys("\x1b[57419u\x1b[13;2~"),
+            [vk::UP.value(), (kbmod::SHIFT | vk::F3).value()]
+        );
+        // Lone modifier keys are ignored.
+        assert!(parse_keys("\x1b[57441;2u").is_empty());
+    }
+
+    #[test]
+    fn test_osc_clipboard() {
+        let mut vt_parser = vt::Parser::new();
+        let mut parser = Parser::new();
+
+        // The reply may be split up across reads.
+        assert!(parser.parse(vt_parser.parse("\x1b]52;c;aGVsbG8g")).next().is_none());
+        let input = parser.parse(vt_parser.parse("d29ybGQ=\x1b\\")).next();
+        assert!(matches!(input, Some(Input::HostClipboard(data)) if data == b"hello world"));
+    }
 }
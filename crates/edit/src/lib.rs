@@ -23,6 +23,7 @@ pub mod buffer;
 pub mod cell;
 pub mod clipboard;
 pub mod document;
+pub mod encodings;
 pub mod framebuffer;
 pub mod fuzzy;
 pub mod hash;
@@ -30,10 +31,14 @@ pub mod helpers;
 pub mod icu;
 pub mod input;
 pub mod json;
+pub mod message_format;
 pub mod oklab;
 pub mod path;
+pub mod regex;
 pub mod simd;
+pub mod spell;
 pub mod sys;
+pub mod theme;
 pub mod tui;
 pub mod unicode;
 pub mod vt;
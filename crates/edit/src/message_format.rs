@@ -0,0 +1,396 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! A subset of ICU MessageFormat, used for the translations in `i18n/edit.toml`.
+//!
+//! Messages can contain simple arguments such as `{size}` and plural selections:
+//! ```text
+//! {count, plural, =0 {No files} one {# file} other {# files}}
+//! ```
+//! Within a plural branch `#` stands for the number. Apostrophes quote the syntax
+//! characters `{`, `}` and `#`, so `'{'` is a literal brace and `''` a literal apostrophe.
+//! Any other apostrophe is just that, so "l'éditeur" needs no escaping.
+//!
+//! The build script compiles this file as well, to validate the translations.
+//! It must thus not depend on anything but `std`.
+
+use std::fmt;
+
+/// A value for an argument of a message.
+#[derive(Clone, Copy, Debug)]
+pub enum Arg<'a> {
+    Str(&'a str),
+    Int(i64),
+}
+
+impl<'a> From<&'a str> for Arg<'a> {
+    fn from(s: &'a str) -> Self {
+        Self::Str(s)
+    }
+}
+
+impl From<i64> for Arg<'_> {
+    fn from(n: i64) -> Self {
+        Self::Int(n)
+    }
+}
+
+impl From<usize> for Arg<'_> {
+    fn from(n: usize) -> Self {
+        Self::Int(n as i64)
+    }
+}
+
+impl fmt::Display for Arg<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Str(s) => f.write_str(s),
+            Self::Int(n) => write!(f, "{n}"),
+        }
+    }
+}
+
+/// A syntax error in a message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Error {
+    /// Byte offset of the error in the message.
+    pub offset: usize,
+    pub message: &'static str,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.message, self.offset)
+    }
+}
+
+enum Node<'a> {
+    Text(&'a str),
+    Arg(&'a str),
+    /// `#` within a plural branch.
+    Number,
+    Plural {
+        name: &'a str,
+        branches: Vec<(&'a str, Vec<Node<'a>>)>,
+    },
+}
+
+/// Checks the syntax of `message` and returns the names of its arguments, sorted and deduplicated.
+pub fn arguments(message: &str) -> Result<Vec<&str>, Error> {
+    fn collect<'a>(nodes: &[Node<'a>], names: &mut Vec<&'a str>) {
+        for node in nodes {
+            match node {
+                Node::Arg(name) => names.push(name),
+                Node::Plural { name, branches } => {
+                    names.push(name);
+                    for (_, nodes) in branches {
+                        collect(nodes, names);
+                    }
+                }
+                Node::Text(_) | Node::Number => {}
+            }
+        }
+    }
+
+    let nodes = parse(message)?;
+    let mut names = Vec::new();
+    collect(&nodes, &mut names);
+    names.sort_unstable();
+    names.dedup();
+    Ok(names)
+}
+
+/// Formats `message` into `out`, with plural rules for the language `lang` (e.g. "pt_br").
+///
+/// Arguments missing from `args` are left as is, e.g. "{size}".
+pub fn format(
+    out: &mut dyn fmt::Write,
+    message: &str,
+    lang: &str,
+    args: &[(&str, Arg)],
+) -> Result<(), Error> {
+    fn write(out: &mut dyn fmt::Write, nodes: &[Node], lang: &str, args: &[(&str, Arg)], n: i64) {
+        let get = |name: &str| args.iter().find(|(k, _)| *k == name).map(|(_, v)| *v);
+
+        for node in nodes {
+            _ = match node {
+                Node::Text(text) => out.write_str(text),
+                Node::Number => write!(out, "{n}"),
+                Node::Arg(name) => match get(name) {
+                    Some(arg) => write!(out, "{arg}"),
+                    None => write!(out, "{{{name}}}"),
+                },
+                Node::Plural { name, branches } => {
+                    let n = match get(name) {
+                        Some(Arg::Int(n)) => n,
+                        _ => 0,
+                    };
+                    let category = plural_category(lang, n.unsigned_abs());
+                    let exact =
+                        |s: &str| s.strip_prefix('=').and_then(|s| s.parse().ok()) == Some(n);
+                    let branch = branches
+                        .iter()
+                        .find(|(s, _)| exact(s))
+                        .or_else(|| branches.iter().find(|(s, _)| *s == category))
+                        .or_else(|| branches.iter().find(|(s, _)| *s == "other"));
+                    if let Some((_, nodes)) = branch {
+                        write(out, nodes, lang, args, n);
+                    }
+                    Ok(())
+                }
+            };
+        }
+    }
+
+    let nodes = parse(message)?;
+    write(out, &nodes, lang, args, 0);
+    Ok(())
+}
+
+/// Returns the CLDR plural category of the integer `n` in the language `lang`:
+/// "zero", "one", "two", "few", "many" or "other".
+pub fn plural_category(lang: &str, n: u64) -> &'static str {
+    let root = lang.split(['_', '-']).next().unwrap_or(lang);
+    let (n10, n100) = (n % 10, n % 100);
+
+    match root {
+        "ja" | "ko" | "zh" | "id" | "th" | "vi" => "other",
+        "fr" | "hi" | "bn" | "fa" if n <= 1 => "one",
+        "pt" if n <= 1 && lang != "pt_pt" => "one",
+        "ru" | "uk" | "be" | "pl" | "sr" | "hr" | "bs" => {
+            if n == 1 || (root != "pl" && n10 == 1 && n100 != 11) {
+                "one"
+            } else if (2..=4).contains(&n10) && !(12..=14).contains(&n100) {
+                "few"
+            } else if root == "pl" || root == "ru" || root == "uk" || root == "be" {
+                "many"
+            } else {
+                "other"
+            }
+        }
+        "cs" | "sk" => match n {
+            1 => "one",
+            2..=4 => "few",
+            _ => "other",
+        },
+        "ar" => match (n, n100) {
+            (0, _) => "zero",
+            (1, _) => "one",
+            (2, _) => "two",
+            (_, 3..=10) => "few",
+            (_, 11..=99) => "many",
+            _ => "other",
+        },
+        _ if n == 1 => "one",
+        _ => "other",
+    }
+}
+
+fn parse(message: &str) -> Result<Vec<Node<'_>>, Error> {
+    let mut parser = Parser { src: message, pos: 0 };
+    let nodes = parser.nodes(false)?;
+    if parser.pos < message.len() {
+        return Err(parser.error("unbalanced '}'"));
+    }
+    Ok(nodes)
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, message: &'static str) -> Error {
+        Error { offset: self.pos, message }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.src.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    /// Consumes `[a-zA-Z0-9_=]+`.
+    fn identifier(&mut self) -> Result<&'a str, Error> {
+        self.skip_whitespace();
+        let beg = self.pos;
+        while self.peek().is_some_and(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'=') {
+            self.pos += 1;
+        }
+        if beg == self.pos {
+            return Err(self.error("expected an identifier"));
+        }
+        Ok(&self.src[beg..self.pos])
+    }
+
+    fn expect(&mut self, ch: u8, message: &'static str) -> Result<(), Error> {
+        self.skip_whitespace();
+        if self.peek() != Some(ch) {
+            return Err(self.error(message));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    /// Parses text and arguments up to an unquoted '}' or the end.
+    fn nodes(&mut self, in_plural: bool) -> Result<Vec<Node<'a>>, Error> {
+        let bytes = self.src.as_bytes();
+        let mut nodes = Vec::new();
+        let mut beg = self.pos;
+
+        while let Some(b) = self.peek() {
+            let is_syntax = |b: Option<&u8>| match b {
+                Some(b'{' | b'}') => true,
+                Some(b'#') => in_plural,
+                _ => false,
+            };
+
+            match b {
+                b'\'' if bytes.get(self.pos + 1) == Some(&b'\'') => {
+                    nodes.push(Node::Text(&self.src[beg..self.pos + 1]));
+                    self.pos += 2;
+                    beg = self.pos;
+                }
+                b'\'' if is_syntax(bytes.get(self.pos + 1)) => {
+                    nodes.push(Node::Text(&self.src[beg..self.pos]));
+                    let quoted = self.pos + 1;
+                    let Some(len) = self.src[quoted..].find('\'') else {
+                        return Err(self.error("unterminated quote"));
+                    };
+                    nodes.push(Node::Text(&self.src[quoted..quoted + len]));
+                    self.pos = quoted + len + 1;
+                    beg = self.pos;
+                }
+                b'#' if in_plural => {
+                    nodes.push(Node::Text(&self.src[beg..self.pos]));
+                    nodes.push(Node::Number);
+                    self.pos += 1;
+                    beg = self.pos;
+                }
+                b'{' => {
+                    nodes.push(Node::Text(&self.src[beg..self.pos]));
+                    self.pos += 1;
+                    nodes.push(self.argument()?);
+                    beg = self.pos;
+                }
+                b'}' => break,
+                _ => self.pos += 1,
+            }
+        }
+
+        nodes.push(Node::Text(&self.src[beg..self.pos]));
+        nodes.retain(|n| !matches!(n, Node::Text("")));
+        Ok(nodes)
+    }
+
+    /// Parses the remainder of an argument after its opening '{'.
+    fn argument(&mut self) -> Result<Node<'a>, Error> {
+        let name = self.identifier()?;
+        self.skip_whitespace();
+
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(Node::Arg(name));
+        }
+
+        self.expect(b',', "expected '}' or ','")?;
+        if self.identifier()? != "plural" {
+            return Err(self.error("unsupported argument type"));
+        }
+        self.expect(b',', "expected ','")?;
+
+        let mut branches = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some(b'}') {
+                self.pos += 1;
+                break;
+            }
+
+            let selector = self.identifier()?;
+            let valid = match selector.strip_prefix('=') {
+                Some(n) => n.parse::<i64>().is_ok(),
+                None => matches!(selector, "zero" | "one" | "two" | "few" | "many" | "other"),
+            };
+            if !valid {
+                return Err(self.error("invalid plural selector"));
+            }
+
+            self.expect(b'{', "expected '{'")?;
+            let nodes = self.nodes(true)?;
+            self.expect(b'}', "expected '}'")?;
+            branches.push((selector, nodes));
+        }
+
+        if !branches.iter().any(|(s, _)| *s == "other") {
+            return Err(self.error("plural without 'other'"));
+        }
+        Ok(Node::Plural { name, branches })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fmt(message: &str, lang: &str, args: &[(&str, Arg)]) -> String {
+        let mut out = String::new();
+        format(&mut out, message, lang, args).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(fmt("You copied {size}.", "en", &[("size", "5 MB".into())]), "You copied 5 MB.");
+        assert_eq!(fmt("{a} of {b}", "en", &[("a", 1usize.into())]), "1 of {b}");
+        assert_eq!(fmt("'{'quoted'}' l'éditeur ''#''", "fr", &[]), "{quoted} l'éditeur '#'");
+
+        let files = "{n, plural, =0 {No files} one {# file} other {# files in '#'{dir}}}";
+        let args = |n: i64| [("n", Arg::Int(n)), ("dir", Arg::Str("src"))];
+        assert_eq!(fmt(files, "en", &args(0)), "No files");
+        assert_eq!(fmt(files, "en", &args(1)), "1 file");
+        assert_eq!(fmt(files, "en", &args(7)), "7 files in #src");
+        // Japanese has no "one" category.
+        assert_eq!(fmt(files, "ja", &args(1)), "1 files in #src");
+    }
+
+    #[test]
+    fn test_arguments() {
+        assert_eq!(arguments("{b} {a} {b}"), Ok(vec!["a", "b"]));
+        assert_eq!(arguments("{n, plural, one {#} other {{x}}}"), Ok(vec!["n", "x"]));
+        assert_eq!(arguments("no args # here"), Ok(vec![]));
+
+        for invalid in ["{", "}", "{a", "{a, select, other {}}", "{n, plural, one {#}}", "'{"] {
+            assert!(arguments(invalid).is_err(), "{invalid:?}");
+        }
+    }
+
+    #[test]
+    fn test_plural_category() {
+        let categories = |lang| [0, 1, 2, 5, 11, 21, 22, 25, 101].map(|n| plural_category(lang, n));
+        assert_eq!(
+            categories("en"),
+            ["other", "one", "other", "other", "other", "other", "other", "other", "other"]
+        );
+        assert_eq!(
+            categories("fr"),
+            ["one", "one", "other", "other", "other", "other", "other", "other", "other"]
+        );
+        assert_eq!(
+            categories("ru"),
+            ["many", "one", "few", "many", "many", "one", "few", "many", "one"]
+        );
+        assert_eq!(
+            categories("pl"),
+            ["many", "one", "few", "many", "many", "many", "few", "many", "many"]
+        );
+        assert_eq!(plural_category("pt_br", 0), "one");
+        assert_eq!(plural_category("pt_pt", 0), "other");
+        assert_eq!(plural_category("zh_hans", 1), "other");
+    }
+}
@@ -0,0 +1,1655 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! A regex engine for when ICU isn't installed.
+//!
+//! [`crate::icu::Regex`] falls back to it, so that search still works without ICU.
+//! It's a Pike VM, which is a Thompson NFA that also tracks capture groups.
+//! It runs in linear time and reads the text chunk by chunk via [`ReadableDocument`].
+//!
+//! The syntax is the subset of ICU's that doesn't need backtracking.
+//! Backreferences, lookaround and possessive quantifiers fail with the error
+//! ICU uses for unimplemented features. Unicode properties and classes like `\w`
+//! are approximated with what [`char`] offers, and case-insensitive matching
+//! compares lowercase characters instead of using ICU's case folding.
+
+use std::mem;
+use std::ops::Range;
+
+use crate::apperr;
+use crate::document::ReadableDocument;
+use crate::simd::memchr2;
+use crate::unicode::Utf8Chars;
+
+// The ICU error codes we report, so that errors are the same with or without ICU.
+const U_REGEX_RULE_SYNTAX: u32 = 0x10301;
+const U_REGEX_BAD_ESCAPE_SEQUENCE: u32 = 0x10303;
+const U_REGEX_PROPERTY_SYNTAX: u32 = 0x10304;
+const U_REGEX_UNIMPLEMENTED: u32 = 0x10305;
+const U_REGEX_MISMATCHED_PAREN: u32 = 0x10306;
+const U_REGEX_NUMBER_TOO_BIG: u32 = 0x10307;
+const U_REGEX_BAD_INTERVAL: u32 = 0x10308;
+const U_REGEX_MAX_LT_MIN: u32 = 0x10309;
+const U_REGEX_INVALID_FLAG: u32 = 0x1030b;
+const U_REGEX_MISSING_CLOSE_BRACKET: u32 = 0x1030f;
+const U_REGEX_INVALID_RANGE: u32 = 0x10310;
+const U_REGEX_PATTERN_TOO_BIG: u32 = 0x10314;
+
+const ERROR_NAMES: [(u32, &str); 12] = [
+    (U_REGEX_RULE_SYNTAX, "U_REGEX_RULE_SYNTAX"),
+    (U_REGEX_BAD_ESCAPE_SEQUENCE, "U_REGEX_BAD_ESCAPE_SEQUENCE"),
+    (U_REGEX_PROPERTY_SYNTAX, "U_REGEX_PROPERTY_SYNTAX"),
+    (U_REGEX_UNIMPLEMENTED, "U_REGEX_UNIMPLEMENTED"),
+    (U_REGEX_MISMATCHED_PAREN, "U_REGEX_MISMATCHED_PAREN"),
+    (U_REGEX_NUMBER_TOO_BIG, "U_REGEX_NUMBER_TOO_BIG"),
+    (U_REGEX_BAD_INTERVAL, "U_REGEX_BAD_INTERVAL"),
+    (U_REGEX_MAX_LT_MIN, "U_REGEX_MAX_LT_MIN"),
+    (U_REGEX_INVALID_FLAG, "U_REGEX_INVALID_FLAG"),
+    (U_REGEX_MISSING_CLOSE_BRACKET, "U_REGEX_MISSING_CLOSE_BRACKET"),
+    (U_REGEX_INVALID_RANGE, "U_REGEX_INVALID_RANGE"),
+    (U_REGEX_PATTERN_TOO_BIG, "U_REGEX_PATTERN_TOO_BIG"),
+];
+
+/// Returns ICU's name for one of the errors we report, since ICU isn't there to ask.
+pub fn error_name(code: u32) -> Option<&'static str> {
+    ERROR_NAMES.iter().find(|&&(c, _)| c == code).map(|&(_, name)| name)
+}
+
+fn error(code: u32) -> apperr::Error {
+    apperr::Error::new_icu(code)
+}
+
+/// Patterns that compile to more instructions than this are rejected.
+/// Each VM thread list holds this many entries with a copy of all capture slots each.
+const MAX_INSTS: usize = 64 * 1024;
+
+/// Repetition counts larger than this are rejected.
+const MAX_REPEAT: u32 = MAX_INSTS as u32;
+
+/// Bytes that start a match, searched for with [`memchr2`] while no thread is alive.
+type Prefix = (u8, u8);
+
+/// A compiled regex, along with the state of the last search.
+///
+/// Unlike ICU, it doesn't hold on to the text. It's passed to [`Regex::find_next`] instead.
+pub struct Regex {
+    insts: Vec<Inst>,
+    classes: Vec<Class>,
+    prefix: Option<Prefix>,
+    /// Start and end offsets of each capture group of the last match.
+    /// Group 0 is the entire match. Groups that didn't participate are `usize::MAX`.
+    slots: Vec<usize>,
+    /// Where the next search starts.
+    offset: usize,
+    /// Whether `slots` holds a match.
+    matched: bool,
+    /// If the last search failed, we don't search again until [`Regex::reset`],
+    /// or a pattern that matches the empty string would match at the end again.
+    exhausted: bool,
+    vm: Vm,
+}
+
+impl Regex {
+    // These have the same values as their ICU counterparts, so that flags can be passed through.
+
+    /// Enable case-insensitive matching.
+    pub const CASE_INSENSITIVE: i32 = 2;
+
+    /// If set, ^ and $ match the start and end of each line.
+    /// This is always the case, since [`crate::icu::Regex`] always sets it.
+    pub const MULTILINE: i32 = 8;
+
+    /// Treat the given pattern as a literal string.
+    pub const LITERAL: i32 = 16;
+
+    /// Compiles `pattern`. Syntax errors are reported with ICU's error codes.
+    pub fn new(pattern: &str, flags: i32) -> apperr::Result<Self> {
+        let mut parser = Parser {
+            chars: pattern.chars().collect(),
+            pos: 0,
+            flags: Flags { case_insensitive: flags & Self::CASE_INSENSITIVE != 0, dot_all: false },
+            groups: 0,
+            classes: Vec::new(),
+        };
+
+        let node = if flags & Self::LITERAL != 0 {
+            let chars = mem::take(&mut parser.chars);
+            concat(chars.into_iter().map(|c| parser.char_node(c)).collect())
+        } else {
+            let node = parser.parse_alt()?;
+            if parser.pos < parser.chars.len() {
+                // `parse_alt` only stops early at an unbalanced ')'.
+                return Err(error(U_REGEX_MISMATCHED_PAREN));
+            }
+            node
+        };
+
+        let mut compiler = Compiler { insts: Vec::new() };
+        compiler.emit(Inst::Save(0))?;
+        compiler.compile(&node)?;
+        compiler.emit(Inst::Save(1))?;
+        compiler.emit(Inst::Match)?;
+
+        let insts = compiler.insts;
+        let slot_count = (parser.groups + 1) * 2;
+
+        Ok(Self {
+            prefix: prefix(&node),
+            vm: Vm::new(insts.len(), slot_count),
+            insts,
+            classes: parser.classes,
+            slots: vec![usize::MAX; slot_count],
+            offset: 0,
+            matched: false,
+            exhausted: false,
+        })
+    }
+
+    /// Sets the offset at which the next search starts and forgets the last match.
+    pub fn reset(&mut self, offset: usize) {
+        self.offset = offset;
+        self.matched = false;
+        self.exhausted = false;
+    }
+
+    /// Finds the next match in `doc`, like ICU's `uregex_findNext`.
+    pub fn find_next(&mut self, doc: &dyn ReadableDocument) -> Option<Range<usize>> {
+        if self.exhausted {
+            return None;
+        }
+
+        let mut start = self.offset;
+
+        // After an empty match, move on by a character, or we'd find it again.
+        if self.matched && self.slots[0] == self.slots[1] {
+            match Input::new(doc).char_at(start) {
+                Some((_, len)) => start += len,
+                None => {
+                    self.matched = false;
+                    self.exhausted = true;
+                    return None;
+                }
+            }
+        }
+
+        self.matched = self.search(doc, start);
+        if !self.matched {
+            self.exhausted = true;
+            return None;
+        }
+
+        self.offset = self.slots[1];
+        Some(self.slots[0]..self.slots[1])
+    }
+
+    /// Gets captured group count.
+    pub fn group_count(&self) -> i32 {
+        (self.slots.len() / 2 - 1) as i32
+    }
+
+    /// Gets the text range of a captured group of the last match by index.
+    /// Returns `None` if there's no match or the group didn't participate in it.
+    pub fn group(&self, group: i32) -> Option<Range<usize>> {
+        if !self.matched || group < 0 {
+            return None;
+        }
+
+        let i = group as usize * 2;
+        let beg = *self.slots.get(i)?;
+        let end = self.slots[i + 1];
+        if beg == usize::MAX || end == usize::MAX { None } else { Some(beg..end) }
+    }
+
+    /// Runs the VM from `start` until the leftmost match is found.
+    /// Its capture groups are stored in `slots`.
+    fn search(&mut self, doc: &dyn ReadableDocument, start: usize) -> bool {
+        let Self { insts, classes, prefix, slots, vm, .. } = self;
+        let Vm { clist, nlist, stack, scratch } = vm;
+        let mut input = Input::new(doc);
+        let mut ctx =
+            Context { at: start, prev: input.char_before(start), cur: input.char_at(start) };
+        let mut matched = false;
+
+        clist.clear();
+
+        loop {
+            if clist.is_empty() {
+                if matched {
+                    break;
+                }
+
+                // Skip ahead to where a match could start.
+                if let Some((a, b)) = *prefix {
+                    let Some(at) = input.find(a, b, ctx.at) else {
+                        break;
+                    };
+                    if at != ctx.at {
+                        ctx = Context { at, prev: input.char_before(at), cur: input.char_at(at) };
+                    }
+                }
+            }
+
+            // Start a new attempt at every offset, with the lowest priority,
+            // until we have a match. This makes the search unanchored.
+            if !matched {
+                scratch.fill(usize::MAX);
+                add_thread(insts, clist, stack, scratch, 0, ctx, &mut input);
+            }
+
+            let next = ctx.cur.map(|(ch, len)| {
+                let at = ctx.at + len;
+                Context { at, prev: Some(ch), cur: input.char_at(at) }
+            });
+
+            nlist.clear();
+
+            for i in 0..clist.dense.len() {
+                let pc = clist.dense[i];
+                let hit = match (insts[pc], ctx.cur) {
+                    (Inst::Match, _) => {
+                        // Threads after this one have a lower priority. Cut them off.
+                        slots.copy_from_slice(clist.slots(pc));
+                        matched = true;
+                        break;
+                    }
+                    (_, None) => false,
+                    (Inst::Char(c), Some((ch, _))) => ch == c,
+                    (Inst::CharFold(c), Some((ch, _))) => fold(ch) == c,
+                    (Inst::Dot { dot_all }, Some((ch, _))) => dot_all || !is_line_terminator(ch),
+                    (Inst::Class(i, ci), Some((ch, _))) => classes[i].matches(ch, ci),
+                    // Instructions that don't consume anything have already been followed.
+                    _ => false,
+                };
+                if hit && let Some(next) = next {
+                    scratch.copy_from_slice(clist.slots(pc));
+                    add_thread(insts, nlist, stack, scratch, pc + 1, next, &mut input);
+                }
+            }
+
+            let Some(next) = next else {
+                break;
+            };
+            mem::swap(clist, nlist);
+            ctx = next;
+        }
+
+        matched
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Flags {
+    case_insensitive: bool,
+    dot_all: bool,
+}
+
+enum Node {
+    Empty,
+    Char(char),
+    /// A character, matched case-insensitively. It's stored [`fold`]ed.
+    CharFold(char),
+    Dot {
+        dot_all: bool,
+    },
+    /// An index into [`Regex::classes`] and whether it's case-insensitive.
+    Class(usize, bool),
+    Look(Look),
+    Capture(usize, Box<Node>),
+    Concat(Vec<Node>),
+    Alt(Vec<Node>),
+    Repeat {
+        node: Box<Node>,
+        min: u32,
+        max: Option<u32>,
+        greedy: bool,
+    },
+}
+
+fn concat(mut nodes: Vec<Node>) -> Node {
+    match nodes.len() {
+        0 => Node::Empty,
+        1 => nodes.pop().unwrap(),
+        _ => Node::Concat(nodes),
+    }
+}
+
+/// Zero-width assertions.
+#[derive(Clone, Copy)]
+enum Look {
+    /// `^`
+    LineStart,
+    /// `$`
+    LineEnd,
+    /// `\A`
+    TextStart,
+    /// `\z`
+    TextEnd,
+    /// `\Z`
+    TextEndBeforeNewline,
+    /// `\b`
+    WordBoundary,
+    /// `\B`
+    NotWordBoundary,
+}
+
+impl Look {
+    /// Line starts and ends work like ICU's in multi-line mode:
+    /// `^` doesn't match after a trailing newline and `$` doesn't match between CR and LF.
+    fn matches(self, ctx: Context, input: &mut Input) -> bool {
+        let prev = ctx.prev;
+        let cur = ctx.cur.map(|(ch, _)| ch);
+
+        match self {
+            Self::LineStart => {
+                ctx.at == 0 || (cur.is_some() && prev.is_some_and(is_line_terminator))
+            }
+            Self::LineEnd => match cur {
+                None => true,
+                Some(ch) => is_line_terminator(ch) && !(ch == '\n' && prev == Some('\r')),
+            },
+            Self::TextStart => ctx.at == 0,
+            Self::TextEnd => cur.is_none(),
+            Self::TextEndBeforeNewline => match ctx.cur {
+                None => true,
+                Some((ch, len)) if is_line_terminator(ch) => {
+                    let mut end = ctx.at + len;
+                    if ch == '\r'
+                        && let Some(('\n', len)) = input.char_at(end)
+                    {
+                        end += len;
+                    }
+                    input.char_at(end).is_none()
+                }
+                Some(_) => false,
+            },
+            Self::WordBoundary => prev.is_some_and(is_word) != cur.is_some_and(is_word),
+            Self::NotWordBoundary => prev.is_some_and(is_word) == cur.is_some_and(is_word),
+        }
+    }
+}
+
+/// A bracket expression like `[a-z\d]`.
+struct Class {
+    negated: bool,
+    items: Vec<ClassItem>,
+}
+
+enum ClassItem {
+    Range(char, char),
+    /// A class like `\d` and whether it's negated, like `\D`.
+    Kind(CharKind, bool),
+    /// A nested bracket expression.
+    Class(Class),
+}
+
+impl Class {
+    fn matches(&self, ch: char, case_insensitive: bool) -> bool {
+        let mut hit = self.contains(ch);
+        if !hit && case_insensitive {
+            let lower = simple_lowercase(ch);
+            let upper = simple_uppercase(ch);
+            hit = (lower != ch && self.contains(lower)) || (upper != ch && self.contains(upper));
+        }
+        hit != self.negated
+    }
+
+    fn contains(&self, ch: char) -> bool {
+        self.items.iter().any(|item| match *item {
+            ClassItem::Range(lo, hi) => (lo..=hi).contains(&ch),
+            ClassItem::Kind(kind, negated) => kind.matches(ch) != negated,
+            ClassItem::Class(ref class) => class.matches(ch, false),
+        })
+    }
+}
+
+/// Classes like `\d`, `\p{Lu}` and `[:alpha:]`.
+#[derive(Clone, Copy)]
+enum CharKind {
+    Any,
+    Ascii,
+    Alphabetic,
+    Alnum,
+    Cntrl,
+    Digit,
+    Graph,
+    HorizSpace,
+    Lowercase,
+    Numeric,
+    Print,
+    Punct,
+    Space,
+    Uppercase,
+    Word,
+    XDigit,
+}
+
+impl CharKind {
+    fn matches(self, ch: char) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Ascii => ch.is_ascii(),
+            Self::Alphabetic => ch.is_alphabetic(),
+            Self::Alnum => ch.is_alphanumeric(),
+            Self::Cntrl => ch.is_control(),
+            Self::Digit => is_decimal_digit(ch),
+            Self::Graph => !ch.is_control() && !ch.is_whitespace(),
+            Self::HorizSpace => ch.is_whitespace() && !is_line_terminator(ch),
+            Self::Lowercase => ch.is_lowercase(),
+            Self::Numeric => ch.is_numeric(),
+            Self::Print => !ch.is_control(),
+            Self::Punct => ch.is_ascii_punctuation(),
+            // ICU defines `\s` as `[\t\n\f\r\p{Z}]`.
+            Self::Space => ch.is_whitespace() && !matches!(ch, '\x0b' | '\u{85}'),
+            Self::Uppercase => ch.is_uppercase(),
+            Self::Word => is_word(ch),
+            Self::XDigit => ch.is_ascii_hexdigit(),
+        }
+    }
+}
+
+/// Names for `\p{...}` and `[:...:]`, compared case-insensitively.
+const PROPERTIES: [(&str, CharKind); 28] = [
+    ("Any", CharKind::Any),
+    ("ASCII", CharKind::Ascii),
+    ("L", CharKind::Alphabetic),
+    ("Letter", CharKind::Alphabetic),
+    ("Alphabetic", CharKind::Alphabetic),
+    ("Alpha", CharKind::Alphabetic),
+    ("Alnum", CharKind::Alnum),
+    ("Cc", CharKind::Cntrl),
+    ("Control", CharKind::Cntrl),
+    ("Cntrl", CharKind::Cntrl),
+    ("Nd", CharKind::Digit),
+    ("Digit", CharKind::Digit),
+    ("Graph", CharKind::Graph),
+    ("Blank", CharKind::HorizSpace),
+    ("Ll", CharKind::Lowercase),
+    ("Lowercase", CharKind::Lowercase),
+    ("Lower", CharKind::Lowercase),
+    ("N", CharKind::Numeric),
+    ("Number", CharKind::Numeric),
+    ("Print", CharKind::Print),
+    ("Punct", CharKind::Punct),
+    ("White_Space", CharKind::Space),
+    ("Space", CharKind::Space),
+    ("Lu", CharKind::Uppercase),
+    ("Uppercase", CharKind::Uppercase),
+    ("Upper", CharKind::Uppercase),
+    ("Word", CharKind::Word),
+    ("XDigit", CharKind::XDigit),
+];
+
+fn property(name: &str) -> apperr::Result<CharKind> {
+    PROPERTIES
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|&(_, kind)| kind)
+        .ok_or(error(U_REGEX_PROPERTY_SYNTAX))
+}
+
+/// The result of parsing an escape sequence that's valid inside and outside of brackets.
+enum Escape {
+    Char(char),
+    Kind(CharKind, bool),
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    flags: Flags,
+    groups: usize,
+    classes: Vec<Class>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let ch = self.peek();
+        self.pos += ch.is_some() as usize;
+        ch
+    }
+
+    fn eat(&mut self, ch: char) -> bool {
+        let ok = self.peek() == Some(ch);
+        self.pos += ok as usize;
+        ok
+    }
+
+    fn char_node(&self, ch: char) -> Node {
+        if self.flags.case_insensitive && (simple_lowercase(ch) != ch || simple_uppercase(ch) != ch)
+        {
+            Node::CharFold(fold(ch))
+        } else {
+            Node::Char(ch)
+        }
+    }
+
+    fn class_node(&mut self, class: Class) -> Node {
+        self.classes.push(class);
+        Node::Class(self.classes.len() - 1, self.flags.case_insensitive)
+    }
+
+    fn parse_alt(&mut self) -> apperr::Result<Node> {
+        let mut alts = vec![self.parse_concat()?];
+        while self.eat('|') {
+            alts.push(self.parse_concat()?);
+        }
+        Ok(if alts.len() == 1 { alts.pop().unwrap() } else { Node::Alt(alts) })
+    }
+
+    fn parse_concat(&mut self) -> apperr::Result<Node> {
+        let mut nodes = Vec::new();
+        while let Some(ch) = self.peek()
+            && ch != '|'
+            && ch != ')'
+        {
+            let node = self.parse_repeat()?;
+            if !matches!(node, Node::Empty) {
+                nodes.push(node);
+            }
+        }
+        Ok(concat(nodes))
+    }
+
+    fn parse_repeat(&mut self) -> apperr::Result<Node> {
+        let mut node = self.parse_atom()?;
+
+        loop {
+            let (min, max) = match self.peek() {
+                Some('*') => {
+                    self.pos += 1;
+                    (0, None)
+                }
+                Some('+') => {
+                    self.pos += 1;
+                    (1, None)
+                }
+                Some('?') => {
+                    self.pos += 1;
+                    (0, Some(1))
+                }
+                Some('{') => {
+                    self.pos += 1;
+                    self.parse_interval()?
+                }
+                _ => return Ok(node),
+            };
+
+            let greedy = !self.eat('?');
+            if self.peek() == Some('+') {
+                // Possessive quantifiers need backtracking.
+                return Err(error(U_REGEX_UNIMPLEMENTED));
+            }
+
+            // Each node compiles to at least one instruction, except for empty ones,
+            // which we shouldn't repeat, or `MAX_INSTS` wouldn't limit the work.
+            if !matches!(node, Node::Empty) {
+                node = Node::Repeat { node: Box::new(node), min, max, greedy };
+            }
+        }
+    }
+
+    /// Parses the `n}`, `n,}` or `n,m}` after a `{`.
+    fn parse_interval(&mut self) -> apperr::Result<(u32, Option<u32>)> {
+        let min = self.parse_number()?.ok_or(error(U_REGEX_BAD_INTERVAL))?;
+        let max = if self.eat(',') { self.parse_number()? } else { Some(min) };
+        if !self.eat('}') {
+            return Err(error(U_REGEX_BAD_INTERVAL));
+        }
+        if max.is_some_and(|max| max < min) {
+            return Err(error(U_REGEX_MAX_LT_MIN));
+        }
+        Ok((min, max))
+    }
+
+    fn parse_number(&mut self) -> apperr::Result<Option<u32>> {
+        let mut res = None;
+        while let Some(digit) = self.peek().and_then(|ch| ch.to_digit(10)) {
+            self.pos += 1;
+            let n = res.unwrap_or(0u32) * 10 + digit;
+            if n > MAX_REPEAT {
+                return Err(error(U_REGEX_NUMBER_TOO_BIG));
+            }
+            res = Some(n);
+        }
+        Ok(res)
+    }
+
+    fn parse_atom(&mut self) -> apperr::Result<Node> {
+        let Some(ch) = self.next() else {
+            return Err(error(U_REGEX_RULE_SYNTAX));
+        };
+
+        Ok(match ch {
+            '(' => return self.parse_group(),
+            '[' => {
+                let class = self.parse_class()?;
+                self.class_node(class)
+            }
+            '.' => Node::Dot { dot_all: self.flags.dot_all },
+            '^' => Node::Look(Look::LineStart),
+            '$' => Node::Look(Look::LineEnd),
+            '\\' => return self.parse_escape(),
+            // Nothing to repeat.
+            '*' | '+' | '?' | '{' => return Err(error(U_REGEX_RULE_SYNTAX)),
+            ch => self.char_node(ch),
+        })
+    }
+
+    /// Parses a group after its opening `(`, including its closing `)`.
+    fn parse_group(&mut self) -> apperr::Result<Node> {
+        let flags = self.flags;
+        let mut capture = None;
+
+        if self.eat('?') {
+            match self.peek() {
+                Some(':') => self.pos += 1,
+                Some('#') => {
+                    while let Some(ch) = self.next() {
+                        if ch == ')' {
+                            return Ok(Node::Empty);
+                        }
+                    }
+                    return Err(error(U_REGEX_MISMATCHED_PAREN));
+                }
+                Some('<') if !matches!(self.peek_at(1), Some('=' | '!')) => {
+                    // Named groups can't be referred to by name in replacements,
+                    // but they're still numbered like any other group.
+                    while let Some(ch) = self.next() {
+                        if ch == '>' {
+                            break;
+                        }
+                        if !ch.is_ascii_alphanumeric() && ch != '<' {
+                            return Err(error(U_REGEX_RULE_SYNTAX));
+                        }
+                    }
+                    self.groups += 1;
+                    capture = Some(self.groups);
+                }
+                // Lookahead, lookbehind and atomic groups.
+                Some('=' | '!' | '<' | '>') => return Err(error(U_REGEX_UNIMPLEMENTED)),
+                _ => {
+                    if self.parse_flags()? {
+                        // `(?i)` applies until the end of the enclosing group.
+                        return Ok(Node::Empty);
+                    }
+                }
+            }
+        } else {
+            self.groups += 1;
+            capture = Some(self.groups);
+        }
+
+        let node = self.parse_alt()?;
+        if !self.eat(')') {
+            return Err(error(U_REGEX_MISMATCHED_PAREN));
+        }
+        self.flags = flags;
+
+        Ok(match capture {
+            Some(group) => Node::Capture(group, Box::new(node)),
+            None => node,
+        })
+    }
+
+    /// Parses the flags in `(?i-s)` or `(?i-s:...)`.
+    /// Returns true if it ended with `)`, or false if a group follows.
+    fn parse_flags(&mut self) -> apperr::Result<bool> {
+        let mut on = true;
+        loop {
+            match self.next() {
+                Some('i') => self.flags.case_insensitive = on,
+                Some('s') => self.flags.dot_all = on,
+                // ^ and $ always match at line breaks.
+                Some('m') => {}
+                Some('-') if on => on = false,
+                Some(')') => return Ok(true),
+                Some(':') => return Ok(false),
+                Some('x' | 'w') => return Err(error(U_REGEX_UNIMPLEMENTED)),
+                _ => return Err(error(U_REGEX_INVALID_FLAG)),
+            }
+        }
+    }
+
+    /// Parses an escape sequence after its `\`, outside of brackets.
+    fn parse_escape(&mut self) -> apperr::Result<Node> {
+        let look = match self.peek() {
+            Some('b') => Look::WordBoundary,
+            Some('B') => Look::NotWordBoundary,
+            Some('A') => Look::TextStart,
+            Some('z') => Look::TextEnd,
+            Some('Z') => Look::TextEndBeforeNewline,
+            Some('Q') => {
+                self.pos += 1;
+                let mut nodes = Vec::new();
+                while let Some(ch) = self.next() {
+                    if ch == '\\' && self.eat('E') {
+                        break;
+                    }
+                    nodes.push(self.char_node(ch));
+                }
+                return Ok(concat(nodes));
+            }
+            // Backreferences and other things that we can't do.
+            Some('1'..='9' | 'k' | 'G' | 'X' | 'R' | 'N') => {
+                return Err(error(U_REGEX_UNIMPLEMENTED));
+            }
+            _ => {
+                return Ok(match self.parse_escape_char()? {
+                    Escape::Char(ch) => self.char_node(ch),
+                    Escape::Kind(kind, negated) => self.class_node(Class {
+                        negated: false,
+                        items: vec![ClassItem::Kind(kind, negated)],
+                    }),
+                });
+            }
+        };
+
+        self.pos += 1;
+        Ok(Node::Look(look))
+    }
+
+    /// Parses an escape sequence after its `\` that's valid inside and outside of brackets.
+    fn parse_escape_char(&mut self) -> apperr::Result<Escape> {
+        let Some(ch) = self.next() else {
+            return Err(error(U_REGEX_BAD_ESCAPE_SEQUENCE));
+        };
+
+        let kind = match ch {
+            'd' | 'D' => CharKind::Digit,
+            'h' | 'H' => CharKind::HorizSpace,
+            's' | 'S' => CharKind::Space,
+            'w' | 'W' => CharKind::Word,
+            'p' | 'P' => self.parse_property()?,
+            _ => {
+                return Ok(Escape::Char(match ch {
+                    'a' => '\x07',
+                    'e' => '\x1b',
+                    'f' => '\x0c',
+                    'n' => '\n',
+                    'r' => '\r',
+                    't' => '\t',
+                    'x' if self.eat('{') => {
+                        let ch = self.parse_hex(1, 8)?;
+                        if !self.eat('}') {
+                            return Err(error(U_REGEX_BAD_ESCAPE_SEQUENCE));
+                        }
+                        ch
+                    }
+                    'x' => self.parse_hex(2, 2)?,
+                    'u' => self.parse_hex(4, 4)?,
+                    'U' => self.parse_hex(8, 8)?,
+                    '0' => {
+                        let mut cp = 0;
+                        for _ in 0..3 {
+                            let Some(digit) = self.peek().and_then(|ch| ch.to_digit(8)) else {
+                                break;
+                            };
+                            self.pos += 1;
+                            cp = cp * 8 + digit;
+                        }
+                        char::from_u32(cp).ok_or(error(U_REGEX_BAD_ESCAPE_SEQUENCE))?
+                    }
+                    'c' => match self.next() {
+                        Some(ch) if ch.is_ascii() => (ch as u8 ^ 0x40) as char,
+                        _ => return Err(error(U_REGEX_BAD_ESCAPE_SEQUENCE)),
+                    },
+                    // Like ICU with `UREGEX_ERROR_ON_UNKNOWN_ESCAPES`.
+                    ch if ch.is_ascii_alphanumeric() => {
+                        return Err(error(U_REGEX_BAD_ESCAPE_SEQUENCE));
+                    }
+                    ch => ch,
+                }));
+            }
+        };
+
+        Ok(Escape::Kind(kind, ch.is_ascii_uppercase()))
+    }
+
+    fn parse_hex(&mut self, min: usize, max: usize) -> apperr::Result<char> {
+        let mut cp = 0u32;
+        let mut len = 0;
+        while len < max
+            && let Some(digit) = self.peek().and_then(|ch| ch.to_digit(16))
+        {
+            self.pos += 1;
+            cp = cp * 16 + digit;
+            len += 1;
+        }
+        if len < min {
+            return Err(error(U_REGEX_BAD_ESCAPE_SEQUENCE));
+        }
+        char::from_u32(cp).ok_or(error(U_REGEX_BAD_ESCAPE_SEQUENCE))
+    }
+
+    /// Parses the `L` or `{Lu}` after `\p`.
+    fn parse_property(&mut self) -> apperr::Result<CharKind> {
+        if !self.eat('{') {
+            let name = self.next().ok_or(error(U_REGEX_PROPERTY_SYNTAX))?;
+            return property(name.encode_utf8(&mut [0; 4]));
+        }
+
+        let mut name = String::new();
+        loop {
+            match self.next() {
+                Some('}') => return property(&name),
+                Some(ch) => name.push(ch),
+                None => return Err(error(U_REGEX_PROPERTY_SYNTAX)),
+            }
+        }
+    }
+
+    /// Parses a bracket expression after its opening `[`, including its closing `]`.
+    fn parse_class(&mut self) -> apperr::Result<Class> {
+        let negated = self.eat('^');
+        let mut items = Vec::new();
+        let mut first = true;
+
+        loop {
+            let Some(ch) = self.next() else {
+                return Err(error(U_REGEX_MISSING_CLOSE_BRACKET));
+            };
+
+            let lo = match ch {
+                // A `]` right at the start is a literal.
+                ']' if !first => return Ok(Class { negated, items }),
+                '[' if self.eat(':') => {
+                    let negated = self.eat('^');
+                    let mut name = String::new();
+                    while let Some(ch) = self.next() {
+                        if ch == ':' {
+                            break;
+                        }
+                        name.push(ch);
+                    }
+                    if !self.eat(']') {
+                        return Err(error(U_REGEX_PROPERTY_SYNTAX));
+                    }
+                    items.push(ClassItem::Kind(property(&name)?, negated));
+                    first = false;
+                    continue;
+                }
+                '[' => {
+                    items.push(ClassItem::Class(self.parse_class()?));
+                    first = false;
+                    continue;
+                }
+                // Set intersection and difference.
+                '&' | '-' if self.peek() == Some(ch) => {
+                    return Err(error(U_REGEX_UNIMPLEMENTED));
+                }
+                '\\' => match self.parse_escape_char()? {
+                    Escape::Char(ch) => ch,
+                    Escape::Kind(kind, negated) => {
+                        items.push(ClassItem::Kind(kind, negated));
+                        first = false;
+                        continue;
+                    }
+                },
+                ch => ch,
+            };
+
+            first = false;
+
+            let mut hi = lo;
+            if self.peek() == Some('-') && !matches!(self.peek_at(1), Some(']') | None) {
+                self.pos += 1;
+                hi = match self.next() {
+                    Some('\\') => match self.parse_escape_char()? {
+                        Escape::Char(ch) => ch,
+                        Escape::Kind(..) => return Err(error(U_REGEX_INVALID_RANGE)),
+                    },
+                    Some('[') => return Err(error(U_REGEX_INVALID_RANGE)),
+                    Some(ch) => ch,
+                    None => return Err(error(U_REGEX_MISSING_CLOSE_BRACKET)),
+                };
+                if hi < lo {
+                    return Err(error(U_REGEX_INVALID_RANGE));
+                }
+            }
+
+            items.push(ClassItem::Range(lo, hi));
+        }
+    }
+}
+
+/// Returns the bytes that every match must start with, if there's such a thing.
+fn prefix(node: &Node) -> Option<Prefix> {
+    enum First {
+        Char(char, bool),
+        /// Consumes nothing, so the first character comes after it.
+        Transparent,
+        Unknown,
+    }
+
+    fn first(node: &Node) -> First {
+        match node {
+            Node::Empty | Node::Look(_) => First::Transparent,
+            Node::Char(ch) => First::Char(*ch, false),
+            Node::CharFold(ch) => First::Char(*ch, true),
+            Node::Capture(_, node) => first(node),
+            Node::Concat(nodes) => {
+                for node in nodes {
+                    match first(node) {
+                        First::Transparent => {}
+                        res => return res,
+                    }
+                }
+                First::Transparent
+            }
+            Node::Repeat { node, min, .. } if *min > 0 => first(node),
+            _ => First::Unknown,
+        }
+    }
+
+    match first(node) {
+        First::Char(ch, false) => {
+            let b = ch.encode_utf8(&mut [0; 4]).as_bytes()[0];
+            Some((b, b))
+        }
+        // The Kelvin sign lowercases to "k", so that's the only exception among ASCII letters.
+        First::Char(ch, true) if ch.is_ascii_lowercase() && ch != 'k' => {
+            Some((ch as u8, ch.to_ascii_uppercase() as u8))
+        }
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Inst {
+    Char(char),
+    CharFold(char),
+    Dot {
+        dot_all: bool,
+    },
+    Class(usize, bool),
+    Assert(Look),
+    /// Continue at both targets, preferring the first one.
+    Split(usize, usize),
+    Jmp(usize),
+    /// Store the current offset in the given capture slot.
+    Save(usize),
+    Match,
+}
+
+struct Compiler {
+    insts: Vec<Inst>,
+}
+
+impl Compiler {
+    fn emit(&mut self, inst: Inst) -> apperr::Result<usize> {
+        if self.insts.len() >= MAX_INSTS {
+            return Err(error(U_REGEX_PATTERN_TOO_BIG));
+        }
+        self.insts.push(inst);
+        Ok(self.insts.len() - 1)
+    }
+
+    fn compile(&mut self, node: &Node) -> apperr::Result<()> {
+        match node {
+            Node::Empty => {}
+            Node::Char(ch) => _ = self.emit(Inst::Char(*ch))?,
+            Node::CharFold(ch) => _ = self.emit(Inst::CharFold(*ch))?,
+            Node::Dot { dot_all } => _ = self.emit(Inst::Dot { dot_all: *dot_all })?,
+            Node::Class(i, ci) => _ = self.emit(Inst::Class(*i, *ci))?,
+            Node::Look(look) => _ = self.emit(Inst::Assert(*look))?,
+            Node::Capture(group, node) => {
+                self.emit(Inst::Save(group * 2))?;
+                self.compile(node)?;
+                self.emit(Inst::Save(group * 2 + 1))?;
+            }
+            Node::Concat(nodes) => {
+                for node in nodes {
+                    self.compile(node)?;
+                }
+            }
+            Node::Alt(nodes) => {
+                let mut jumps = Vec::new();
+                for (i, node) in nodes.iter().enumerate() {
+                    if i + 1 == nodes.len() {
+                        self.compile(node)?;
+                        break;
+                    }
+                    let split = self.emit(Inst::Split(0, 0))?;
+                    self.compile(node)?;
+                    jumps.push(self.emit(Inst::Jmp(0))?);
+                    self.insts[split] = Inst::Split(split + 1, self.insts.len());
+                }
+                let end = self.insts.len();
+                for jump in jumps {
+                    self.insts[jump] = Inst::Jmp(end);
+                }
+            }
+            &Node::Repeat { ref node, min, max, greedy } => {
+                let fork = |more, done| {
+                    if greedy { Inst::Split(more, done) } else { Inst::Split(done, more) }
+                };
+
+                match max {
+                    None if min == 0 => {
+                        let split = self.emit(Inst::Split(0, 0))?;
+                        self.compile(node)?;
+                        self.emit(Inst::Jmp(split))?;
+                        self.insts[split] = fork(split + 1, self.insts.len());
+                    }
+                    None => {
+                        for _ in 1..min {
+                            self.compile(node)?;
+                        }
+                        let beg = self.insts.len();
+                        self.compile(node)?;
+                        let end = self.insts.len() + 1;
+                        self.emit(fork(beg, end))?;
+                    }
+                    Some(max) => {
+                        for _ in 0..min {
+                            self.compile(node)?;
+                        }
+                        let mut splits = Vec::new();
+                        for _ in min..max {
+                            splits.push(self.emit(Inst::Split(0, 0))?);
+                            self.compile(node)?;
+                        }
+                        let end = self.insts.len();
+                        for split in splits {
+                            self.insts[split] = fork(split + 1, end);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The text around the offset the VM is at.
+#[derive(Clone, Copy)]
+struct Context {
+    at: usize,
+    prev: Option<char>,
+    /// The character at `at` and its length.
+    cur: Option<(char, usize)>,
+}
+
+/// A set of threads, ordered by priority. Each one is identified by its instruction.
+struct Threads {
+    dense: Vec<usize>,
+    sparse: Box<[usize]>,
+    /// The capture slots of each thread, indexed by instruction.
+    slots: Box<[usize]>,
+    slot_count: usize,
+}
+
+impl Threads {
+    fn new(inst_count: usize, slot_count: usize) -> Self {
+        Self {
+            dense: Vec::with_capacity(inst_count),
+            sparse: vec![0; inst_count].into_boxed_slice(),
+            slots: vec![usize::MAX; inst_count * slot_count].into_boxed_slice(),
+            slot_count,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.dense.is_empty()
+    }
+
+    fn clear(&mut self) {
+        self.dense.clear();
+    }
+
+    fn contains(&self, pc: usize) -> bool {
+        let i = self.sparse[pc];
+        i < self.dense.len() && self.dense[i] == pc
+    }
+
+    fn insert(&mut self, pc: usize) {
+        self.sparse[pc] = self.dense.len();
+        self.dense.push(pc);
+    }
+
+    fn slots(&self, pc: usize) -> &[usize] {
+        &self.slots[pc * self.slot_count..(pc + 1) * self.slot_count]
+    }
+
+    fn slots_mut(&mut self, pc: usize) -> &mut [usize] {
+        &mut self.slots[pc * self.slot_count..(pc + 1) * self.slot_count]
+    }
+}
+
+enum Frame {
+    Explore(usize),
+    /// Undoes a [`Inst::Save`] once the threads after it have been added.
+    Restore(usize, usize),
+}
+
+/// Memory for [`Regex::search`] that's kept around between searches.
+struct Vm {
+    clist: Threads,
+    nlist: Threads,
+    stack: Vec<Frame>,
+    /// The capture slots of the thread that's being added.
+    scratch: Vec<usize>,
+}
+
+impl Vm {
+    fn new(inst_count: usize, slot_count: usize) -> Self {
+        Self {
+            clist: Threads::new(inst_count, slot_count),
+            nlist: Threads::new(inst_count, slot_count),
+            stack: Vec::new(),
+            scratch: vec![usize::MAX; slot_count],
+        }
+    }
+}
+
+/// Adds the thread at `pc` to `list`, following all instructions that don't consume
+/// a character, so that only threads waiting for a character (or done) end up in it.
+fn add_thread(
+    insts: &[Inst],
+    list: &mut Threads,
+    stack: &mut Vec<Frame>,
+    slots: &mut [usize],
+    pc: usize,
+    ctx: Context,
+    input: &mut Input,
+) {
+    stack.push(Frame::Explore(pc));
+
+    while let Some(frame) = stack.pop() {
+        let pc = match frame {
+            Frame::Explore(pc) => pc,
+            Frame::Restore(slot, offset) => {
+                slots[slot] = offset;
+                continue;
+            }
+        };
+
+        // A thread that got here first has a higher priority.
+        if list.contains(pc) {
+            continue;
+        }
+        list.insert(pc);
+
+        match insts[pc] {
+            Inst::Split(a, b) => {
+                stack.push(Frame::Explore(b));
+                stack.push(Frame::Explore(a));
+            }
+            Inst::Jmp(target) => stack.push(Frame::Explore(target)),
+            Inst::Save(slot) => {
+                stack.push(Frame::Restore(slot, slots[slot]));
+                stack.push(Frame::Explore(pc + 1));
+                slots[slot] = ctx.at;
+            }
+            Inst::Assert(look) => {
+                if look.matches(ctx, input) {
+                    stack.push(Frame::Explore(pc + 1));
+                }
+            }
+            _ => list.slots_mut(pc).copy_from_slice(slots),
+        }
+    }
+}
+
+/// Reads characters from a [`ReadableDocument`], caching the current chunk.
+struct Input<'a> {
+    doc: &'a dyn ReadableDocument,
+    chunk: &'a [u8],
+    chunk_off: usize,
+}
+
+impl<'a> Input<'a> {
+    fn new(doc: &'a dyn ReadableDocument) -> Self {
+        Self { doc, chunk: &[], chunk_off: 0 }
+    }
+
+    /// Returns the character at `off` and its length.
+    /// Invalid UTF-8 is returned as U+FFFD, like [`Utf8Chars`] does.
+    fn char_at(&mut self, off: usize) -> Option<(char, usize)> {
+        if off < self.chunk_off || off >= self.chunk_off + self.chunk.len() {
+            self.chunk = self.doc.read_forward(off);
+            self.chunk_off = off;
+            if self.chunk.is_empty() {
+                return None;
+            }
+        }
+
+        let bytes = &self.chunk[off - self.chunk_off..];
+        if bytes.len() >= 4 || bytes[0] < 0x80 {
+            return Some(decode(bytes));
+        }
+
+        // The character may continue in the next chunk.
+        let mut buf = [0; 4];
+        let mut len = 0;
+        while len < buf.len() {
+            let chunk = self.doc.read_forward(off + len);
+            if chunk.is_empty() {
+                break;
+            }
+            let n = chunk.len().min(buf.len() - len);
+            buf[len..len + n].copy_from_slice(&chunk[..n]);
+            len += n;
+        }
+        Some(decode(&buf[..len]))
+    }
+
+    /// Returns the character before `off`.
+    fn char_before(&self, off: usize) -> Option<char> {
+        let chunk = self.doc.read_backward(off);
+        let len = chunk.len();
+        if len == 0 {
+            return None;
+        }
+
+        let mut beg = len - 1;
+        while beg > 0 && len - beg < 4 && chunk[beg] & 0xc0 == 0x80 {
+            beg -= 1;
+        }
+
+        let (ch, n) = decode(&chunk[beg..]);
+        Some(if beg + n == len { ch } else { char::REPLACEMENT_CHARACTER })
+    }
+
+    /// Returns the offset of the next occurrence of either byte at or after `off`.
+    fn find(&self, a: u8, b: u8, mut off: usize) -> Option<usize> {
+        loop {
+            let chunk = self.doc.read_forward(off);
+            if chunk.is_empty() {
+                return None;
+            }
+            let i = memchr2(a, b, chunk, 0);
+            if i < chunk.len() {
+                return Some(off + i);
+            }
+            off += chunk.len();
+        }
+    }
+}
+
+fn decode(bytes: &[u8]) -> (char, usize) {
+    let mut chars = Utf8Chars::new(bytes, 0);
+    let ch = chars.next().unwrap_or(char::REPLACEMENT_CHARACTER);
+    (ch, chars.offset().max(1))
+}
+
+/// Line terminators, as far as `.`, `^` and `$` are concerned.
+fn is_line_terminator(ch: char) -> bool {
+    matches!(ch, '\n' | '\x0b' | '\x0c' | '\r' | '\u{85}' | '\u{2028}' | '\u{2029}')
+}
+
+/// The zeros of all decimal digits (general category Nd), which come in runs of 10.
+const DIGIT_ZEROS: [char; 66] = [
+    '\u{30}',
+    '\u{660}',
+    '\u{6f0}',
+    '\u{7c0}',
+    '\u{966}',
+    '\u{9e6}',
+    '\u{a66}',
+    '\u{ae6}',
+    '\u{b66}',
+    '\u{be6}',
+    '\u{c66}',
+    '\u{ce6}',
+    '\u{d66}',
+    '\u{de6}',
+    '\u{e50}',
+    '\u{ed0}',
+    '\u{f20}',
+    '\u{1040}',
+    '\u{1090}',
+    '\u{17e0}',
+    '\u{1810}',
+    '\u{1946}',
+    '\u{19d0}',
+    '\u{1a80}',
+    '\u{1a90}',
+    '\u{1b50}',
+    '\u{1bb0}',
+    '\u{1c40}',
+    '\u{1c50}',
+    '\u{a620}',
+    '\u{a8d0}',
+    '\u{a900}',
+    '\u{a9d0}',
+    '\u{a9f0}',
+    '\u{aa50}',
+    '\u{abf0}',
+    '\u{ff10}',
+    '\u{104a0}',
+    '\u{10d30}',
+    '\u{11066}',
+    '\u{110f0}',
+    '\u{11136}',
+    '\u{111d0}',
+    '\u{112f0}',
+    '\u{11450}',
+    '\u{114d0}',
+    '\u{11650}',
+    '\u{116c0}',
+    '\u{11730}',
+    '\u{118e0}',
+    '\u{11950}',
+    '\u{11c50}',
+    '\u{11d50}',
+    '\u{11da0}',
+    '\u{16a60}',
+    '\u{16ac0}',
+    '\u{16b50}',
+    '\u{1d7ce}',
+    '\u{1d7d8}',
+    '\u{1d7e2}',
+    '\u{1d7ec}',
+    '\u{1d7f6}',
+    '\u{1e140}',
+    '\u{1e2f0}',
+    '\u{1e950}',
+    '\u{1fbf0}',
+];
+
+fn is_decimal_digit(ch: char) -> bool {
+    if ch.is_ascii() {
+        return ch.is_ascii_digit();
+    }
+    let i = DIGIT_ZEROS.partition_point(|&zero| zero <= ch);
+    i > 0 && (ch as u32) < DIGIT_ZEROS[i - 1] as u32 + 10
+}
+
+/// ICU defines `\w` as `[\p{Alphabetic}\p{Mark}\p{Nd}\p{Pc}\u200c\u200d]`.
+/// We lack the marks and the connector punctuation other than `_`.
+fn is_word(ch: char) -> bool {
+    ch.is_alphabetic() || is_decimal_digit(ch) || matches!(ch, '_' | '\u{200c}' | '\u{200d}')
+}
+
+fn simple_lowercase(ch: char) -> char {
+    let mut lower = ch.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(lower), None) => lower,
+        _ => ch,
+    }
+}
+
+fn simple_uppercase(ch: char) -> char {
+    let mut upper = ch.to_uppercase();
+    match (upper.next(), upper.next()) {
+        (Some(upper), None) => upper,
+        _ => ch,
+    }
+}
+
+fn fold(ch: char) -> char {
+    simple_lowercase(ch)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter;
+
+    use super::*;
+    use crate::buffer::TextBuffer;
+    use crate::icu;
+
+    struct ChunkedDoc<'a>(Vec<&'a [u8]>);
+
+    impl ReadableDocument for ChunkedDoc<'_> {
+        fn read_forward(&self, mut off: usize) -> &[u8] {
+            for chunk in &self.0 {
+                if off < chunk.len() {
+                    return &chunk[off..];
+                }
+                off -= chunk.len();
+            }
+            &[]
+        }
+
+        fn read_backward(&self, off: usize) -> &[u8] {
+            let mut beg = 0;
+            for chunk in &self.0 {
+                if off <= beg + chunk.len() {
+                    return &chunk[..off - beg];
+                }
+                beg += chunk.len();
+            }
+            self.0.last().copied().unwrap_or_default()
+        }
+    }
+
+    fn find_all(pattern: &str, flags: i32, doc: &dyn ReadableDocument) -> Vec<Range<usize>> {
+        let mut re = Regex::new(pattern, flags).unwrap();
+        iter::from_fn(|| re.find_next(doc)).collect()
+    }
+
+    fn find_strs<'a>(pattern: &str, flags: i32, text: &'a str) -> Vec<&'a str> {
+        find_all(pattern, flags, &text.as_bytes()).into_iter().map(|r| &text[r]).collect()
+    }
+
+    #[test]
+    fn test_syntax() {
+        let cases: &[(&str, &str, &[&str])] = &[
+            ("a+", "baaac a", &["aaa", "a"]),
+            ("cat|dog", "hotdog catalog", &["dog", "cat"]),
+            ("colou?r", "color colour colouur", &["color", "colour"]),
+            ("x{2,3}", "xxxxxxx", &["xxx", "xxx"]),
+            ("x{2}", "xxxxx", &["xx", "xx"]),
+            ("x{2,}y", "xy xxy xxxxy", &["xxy", "xxxxy"]),
+            ("a.c", "abc a\nc a\rc aüc", &["abc", "aüc"]),
+            ("(?s)a.c", "a\nc", &["a\nc"]),
+            ("[a-c]+", "xabcabd", &["abcab"]),
+            (r"[^a-c\s]+", "ab de\tfc", &["de", "f"]),
+            (r"[]a]+", "]a]b", &["]a]"]),
+            (r"[\w-]+", "foo-bar baz", &["foo-bar", "baz"]),
+            (r"[[:digit:]x]+", "a1x2b", &["1x2"]),
+            (r"\d+", "a12b345", &["12", "345"]),
+            (r"\b\p{Lu}\p{Ll}+", "foo Bar ÄÖü Über", &["Bar", "Über"]),
+            (r"\d+", "١٢٣ ½ 4", &["١٢٣", "4"]),
+            (r"\bfoo\b", "foo foobar barfoo foo", &["foo", "foo"]),
+            (r"\Bo\B", "foo oops", &["o", "o"]),
+            (r"^b", "b\nab\nb\r\nb", &["b", "b", "b"]),
+            (r"a$", "a\nba\r\naa", &["a", "a", "a"]),
+            (r"^$", "a\n\nb\n", &[""]),
+            (r"\Aa|a\z", "aba", &["a", "a"]),
+            (r"a\Z", "a a\n", &["a"]),
+            ("(?i)straße", "STRAßE Straße", &["STRAßE", "Straße"]),
+            ("(?i:a)A", "aA AA aa", &["aA", "AA"]),
+            ("(?i)[a-c]+", "xAbC", &["AbC"]),
+            ("(?i)[^a]", "aAb", &["b"]),
+            ("a*?b", "aab", &["aab"]),
+            ("a+?", "aaa", &["a", "a", "a"]),
+            (r"\Qa.b\E.", "a.bc axbc", &["a.bc"]),
+            (r"\x41\x{1F389}ä\n", "A🎉ä\n", &["A🎉ä\n"]),
+            ("(?#comment)a", "a", &["a"]),
+            ("(?<name>a)b", "ab", &["ab"]),
+            ("😀+", "x😀😀y", &["😀😀"]),
+        ];
+
+        for &(pattern, text, expected) in cases {
+            assert_eq!(find_strs(pattern, 0, text), expected, "{pattern:?} in {text:?}");
+        }
+    }
+
+    #[test]
+    fn test_flags() {
+        let ci = Regex::CASE_INSENSITIVE;
+        assert_eq!(find_strs("FOO", ci, "foo Foo fOO"), ["foo", "Foo", "fOO"]);
+        assert_eq!(find_strs("a.b", Regex::LITERAL, "axb a.b"), ["a.b"]);
+        assert_eq!(find_strs("A(", Regex::LITERAL | ci, "a( A("), ["a(", "A("]);
+        // The Kelvin sign lowercases to "k".
+        assert_eq!(find_strs("k", ci, "K k K"), ["K", "k", "K"]);
+    }
+
+    #[test]
+    fn test_empty_matches() {
+        let text = "axx";
+        assert_eq!(find_all("x*", 0, &text.as_bytes()), [0..0, 1..3, 3..3]);
+        assert_eq!(find_all("", 0, &text.as_bytes()), [0..0, 1..1, 2..2, 3..3]);
+        assert_eq!(find_all("^", 0, &"a\n".as_bytes()), vec![0..0]);
+    }
+
+    #[test]
+    fn test_groups() {
+        let text = "foo@bar";
+        let doc = text.as_bytes();
+        let mut re = Regex::new(r"(\w+)@(\w+)(x)?", 0).unwrap();
+        assert_eq!(re.group_count(), 3);
+        assert_eq!(re.group(0), None);
+        assert_eq!(re.find_next(&doc), Some(0..7));
+        assert_eq!(re.group(1), Some(0..3));
+        assert_eq!(re.group(2), Some(4..7));
+        assert_eq!(re.group(3), None);
+        assert_eq!(re.group(4), None);
+        assert_eq!(re.find_next(&doc), None);
+        assert_eq!(re.group(0), None);
+
+        // Leftmost-first, like a backtracking engine.
+        let mut re = Regex::new("(a|ab)(c|bcd)", 0).unwrap();
+        assert_eq!(re.find_next(&"abcd".as_bytes()), Some(0..4));
+        assert_eq!(re.group(1), Some(0..1));
+        assert_eq!(re.group(2), Some(1..4));
+
+        // The last iteration wins.
+        let mut re = Regex::new("(?:(a)|b)+", 0).unwrap();
+        assert_eq!(re.find_next(&"ab".as_bytes()), Some(0..2));
+        assert_eq!(re.group(1), Some(0..1));
+    }
+
+    #[test]
+    fn test_reset() {
+        let doc = "aXbX".as_bytes();
+        let mut re = Regex::new("X", 0).unwrap();
+        assert_eq!(re.find_next(&doc), Some(1..2));
+        assert_eq!(re.find_next(&doc), Some(3..4));
+        assert_eq!(re.find_next(&doc), None);
+        assert_eq!(re.find_next(&doc), None);
+        re.reset(2);
+        assert_eq!(re.find_next(&doc), Some(3..4));
+    }
+
+    #[test]
+    fn test_chunks() {
+        let text = "Grüße\r\nfoo 世界 bar\nä🎉b foo";
+        let bytes: Vec<&[u8]> = text.as_bytes().chunks(1).collect();
+        let chunked = ChunkedDoc(bytes);
+
+        for pattern in [r"\w+", "ü|界|🎉", r"^\S+$", r"\bfoo\b", r"[^\x00-\x7f]+", "(?i)GRÜ"] {
+            assert_eq!(
+                find_all(pattern, 0, &chunked),
+                find_all(pattern, 0, &text.as_bytes()),
+                "{pattern:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let patterns = [
+            "(",
+            ")",
+            "a)",
+            "[a",
+            "a{2,1}",
+            "a{",
+            "a{x}",
+            "*",
+            "a**+",
+            r"\1",
+            "(?=a)",
+            "(?<!a)",
+            "(?>a)",
+            r"\q",
+            "[z-a]",
+            r"[a-\d]",
+            r"\p{Nope}",
+            "(?z)",
+            "a++",
+            r"\",
+        ];
+        for pattern in patterns {
+            assert!(Regex::new(pattern, 0).is_err(), "{pattern:?}");
+        }
+        assert_eq!(
+            Regex::new("a{2,1}", 0).err().and_then(|err| match err {
+                apperr::Error::Icu(code) => error_name(code),
+                _ => None,
+            }),
+            Some("U_REGEX_MAX_LT_MIN")
+        );
+        assert!(Regex::new("a{100000}", 0).is_err());
+        assert!(Regex::new("(?:a{1000}){1000}", 0).is_err());
+    }
+
+    /// Compares the results with ICU's, if it's installed.
+    #[test]
+    fn test_icu_parity() {
+        if icu::init().is_err() {
+            return;
+        }
+
+        let texts = [
+            "foo Foo FOO\nbar foobar\r\nbaz_1 2 ",
+            "\n\na\r\n\r\nb\n",
+            "Grüße, Straße! ÄÖÜ äöü 123 ½\tend",
+            "color colour colouur aaa bbb abab",
+        ];
+        let patterns = [
+            "foo",
+            r"\bfoo\b",
+            r"^\w+",
+            r"\w+$",
+            "^",
+            "$",
+            "^$",
+            "a*",
+            "(?:ab)+",
+            "colou?r",
+            r"\s+",
+            r"\S+",
+            r"\d",
+            r"[^\s\w]",
+            "[a-z]+",
+            "[A-Z]",
+            r".\r?\n",
+            "ß|ö+",
+            r"(\w)(\w)?",
+            "(?i)straße",
+            r"a{2,}|b{1,2}",
+        ];
+
+        for text in texts {
+            let mut tb = TextBuffer::new(false).unwrap();
+            tb.write_raw(text.as_bytes());
+
+            for pattern in patterns {
+                for flags in [0, Regex::CASE_INSENSITIVE] {
+                    let icu_text = unsafe { icu::Text::new(&tb).unwrap() };
+                    let mut icu_regex = unsafe {
+                        icu::Regex::new(pattern, icu::Regex::MULTILINE | flags, &icu_text).unwrap()
+                    };
+                    let mut regex = Regex::new(pattern, flags).unwrap();
+
+                    loop {
+                        let expected = icu_regex.next();
+                        let actual = regex.find_next(&tb);
+                        assert_eq!(actual, expected, "{pattern:?} ({flags}) in {text:?}");
+                        if expected.is_none() {
+                            break;
+                        }
+                        for group in 1..=regex.group_count() {
+                            let expected = icu_regex.group(group).filter(|r| !r.is_empty());
+                            let actual = regex.group(group).filter(|r| !r.is_empty());
+                            assert_eq!(actual, expected, "{pattern:?} group {group} in {text:?}");
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,647 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! A small spell checker for Hunspell-style `.dic`/`.aff` dictionaries.
+//!
+//! Only the subset of the affix file format that is needed to check
+//! prose is supported: `SET`, `FLAG`, `TRY`, `NEEDAFFIX`, `FORBIDDENWORD`
+//! and `PFX`/`SFX` rules (including cross products). Compounding, `REP`
+//! tables and morphological fields are ignored.
+//!
+//! Instead of implementing Hunspell's affix stripping at lookup time,
+//! all word forms are expanded when the dictionary is loaded and stored
+//! in a sorted list. This keeps lookups simple and allows prefix search
+//! for completion, at the cost of a slower load and higher memory usage.
+
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::{env, fs};
+
+use stdext::arena::scratch_arena;
+
+use crate::{apperr, sys};
+
+/// The maximum number of suggestions returned by [`Dictionary::suggest`].
+const MAX_SUGGESTIONS: usize = 8;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FlagType {
+    /// Each character is a flag.
+    Char,
+    /// Each pair of characters is a flag.
+    Long,
+    /// Flags are comma-separated decimal numbers.
+    Num,
+}
+
+enum CharClass {
+    Any,
+    Char(char),
+    Set { negated: bool, chars: Vec<char> },
+}
+
+impl CharClass {
+    fn matches(&self, ch: char) -> bool {
+        match self {
+            CharClass::Any => true,
+            CharClass::Char(c) => *c == ch,
+            CharClass::Set { negated, chars } => chars.contains(&ch) != *negated,
+        }
+    }
+}
+
+struct AffixRule {
+    strip: String,
+    add: String,
+    condition: Vec<CharClass>,
+}
+
+struct Affix {
+    flag: u32,
+    suffix: bool,
+    cross_product: bool,
+    rules: Vec<AffixRule>,
+}
+
+impl Affix {
+    /// Applies the rule to `word` if its condition matches and returns the new form.
+    fn apply(&self, rule: &AffixRule, word: &str) -> Option<String> {
+        let chars: Vec<char> = word.chars().collect();
+        let len = rule.condition.len();
+        if chars.len() < len {
+            return None;
+        }
+
+        let matches = if self.suffix {
+            word.ends_with(rule.strip.as_str())
+                && chars[chars.len() - len..]
+                    .iter()
+                    .zip(&rule.condition)
+                    .all(|(&c, cc)| cc.matches(c))
+        } else {
+            word.starts_with(rule.strip.as_str())
+                && chars[..len].iter().zip(&rule.condition).all(|(&c, cc)| cc.matches(c))
+        };
+        if !matches {
+            return None;
+        }
+
+        let mut res = String::with_capacity(word.len() + rule.add.len());
+        if self.suffix {
+            res.push_str(&word[..word.len() - rule.strip.len()]);
+            res.push_str(&rule.add);
+        } else {
+            res.push_str(&rule.add);
+            res.push_str(&word[rule.strip.len()..]);
+        }
+        // A rule that strips the entire word would produce garbage.
+        if res.is_empty() { None } else { Some(res) }
+    }
+}
+
+/// A loaded spell checking dictionary.
+pub struct Dictionary {
+    /// All word forms, sorted by their bytes.
+    words: Vec<String>,
+    /// Characters to try when generating suggestions, most common first.
+    try_chars: Vec<char>,
+}
+
+impl Dictionary {
+    /// Loads a dictionary from the given `.aff` and `.dic` files.
+    pub fn load(aff_path: &Path, dic_path: &Path) -> apperr::Result<Self> {
+        let aff = fs::read(aff_path)?;
+        let dic = fs::read(dic_path)?;
+        Ok(Self::parse_bytes(&aff, &dic))
+    }
+
+    /// Parses a dictionary from the raw contents of an `.aff` and `.dic` file.
+    /// Both are decoded according to the `SET` directive of the affix file.
+    pub fn parse_bytes(aff: &[u8], dic: &[u8]) -> Self {
+        let latin1 =
+            aff.split(|&b| b == b'\n').filter_map(|line| line.strip_prefix(b"SET")).any(|set| {
+                let set = set.trim_ascii();
+                set.eq_ignore_ascii_case(b"ISO8859-1") || set.eq_ignore_ascii_case(b"ISO-8859-1")
+            });
+        let decode = |bytes: &[u8]| -> String {
+            if latin1 {
+                bytes.iter().map(|&b| b as char).collect()
+            } else {
+                String::from_utf8_lossy(bytes).into_owned()
+            }
+        };
+        Self::parse(&decode(aff), &decode(dic))
+    }
+
+    /// Parses a dictionary from the contents of an `.aff` and `.dic` file.
+    pub fn parse(aff: &str, dic: &str) -> Self {
+        let mut flag_type = FlagType::Char;
+        let mut try_chars = Vec::new();
+        let mut need_affix = None;
+        let mut forbidden = None;
+        let mut affixes: Vec<Affix> = Vec::new();
+
+        for line in aff.lines() {
+            let mut tokens = line.split_whitespace();
+            let Some(directive) = tokens.next() else {
+                continue;
+            };
+
+            match directive {
+                "FLAG" => {
+                    flag_type = match tokens.next() {
+                        Some("long") => FlagType::Long,
+                        Some("num") => FlagType::Num,
+                        _ => FlagType::Char,
+                    };
+                }
+                "TRY" => try_chars = tokens.next().unwrap_or_default().chars().collect(),
+                "NEEDAFFIX" | "PSEUDOROOT" => {
+                    need_affix =
+                        tokens.next().and_then(|f| parse_flags(f, flag_type).first().copied())
+                }
+                "FORBIDDENWORD" => {
+                    forbidden =
+                        tokens.next().and_then(|f| parse_flags(f, flag_type).first().copied())
+                }
+                "PFX" | "SFX" => {
+                    let suffix = directive == "SFX";
+                    let (Some(flag), Some(a), Some(b)) =
+                        (tokens.next(), tokens.next(), tokens.next())
+                    else {
+                        continue;
+                    };
+                    let Some(&flag) = parse_flags(flag, flag_type).first() else {
+                        continue;
+                    };
+
+                    // A header looks like "SFX D Y 4", a rule like "SFX D 0 ed [^ey]".
+                    let is_header = matches!(a, "Y" | "N") && b.parse::<usize>().is_ok();
+                    if is_header {
+                        affixes.push(Affix {
+                            flag,
+                            suffix,
+                            cross_product: a == "Y",
+                            rules: Vec::new(),
+                        });
+                        continue;
+                    }
+
+                    let Some(affix) =
+                        affixes.iter_mut().rev().find(|x| x.flag == flag && x.suffix == suffix)
+                    else {
+                        continue;
+                    };
+                    let strip = if a == "0" { "" } else { a };
+                    // Continuation classes ("ed/XY") are not supported.
+                    let add = b.split('/').next().unwrap_or_default();
+                    let add = if add == "0" { "" } else { add };
+                    let condition = parse_condition(tokens.next().unwrap_or("."));
+                    affix.rules.push(AffixRule {
+                        strip: strip.to_string(),
+                        add: add.to_string(),
+                        condition,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        let mut words = Vec::new();
+        let mut lines = dic.lines();
+        // The first line contains the approximate word count.
+        if let Some(first) = lines.next()
+            && first.trim().parse::<usize>().is_err()
+        {
+            // ...unless it doesn't. Be lenient and treat it as a word.
+            Self::expand(first, flag_type, need_affix, forbidden, &affixes, &mut words);
+        }
+        for line in lines {
+            Self::expand(line, flag_type, need_affix, forbidden, &affixes, &mut words);
+        }
+
+        words.sort_unstable();
+        words.dedup();
+
+        if try_chars.is_empty() {
+            try_chars = "esianrtolcdugmphbyfvkwzxqj'".chars().collect();
+        }
+
+        Self { words, try_chars }
+    }
+
+    fn expand(
+        line: &str,
+        flag_type: FlagType,
+        need_affix: Option<u32>,
+        forbidden: Option<u32>,
+        affixes: &[Affix],
+        words: &mut Vec<String>,
+    ) {
+        // Morphological fields are separated by whitespace.
+        let entry = line.split(['\t', ' ']).next().unwrap_or_default();
+        if entry.is_empty() || entry.starts_with('#') {
+            return;
+        }
+
+        let (word, flags) = match entry.split_once('/') {
+            Some((word, flags)) => (word, parse_flags(flags, flag_type)),
+            None => (entry, Vec::new()),
+        };
+        if word.is_empty() || forbidden.is_some_and(|f| flags.contains(&f)) {
+            return;
+        }
+
+        if !need_affix.is_some_and(|f| flags.contains(&f)) {
+            words.push(word.to_string());
+        }
+
+        let has = |affix: &&Affix| flags.contains(&affix.flag);
+
+        for sfx in affixes.iter().filter(|a| a.suffix).filter(has) {
+            for rule in &sfx.rules {
+                let Some(form) = sfx.apply(rule, word) else {
+                    continue;
+                };
+
+                if sfx.cross_product {
+                    for pfx in affixes.iter().filter(|a| !a.suffix && a.cross_product).filter(has) {
+                        for rule in &pfx.rules {
+                            if let Some(form) = pfx.apply(rule, &form) {
+                                words.push(form);
+                            }
+                        }
+                    }
+                }
+
+                words.push(form);
+            }
+        }
+
+        for pfx in affixes.iter().filter(|a| !a.suffix).filter(has) {
+            for rule in &pfx.rules {
+                if let Some(form) = pfx.apply(rule, word) {
+                    words.push(form);
+                }
+            }
+        }
+    }
+
+    /// Returns the number of word forms in the dictionary.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Returns true if the dictionary contains no words.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    fn contains(&self, word: &str) -> bool {
+        self.words.binary_search_by(|w| w.as_str().cmp(word)).is_ok()
+    }
+
+    /// Returns true if the given word is spelled correctly.
+    ///
+    /// Capitalized words ("Hello") and all-caps words ("HELLO")
+    /// are accepted if their lowercase form is in the dictionary.
+    pub fn check(&self, word: &str) -> bool {
+        let normalized;
+        let word = if word.contains('\u{2019}') {
+            // Typographic apostrophes are common in prose, but dictionaries use ASCII ones.
+            normalized = word.replace('\u{2019}', "'");
+            normalized.as_str()
+        } else {
+            word
+        };
+
+        if self.contains(word) {
+            return true;
+        }
+
+        match Casing::of(word) {
+            Casing::Capitalized => self.contains(&word.to_lowercase()),
+            Casing::Upper => {
+                let lower = word.to_lowercase();
+                self.contains(&lower) || self.contains(&capitalize(&lower))
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns spelling suggestions for a misspelled word, best first.
+    pub fn suggest(&self, word: &str) -> Vec<String> {
+        let casing = Casing::of(word);
+        let lower = if casing == Casing::Lower { word.to_string() } else { word.to_lowercase() };
+        let chars: Vec<char> = lower.chars().collect();
+        let mut candidates = Vec::new();
+        let mut push = |candidate: Vec<char>| {
+            let candidate: String = candidate.into_iter().collect();
+            if candidate != lower && self.contains(&candidate) && !candidates.contains(&candidate) {
+                candidates.push(candidate);
+            }
+        };
+
+        // Swapped adjacent characters are the most common typo, followed by
+        // missing, extraneous and wrong characters, in that order.
+        for i in 1..chars.len() {
+            let mut c = chars.clone();
+            c.swap(i - 1, i);
+            push(c);
+        }
+        for i in 0..=chars.len() {
+            for &t in &self.try_chars {
+                let mut c = chars.clone();
+                c.insert(i, t);
+                push(c);
+            }
+        }
+        for i in 0..chars.len() {
+            let mut c = chars.clone();
+            c.remove(i);
+            push(c);
+        }
+        for i in 0..chars.len() {
+            for &t in &self.try_chars {
+                if t != chars[i] {
+                    let mut c = chars.clone();
+                    c[i] = t;
+                    push(c);
+                }
+            }
+        }
+
+        candidates.truncate(MAX_SUGGESTIONS);
+        for c in &mut candidates {
+            *c = casing.apply(c);
+        }
+        candidates
+    }
+
+    /// Returns up to `max` dictionary words that start with `prefix`.
+    /// The casing of `prefix` is applied to the results.
+    pub fn complete(&self, prefix: &str, max: usize) -> Vec<String> {
+        let casing = Casing::of(prefix);
+        let lower = prefix.to_lowercase();
+        let mut results = Vec::new();
+
+        for needle in [prefix, lower.as_str()] {
+            let lower_bound = self
+                .words
+                .binary_search_by(|w| match w.as_str().cmp(needle) {
+                    // Find the first word that's >= needle.
+                    Ordering::Equal => Ordering::Greater,
+                    other => other,
+                })
+                .unwrap_or_else(|i| i);
+
+            for word in self.words[lower_bound..].iter().take_while(|w| w.starts_with(needle)) {
+                if results.len() >= max {
+                    break;
+                }
+                let word = if needle == prefix { word.clone() } else { casing.apply(word) };
+                if word != prefix && !results.contains(&word) {
+                    results.push(word);
+                }
+            }
+        }
+
+        results
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Casing {
+    Lower,
+    Capitalized,
+    Upper,
+    Mixed,
+}
+
+impl Casing {
+    fn of(word: &str) -> Self {
+        let mut chars = word.chars().filter(|c| c.is_alphabetic());
+        let Some(first) = chars.next() else {
+            return Casing::Lower;
+        };
+        let (mut upper, mut lower) = (0, 0);
+        for c in chars {
+            if c.is_uppercase() {
+                upper += 1;
+            } else if c.is_lowercase() {
+                lower += 1;
+            }
+        }
+        match (first.is_uppercase(), upper, lower) {
+            (false, 0, _) => Casing::Lower,
+            (true, 0, _) => Casing::Capitalized,
+            (true, _, 0) => Casing::Upper,
+            _ => Casing::Mixed,
+        }
+    }
+
+    fn apply(self, word: &str) -> String {
+        match self {
+            Casing::Capitalized => capitalize(word),
+            Casing::Upper => word.to_uppercase(),
+            _ => word.to_string(),
+        }
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn parse_flags(flags: &str, flag_type: FlagType) -> Vec<u32> {
+    match flag_type {
+        FlagType::Char => flags.chars().map(|c| c as u32).collect(),
+        FlagType::Long => {
+            let chars: Vec<char> = flags.chars().collect();
+            chars
+                .chunks(2)
+                .map(|c| (c[0] as u32) << 16 | c.get(1).map_or(0, |&c| c as u32))
+                .collect()
+        }
+        FlagType::Num => flags.split(',').filter_map(|f| f.trim().parse().ok()).collect(),
+    }
+}
+
+fn parse_condition(condition: &str) -> Vec<CharClass> {
+    let mut res = Vec::new();
+    if condition == "." {
+        return res;
+    }
+
+    let mut chars = condition.chars();
+    while let Some(c) = chars.next() {
+        res.push(match c {
+            '.' => CharClass::Any,
+            '[' => {
+                let mut negated = false;
+                let mut set = Vec::new();
+                for c in chars.by_ref() {
+                    match c {
+                        ']' => break,
+                        '^' if set.is_empty() && !negated => negated = true,
+                        c => set.push(c),
+                    }
+                }
+                CharClass::Set { negated, chars: set }
+            }
+            c => CharClass::Char(c),
+        });
+    }
+    res
+}
+
+/// Returns the dictionary for the user's preferred language, if one can be found.
+///
+/// The dictionary is loaded on first use. If the `EDIT_DICTIONARY` environment
+/// variable is set, it's used as the path to the `.dic` file (the `.aff` file
+/// is expected next to it). Otherwise, the usual Hunspell directories are searched.
+pub fn dictionary() -> Option<&'static Dictionary> {
+    static DICTIONARY: OnceLock<Option<Dictionary>> = OnceLock::new();
+    DICTIONARY.get_or_init(load_default_dictionary).as_ref()
+}
+
+fn load_default_dictionary() -> Option<Dictionary> {
+    if let Some(path) = env::var_os("EDIT_DICTIONARY") {
+        let dic = PathBuf::from(path);
+        return Dictionary::load(&dic.with_extension("aff"), &dic).ok();
+    }
+
+    let mut dirs = Vec::new();
+    if let Some(home) = env::var_os("HOME") {
+        let home = PathBuf::from(home);
+        dirs.push(home.join(".local/share/hunspell"));
+        dirs.push(home.join("Library/Spelling"));
+    }
+    for dir in [
+        "/usr/share/hunspell",
+        "/usr/local/share/hunspell",
+        "/usr/share/myspell",
+        "/usr/share/myspell/dicts",
+        "/Library/Spelling",
+    ] {
+        dirs.push(PathBuf::from(dir));
+    }
+
+    let scratch = scratch_arena(None);
+    let mut names = Vec::new();
+    for lang in sys::preferred_languages(&scratch) {
+        // "en-US.UTF-8" -> "en_US"
+        let lang = lang.split(['.', '@']).next().unwrap_or_default().replace('-', "_");
+        if lang.is_empty() || lang == "C" || lang == "POSIX" {
+            continue;
+        }
+        if let Some((language, _)) = lang.split_once('_') {
+            names.push(lang.clone());
+            names.push(language.to_string());
+        } else {
+            // "de" -> "de_DE", "de"
+            names.push(format!("{}_{}", lang, lang.to_uppercase()));
+            names.push(lang);
+        }
+    }
+    names.push("en_US".to_string());
+
+    for name in &names {
+        for dir in &dirs {
+            let dic = dir.join(name).with_extension("dic");
+            if dic.exists()
+                && let Ok(dictionary) = Dictionary::load(&dic.with_extension("aff"), &dic)
+            {
+                return Some(dictionary);
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFF: &str = "\
+SET UTF-8
+TRY esianrtolcdugmphbyfvkwzxqj
+NEEDAFFIX X
+
+SFX D Y 4
+SFX D   0     d          e
+SFX D   y     ied        [^aeiou]y
+SFX D   0     ed         [^ey]
+SFX D   0     ed         [aeiou]y
+
+SFX S Y 2
+SFX S   y     ies        [^aeiou]y
+SFX S   0     s          [^sxzhy]
+
+PFX U Y 1
+PFX U   0     un         .
+";
+
+    const DIC: &str = "\
+6
+hello
+world/S
+try/DS
+play/D
+lock/DU
+bound/UX
+";
+
+    #[test]
+    fn test_affix_expansion() {
+        let dict = Dictionary::parse(AFF, DIC);
+        for word in [
+            "hello", "world", "worlds", "try", "tried", "tries", "play", "played", "lock",
+            "locked", "unlock", "unlocked", "unbound",
+        ] {
+            assert!(dict.check(word), "{word}");
+        }
+        for word in ["tryed", "playied", "unplay", "bound", "worldes", "helo"] {
+            assert!(!dict.check(word), "{word}");
+        }
+    }
+
+    #[test]
+    fn test_casing() {
+        let dict = Dictionary::parse(AFF, DIC);
+        assert!(dict.check("Hello"));
+        assert!(dict.check("HELLO"));
+        assert!(!dict.check("hELLO"));
+    }
+
+    #[test]
+    fn test_suggest() {
+        let dict = Dictionary::parse(AFF, DIC);
+        assert_eq!(dict.suggest("hlelo"), ["hello"]);
+        assert_eq!(dict.suggest("Wrold"), ["World"]);
+        assert_eq!(dict.suggest("helo"), ["hello"]);
+        assert!(dict.suggest("xyzzy").is_empty());
+    }
+
+    #[test]
+    fn test_complete() {
+        let dict = Dictionary::parse(AFF, DIC);
+        assert_eq!(dict.complete("tr", 10), ["tried", "tries", "try"]);
+        assert_eq!(dict.complete("Unl", 10), ["Unlock", "Unlocked"]);
+        assert_eq!(dict.complete("tr", 1), ["tried"]);
+    }
+
+    #[test]
+    fn test_latin1() {
+        let aff = b"SET ISO8859-1\n";
+        let dic = b"1\ncaf\xe9\n";
+        let dict = Dictionary::parse_bytes(aff, dic);
+        assert!(dict.check("caf\u{e9}"));
+    }
+}
@@ -15,3 +15,16 @@ pub use std::fs::canonicalize;
 pub use unix::*;
 #[cfg(windows)]
 pub use windows::*;
+
+/// A point in time, broken down in the local time zone. See [`local_time`].
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub struct LocalTime {
+    pub year: i32,
+    /// 1-12
+    pub month: u8,
+    /// 1-31
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
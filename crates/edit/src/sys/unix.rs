@@ -6,10 +6,11 @@
 //! Read the `windows` module for reference.
 //! TODO: This reminds me that the sys API should probably be a trait.
 
-use std::ffi::{CStr, c_char, c_int, c_void};
+use std::ffi::{CStr, OsStr, c_char, c_int, c_void};
 use std::fs::File;
 use std::mem::{self, ManuallyDrop, MaybeUninit};
 use std::os::fd::{AsRawFd as _, FromRawFd as _};
+use std::os::unix::fs::PermissionsExt as _;
 use std::path::Path;
 use std::ptr::{NonNull, null_mut};
 use std::{thread, time};
@@ -17,7 +18,9 @@ use std::{thread, time};
 use stdext::arena::{Arena, ArenaString, scratch_arena};
 use stdext::arena_format;
 
+use super::LocalTime;
 use crate::apperr;
+use crate::buffer::links;
 use crate::helpers::*;
 
 struct State {
@@ -539,6 +542,283 @@ pub fn preferred_languages(arena: &Arena) -> Vec<ArenaString<'_>, &Arena> {
     locales
 }
 
+/// A command-line tool that provides access to the host's clipboard.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ClipboardBackend {
+    /// `wl-copy` and `wl-paste` from wl-clipboard.
+    Wayland,
+    /// `xclip`
+    Xclip,
+    /// `xsel`
+    Xsel,
+    /// `pbcopy` and `pbpaste` on macOS.
+    Pasteboard,
+    /// The paste buffers of the tmux session we're running in.
+    Tmux,
+}
+
+impl ClipboardBackend {
+    pub const ALL: [Self; 5] =
+        [Self::Wayland, Self::Xclip, Self::Xsel, Self::Pasteboard, Self::Tmux];
+
+    /// A user-facing name of the backend.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Wayland => "wl-clipboard",
+            Self::Xclip => "xclip",
+            Self::Xsel => "xsel",
+            Self::Pasteboard => "pbcopy",
+            Self::Tmux => "tmux",
+        }
+    }
+
+    /// The program and arguments that write stdin to the clipboard.
+    fn copy_command(self) -> (&'static str, &'static [&'static str]) {
+        match self {
+            Self::Wayland => ("wl-copy", &[]),
+            Self::Xclip => ("xclip", &["-selection", "clipboard", "-in"]),
+            Self::Xsel => ("xsel", &["--clipboard", "--input"]),
+            Self::Pasteboard => ("pbcopy", &[]),
+            Self::Tmux => ("tmux", &["load-buffer", "-"]),
+        }
+    }
+
+    /// The program and arguments that write the clipboard to stdout.
+    fn paste_command(self) -> (&'static str, &'static [&'static str]) {
+        match self {
+            Self::Wayland => ("wl-paste", &["--no-newline"]),
+            Self::Xclip => ("xclip", &["-selection", "clipboard", "-out"]),
+            Self::Xsel => ("xsel", &["--clipboard", "--output"]),
+            Self::Pasteboard => ("pbpaste", &[]),
+            Self::Tmux => ("tmux", &["save-buffer", "-"]),
+        }
+    }
+
+    /// Returns true if the required tools can be found in `$PATH`.
+    pub fn is_available(self) -> bool {
+        find_in_path(self.copy_command().0) && find_in_path(self.paste_command().0)
+    }
+}
+
+/// Picks the clipboard backend that best fits the current session, if any.
+pub fn clipboard_detect() -> Option<ClipboardBackend> {
+    let has_env = |key| std::env::var_os(key).is_some_and(|v| !v.is_empty());
+
+    // tmux is never picked automatically: OSC 52 already reaches its buffers
+    // (and the host clipboard, if `set-clipboard` is enabled), so that remains the default.
+    let candidates = [
+        (ClipboardBackend::Wayland, has_env("WAYLAND_DISPLAY")),
+        (ClipboardBackend::Xclip, has_env("DISPLAY")),
+        (ClipboardBackend::Xsel, has_env("DISPLAY")),
+        (ClipboardBackend::Pasteboard, cfg!(target_os = "macos")),
+    ];
+    candidates
+        .into_iter()
+        .find(|&(backend, applicable)| applicable && backend.is_available())
+        .map(|(backend, _)| backend)
+}
+
+/// Writes `data` to the host's clipboard.
+pub fn clipboard_write(backend: ClipboardBackend, data: &[u8]) -> apperr::Result<()> {
+    let (program, args) = backend.copy_command();
+    clipboard_run(program.as_ref(), args, Some(data)).map(|_| ())
+}
+
+/// Reads the contents of the host's clipboard.
+pub fn clipboard_read(backend: ClipboardBackend) -> apperr::Result<Vec<u8>> {
+    let (program, args) = backend.paste_command();
+    clipboard_run(program.as_ref(), args, None)
+}
+
+/// Runs a clipboard tool, feeds it `input` (if any) and returns its output.
+fn clipboard_run(program: &OsStr, args: &[&str], input: Option<&[u8]>) -> apperr::Result<Vec<u8>> {
+    use std::io::Write as _;
+    use std::process::{Command, Stdio};
+
+    // The tools must not write to our terminal, nor read from it.
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(if input.is_some() { Stdio::null() } else { Stdio::piped() })
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    if let Some(input) = input
+        && let Some(mut stdin) = child.stdin.take()
+    {
+        stdin.write_all(input)?;
+        // Dropping `stdin` closes the pipe, which signals EOF to the tool.
+    }
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(errno_to_apperr(libc::EIO));
+    }
+    Ok(output.stdout)
+}
+
+/// Opens the given URL in the user's preferred application.
+pub fn open_url(url: &str) -> apperr::Result<()> {
+    use std::process::{Command, Stdio};
+
+    if !links::has_known_scheme(url) {
+        return Err(errno_to_apperr(libc::EACCES));
+    }
+
+    let program = if cfg!(target_os = "macos") { "open" } else { "xdg-open" };
+    // We don't wait for the child, because browsers may block until they're closed.
+    Command::new(program)
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(())
+}
+
+/// Converts the given time into the local time zone.
+pub fn local_time(time: time::SystemTime) -> LocalTime {
+    let secs = match time.duration_since(time::UNIX_EPOCH) {
+        Ok(d) => d.as_secs() as libc::time_t,
+        Err(e) => -(e.duration().as_secs() as libc::time_t),
+    };
+
+    unsafe {
+        let mut tm: libc::tm = mem::zeroed();
+        if libc::localtime_r(&secs, &mut tm).is_null() {
+            return LocalTime::default();
+        }
+        LocalTime {
+            year: tm.tm_year + 1900,
+            month: (tm.tm_mon + 1) as u8,
+            day: tm.tm_mday as u8,
+            hour: tm.tm_hour as u8,
+            minute: tm.tm_min as u8,
+            second: tm.tm_sec as u8,
+        }
+    }
+}
+
+/// Moves the given file or directory into the user's trash.
+///
+/// On macOS this is `~/.Trash`. Elsewhere it's the home trash of the
+/// FreeDesktop.org trash specification, so that file managers can restore it.
+pub fn move_to_trash(path: &Path) -> apperr::Result<()> {
+    use std::fmt::Write as _;
+    use std::io::{ErrorKind, Write as _};
+    use std::os::unix::fs::{DirBuilderExt as _, MetadataExt as _};
+    use std::path::PathBuf;
+    use std::{env, fs};
+
+    let path = std::path::absolute(path)?;
+    let (Some(name), Some(home)) = (path.file_name(), env::var_os("HOME")) else {
+        return Err(errno_to_apperr(libc::EINVAL));
+    };
+
+    if cfg!(target_os = "macos") {
+        let trash = Path::new(&home).join(".Trash");
+        for i in 1.. {
+            let target = trash.join(trash_name(name, i));
+            if fs::symlink_metadata(&target).is_err() {
+                fs::rename(&path, &target)?;
+                break;
+            }
+        }
+        return Ok(());
+    }
+
+    let home_trash = match env::var_os("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir).join("Trash"),
+        _ => Path::new(&home).join(".local/share/Trash"),
+    };
+    fs::create_dir_all(&home_trash)?;
+
+    // Files can't be renamed across filesystems. For those the spec has a trash
+    // directory at the top of the filesystem the file is on: `$topdir/.Trash-$uid`.
+    let dev = fs::symlink_metadata(&path)?.dev();
+    let (trash, info_path) = if fs::metadata(&home_trash)?.dev() == dev {
+        (home_trash, path.clone())
+    } else {
+        let mut topdir = path.parent().unwrap_or(&path);
+        while let Some(parent) = topdir.parent()
+            && fs::metadata(parent).is_ok_and(|m| m.dev() == dev)
+        {
+            topdir = parent;
+        }
+
+        let trash = topdir.join(format!(".Trash-{}", unsafe { libc::getuid() }));
+        fs::DirBuilder::new().mode(0o700).create(&trash).or_else(|err| {
+            if err.kind() == ErrorKind::AlreadyExists { Ok(()) } else { Err(err) }
+        })?;
+        // Within a `$topdir` trash the path is relative to `$topdir`.
+        let relative = path.strip_prefix(topdir).unwrap_or(&path).to_path_buf();
+        (trash, relative)
+    };
+
+    let files = trash.join("files");
+    let info = trash.join("info");
+    fs::create_dir_all(&files)?;
+    fs::create_dir_all(&info)?;
+
+    // The spec reserves a name by atomically creating its .trashinfo file.
+    for i in 1.. {
+        let name = trash_name(name, i);
+        let mut info_name = name.clone();
+        info_name.push(".trashinfo");
+        let info_file = info.join(&info_name);
+
+        let mut file = match fs::OpenOptions::new().write(true).create_new(true).open(&info_file) {
+            Ok(file) => file,
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut contents = String::from("[Trash Info]\nPath=");
+        for &b in info_path.as_os_str().as_encoded_bytes() {
+            if b.is_ascii_alphanumeric() || b"/-_.~".contains(&b) {
+                contents.push(b as char);
+            } else {
+                _ = write!(contents, "%{b:02X}");
+            }
+        }
+        let now = local_time(time::SystemTime::now());
+        _ = writeln!(
+            contents,
+            "\nDeletionDate={:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            now.year, now.month, now.day, now.hour, now.minute, now.second
+        );
+
+        let res =
+            file.write_all(contents.as_bytes()).and_then(|_| fs::rename(&path, files.join(&name)));
+        if let Err(err) = res {
+            _ = fs::remove_file(&info_file);
+            return Err(err.into());
+        }
+        break;
+    }
+
+    Ok(())
+}
+
+/// Returns `name` for the first attempt and `name N` for the following ones.
+fn trash_name(name: &OsStr, attempt: usize) -> std::ffi::OsString {
+    let mut name = name.to_owned();
+    if attempt > 1 {
+        name.push(format!(" {attempt}"));
+    }
+    name
+}
+
+fn find_in_path(program: &str) -> bool {
+    let Some(path) = std::env::var_os("PATH") else {
+        return false;
+    };
+    std::env::split_paths(&path).any(|dir| {
+        std::fs::metadata(dir.join(program))
+            .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+    })
+}
+
 #[inline]
 fn errno() -> i32 {
     // Under `-O -Copt-level=s` the 1.87 compiler fails to fully inline and
@@ -575,6 +855,14 @@ pub fn apperr_is_not_found(err: apperr::Error) -> bool {
     err == errno_to_apperr(libc::ENOENT)
 }
 
+pub fn apperr_already_exists() -> apperr::Error {
+    errno_to_apperr(libc::EEXIST)
+}
+
+pub fn apperr_invalid_name() -> apperr::Error {
+    errno_to_apperr(libc::EINVAL)
+}
+
 const fn errno_to_apperr(no: c_int) -> apperr::Error {
     apperr::Error::new_sys(if no < 0 { 0 } else { no as u32 })
 }
@@ -582,3 +870,34 @@ const fn errno_to_apperr(no: c_int) -> apperr::Error {
 fn check_int_return(ret: libc::c_int) -> apperr::Result<libc::c_int> {
     if ret < 0 { Err(get_last_error()) } else { Ok(ret) }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clipboard_stub() {
+        let dir = std::env::temp_dir().join(format!("edit-clipboard-{}", std::process::id()));
+        _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // A stand-in for xclip, which stores the clipboard in a file next to it.
+        let stub = dir.join("xclip");
+        let script = format!(
+            "#!/bin/sh\ncase \"$3\" in\n-in) cat > '{0}/buffer' ;;\n-out) cat '{0}/buffer' ;;\n*) exit 1 ;;\nesac\n",
+            dir.display()
+        );
+        std::fs::write(&stub, script).unwrap();
+        std::fs::set_permissions(&stub, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let (_, copy_args) = ClipboardBackend::Xclip.copy_command();
+        let (_, paste_args) = ClipboardBackend::Xclip.paste_command();
+        clipboard_run(stub.as_os_str(), copy_args, Some(b"hello\nworld")).unwrap();
+        assert_eq!(clipboard_run(stub.as_os_str(), paste_args, None).unwrap(), b"hello\nworld");
+
+        // Failing tools are reported as errors.
+        assert!(clipboard_run(stub.as_os_str(), &[], None).is_err());
+
+        _ = std::fs::remove_dir_all(&dir);
+    }
+}
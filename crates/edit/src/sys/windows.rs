@@ -18,7 +18,9 @@ use windows_sys::Win32::System::{Console, IO, LibraryLoader, Threading};
 use windows_sys::Win32::{Foundation, Globalization};
 use windows_sys::core::*;
 
+use super::LocalTime;
 use crate::apperr;
+use crate::buffer::links;
 use crate::helpers::*;
 
 macro_rules! w_env {
@@ -704,6 +706,121 @@ pub fn apperr_format(f: &mut std::fmt::Formatter<'_>, code: u32) -> std::fmt::Re
     }
 }
 
+/// A command-line tool that provides access to the host's clipboard.
+/// None are supported on Windows yet.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ClipboardBackend {}
+
+impl ClipboardBackend {
+    pub const ALL: [Self; 0] = [];
+
+    pub fn name(self) -> &'static str {
+        match self {}
+    }
+
+    pub fn is_available(self) -> bool {
+        match self {}
+    }
+}
+
+pub fn clipboard_detect() -> Option<ClipboardBackend> {
+    None
+}
+
+pub fn clipboard_write(backend: ClipboardBackend, _data: &[u8]) -> apperr::Result<()> {
+    match backend {}
+}
+
+pub fn clipboard_read(backend: ClipboardBackend) -> apperr::Result<Vec<u8>> {
+    match backend {}
+}
+
+/// Opens the given URL in the user's preferred application.
+pub fn open_url(url: &str) -> apperr::Result<()> {
+    use windows_sys::Win32::UI::{Shell, WindowsAndMessaging};
+
+    if !links::has_known_scheme(url) {
+        return Err(gle_to_apperr(Foundation::ERROR_ACCESS_DENIED));
+    }
+
+    let url: Vec<u16> = url.encode_utf16().chain(Some(0)).collect();
+    unsafe {
+        let ret = Shell::ShellExecuteW(
+            null_mut(),
+            w!("open"),
+            url.as_ptr(),
+            null(),
+            null(),
+            WindowsAndMessaging::SW_SHOWNORMAL,
+        );
+        // Anything above 32 is a success. Otherwise, the last error is set.
+        if ret as usize > 32 { Ok(()) } else { Err(get_last_error()) }
+    }
+}
+
+/// Converts the given time into the local time zone.
+pub fn local_time(time: time::SystemTime) -> LocalTime {
+    use windows_sys::Win32::System::Time;
+
+    // FILETIMEs count 100ns intervals since 1601-01-01.
+    const UNIX_EPOCH_INTERVALS: u64 = 116444736000000000;
+    let intervals = match time.duration_since(time::UNIX_EPOCH) {
+        Ok(d) => UNIX_EPOCH_INTERVALS + (d.as_nanos() / 100) as u64,
+        Err(e) => UNIX_EPOCH_INTERVALS.saturating_sub((e.duration().as_nanos() / 100) as u64),
+    };
+
+    unsafe {
+        let utc = Foundation::FILETIME {
+            dwLowDateTime: intervals as u32,
+            dwHighDateTime: (intervals >> 32) as u32,
+        };
+        let mut local: Foundation::FILETIME = mem::zeroed();
+        let mut st: Foundation::SYSTEMTIME = mem::zeroed();
+        if FileSystem::FileTimeToLocalFileTime(&utc, &mut local) == 0
+            || Time::FileTimeToSystemTime(&local, &mut st) == 0
+        {
+            return LocalTime::default();
+        }
+        LocalTime {
+            year: st.wYear as i32,
+            month: st.wMonth as u8,
+            day: st.wDay as u8,
+            hour: st.wHour as u8,
+            minute: st.wMinute as u8,
+            second: st.wSecond as u8,
+        }
+    }
+}
+
+/// Moves the given file or directory into the recycle bin.
+pub fn move_to_trash(path: &Path) -> apperr::Result<()> {
+    use std::os::windows::ffi::OsStrExt as _;
+
+    use windows_sys::Win32::UI::Shell;
+
+    let path = std::path::absolute(path)?;
+    // `pFrom` is a list of paths, terminated by an empty one.
+    let mut from: Vec<u16> = path.as_os_str().encode_wide().collect();
+    from.extend_from_slice(&[0, 0]);
+
+    unsafe {
+        let mut op: Shell::SHFILEOPSTRUCTW = mem::zeroed();
+        op.wFunc = Shell::FO_DELETE;
+        op.pFrom = from.as_ptr();
+        op.fFlags = Shell::FOF_ALLOWUNDO
+            | Shell::FOF_NOCONFIRMATION
+            | Shell::FOF_NOERRORUI
+            | Shell::FOF_SILENT;
+
+        // The return value isn't a regular error code, but close enough for an error message.
+        match Shell::SHFileOperationW(&mut op) {
+            0 if op.fAnyOperationsAborted == 0 => Ok(()),
+            0 => Err(gle_to_apperr(Foundation::ERROR_CANCELLED)),
+            err => Err(gle_to_apperr(err as u32)),
+        }
+    }
+}
+
 /// Checks if the given error is a "file not found" error.
 pub fn apperr_is_not_found(err: apperr::Error) -> bool {
     const FNF: apperr::Error = gle_to_apperr(Foundation::ERROR_FILE_NOT_FOUND);
@@ -711,6 +828,16 @@ pub fn apperr_is_not_found(err: apperr::Error) -> bool {
     err == FNF || err == PNF
 }
 
+/// Returns the error for a file that already exists.
+pub fn apperr_already_exists() -> apperr::Error {
+    gle_to_apperr(Foundation::ERROR_ALREADY_EXISTS)
+}
+
+/// Returns the error for a file name that isn't valid.
+pub fn apperr_invalid_name() -> apperr::Error {
+    gle_to_apperr(Foundation::ERROR_INVALID_NAME)
+}
+
 fn check_bool_return(ret: BOOL) -> apperr::Result<()> {
     if ret == 0 { Err(get_last_error()) } else { Ok(()) }
 }
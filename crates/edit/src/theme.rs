@@ -0,0 +1,530 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! CSS-like themes for the [`crate::tui`] framework.
+//!
+//! A theme is a list of rules, each of which consists of selectors and declarations:
+//!
+//! ```text
+//! /* The menubar and its flyouts. */
+//! menubar, statusbar { background: bright-blue / 50%; foreground: auto; }
+//! menubar > menu_button:focused { background: green; }
+//! flyout, *:modal { border-color: bright-black; }
+//! ```
+//!
+//! Selectors match the classnames that nodes were created with,
+//! or the type of widget they belong to (e.g. `button`).
+//! Like in CSS, they can be combined with `>` (direct child) and whitespace (descendant),
+//! and refined with the `:focused`, `:focus-within` and `:modal` pseudo-classes.
+//! `*` matches any node.
+//! If multiple rules apply to a node, the more specific one wins, otherwise the later one.
+//!
+//! The supported properties are:
+//! * `background`, `foreground` and `border-color`:
+//!   `#rgb`, `#rrggbb`, `#rrggbbaa`, the name of one of the 16 VT colors in kebab-case
+//!   (e.g. `bright-blue`), or the terminal's `background` and `foreground` colors.
+//!   Any of them may be followed by an alpha value like `/ 50%`.
+//!   `default` is the terminal's default color and `auto` contrasts the background.
+//! * `border`: `solid` or `none`.
+//! * `reverse`: `true` or `false`.
+//! * `attributes`: any combination of `italic`, `underline` and `curly-underline`, or `none`.
+
+use std::fmt;
+
+use crate::framebuffer::{Attributes, IndexedColor};
+use crate::oklab::StraightRgba;
+
+const COLOR_NAMES: [(&str, IndexedColor); 18] = [
+    ("black", IndexedColor::Black),
+    ("red", IndexedColor::Red),
+    ("green", IndexedColor::Green),
+    ("yellow", IndexedColor::Yellow),
+    ("blue", IndexedColor::Blue),
+    ("magenta", IndexedColor::Magenta),
+    ("cyan", IndexedColor::Cyan),
+    ("white", IndexedColor::White),
+    ("bright-black", IndexedColor::BrightBlack),
+    ("bright-red", IndexedColor::BrightRed),
+    ("bright-green", IndexedColor::BrightGreen),
+    ("bright-yellow", IndexedColor::BrightYellow),
+    ("bright-blue", IndexedColor::BrightBlue),
+    ("bright-magenta", IndexedColor::BrightMagenta),
+    ("bright-cyan", IndexedColor::BrightCyan),
+    ("bright-white", IndexedColor::BrightWhite),
+    ("background", IndexedColor::Background),
+    ("foreground", IndexedColor::Foreground),
+];
+
+#[derive(Debug, Clone)]
+pub struct ThemeError {
+    message: &'static str,
+    line: usize,
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ThemeError {}
+
+/// A color value in a theme.
+#[derive(Clone, Copy)]
+pub enum ThemeColor {
+    /// The terminal's default color.
+    Default,
+    /// A color that contrasts the node's background color.
+    Auto,
+    /// A fixed color.
+    Rgba(StraightRgba),
+    /// A palette color with the given alpha (0-255).
+    Indexed(IndexedColor, u8),
+}
+
+/// The properties a theme assigns to a node. Unset properties are [`None`].
+#[derive(Clone, Copy, Default)]
+pub struct Style {
+    pub background: Option<ThemeColor>,
+    pub foreground: Option<ThemeColor>,
+    pub border_color: Option<ThemeColor>,
+    pub border: Option<bool>,
+    pub reverse: Option<bool>,
+    pub attributes: Option<Attributes>,
+}
+
+impl Style {
+    fn merge(&mut self, other: &Self) {
+        self.background = other.background.or(self.background);
+        self.foreground = other.foreground.or(self.foreground);
+        self.border_color = other.border_color.or(self.border_color);
+        self.border = other.border.or(self.border);
+        self.reverse = other.reverse.or(self.reverse);
+        self.attributes = other.attributes.or(self.attributes);
+    }
+}
+
+/// The view of a UI node that selectors are matched against.
+pub trait ThemeNode: Sized {
+    fn classname(&self) -> &str;
+    fn widget(&self) -> &str;
+    fn is_focused(&self) -> bool;
+    fn contains_focus(&self) -> bool;
+    fn is_modal(&self) -> bool;
+    fn parent(&self) -> Option<Self>;
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Combinator {
+    /// `a > b`
+    Child,
+    /// `a b`
+    Descendant,
+}
+
+struct Compound {
+    /// [`None`] for `*`.
+    classname: Option<String>,
+    focused: bool,
+    focus_within: bool,
+    modal: bool,
+    /// How this compound relates to the one to its left.
+    combinator: Combinator,
+}
+
+impl Compound {
+    fn matches<N: ThemeNode>(&self, node: &N) -> bool {
+        self.classname.as_ref().is_none_or(|c| c == node.classname() || c == node.widget())
+            && (!self.focused || node.is_focused())
+            && (!self.focus_within || node.contains_focus())
+            && (!self.modal || node.is_modal())
+    }
+}
+
+struct Rule {
+    /// The selector's compounds from right to left.
+    compounds: Vec<Compound>,
+    specificity: usize,
+    style: Style,
+}
+
+/// A parsed theme. The default theme is empty and leaves all nodes unchanged.
+#[derive(Default)]
+pub struct Theme {
+    /// Sorted by specificity, and otherwise in source order.
+    rules: Vec<Rule>,
+}
+
+impl Theme {
+    /// Parses a theme file.
+    pub fn parse(input: &str) -> Result<Self, ThemeError> {
+        let input = strip_comments(input);
+        let line_of = |off: usize| input[..off].bytes().filter(|&b| b == b'\n').count() + 1;
+        let mut rules = Vec::new();
+        let mut pos = 0;
+
+        while let Some(open) = input[pos..].find('{').map(|i| pos + i) {
+            let fail = |message| ThemeError { message, line: line_of(open) };
+            let Some(close) = input[open..].find('}').map(|i| open + i) else {
+                return Err(fail("Missing closing brace"));
+            };
+
+            let style = parse_declarations(&input[open + 1..close])
+                .map_err(|message| ThemeError { message, line: line_of(close) })?;
+
+            for selector in input[pos..open].split(',') {
+                let compounds = parse_selector(selector).ok_or(fail("Invalid selector"))?;
+                let specificity = compounds
+                    .iter()
+                    .map(|c| {
+                        c.classname.is_some() as usize
+                            + c.focused as usize
+                            + c.focus_within as usize
+                            + c.modal as usize
+                    })
+                    .sum();
+                rules.push(Rule { compounds, specificity, style });
+            }
+
+            pos = close + 1;
+        }
+
+        if !input[pos..].trim().is_empty() {
+            return Err(ThemeError { message: "Missing opening brace", line: line_of(pos) });
+        }
+
+        // A stable sort keeps the source order for rules of equal specificity.
+        rules.sort_by_key(|r| r.specificity);
+        Ok(Self { rules })
+    }
+
+    /// Returns true if the theme has no rules.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Computes the style for the given node.
+    pub fn style<N: ThemeNode>(&self, node: &N) -> Style {
+        let mut style = Style::default();
+        for rule in &self.rules {
+            if selector_matches(&rule.compounds, node) {
+                style.merge(&rule.style);
+            }
+        }
+        style
+    }
+}
+
+fn selector_matches<N: ThemeNode>(compounds: &[Compound], node: &N) -> bool {
+    let Some((compound, rest)) = compounds.split_first() else {
+        return true;
+    };
+    if !compound.matches(node) {
+        return false;
+    }
+    if rest.is_empty() {
+        return true;
+    }
+
+    let mut ancestor = node.parent();
+    while let Some(a) = ancestor {
+        if selector_matches(rest, &a) {
+            return true;
+        }
+        if compound.combinator == Combinator::Child {
+            return false;
+        }
+        ancestor = a.parent();
+    }
+
+    false
+}
+
+/// Replaces `/* ... */` comments with whitespace, preserving newlines for error reporting.
+fn strip_comments(input: &str) -> String {
+    let mut result = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(beg) = rest.find("/*") {
+        result.push_str(&rest[..beg]);
+        let end = rest[beg..].find("*/").map_or(rest.len(), |i| beg + i + 2);
+        result.extend(rest[beg..end].chars().map(|c| if c == '\n' { '\n' } else { ' ' }));
+        rest = &rest[end..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+fn parse_selector(selector: &str) -> Option<Vec<Compound>> {
+    let mut compounds = Vec::new();
+    let mut combinator = Combinator::Descendant;
+
+    for token in selector.replace('>', " > ").split_whitespace() {
+        if token == ">" {
+            // `> a` and `a > > b` are invalid.
+            if compounds.is_empty() || combinator == Combinator::Child {
+                return None;
+            }
+            combinator = Combinator::Child;
+            continue;
+        }
+
+        let mut parts = token.split(':');
+        let name = parts.next()?;
+        let mut compound = Compound {
+            classname: None,
+            focused: false,
+            focus_within: false,
+            modal: false,
+            combinator: Combinator::Descendant,
+        };
+
+        if name != "*" {
+            if name.is_empty()
+                || !name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-')
+            {
+                return None;
+            }
+            compound.classname = Some(name.to_string());
+        }
+
+        for pseudo in parts {
+            match pseudo {
+                "focused" => compound.focused = true,
+                "focus-within" => compound.focus_within = true,
+                "modal" => compound.modal = true,
+                _ => return None,
+            }
+        }
+
+        // Each compound stores its relation to the compound to its left.
+        compound.combinator = combinator;
+        combinator = Combinator::Descendant;
+        compounds.push(compound);
+    }
+
+    if compounds.is_empty() || combinator == Combinator::Child {
+        return None;
+    }
+
+    // We match right to left, starting with the node itself.
+    compounds.reverse();
+    Some(compounds)
+}
+
+fn parse_declarations(body: &str) -> Result<Style, &'static str> {
+    let mut style = Style::default();
+
+    for declaration in body.split(';') {
+        if declaration.trim().is_empty() {
+            continue;
+        }
+
+        let Some((name, value)) = declaration.split_once(':') else {
+            return Err("Expected a property");
+        };
+        let value = value.trim();
+
+        match name.trim() {
+            "background" => style.background = Some(parse_color(value)?),
+            "foreground" => style.foreground = Some(parse_color(value)?),
+            "border-color" => style.border_color = Some(parse_color(value)?),
+            "border" => {
+                style.border = Some(match value {
+                    "solid" => true,
+                    "none" => false,
+                    _ => return Err("Invalid border"),
+                })
+            }
+            "reverse" => {
+                style.reverse = Some(match value {
+                    "true" => true,
+                    "false" => false,
+                    _ => return Err("Invalid boolean"),
+                })
+            }
+            "attributes" => {
+                let mut attr = Attributes::None;
+                for word in value.split_whitespace() {
+                    attr = attr
+                        | match word {
+                            "none" => Attributes::None,
+                            "italic" => Attributes::Italic,
+                            "underline" => Attributes::Underlined,
+                            "curly-underline" => Attributes::CurlyUnderlined,
+                            _ => return Err("Invalid attribute"),
+                        };
+                }
+                style.attributes = Some(attr);
+            }
+            _ => return Err("Unknown property"),
+        }
+    }
+
+    Ok(style)
+}
+
+fn parse_color(value: &str) -> Result<ThemeColor, &'static str> {
+    const INVALID: &str = "Invalid color";
+
+    let (color, alpha) = match value.split_once('/') {
+        Some((color, alpha)) => (color.trim(), Some(parse_alpha(alpha.trim()).ok_or(INVALID)?)),
+        None => (value, None),
+    };
+
+    match color {
+        "default" if alpha.is_none() => Ok(ThemeColor::Default),
+        "auto" if alpha.is_none() => Ok(ThemeColor::Auto),
+        _ => {
+            if let Some(hex) = color.strip_prefix('#') {
+                let rgba = parse_hex(hex).ok_or(INVALID)?;
+                let rgba = match alpha {
+                    Some(a) => (rgba & !0xff) | a as u32,
+                    None => rgba,
+                };
+                Ok(ThemeColor::Rgba(StraightRgba::from_be(rgba)))
+            } else {
+                let &(_, index) = COLOR_NAMES.iter().find(|(n, _)| *n == color).ok_or(INVALID)?;
+                Ok(ThemeColor::Indexed(index, alpha.unwrap_or(0xff)))
+            }
+        }
+    }
+}
+
+/// Parses `rgb`, `rrggbb` and `rrggbbaa` into `0xrrggbbaa`.
+fn parse_hex(hex: &str) -> Option<u32> {
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let val = u32::from_str_radix(hex, 16).ok()?;
+    match hex.len() {
+        3 => {
+            let (r, g, b) = ((val >> 8) & 0xf, (val >> 4) & 0xf, val & 0xf);
+            Some((r * 0x11) << 24 | (g * 0x11) << 16 | (b * 0x11) << 8 | 0xff)
+        }
+        6 => Some(val << 8 | 0xff),
+        8 => Some(val),
+        _ => None,
+    }
+}
+
+/// Parses `50%` or `0.5` into 0-255.
+fn parse_alpha(alpha: &str) -> Option<u8> {
+    let val = match alpha.strip_suffix('%') {
+        Some(percent) => percent.parse::<f32>().ok()? / 100.0,
+        None => alpha.parse::<f32>().ok()?,
+    };
+    (0.0..=1.0).contains(&val).then_some((val * 255.0 + 0.5) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestNode<'a> {
+        path: &'a [(&'a str, bool)],
+    }
+
+    impl ThemeNode for TestNode<'_> {
+        fn classname(&self) -> &str {
+            self.path.last().unwrap().0
+        }
+
+        fn widget(&self) -> &str {
+            if self.classname() == "yes" { "button" } else { "" }
+        }
+
+        fn is_focused(&self) -> bool {
+            self.path.last().unwrap().1
+        }
+
+        fn contains_focus(&self) -> bool {
+            false
+        }
+
+        fn is_modal(&self) -> bool {
+            self.classname() == "about"
+        }
+
+        fn parent(&self) -> Option<Self> {
+            let len = self.path.len();
+            (len > 1).then(|| TestNode { path: &self.path[..len - 1] })
+        }
+    }
+
+    fn background(theme: &Theme, path: &[(&str, bool)]) -> Option<u32> {
+        match theme.style(&TestNode { path }).background? {
+            ThemeColor::Rgba(c) => Some(c.to_be()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_selectors() {
+        let theme = Theme::parse(
+            "/* comment */
+            * { background: #000; }
+            menubar button { background: #111111; }
+            menubar > button:focused, statusbar { background: #22222280; }
+            button { background: #333 / 50%; }
+            *:modal { background: bright-red; }",
+        )
+        .unwrap();
+
+        assert_eq!(background(&theme, &[("root", false)]), Some(0x000000ff));
+        assert_eq!(background(&theme, &[("root", false), ("button", false)]), Some(0x33333380));
+        assert_eq!(
+            background(&theme, &[("menubar", false), ("row", false), ("button", false)]),
+            Some(0x111111ff)
+        );
+        assert_eq!(
+            background(&theme, &[("menubar", false), ("row", false), ("button", true)]),
+            Some(0x111111ff)
+        );
+        assert_eq!(background(&theme, &[("menubar", false), ("button", true)]), Some(0x22222280));
+        assert_eq!(background(&theme, &[("statusbar", false)]), Some(0x22222280));
+        assert_eq!(background(&theme, &[("about", false)]), None);
+        assert_eq!(background(&theme, &[("menubar", false), ("yes", true)]), Some(0x22222280));
+    }
+
+    #[test]
+    fn test_declarations() {
+        let theme = Theme::parse(
+            "modal { border: solid; reverse: true; attributes: italic underline; foreground: auto; }",
+        )
+        .unwrap();
+        let style = theme.style(&TestNode { path: &[("modal", false)] });
+        assert_eq!(style.border, Some(true));
+        assert_eq!(style.reverse, Some(true));
+        assert!(style.attributes == Some(Attributes::Italic | Attributes::Underlined));
+        assert!(matches!(style.foreground, Some(ThemeColor::Auto)));
+        assert!(style.background.is_none());
+
+        assert_eq!(
+            Theme::parse("a { color: red; }").err().unwrap().to_string(),
+            "1: Unknown property"
+        );
+        assert_eq!(
+            Theme::parse("a {}\n> b { background: red; }").err().unwrap().to_string(),
+            "2: Invalid selector"
+        );
+        assert_eq!(
+            Theme::parse("a { background: #12345; }").err().unwrap().to_string(),
+            "1: Invalid color"
+        );
+        assert!(Theme::parse("a { background: bright-blue / 50% }").is_ok());
+        assert!(Theme::parse("a { background: red").is_err());
+    }
+
+    #[test]
+    fn test_builtin_themes() {
+        for source in [
+            include_str!("bin/edit/themes/light.theme"),
+            include_str!("bin/edit/themes/dark.theme"),
+            include_str!("bin/edit/themes/high-contrast.theme"),
+        ] {
+            assert!(!Theme::parse(source).unwrap().is_empty());
+        }
+    }
+}
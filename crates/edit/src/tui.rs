@@ -83,8 +83,8 @@
 //! guarantees that all of the parent nodes must have equivalent IDs as well.
 //! This turns "is the focus anywhere inside this subtree" into an O(1) check.
 //!
-//! The reason "classnames" are used is because I was hoping to add theming
-//! in the future with a syntax similar to CSS (simplified, however).
+//! The reason "classnames" are used is because they double as the selectors
+//! for theming with a syntax similar to CSS (simplified, however). See [`crate::theme`].
 //!
 //! # Example
 //!
@@ -152,21 +152,29 @@ use std::{iter, mem, ptr, time};
 use stdext::arena::{Arena, ArenaString, scratch_arena};
 use stdext::arena_format;
 
-use crate::buffer::{CursorMovement, MoveLineDirection, RcTextBuffer, TextBuffer, TextBufferCell};
+use crate::buffer::{
+    CursorMovement, MoveLineDirection, RcTextBuffer, TextBuffer, TextBufferCell, TextLink, hex,
+};
 use crate::cell::*;
 use crate::clipboard::Clipboard;
 use crate::document::WriteableDocument;
-use crate::framebuffer::{Attributes, Framebuffer, INDEXED_COLORS_COUNT, IndexedColor};
+use crate::framebuffer::{Attributes, ColorDepth, Framebuffer, INDEXED_COLORS_COUNT, IndexedColor};
 use crate::hash::*;
 use crate::helpers::*;
 use crate::input::{InputKeyMod, kbmod, vk};
 use crate::oklab::StraightRgba;
+use crate::theme::{Theme, ThemeColor, ThemeNode};
 use crate::{apperr, input, simd, unicode};
 
 const ROOT_ID: u64 = 0x14057B7EF767814F; // Knuth's MMIX constant
 const SHIFT_TAB: InputKey = vk::TAB.with_modifiers(kbmod::SHIFT);
 const KBMOD_FOR_WORD_NAV: InputKeyMod =
     if cfg!(target_os = "macos") { kbmod::ALT } else { kbmod::CTRL };
+const COMPLETION_MAX_VISIBLE_ITEMS: CoordType = 8;
+const COMPLETION_DOC_MIN_WIDTH: CoordType = 16;
+const COMPLETION_DOC_MAX_WIDTH: CoordType = 48;
+const COMPLETION_DOC_MAX_LINES: CoordType = 12;
+const COMPLETION_DOC_SCROLL_STEP: CoordType = 3;
 
 type Input<'input> = input::Input<'input>;
 type InputKey = input::InputKey;
@@ -233,6 +241,32 @@ pub enum ListSelection {
     Activated,
 }
 
+/// The expansion state of a tree item. See [`Context::tree_item`].
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TreeItemState {
+    /// The item has no children.
+    Leaf,
+    /// The item has children, but they aren't shown.
+    Collapsed,
+    /// The item's children follow it, one level deeper.
+    Expanded,
+}
+
+/// Informs you about what the user did with a tree item.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TreeAction {
+    /// Same as [`ListSelection::Unchanged`].
+    Unchanged,
+    /// Same as [`ListSelection::Selected`].
+    Selected,
+    /// Same as [`ListSelection::Activated`].
+    Activated,
+    /// The item is collapsed and the user asked to show its children (Right).
+    Expand,
+    /// The item is expanded and the user asked to hide its children (Left).
+    Collapse,
+}
+
 /// Controls the position of a node relative to its parent.
 #[derive(Default)]
 pub enum Position {
@@ -322,6 +356,7 @@ pub struct Tui {
     floater_default_fg: StraightRgba,
     modal_default_bg: StraightRgba,
     modal_default_fg: StraightRgba,
+    theme: Theme,
 
     /// Last known terminal size.
     ///
@@ -368,6 +403,10 @@ pub struct Tui {
     /// The clipboard contents.
     clipboard: Clipboard,
 
+    /// The maximum scroll offset of the completion popup's documentation pane,
+    /// if the last frame showed one. Only then do PageUp/PageDown scroll it instead of the text.
+    completion_documentation_max_scroll: Option<CoordType>,
+
     settling_have: i32,
     settling_want: i32,
     read_timeout: time::Duration,
@@ -398,6 +437,7 @@ impl Tui {
             floater_default_fg: StraightRgba::zero(),
             modal_default_bg: StraightRgba::zero(),
             modal_default_fg: StraightRgba::zero(),
+            theme: Theme::default(),
 
             size: Size { width: 0, height: 0 },
             mouse_position: Point::MIN,
@@ -418,6 +458,8 @@ impl Tui {
 
             clipboard: Default::default(),
 
+            completion_documentation_max_scroll: None,
+
             settling_have: 0,
             settling_want: 0,
             read_timeout: time::Duration::MAX,
@@ -432,6 +474,16 @@ impl Tui {
         self.framebuffer.set_indexed_colors(colors);
     }
 
+    /// Sets the color depth the terminal supports.
+    pub fn setup_color_depth(&mut self, depth: ColorDepth) {
+        self.framebuffer.set_color_depth(depth);
+    }
+
+    /// Sets the theme that overrides the colors and borders of the UI nodes.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
     /// Set up translations for Ctrl/Alt/Shift modifiers.
     pub fn setup_modifier_translations(&mut self, translations: ModifierTranslations) {
         self.modifier_translations = translations;
@@ -566,6 +618,14 @@ impl Tui {
                 clipboard.mark_as_synchronized();
                 input_keyboard = Some(kbmod::CTRL | vk::V);
             }
+            Some(Input::HostClipboard(data)) => {
+                let clipboard = self.clipboard_mut();
+                // Don't reset `is_line_copy()` if the host merely echoes our own contents.
+                if !data.is_empty() && data != clipboard.read() {
+                    clipboard.write(data);
+                    clipboard.mark_as_synchronized();
+                }
+            }
             Some(Input::Keyboard(keyboard)) => {
                 input_keyboard = Some(keyboard);
             }
@@ -704,6 +764,7 @@ impl Tui {
             input_mouse_click,
             input_scroll_delta,
             input_consumed,
+            activated_link: None,
 
             tree,
             last_modal: None,
@@ -760,6 +821,10 @@ impl Tui {
         // so that pop_focusable_node() has access to the newest version of the tree.
         needs_settling |= self.pop_focusable_node(focus_path_pop_min);
 
+        // Now that the tree and focus are final, apply the theme. This needs to happen
+        // before the layout pass below, because it may add or remove borders.
+        self.apply_theme();
+
         // `needs_more_settling()` depends on the current value
         // of `settling_have` and so we increment it first.
         self.settling_have += 1;
@@ -854,6 +919,7 @@ impl Tui {
     /// Renders the last frame into the framebuffer and returns the VT output.
     pub fn render<'a>(&mut self, arena: &'a Arena) -> ArenaString<'a> {
         self.framebuffer.flip(self.size);
+        self.completion_documentation_max_scroll = None;
         for child in self.prev_tree.iterate_roots() {
             let mut child = child.borrow_mut();
             self.render_node(&mut child);
@@ -861,6 +927,60 @@ impl Tui {
         self.framebuffer.render(arena)
     }
 
+    /// Returns a textual dump of the last rendered frame.
+    /// See [`Framebuffer::snapshot()`].
+    pub fn snapshot(&self) -> String {
+        self.framebuffer.snapshot()
+    }
+
+    /// Applies the theme's styles to all nodes of the last frame.
+    fn apply_theme(&self) {
+        if self.theme.is_empty() {
+            return;
+        }
+
+        let mut node = Some(self.prev_tree.root_first);
+
+        while let Some(cell) = node {
+            let style = self.theme.style(&ThemedNode { tui: self, node: cell });
+            let mut n = cell.borrow_mut();
+            let attributes = &mut n.attributes;
+
+            if let Some(color) = style.background {
+                attributes.bg = self.resolve_theme_color(color, StraightRgba::zero());
+            }
+            if let Some(color) = style.foreground {
+                attributes.fg = self.resolve_theme_color(color, attributes.bg);
+            }
+            if let Some(color) = style.border_color {
+                attributes.border_fg = self.resolve_theme_color(color, attributes.bg);
+            }
+            if let Some(border) = style.border {
+                attributes.bordered = border;
+            }
+            if let Some(reverse) = style.reverse {
+                attributes.reverse = reverse;
+            }
+            if let Some(attr) = style.attributes {
+                attributes.attr = attr;
+            }
+
+            node = n.next;
+        }
+    }
+
+    fn resolve_theme_color(&self, color: ThemeColor, bg: StraightRgba) -> StraightRgba {
+        match color {
+            ThemeColor::Default => StraightRgba::zero(),
+            ThemeColor::Auto => {
+                let canvas = self.indexed(IndexedColor::Background);
+                self.contrasted(if bg.alpha() == 0xff { bg } else { canvas.oklab_blend(bg) })
+            }
+            ThemeColor::Rgba(color) => color,
+            ThemeColor::Indexed(index, alpha) => self.indexed_alpha(index, alpha as u32, 0xff),
+        }
+    }
+
     /// Recursively renders each node and its children.
     #[allow(clippy::only_used_in_recursion)]
     fn render_node(&mut self, node: &mut Node) {
@@ -951,12 +1071,28 @@ impl Tui {
             self.framebuffer.reverse(outer_clipped);
         }
 
+        if node.attributes.bordered && node.attributes.border_fg != StraightRgba::zero() {
+            let Rect { left, top, right, bottom } = outer_clipped;
+            for edge in [
+                Rect { left, top, right, bottom: top + 1 },
+                Rect { left, top: bottom - 1, right, bottom },
+                Rect { left, top, right: left + 1, bottom },
+                Rect { left: right - 1, top, right, bottom },
+            ] {
+                self.framebuffer.blend_fg(edge, node.attributes.border_fg);
+            }
+        }
+
         let inner = node.inner;
         let inner_clipped = node.inner_clipped;
         if inner_clipped.is_empty() {
             return;
         }
 
+        if node.attributes.attr != Attributes::None {
+            self.framebuffer.replace_attr(inner_clipped, Attributes::All, node.attributes.attr);
+        }
+
         match &mut node.content {
             NodeContent::Modal(title) => {
                 if !title.is_empty() {
@@ -1008,11 +1144,13 @@ impl Tui {
                         right: inner_clipped.right,
                         bottom: inner_clipped.bottom,
                     };
+                    let content_height = tb.visual_line_count() + inner.height() - 1;
                     tc.thumb_height = self.framebuffer.draw_scrollbar(
                         inner_clipped,
                         track,
                         tc.scroll_offset.y,
-                        tb.visual_line_count() + inner.height() - 1,
+                        content_height,
+                        tb.find_match_lines(),
                     );
                 }
             }
@@ -1029,6 +1167,7 @@ impl Tui {
                     track,
                     sc.scroll_offset.y,
                     content.intrinsic_size.height,
+                    &[],
                 );
             }
             _ => {}
@@ -1051,172 +1190,223 @@ impl Tui {
             return;
         }
 
-        // Calculate the popup position based on cursor position
-        let cursor_pos = tb.cursor_visual_pos();
-        let screen_x = tc.scroll_offset.x;
-        let screen_y = tc.scroll_offset.y;
-        
         // Calculate popup position RELATIVE to cursor in viewport coordinates
-        let cursor_screen_x = cursor_pos.x - screen_x;
-        let cursor_screen_y = cursor_pos.y - screen_y;
-        
-        // FIXED: Place popup below the cursor line in the viewport
-        let popup_left = cursor_screen_x.max(0); // Start at cursor x position
-        let popup_top = cursor_screen_y + 1; // Place ONE line below cursor in viewport space
-
-        // Determine popup dimensions
-        let mut max_width = state.prefix.len() + 10; // Minimum width
+        let cursor_pos = tb.cursor_visual_pos();
+        let cursor_screen_x = cursor_pos.x - tc.scroll_offset.x;
+        let cursor_screen_y = cursor_pos.y - tc.scroll_offset.y;
+
+        // Place popup below the cursor line in the viewport
+        let popup_left = cursor_screen_x.max(0);
+        let popup_top = cursor_screen_y + 1;
+
+        // Determine popup dimensions. Each row consists of the
+        // selection marker, the kind icon and the label: "> K label".
+        let mut max_width = 0;
         for item in &state.items {
-            max_width = max_width.max(item.label.len() + 4); // +4 for padding and selection marker
+            max_width = max_width.max(completion_text_width(&item.label));
         }
-        let popup_width = (max_width as CoordType).min(40).max(15); // Min 15, Max 40 chars
-        let popup_height = (state.items.len().min(8) as CoordType).min(8).max(3); // Min 3, Max 8 items
-        
-        // Get viewport dimensions (using the passed inner_clipped parameter)
+        let visible_items = (state.items.len() as CoordType).min(COMPLETION_MAX_VISIBLE_ITEMS);
+        let popup_width = (max_width + 4 + 2).clamp(15, 40);
+        let popup_height = visible_items + 2;
+
         let viewport_width = inner_clipped.width();
         let viewport_height = inner_clipped.height();
-        
-        // Constrain popup to viewport with safety margins
-        let constrained_left = popup_left.max(0).min(viewport_width - popup_width.max(10));
-        let constrained_top = popup_top.max(0).min(viewport_height - popup_height.max(5));
-        
-        // Define popup bounds
+
+        // Constrain popup to viewport
+        let constrained_left = popup_left.min(viewport_width - popup_width).max(0);
+        let constrained_top = popup_top.min(viewport_height - popup_height).max(0);
+
         let popup_rect = Rect {
-            left: constrained_left,
-            top: constrained_top,
-            right: constrained_left + popup_width,
-            bottom: constrained_top + popup_height,
+            left: inner_clipped.left + constrained_left,
+            top: inner_clipped.top + constrained_top,
+            right: inner_clipped.left + constrained_left + popup_width,
+            bottom: inner_clipped.top + constrained_top + popup_height,
         };
-
-        // Extremely permissive rendering - almost always show the popup
-        if popup_rect.width() < 1 || popup_rect.height() < 1 {
+        if popup_rect.intersect(inner_clipped).is_empty() {
             return;
         }
 
-        // Draw the popup background
-        let bg_color = self.indexed(crate::framebuffer::IndexedColor::Background);
+        let bg_color = self.indexed(IndexedColor::Background);
         let fg_color = self.contrasted(bg_color);
-        
-        // Fill the popup background using simple string operations
-        let fill_str = " ".repeat(popup_rect.width() as usize);
-        
-        for y in popup_rect.top..popup_rect.bottom {
-            // Convert viewport-relative coordinates back to framebuffer coordinates
-            let fb_y = inner_clipped.top + y;
-            if fb_y < inner_clipped.bottom {
-                self.framebuffer.replace_text(fb_y, inner_clipped.left + popup_rect.left, inner_clipped.left + popup_rect.right, &fill_str);
-                self.framebuffer.blend_bg(
-                    Rect { 
-                        left: inner_clipped.left + popup_rect.left, 
-                        top: fb_y, 
-                        right: inner_clipped.left + popup_rect.right, 
-                        bottom: fb_y + 1 
-                    },
-                    bg_color,
-                );
-            }
-        }
+        self.render_completion_frame(popup_rect, inner_clipped, bg_color, fg_color);
 
-        // Draw the border using simple string operations
-        if popup_rect.width() >= 3 && popup_rect.height() >= 3 {
-            let border_top_str = format!("┌{}┐", "─".repeat((popup_rect.width() - 2) as usize));
-            let border_bottom_str = format!("└{}┘", "─".repeat((popup_rect.width() - 2) as usize));
-            
-            let top_fb_y = inner_clipped.top + popup_rect.top;
-            let bottom_fb_y = inner_clipped.top + popup_rect.bottom - 1;
-            
-            if top_fb_y < inner_clipped.bottom {
-                self.framebuffer.replace_text(top_fb_y, inner_clipped.left + popup_rect.left, inner_clipped.left + popup_rect.right, &border_top_str);
+        // Scroll the list so that the selected item is always visible.
+        let selected = state.selected_index as CoordType;
+        let first = (selected - visible_items + 1).max(0);
+        let scratch = scratch_arena(None);
+
+        for i in 0..visible_items {
+            let idx = first + i;
+            let Some(item) = state.items.get(idx as usize) else {
+                break;
+            };
+
+            let y = popup_rect.top + 1 + i;
+            if y >= inner_clipped.bottom {
+                break;
             }
-            if bottom_fb_y < inner_clipped.bottom {
-                self.framebuffer.replace_text(bottom_fb_y, inner_clipped.left + popup_rect.left, inner_clipped.left + popup_rect.right, &border_bottom_str);
+
+            let is_selected = idx == selected;
+            let mut line = ArenaString::new_in(&scratch);
+            line.push_str(if is_selected { "> " } else { "  " });
+            line.push(item.kind.icon());
+            line.push(' ');
+            line.push_str(&item.label);
+
+            let row = Rect {
+                left: popup_rect.left + 1,
+                top: y,
+                right: popup_rect.right - 1,
+                bottom: y + 1,
             }
-            
-            for y in popup_rect.top + 1..popup_rect.bottom - 1 {
-                let fb_y = inner_clipped.top + y;
-                if fb_y < inner_clipped.bottom {
-                    self.framebuffer.replace_text(
-                        fb_y,
-                        inner_clipped.left + popup_rect.left,
-                        inner_clipped.left + popup_rect.left + 1,
-                        "│",
-                    );
-                    self.framebuffer.replace_text(
-                        fb_y,
-                        inner_clipped.left + popup_rect.right - 1,
-                        inner_clipped.left + popup_rect.right,
-                        "│",
-                    );
-                }
+            .intersect(inner_clipped);
+            self.framebuffer.replace_text(y, row.left, row.right, &line);
+
+            // Italicize the kind icon so that it's visually distinct from the label.
+            let icon = Rect { left: row.left + 2, top: y, right: row.left + 3, bottom: y + 1 };
+            self.framebuffer.replace_attr(
+                icon.intersect(row),
+                Attributes::Italic,
+                Attributes::Italic,
+            );
+
+            if is_selected {
+                let highlight_color = self.indexed(IndexedColor::Green);
+                let highlight_fg = self.contrasted(highlight_color);
+                self.framebuffer.blend_bg(row, highlight_color);
+                self.framebuffer.blend_fg(row, highlight_fg);
             }
         }
 
-        // Draw the completion items
-        let content_top = popup_rect.top + 1;
-        let content_bottom = popup_rect.bottom - 1;
-        let content_width = popup_rect.width() - 2; // Account for borders
-        
-        for (i, item) in state.items.iter().take((content_bottom - content_top) as usize).enumerate() {
-            let y = content_top + i as CoordType;
-            if y >= content_bottom {
-                break;
-            }
-            
-            let fb_y = inner_clipped.top + y;
-            if fb_y >= inner_clipped.bottom {
+        if let Some(documentation) =
+            state.selected_item().and_then(|item| item.documentation.as_deref())
+        {
+            self.render_completion_documentation(
+                documentation,
+                state.documentation_scroll,
+                popup_rect,
+                inner_clipped,
+                bg_color,
+                fg_color,
+            );
+        }
+    }
+
+    /// Renders the documentation of the selected completion item
+    /// in a second floating pane next to the completion popup.
+    fn render_completion_documentation(
+        &mut self,
+        documentation: &str,
+        scroll: CoordType,
+        popup_rect: Rect,
+        inner_clipped: Rect,
+        bg: StraightRgba,
+        fg: StraightRgba,
+    ) {
+        // Prefer the right side of the popup and fall back to the left side.
+        let space_right = inner_clipped.right - popup_rect.right;
+        let space_left = popup_rect.left - inner_clipped.left;
+        let (left, right) = if space_right >= COMPLETION_DOC_MIN_WIDTH {
+            let right = popup_rect.right + space_right.min(COMPLETION_DOC_MAX_WIDTH);
+            (popup_rect.right, right)
+        } else if space_left >= COMPLETION_DOC_MIN_WIDTH {
+            let left = popup_rect.left - space_left.min(COMPLETION_DOC_MAX_WIDTH);
+            (left, popup_rect.left)
+        } else {
+            return;
+        };
+
+        let text_width = right - left - 2;
+        let text = documentation.as_bytes();
+        let total_lines = {
+            let mut cfg = unicode::MeasurementConfig::new(&text).with_word_wrap_column(text_width);
+            cfg.goto_offset(text.len()).visual_pos.y + 1
+        };
+
+        // The pane is at least as tall as the popup, but never taller than the viewport.
+        let height = (total_lines.min(COMPLETION_DOC_MAX_LINES) + 2)
+            .max(popup_rect.height())
+            .min(inner_clipped.height());
+        let top = popup_rect.top.min(inner_clipped.bottom - height).max(inner_clipped.top);
+        let pane = Rect { left, top, right, bottom: top + height };
+        if height < 3 {
+            return;
+        }
+
+        self.render_completion_frame(pane, inner_clipped, bg, fg);
+
+        let text_height = height - 2;
+        let max_scroll = (total_lines - text_height).max(0);
+        let scroll = scroll.min(max_scroll);
+        self.completion_documentation_max_scroll = Some(max_scroll);
+        let mut cfg = unicode::MeasurementConfig::new(&text).with_word_wrap_column(text_width);
+
+        for i in 0..text_height {
+            let line = scroll + i;
+            let beg = cfg.goto_visual(Point { x: 0, y: line });
+            if beg.visual_pos.y != line {
                 break;
             }
-            
-            let mut line_content = item.label.clone();
-            // Truncate if too long
-            if line_content.len() > (content_width - 3) as usize {
-                line_content = line_content[..(content_width - 3) as usize].to_string();
-            }
-            
-            // Pad the line to fill content area
-            let padding_needed = (content_width - 3 - line_content.len() as CoordType).max(0) as usize;
-            line_content.push_str(&" ".repeat(padding_needed));
-            
-            // Add selection indicator
-            let prefix = if i == state.selected_index { "> " } else { "  " };
-            let full_line = format!("{}{}", prefix, line_content);
-            
-            self.framebuffer.replace_text(fb_y, inner_clipped.left + popup_rect.left + 1, inner_clipped.left + popup_rect.right - 1, &full_line);
-            
-            // Highlight selected item
-            if i == state.selected_index {
-                let highlight_color = self.indexed(crate::framebuffer::IndexedColor::Green);
-                let highlight_fg = self.contrasted(highlight_color);
-                self.framebuffer.blend_bg(
-                    Rect { 
-                        left: inner_clipped.left + popup_rect.left + 1, 
-                        top: fb_y, 
-                        right: inner_clipped.left + popup_rect.right - 1, 
-                        bottom: fb_y + 1 
-                    },
-                    highlight_color,
-                );
-                self.framebuffer.blend_fg(
-                    Rect { 
-                        left: inner_clipped.left + popup_rect.left + 1, 
-                        top: fb_y, 
-                        right: inner_clipped.left + popup_rect.right - 1, 
-                        bottom: fb_y + 1 
-                    },
-                    highlight_fg,
-                );
-            } else {
-                self.framebuffer.blend_fg(
-                    Rect { 
-                        left: inner_clipped.left + popup_rect.left + 1, 
-                        top: fb_y, 
-                        right: inner_clipped.left + popup_rect.right - 1, 
-                        bottom: fb_y + 1 
-                    },
-                    fg_color,
+            let end = cfg.goto_visual(Point { x: COORD_TYPE_SAFE_MAX, y: line });
+            let chunk = unicode::strip_newline(&text[beg.offset..end.offset]);
+            // SAFETY: The measurement only ever stops at grapheme cluster boundaries.
+            let chunk = unsafe { std::str::from_utf8_unchecked(chunk) };
+
+            let y = pane.top + 1 + i;
+            if y < inner_clipped.bottom {
+                self.framebuffer.replace_text(
+                    y,
+                    pane.left + 1,
+                    (pane.right - 1).min(inner_clipped.right),
+                    chunk,
                 );
             }
         }
+
+        let track = Rect {
+            left: pane.right - 1,
+            top: pane.top + 1,
+            right: pane.right,
+            bottom: pane.bottom - 1,
+        };
+        self.framebuffer.draw_scrollbar(inner_clipped, track, scroll, total_lines, &[]);
+    }
+
+    /// Fills `rect` with the given colors and draws a border around it.
+    fn render_completion_frame(
+        &mut self,
+        rect: Rect,
+        clip: Rect,
+        bg: StraightRgba,
+        fg: StraightRgba,
+    ) {
+        let clipped = rect.intersect(clip);
+        if clipped.is_empty() || rect.width() < 2 || rect.height() < 2 {
+            return;
+        }
+
+        let scratch = scratch_arena(None);
+        let inner_width = (rect.width() - 2) as usize;
+
+        for y in clipped.top..clipped.bottom {
+            let (l, m, r) = if y == rect.top {
+                ('┌', '─', '┐')
+            } else if y == rect.bottom - 1 {
+                ('└', '─', '┘')
+            } else {
+                ('│', ' ', '│')
+            };
+
+            let mut fill = ArenaString::new_in(&scratch);
+            fill.push(l);
+            fill.push_repeat(m, inner_width);
+            fill.push(r);
+            self.framebuffer.replace_text(y, rect.left, clipped.right, &fill);
+        }
+
+        self.framebuffer.replace_attr(clipped, Attributes::All, Attributes::None);
+        self.framebuffer.blend_bg(clipped, bg);
+        self.framebuffer.blend_fg(clipped, fg);
     }
 
     fn render_styled_text(
@@ -1517,6 +1707,8 @@ pub struct Context<'a, 'input> {
     /// By how much the mouse wheel was scrolled since the last frame.
     input_scroll_delta: Point,
     input_consumed: bool,
+    /// The link that was Ctrl+Clicked in a textarea during this frame.
+    activated_link: Option<TextLink>,
 
     tree: Tree<'a>,
     last_modal: Option<&'a NodeCell<'a>>,
@@ -1552,6 +1744,11 @@ impl<'a> Context<'a, '_> {
         self.tui.size()
     }
 
+    /// Sets the theme, starting with the current frame. See [`Tui::set_theme`].
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.tui.set_theme(theme);
+    }
+
     /// Returns an indexed color from the framebuffer.
     #[inline]
     pub fn indexed(&self, index: IndexedColor) -> StraightRgba {
@@ -1877,6 +2074,15 @@ impl<'a> Context<'a, '_> {
         if self.input_consumed { None } else { self.input_keyboard }
     }
 
+    /// Returns the current text input, if any.
+    /// Returns None if the input was already consumed.
+    ///
+    /// Single ASCII characters are also reported by [`Context::keyboard_input`].
+    /// Call [`Context::set_input_consumed`] if you used it.
+    pub fn text_input(&self) -> Option<&str> {
+        if self.input_consumed { None } else { self.input_text }
+    }
+
     #[inline]
     pub fn set_input_consumed(&mut self) {
         debug_assert!(!self.input_consumed);
@@ -2076,9 +2282,9 @@ impl<'a> Context<'a, '_> {
             }
         }
 
-        if focused.is_none() {
+        let Some(focused) = focused else {
             return;
-        }
+        };
 
         let forward = input == next_key;
         let children_idx = if forward { NodeChildren::FIRST } else { NodeChildren::LAST };
@@ -2089,6 +2295,12 @@ impl<'a> Context<'a, '_> {
             return;
         };
 
+        // Wrapping around to the focused cell / row itself would move the focus
+        // out of the node inside it, for instance a textarea in a single-row table.
+        if ptr::eq(focused_next, focused) {
+            return;
+        }
+
         Tui::build_node_path(Some(focused_next), &mut self.tui.focused_node_path);
         self.set_input_consumed();
         self.needs_rerender();
@@ -2198,6 +2410,7 @@ impl<'a> Context<'a, '_> {
     /// Returns true if the button was activated.
     pub fn button(&mut self, classname: &'static str, text: &str, style: ButtonStyle) -> bool {
         self.button_label(classname, text, style);
+        self.tree.last_node.borrow_mut().widget = "button";
         self.attr_focusable();
         if self.is_focused() {
             self.attr_reverse();
@@ -2250,6 +2463,11 @@ impl<'a> Context<'a, '_> {
         self.textarea_internal(classname, TextBufferPayload::Textarea(tb));
     }
 
+    /// Returns the link that was Ctrl+Clicked in a textarea during this frame, if any.
+    pub fn take_activated_link(&mut self) -> Option<TextLink> {
+        self.activated_link.take()
+    }
+
     fn textarea_internal(&mut self, classname: &'static str, payload: TextBufferPayload) -> bool {
         self.block_begin(classname);
         self.block_end();
@@ -2334,6 +2552,10 @@ impl<'a> Context<'a, '_> {
                     make_cursor_visible |= tb.set_width(text_width);
                 }
 
+                if !single_line {
+                    self.textarea_update_link_hover(content, &node_prev);
+                }
+
                 make_cursor_visible |= self.textarea_handle_input(content, &node_prev, single_line);
 
                 if make_cursor_visible {
@@ -2371,6 +2593,41 @@ impl<'a> Context<'a, '_> {
         dirty
     }
 
+    /// Underlines the link under the mouse, if any.
+    fn textarea_update_link_hover(&mut self, tc: &mut TextareaContent, node_prev: &Node) {
+        let mut tb = tc.buffer.borrow_mut();
+        let mouse = self.tui.mouse_position;
+        let inner = node_prev.inner_clipped;
+        let text_rect = Rect {
+            left: inner.left + tb.margin_width(),
+            top: inner.top,
+            right: inner.right - 1,
+            bottom: inner.bottom,
+        };
+        let link = if text_rect.contains(mouse) && !self.tui.mouse_is_drag {
+            let pos = Point {
+                x: mouse.x - node_prev.inner.left - tb.margin_width() + tc.scroll_offset.x,
+                y: mouse.y - node_prev.inner.top + tc.scroll_offset.y,
+            };
+            tb.link_at_visual(tb.visual_pos_from_displayed(pos))
+        } else {
+            None
+        };
+        tb.set_link_hover(link.map(|l| l.range));
+    }
+
+    /// Returns the maximum scroll offset of the documentation pane,
+    /// if the selected completion item has documentation and it's shown on screen.
+    fn completion_documentation_max_scroll(&self, tb: &TextBuffer) -> Option<CoordType> {
+        self.tui.completion_documentation_max_scroll.filter(|_| {
+            tb.is_completing()
+                && tb
+                    .get_auto_completion_state()
+                    .selected_item()
+                    .is_some_and(|item| item.documentation.is_some())
+        })
+    }
+
     fn textarea_handle_input(
         &mut self,
         tc: &mut TextareaContent,
@@ -2415,9 +2672,18 @@ impl<'a> Context<'a, '_> {
                 x: mouse.x - inner.left - tb.margin_width() + tc.scroll_offset.x,
                 y: mouse.y - inner.top + tc.scroll_offset.y,
             };
+            // Rows with right-to-left text are displayed in a different order than they're laid out.
+            let pos = if tb.is_hex_mode() { pos } else { tb.visual_pos_from_displayed(pos) };
 
             if text_rect.contains(self.tui.mouse_down_position) {
-                if self.tui.mouse_is_drag {
+                if tb.is_hex_mode() {
+                    // The hex view has no selection. Clicking only moves the cursor.
+                    if self.tui.mouse_state != InputMouseState::Left || self.tui.mouse_is_drag {
+                        return false;
+                    }
+                    tb.hex_cursor_move_to_visual(pos);
+                    make_cursor_visible = true;
+                } else if self.tui.mouse_is_drag {
                     tb.selection_update_visual(pos);
                     tc.preferred_column = tb.cursor_visual_pos().x;
 
@@ -2473,6 +2739,14 @@ impl<'a> Context<'a, '_> {
                                 tc.preferred_column = tb.cursor_visual_pos().x;
                                 make_cursor_visible = true;
                             }
+                            InputMouseState::Release
+                                if self.input_mouse_modifiers.contains(kbmod::CTRL) =>
+                            {
+                                match tb.link_at_visual(pos) {
+                                    Some(link) => self.activated_link = Some(link),
+                                    None => return false,
+                                }
+                            }
                             _ => return false,
                         },
                     }
@@ -2507,6 +2781,10 @@ impl<'a> Context<'a, '_> {
             return false;
         }
 
+        if tb.is_hex_mode() {
+            return self.textarea_handle_hex_input(tb, node_prev);
+        }
+
         let mut write: &[u8] = &[];
 
         if let Some(input) = &self.input_text {
@@ -2577,6 +2855,11 @@ impl<'a> Context<'a, '_> {
                     }
                 }
                 vk::PRIOR => {
+                    if let Some(max) = self.completion_documentation_max_scroll(tb) {
+                        tb.scroll_completion_documentation(-COMPLETION_DOC_SCROLL_STEP, max);
+                        return true;
+                    }
+
                     let height = node_prev.inner.height() - 1;
 
                     // If the cursor was already on the first line,
@@ -2598,6 +2881,11 @@ impl<'a> Context<'a, '_> {
                     }
                 }
                 vk::NEXT => {
+                    if let Some(max) = self.completion_documentation_max_scroll(tb) {
+                        tb.scroll_completion_documentation(COMPLETION_DOC_SCROLL_STEP, max);
+                        return true;
+                    }
+
                     let height = node_prev.inner.height() - 1;
 
                     // If the cursor was already on the last line,
@@ -2720,6 +3008,8 @@ impl<'a> Context<'a, '_> {
                         tb.selection_update_delta(granularity, -1);
                     } else if let Some((beg, _)) = tb.selection_range() {
                         unsafe { tb.set_cursor(beg) };
+                    } else if modifiers == kbmod::NONE {
+                        tb.cursor_move_delta_displayed(-1);
                     } else {
                         tb.cursor_move_delta(granularity, -1);
                     }
@@ -2787,6 +3077,8 @@ impl<'a> Context<'a, '_> {
                         tb.selection_update_delta(granularity, 1);
                     } else if let Some((_, end)) = tb.selection_range() {
                         unsafe { tb.set_cursor(end) };
+                    } else if modifiers == kbmod::NONE {
+                        tb.cursor_move_delta_displayed(1);
                     } else {
                         tb.cursor_move_delta(granularity, 1);
                     }
@@ -2883,10 +3175,6 @@ impl<'a> Context<'a, '_> {
                     }
                     _ => return false,
                 },
-                vk::H => match modifiers {
-                    kbmod::CTRL => tb.delete(CursorMovement::Word, -1),
-                    _ => return false,
-                },
                 vk::L => match modifiers {
                     kbmod::CTRL => tb.select_line(),
                     _ => return false,
@@ -2940,9 +3228,10 @@ impl<'a> Context<'a, '_> {
             make_cursor_visible = true;
             
             // Trigger auto-completion if we're typing alphabetic characters
+            // or a path separator (for path completion).
             // Changed condition from s.len() >= 2 to s.len() >= 1 for more responsive triggering
             if let Ok(s) = std::str::from_utf8(write) {
-                if s.chars().all(|c| c.is_alphabetic() || c == '_') && s.len() >= 1 {
+                if s.chars().all(|c| c.is_alphabetic() || c == '_' || c == '/') && s.len() >= 1 {
                     tb.trigger_auto_completion();
                 }
             }
@@ -2956,6 +3245,55 @@ impl<'a> Context<'a, '_> {
         make_cursor_visible
     }
 
+    /// The keyboard half of [`Context::textarea_handle_input`] for the hex view.
+    /// Hex digits are written nibble by nibble. Anything that isn't navigation or editing
+    /// is left unconsumed, so that it can be handled as a shortcut elsewhere.
+    fn textarea_handle_hex_input(&mut self, tb: &mut TextBuffer, node_prev: &Node) -> bool {
+        const ROW: isize = hex::BYTES_PER_ROW as isize * 2;
+
+        if let Some(input) = &self.input_text {
+            let digits = input.chars().filter_map(|ch| ch.to_digit(16));
+            if digits.clone().next().is_none() {
+                return false;
+            }
+            for digit in digits {
+                tb.hex_write_nibble(digit as u8);
+            }
+        } else if let Some(input) = &self.input_keyboard {
+            let offset = tb.hex_cursor_offset();
+            let row_start = offset - offset % hex::BYTES_PER_ROW;
+            let page = (node_prev.inner.height() - 1).max(1) * ROW;
+
+            match (input.key(), input.modifiers()) {
+                (vk::LEFT, kbmod::NONE) => tb.hex_cursor_move_nibbles(-1),
+                (vk::RIGHT, kbmod::NONE) => tb.hex_cursor_move_nibbles(1),
+                (vk::LEFT, kbmod::CTRL) => tb.hex_cursor_move_nibbles(-2),
+                (vk::RIGHT, kbmod::CTRL) => tb.hex_cursor_move_nibbles(2),
+                (vk::UP, kbmod::NONE) => tb.hex_cursor_move_nibbles(-ROW),
+                (vk::DOWN, kbmod::NONE) => tb.hex_cursor_move_nibbles(ROW),
+                (vk::PRIOR, kbmod::NONE) => tb.hex_cursor_move_nibbles(-page),
+                (vk::NEXT, kbmod::NONE) => tb.hex_cursor_move_nibbles(page),
+                (vk::HOME, kbmod::NONE) => tb.hex_cursor_move_to_offset(row_start),
+                (vk::END, kbmod::NONE) => {
+                    tb.hex_cursor_move_to_offset(row_start + hex::BYTES_PER_ROW - 1)
+                }
+                (vk::HOME, kbmod::CTRL) => tb.hex_cursor_move_to_offset(0),
+                (vk::END, kbmod::CTRL) => tb.hex_cursor_move_to_offset(usize::MAX),
+                (vk::BACK, kbmod::NONE) => tb.hex_delete(-1),
+                (vk::DELETE, kbmod::NONE) => tb.hex_delete(1),
+                (vk::INSERT, kbmod::NONE) => tb.set_overtype(!tb.is_overtype()),
+                (vk::Z, kbmod::CTRL) => tb.undo(),
+                (vk::Y, kbmod::CTRL) | (vk::Z, kbmod::CTRL_SHIFT) => tb.redo(),
+                _ => return false,
+            }
+        } else {
+            return false;
+        }
+
+        self.set_input_consumed();
+        true
+    }
+
     fn textarea_make_cursor_visible(&self, tc: &mut TextareaContent, node_prev: &Node) {
         let tb = tc.buffer.borrow();
         let mut scroll_x = tc.scroll_offset.x;
@@ -3131,12 +3469,16 @@ impl<'a> Context<'a, '_> {
             .prev_node_map
             .get(last_node.id)
             .and_then(|node| match &node.borrow().content {
-                NodeContent::List(content) => {
-                    Some(ListContent { selected: content.selected, selected_node: None })
-                }
+                NodeContent::List(content) => Some(content.selected),
                 _ => None,
             })
-            .unwrap_or(ListContent { selected: 0, selected_node: None });
+            .unwrap_or(0);
+        let content = ListContent {
+            selected: content,
+            selected_node: None,
+            tree_parents: Vec::new_in(self.arena()),
+            tree_select_child_of: None,
+        };
 
         last_node.attributes.focus_void = true;
         last_node.content = NodeContent::List(content);
@@ -3164,7 +3506,28 @@ impl<'a> Context<'a, '_> {
     /// Ends the current styled list item.
     pub fn styled_list_item_end(&mut self, select: bool) -> ListSelection {
         self.styled_label_end();
+        self.list_item_update_selection(select)
+    }
 
+    /// Creates a list item that contains arbitrary children, e.g. a table row with
+    /// one cell per column. The first label in it receives the selection marker,
+    /// so it should start with two spaces, just like [`Context::styled_list_item_begin`].
+    pub fn list_item_begin(&mut self) {
+        let list = self.tree.current_node;
+        let idx = list.borrow().child_count;
+
+        self.next_block_id_mixin(idx as u64);
+        self.block_begin("item");
+        self.attr_focusable();
+    }
+
+    /// Ends the current list item. See [`Context::list_item_begin`].
+    pub fn list_item_end(&mut self, select: bool) -> ListSelection {
+        self.block_end();
+        self.list_item_update_selection(select)
+    }
+
+    fn list_item_update_selection(&mut self, select: bool) -> ListSelection {
         let list = self.tree.current_node;
 
         let selected_before;
@@ -3195,9 +3558,9 @@ impl<'a> Context<'a, '_> {
             }
         }
 
-        // Clicking an item activates it
+        // Clicking an item (or any of its children) activates it
         let clicked =
-            !self.input_consumed && (self.input_mouse_click == 2 && self.was_mouse_down());
+            !self.input_consumed && (self.input_mouse_click == 2 && self.contains_mouse_down());
         // Pressing Enter on a selected item activates it as well
         let entered = focused
             && selected_before
@@ -3325,10 +3688,17 @@ impl<'a> Context<'a, '_> {
         }
 
         // Now that we know which item is selected we can mark it as such.
-        if let NodeContent::Text(content) = &mut selected_next.borrow_mut().content {
-            unsafe {
-                content.text.as_bytes_mut()[0] = b'>';
+        // Items with children carry the marker in their first label.
+        let mut marked = Some(selected_next);
+        while let Some(node) = marked {
+            let mut node = node.borrow_mut();
+            if let NodeContent::Text(content) = &mut node.content {
+                unsafe {
+                    content.text.as_bytes_mut()[0] = b'>';
+                }
+                break;
             }
+            marked = node.children.first;
         }
 
         // If the list has focus, we also delegate focus to the selected item and colorize it.
@@ -3342,6 +3712,94 @@ impl<'a> Context<'a, '_> {
         }
     }
 
+    /// Creates a tree view. It's a list whose items are indented by their depth.
+    ///
+    /// The tree doesn't know about the hierarchy of your data. Instead, you emit the
+    /// visible items in order via [`Context::tree_item`], with the children of an
+    /// expanded item right after it. This makes lazy loading of children trivial:
+    /// Load them once the item returns [`TreeAction::Expand`].
+    pub fn tree_begin(&mut self, classname: &'static str) {
+        self.list_begin(classname);
+    }
+
+    /// Creates a tree item at the given depth (0 = top-level).
+    ///
+    /// Besides the list navigation, Right expands an item or moves to its first child,
+    /// and Left collapses it or moves to its parent.
+    pub fn tree_item(
+        &mut self,
+        select: bool,
+        depth: usize,
+        state: TreeItemState,
+        text: &str,
+    ) -> TreeAction {
+        self.styled_list_item_begin();
+        {
+            let mut prefix = ArenaString::new_in(self.arena());
+            for _ in 0..depth {
+                prefix.push_str("  ");
+            }
+            prefix.push_str(match state {
+                TreeItemState::Leaf => "  ",
+                TreeItemState::Collapsed => "▸ ",
+                TreeItemState::Expanded => "▾ ",
+            });
+            prefix.push_str(text);
+            self.styled_label_add_text(&prefix);
+        }
+        let selection = self.styled_list_item_end(select);
+
+        let mut action = match selection {
+            ListSelection::Unchanged => TreeAction::Unchanged,
+            ListSelection::Selected => TreeAction::Selected,
+            ListSelection::Activated => TreeAction::Activated,
+        };
+        let item = self.tree.last_node;
+        let focused = self.is_focused();
+        let key = if self.input_consumed { None } else { self.input_keyboard };
+
+        let mut list = self.tree.current_node.borrow_mut();
+        let NodeContent::List(content) = &mut list.content else {
+            unreachable!();
+        };
+
+        let parent = depth.checked_sub(1).and_then(|d| content.tree_parents.get(d).copied());
+        content.tree_parents.truncate(depth);
+        content.tree_parents.push(item);
+
+        // The previous item asked its first child to take over the selection (Right on an
+        // expanded item). If the directory turned out to be empty, this is a sibling instead.
+        if content.tree_select_child_of.take().is_some_and(|d| depth > d) {
+            content.selected = item.borrow().id;
+            content.selected_node = Some(item);
+            action = TreeAction::Selected;
+            self.needs_rerender();
+        }
+
+        // Left/Right are consumed even if there's nothing to do,
+        // so that they don't move the focus out of the tree.
+        if focused && let Some(key @ (vk::LEFT | vk::RIGHT)) = key {
+            match (key, state) {
+                (vk::RIGHT, TreeItemState::Collapsed) => action = TreeAction::Expand,
+                (vk::RIGHT, TreeItemState::Expanded) => content.tree_select_child_of = Some(depth),
+                (vk::LEFT, TreeItemState::Expanded) => action = TreeAction::Collapse,
+                // Just like `list_end`, this only moves the focus. The parent
+                // then reports itself as selected in the next frame.
+                (vk::LEFT, _) if let Some(parent) = parent => content.selected_node = Some(parent),
+                _ => {}
+            }
+            drop(list);
+            self.set_input_consumed();
+        }
+
+        action
+    }
+
+    /// Ends the current tree block.
+    pub fn tree_end(&mut self) {
+        self.list_end();
+    }
+
     /// Creates a menubar, to be shown at the top of the screen.
     pub fn menubar_begin(&mut self) {
         self.table_begin("menubar");
@@ -3866,6 +4324,8 @@ struct NodeAttributes {
     padding: Rect,
     bg: StraightRgba,
     fg: StraightRgba,
+    border_fg: StraightRgba,
+    attr: Attributes,
     reverse: bool,
     bordered: bool,
     focusable: bool,
@@ -3878,6 +4338,10 @@ struct ListContent<'a> {
     selected: u64,
     // Points to the Node that holds this ListContent instance, if any>.
     selected_node: Option<&'a NodeCell<'a>>,
+    // For trees: The last item at each depth, up to the current item's depth.
+    tree_parents: Vec<&'a NodeCell<'a>, &'a Arena>,
+    // For trees: The next item gets selected if it's deeper than this, i.e. the first child.
+    tree_select_child_of: Option<usize>,
 }
 
 /// NOTE: Must not contain items that require drop().
@@ -3981,6 +4445,38 @@ impl<'a> NodeChildren<'a> {
 
 type NodeCell<'a> = SemiRefCell<Node<'a>>;
 
+/// Exposes a node to [`Theme::style`] for selector matching.
+struct ThemedNode<'t, 'a> {
+    tui: &'t Tui,
+    node: &'a NodeCell<'a>,
+}
+
+impl ThemeNode for ThemedNode<'_, '_> {
+    fn classname(&self) -> &str {
+        self.node.borrow().classname
+    }
+
+    fn widget(&self) -> &str {
+        self.node.borrow().widget
+    }
+
+    fn is_focused(&self) -> bool {
+        self.tui.is_node_focused(self.node.borrow().id)
+    }
+
+    fn contains_focus(&self) -> bool {
+        self.tui.is_subtree_focused(&self.node.borrow())
+    }
+
+    fn is_modal(&self) -> bool {
+        matches!(self.node.borrow().content, NodeContent::Modal(_))
+    }
+
+    fn parent(&self) -> Option<Self> {
+        self.node.borrow().parent.map(|node| ThemedNode { tui: self.tui, node })
+    }
+}
+
 /// A node in the UI tree.
 ///
 /// NOTE: Must not contain items that require drop().
@@ -3992,6 +4488,8 @@ struct Node<'a> {
 
     id: u64,
     classname: &'static str,
+    /// The type of widget, for theme selectors. Empty for plain blocks.
+    widget: &'static str,
     parent: Option<&'a NodeCell<'a>>,
     depth: usize,
     siblings: NodeSiblings<'a>,
@@ -4249,3 +4747,9 @@ impl Node<'_> {
         }
     }
 }
+
+/// Returns the width of `text` in terminal columns.
+fn completion_text_width(text: &str) -> CoordType {
+    let text = text.as_bytes();
+    unicode::MeasurementConfig::new(&text).goto_offset(text.len()).visual_pos.x
+}
@@ -0,0 +1,639 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! An implementation of "UAX #9: Unicode Bidirectional Algorithm" for display purposes.
+//!
+//! The editor stores and navigates text in logical order. This module computes
+//! the embedding levels of a line and the visual order derived from them,
+//! which is all that's needed to display Arabic or Hebrew text correctly.
+
+use stdext::arena::Arena;
+
+use super::Utf8Chars;
+use super::bidi_tables::*;
+
+/// The Bidi_Class property of a character.
+///
+/// NOTE: The order of these items must match the `BidiClass` enum in `unicode-gen`.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum BidiClass {
+    L,
+    R,
+    AL,
+    EN,
+    ES,
+    ET,
+    AN,
+    CS,
+    NSM,
+    BN,
+    B,
+    S,
+    WS,
+    ON,
+    LRE,
+    LRO,
+    RLE,
+    RLO,
+    PDF,
+    LRI,
+    RLI,
+    FSI,
+    PDI,
+}
+
+use BidiClass::*;
+
+/// The maximum explicit embedding level as per BD2.
+const MAX_DEPTH: u8 = 125;
+/// The size of the bracket pair stack as per BD16.
+const MAX_BRACKET_PAIRS: usize = 63;
+
+/// Returns the Bidi_Class of `ch`.
+#[inline]
+pub fn bidi_class(ch: char) -> BidiClass {
+    // SAFETY: The table only contains values that are valid `BidiClass` discriminants.
+    unsafe { std::mem::transmute(ucd_bidi_class(ch)) }
+}
+
+/// Returns the Bidi_Mirroring_Glyph of `ch`, if any. For instance, `(` for `)`.
+#[inline]
+pub fn bidi_mirror(ch: char) -> Option<char> {
+    match ucd_bidi_mirror(ch) {
+        0 => None,
+        v => char::from_u32(v & 0x1fffff),
+    }
+}
+
+/// Returns the Bidi_Paired_Bracket_Type of `ch`: 1 for opening brackets, 2 for closing ones.
+#[inline]
+fn bidi_bracket_type(ch: char) -> u32 {
+    ucd_bidi_mirror(ch) >> 21
+}
+
+/// Returns true if `text` contains any characters that make it necessary to reorder it,
+/// i.e. right-to-left letters or explicit right-to-left formatting characters.
+pub fn contains_rtl(text: &[u8]) -> bool {
+    // The first right-to-left character is U+0590, which is encoded as D6 90.
+    if !text.iter().any(|&b| b >= 0xD6) {
+        return false;
+    }
+    Utf8Chars::new(text, 0).any(|ch| matches!(bidi_class(ch), R | AL | RLE | RLO | RLI))
+}
+
+/// Returns the paragraph embedding level given by the first strong character
+/// in `chars` as per rules P2 and P3, or `None` if there's none.
+pub fn base_level(chars: impl IntoIterator<Item = char>) -> Option<u8> {
+    first_strong(chars.into_iter().map(bidi_class))
+}
+
+fn first_strong(classes: impl IntoIterator<Item = BidiClass>) -> Option<u8> {
+    let mut isolates = 0usize;
+    for class in classes {
+        match class {
+            L if isolates == 0 => return Some(0),
+            R | AL if isolates == 0 => return Some(1),
+            LRI | RLI | FSI => isolates += 1,
+            PDI if isolates > 0 => isolates -= 1,
+            B => break,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Characters that are removed by rule X9.
+#[inline]
+fn is_removed(class: BidiClass) -> bool {
+    matches!(class, RLE | LRE | RLO | LRO | PDF | BN)
+}
+
+/// Maps L to L and R, EN and AN to R as required by rules N0 and N1.
+#[inline]
+fn strong_direction(class: BidiClass) -> Option<BidiClass> {
+    match class {
+        L => Some(L),
+        R | AL | EN | AN => Some(R),
+        _ => None,
+    }
+}
+
+#[inline]
+fn direction_of_level(level: u8) -> BidiClass {
+    if level & 1 == 0 { L } else { R }
+}
+
+/// Resolves the embedding levels of a single line of text with the given paragraph level
+/// (0 = left-to-right, 1 = right-to-left), up to and including rule L1.
+pub fn resolve_levels<'a>(arena: &'a Arena, text: &[char], para_level: u8) -> Vec<u8, &'a Arena> {
+    let len = text.len();
+    let mut original = Vec::with_capacity_in(len, arena);
+    original.extend(text.iter().map(|&ch| bidi_class(ch)));
+    let mut classes = original.clone();
+    let mut levels = Vec::with_capacity_in(len, arena);
+    levels.resize(len, para_level);
+
+    // BD9: Find the matching PDI of each isolate initiator.
+    let mut matching_pdi = Vec::with_capacity_in(len, arena);
+    matching_pdi.resize(len, usize::MAX);
+    {
+        let mut stack = Vec::new_in(arena);
+        for (i, &class) in original.iter().enumerate() {
+            match class {
+                LRI | RLI | FSI => stack.push(i),
+                PDI => {
+                    if let Some(j) = stack.pop() {
+                        matching_pdi[j] = i;
+                        matching_pdi[i] = j;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // X1 - X8: Explicit levels and directions.
+    {
+        #[derive(Clone, Copy)]
+        struct Status {
+            level: u8,
+            override_class: Option<BidiClass>,
+            isolate: bool,
+        }
+
+        let mut stack = Vec::with_capacity_in(MAX_DEPTH as usize + 2, arena);
+        stack.push(Status { level: para_level, override_class: None, isolate: false });
+        let mut overflow_isolates = 0usize;
+        let mut overflow_embeddings = 0usize;
+        let mut valid_isolates = 0usize;
+
+        for i in 0..len {
+            let top = *stack.last().unwrap();
+            let class = original[i];
+            levels[i] = top.level;
+
+            match class {
+                RLE | LRE | RLO | LRO => {
+                    let level = if matches!(class, RLE | RLO) {
+                        (top.level + 1) | 1
+                    } else {
+                        (top.level + 2) & !1
+                    };
+                    if level <= MAX_DEPTH && overflow_isolates == 0 && overflow_embeddings == 0 {
+                        let override_class = match class {
+                            RLO => Some(R),
+                            LRO => Some(L),
+                            _ => None,
+                        };
+                        stack.push(Status { level, override_class, isolate: false });
+                    } else if overflow_isolates == 0 {
+                        overflow_embeddings += 1;
+                    }
+                }
+                RLI | LRI | FSI => {
+                    if let Some(c) = top.override_class {
+                        classes[i] = c;
+                    }
+                    let rtl = match class {
+                        RLI => true,
+                        LRI => false,
+                        _ => {
+                            let end =
+                                if matching_pdi[i] == usize::MAX { len } else { matching_pdi[i] };
+                            first_strong(original[i + 1..end].iter().copied()) == Some(1)
+                        }
+                    };
+                    let level = if rtl { (top.level + 1) | 1 } else { (top.level + 2) & !1 };
+                    if level <= MAX_DEPTH && overflow_isolates == 0 && overflow_embeddings == 0 {
+                        valid_isolates += 1;
+                        stack.push(Status { level, override_class: None, isolate: true });
+                    } else {
+                        overflow_isolates += 1;
+                    }
+                }
+                PDI => {
+                    if overflow_isolates > 0 {
+                        overflow_isolates -= 1;
+                    } else if valid_isolates > 0 {
+                        overflow_embeddings = 0;
+                        while !stack.last().unwrap().isolate {
+                            stack.pop();
+                        }
+                        stack.pop();
+                        valid_isolates -= 1;
+                    }
+                    let top = *stack.last().unwrap();
+                    levels[i] = top.level;
+                    if let Some(c) = top.override_class {
+                        classes[i] = c;
+                    }
+                }
+                PDF => {
+                    if overflow_isolates > 0 {
+                    } else if overflow_embeddings > 0 {
+                        overflow_embeddings -= 1;
+                    } else if !top.isolate && stack.len() >= 2 {
+                        stack.pop();
+                    }
+                }
+                B => levels[i] = para_level,
+                BN => {}
+                _ => {
+                    if let Some(c) = top.override_class {
+                        classes[i] = c;
+                    }
+                }
+            }
+        }
+    }
+
+    // X9: The removed characters are skipped from here on.
+    let mut indices = Vec::with_capacity_in(len, arena);
+    indices.extend((0..len).filter(|&i| !is_removed(original[i])));
+
+    // X10: Split the text into level runs and then link them into isolating run sequences.
+    let mut runs = Vec::new_in(arena);
+    let mut run_of = Vec::with_capacity_in(len, arena);
+    run_of.resize(len, usize::MAX);
+    {
+        let mut beg = 0;
+        for k in 1..=indices.len() {
+            if k == indices.len() || levels[indices[k]] != levels[indices[beg]] {
+                for &i in &indices[beg..k] {
+                    run_of[i] = runs.len();
+                }
+                runs.push(beg..k);
+                beg = k;
+            }
+        }
+    }
+
+    let mut sequence = Vec::new_in(arena);
+    for run in &runs {
+        let first = indices[run.start];
+        // Runs that start with a matching PDI continue the sequence of their isolate initiator.
+        if original[first] == PDI && matching_pdi[first] != usize::MAX {
+            continue;
+        }
+
+        sequence.clear();
+        sequence.extend_from_slice(&indices[run.clone()]);
+        loop {
+            let last = *sequence.last().unwrap();
+            let pdi = matching_pdi[last];
+            if !matches!(original[last], LRI | RLI | FSI) || pdi == usize::MAX {
+                break;
+            }
+            let next = &runs[run_of[pdi]];
+            if indices[next.start] != pdi {
+                break;
+            }
+            sequence.extend_from_slice(&indices[next.clone()]);
+        }
+
+        resolve_sequence(
+            arena,
+            text,
+            &original,
+            &mut classes,
+            &levels,
+            &matching_pdi,
+            &sequence,
+            para_level,
+        );
+    }
+
+    // I1 and I2: Resolve the implicit levels.
+    for &i in &indices {
+        let level = levels[i];
+        levels[i] = match (level & 1, classes[i]) {
+            (0, R) => level + 1,
+            (0, AN | EN) => level + 2,
+            (1, L | EN | AN) => level + 1,
+            _ => level,
+        };
+    }
+
+    // The removed characters get the level of the preceding character,
+    // so that they stay next to it during reordering.
+    for i in 0..len {
+        if is_removed(original[i]) {
+            levels[i] = if i > 0 { levels[i - 1] } else { para_level };
+        }
+    }
+
+    // L1: Segment separators and trailing whitespace are reset to the paragraph level.
+    let mut reset = true;
+    for i in (0..len).rev() {
+        match original[i] {
+            S | B => {
+                levels[i] = para_level;
+                reset = true;
+            }
+            WS | FSI | LRI | RLI | PDI | BN | RLE | LRE | RLO | LRO | PDF => {
+                if reset {
+                    levels[i] = para_level;
+                }
+            }
+            _ => reset = false,
+        }
+    }
+
+    levels
+}
+
+/// Resolves the weak and neutral types of an isolating run sequence (rules W1 - N2).
+#[allow(clippy::too_many_arguments)]
+fn resolve_sequence(
+    arena: &Arena,
+    text: &[char],
+    original: &[BidiClass],
+    classes: &mut [BidiClass],
+    levels: &[u8],
+    matching_pdi: &[usize],
+    sequence: &[usize],
+    para_level: u8,
+) {
+    let first = sequence[0];
+    let last = *sequence.last().unwrap();
+    let level = levels[first];
+    let embedding_direction = direction_of_level(level);
+
+    let level_before =
+        (0..first).rev().find(|&i| !is_removed(original[i])).map_or(para_level, |i| levels[i]);
+    let level_after = if matches!(original[last], LRI | RLI | FSI)
+        && matching_pdi[last] == usize::MAX
+    {
+        para_level
+    } else {
+        (last + 1..text.len()).find(|&i| !is_removed(original[i])).map_or(para_level, |i| levels[i])
+    };
+    let sos = direction_of_level(level.max(level_before));
+    let eos = direction_of_level(level.max(level_after));
+
+    // W1: Non-spacing marks take the type of the previous character.
+    let mut prev = sos;
+    for &i in sequence {
+        if classes[i] == NSM {
+            classes[i] = if matches!(prev, LRI | RLI | FSI | PDI) { ON } else { prev };
+        }
+        prev = classes[i];
+    }
+
+    // W2: European numbers after Arabic letters are Arabic numbers.
+    // W3: Arabic letters are right-to-left letters.
+    let mut last_strong = sos;
+    for &i in sequence {
+        match classes[i] {
+            L | R => last_strong = classes[i],
+            AL => {
+                last_strong = AL;
+                classes[i] = R;
+            }
+            EN if last_strong == AL => classes[i] = AN,
+            _ => {}
+        }
+    }
+
+    // W4: A single separator between two numbers of the same type turns into that type.
+    for k in 1..sequence.len().saturating_sub(1) {
+        let prev = classes[sequence[k - 1]];
+        let next = classes[sequence[k + 1]];
+        let class = &mut classes[sequence[k]];
+        if prev == next
+            && ((*class == ES && prev == EN) || (*class == CS && matches!(prev, EN | AN)))
+        {
+            *class = prev;
+        }
+    }
+
+    // W5: Terminators next to European numbers are European numbers.
+    let mut k = 0;
+    while k < sequence.len() {
+        if classes[sequence[k]] != ET {
+            k += 1;
+            continue;
+        }
+        let beg = k;
+        while k < sequence.len() && classes[sequence[k]] == ET {
+            k += 1;
+        }
+        if (beg > 0 && classes[sequence[beg - 1]] == EN)
+            || (k < sequence.len() && classes[sequence[k]] == EN)
+        {
+            for &i in &sequence[beg..k] {
+                classes[i] = EN;
+            }
+        }
+    }
+
+    // W6: Remaining separators and terminators are neutral.
+    // W7: European numbers after left-to-right letters are left-to-right.
+    let mut last_strong = sos;
+    for &i in sequence {
+        match classes[i] {
+            ES | ET | CS => classes[i] = ON,
+            L | R => last_strong = classes[i],
+            EN if last_strong == L => classes[i] = L,
+            _ => {}
+        }
+    }
+
+    // N0: Paired brackets take the direction of their content or context.
+    {
+        let mut pairs = Vec::new_in(arena);
+        let mut stack = Vec::new_in(arena);
+        for (k, &i) in sequence.iter().enumerate() {
+            if classes[i] != ON {
+                continue;
+            }
+            match bidi_bracket_type(text[i]) {
+                1 => {
+                    if stack.len() == MAX_BRACKET_PAIRS {
+                        break;
+                    }
+                    stack.push((bidi_mirror(text[i]), k));
+                }
+                2 => {
+                    if let Some(p) = stack.iter().rposition(|&(close, _)| close == Some(text[i])) {
+                        pairs.push((stack[p].1, k));
+                        stack.truncate(p);
+                    }
+                }
+                _ => {}
+            }
+        }
+        pairs.sort_unstable();
+
+        for &(open, close) in &pairs {
+            let mut found_embedding = false;
+            let mut found_opposite = false;
+            for &i in &sequence[open + 1..close] {
+                match strong_direction(classes[i]) {
+                    Some(d) if d == embedding_direction => found_embedding = true,
+                    Some(_) => found_opposite = true,
+                    None => {}
+                }
+            }
+
+            let direction = if found_embedding {
+                embedding_direction
+            } else if found_opposite {
+                let context = sequence[..open]
+                    .iter()
+                    .rev()
+                    .find_map(|&i| strong_direction(classes[i]))
+                    .unwrap_or(sos);
+                if context != embedding_direction { context } else { embedding_direction }
+            } else {
+                continue;
+            };
+
+            for k in [open, close] {
+                classes[sequence[k]] = direction;
+                for &i in &sequence[k + 1..] {
+                    if original[i] != NSM {
+                        break;
+                    }
+                    classes[i] = direction;
+                }
+            }
+        }
+    }
+
+    // N1 and N2: Neutrals between two characters of the same direction take that direction.
+    // All others take the embedding direction.
+    let is_neutral = |class| matches!(class, B | S | WS | ON | LRI | RLI | FSI | PDI);
+    let mut k = 0;
+    while k < sequence.len() {
+        if !is_neutral(classes[sequence[k]]) {
+            k += 1;
+            continue;
+        }
+        let beg = k;
+        while k < sequence.len() && is_neutral(classes[sequence[k]]) {
+            k += 1;
+        }
+        let before = if beg > 0 { strong_direction(classes[sequence[beg - 1]]) } else { Some(sos) };
+        let after =
+            if k < sequence.len() { strong_direction(classes[sequence[k]]) } else { Some(eos) };
+        let direction = match before {
+            Some(d) if before == after => d,
+            _ => embedding_direction,
+        };
+        for &i in &sequence[beg..k] {
+            classes[i] = direction;
+        }
+    }
+}
+
+/// Returns the logical indices of the given `levels` in visual order (rule L2).
+pub fn visual_order<'a>(arena: &'a Arena, levels: &[u8]) -> Vec<usize, &'a Arena> {
+    let mut order = Vec::with_capacity_in(levels.len(), arena);
+    order.extend(0..levels.len());
+
+    let max = levels.iter().copied().max().unwrap_or(0);
+    let min_odd = levels.iter().copied().filter(|l| l & 1 != 0).min().unwrap_or(max + 1);
+
+    // From the highest level to the lowest odd level, reverse any
+    // contiguous sequence of characters that are at that level or higher.
+    let mut level = max;
+    while level >= min_odd && level > 0 {
+        let mut k = 0;
+        while k < levels.len() {
+            if levels[order[k]] < level {
+                k += 1;
+                continue;
+            }
+            let beg = k;
+            while k < levels.len() && levels[order[k]] >= level {
+                k += 1;
+            }
+            order[beg..k].reverse();
+        }
+        level -= 1;
+    }
+
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use stdext::arena::scratch_arena;
+
+    use super::*;
+
+    /// Returns `text` in visual order, with mirrored characters at odd levels.
+    fn display(text: &str, para_level: Option<u8>) -> String {
+        let scratch = scratch_arena(None);
+        let chars: Vec<char> = text.chars().collect();
+        let para_level = para_level.or_else(|| base_level(text.chars())).unwrap_or(0);
+        let levels = resolve_levels(&scratch, &chars, para_level);
+        visual_order(&scratch, &levels)
+            .iter()
+            .map(|&i| {
+                if levels[i] & 1 != 0 {
+                    bidi_mirror(chars[i]).unwrap_or(chars[i])
+                } else {
+                    chars[i]
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_bidi_class() {
+        assert_eq!(bidi_class('a'), L);
+        assert_eq!(bidi_class(' '), WS);
+        assert_eq!(bidi_class('\t'), S);
+        assert_eq!(bidi_class('1'), EN);
+        assert_eq!(bidi_class('('), ON);
+        assert_eq!(bidi_class('א'), R);
+        assert_eq!(bidi_class('ب'), AL);
+        assert_eq!(bidi_class('٣'), AN);
+        assert_eq!(bidi_class('\u{0301}'), NSM);
+        assert_eq!(bidi_class('\u{200D}'), BN);
+        assert_eq!(bidi_class('\u{2067}'), RLI);
+        assert_eq!(bidi_class('\u{10FFFF}'), BN);
+        assert_eq!(bidi_mirror('('), Some(')'));
+        assert_eq!(bidi_mirror('»'), Some('«'));
+        assert_eq!(bidi_mirror('a'), None);
+        assert_eq!(bidi_bracket_type('['), 1);
+        assert_eq!(bidi_bracket_type(']'), 2);
+        assert_eq!(bidi_bracket_type('<'), 0);
+    }
+
+    #[test]
+    fn test_contains_rtl() {
+        assert!(!contains_rtl(b"hello world"));
+        assert!(!contains_rtl("Grüße, 世界".as_bytes()));
+        assert!(contains_rtl("abc שלום".as_bytes()));
+        assert!(contains_rtl("\u{202E}abc".as_bytes()));
+    }
+
+    #[test]
+    fn test_reorder() {
+        // Plain left-to-right text is left alone.
+        assert_eq!(display("hello world", None), "hello world");
+        // Right-to-left runs are reversed within left-to-right text.
+        assert_eq!(display("abc אבג def", None), "abc גבא def");
+        // ...and vice versa. Numbers keep their order.
+        assert_eq!(display("אבג abc 123 דה", None), "הד abc 123 גבא");
+        assert_eq!(display("אבג 123 דה", None), "הד 123 גבא");
+        // Arabic digits and European digits after Arabic letters stay left-to-right.
+        assert_eq!(display("ب 12 ٣٤", None), "٣٤ 12 ب");
+        // Brackets are mirrored and follow their content.
+        assert_eq!(display("אב (גד) הו", None), "וה (דג) בא");
+        assert_eq!(display("abc (אב) d", None), "abc (בא) d");
+        // Trailing whitespace belongs to the paragraph direction.
+        assert_eq!(display("אב  ", None), "  בא");
+        assert_eq!(display("ab אב  ", None), "ab בא  ");
+        // Overrides force the direction.
+        assert_eq!(display("\u{202E}abc\u{202C} d", Some(0)), "\u{202E}\u{202C}cba d");
+        // Isolates don't affect their surroundings.
+        assert_eq!(display("\u{2067}abc\u{2069} d", Some(0)), "\u{2067}abc\u{2069} d");
+        assert_eq!(display("אב \u{2066}c, d\u{2069}!", None), "!\u{2069}c, d\u{2066} בא");
+    }
+}
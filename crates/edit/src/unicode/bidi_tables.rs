@@ -0,0 +1,147 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+// BEGIN: Generated by grapheme-table-gen on 2026-10-19T09:12:44Z, from Unicode 14.0.0, with --lang=rust --bidi, 7472 bytes
+#[rustfmt::skip]
+const BIDI_CLASS_RUNS: [u32; 1184] = [
+    0x00000009, 0x0000012b, 0x0000014a, 0x0000016b, 0x0000018c, 0x000001aa, 0x000001c9, 0x0000038a, 0x000003eb, 0x0000040c, 0x0000042d, 0x00000465, 0x000004cd, 0x00000564, 0x00000587, 0x000005a4,
+    0x000005c7, 0x00000603, 0x00000747, 0x0000076d, 0x00000820, 0x00000b6d, 0x00000c20, 0x00000f6d, 0x00000fe9, 0x000010aa, 0x000010c9, 0x00001407, 0x0000142d, 0x00001445, 0x000014cd, 0x00001540,
+    0x0000156d, 0x000015a9, 0x000015cd, 0x00001605, 0x00001643, 0x0000168d, 0x000016a0, 0x000016cd, 0x00001723, 0x00001740, 0x0000176d, 0x00001800, 0x00001aed, 0x00001b00, 0x00001eed, 0x00001f00,
+    0x0000572d, 0x00005760, 0x0000584d, 0x00005a00, 0x00005a4d, 0x00005c00, 0x00005cad, 0x00005dc0, 0x00005ded, 0x00006008, 0x00006e00, 0x00006e8d, 0x00006ec0, 0x00006fcd, 0x00006fe0, 0x0000708d,
+    0x000070c0, 0x000070ed, 0x00007100, 0x00007ecd, 0x00007ee0, 0x00009068, 0x00009140, 0x0000b14d, 0x0000b160, 0x0000b1ad, 0x0000b1e5, 0x0000b201, 0x0000b228, 0x0000b7c1, 0x0000b7e8, 0x0000b801,
+    0x0000b828, 0x0000b861, 0x0000b888, 0x0000b8c1, 0x0000b8e8, 0x0000b901, 0x0000c006, 0x0000c0cd, 0x0000c102, 0x0000c125, 0x0000c162, 0x0000c187, 0x0000c1a2, 0x0000c1cd, 0x0000c208, 0x0000c362,
+    0x0000c968, 0x0000cc06, 0x0000cd45, 0x0000cd66, 0x0000cda2, 0x0000ce08, 0x0000ce22, 0x0000dac8, 0x0000dba6, 0x0000dbcd, 0x0000dbe8, 0x0000dca2, 0x0000dce8, 0x0000dd2d, 0x0000dd48, 0x0000ddc2,
+    0x0000de03, 0x0000df42, 0x0000e228, 0x0000e242, 0x0000e608, 0x0000e962, 0x0000f4c8, 0x0000f622, 0x0000f801, 0x0000fd68, 0x0000fe81, 0x0000fecd, 0x0000ff41, 0x0000ffa8, 0x0000ffc1, 0x000102c8,
+    0x00010341, 0x00010368, 0x00010481, 0x000104a8, 0x00010501, 0x00010528, 0x000105c1, 0x00010b28, 0x00010b81, 0x00010c02, 0x00011206, 0x00011242, 0x00011308, 0x00011402, 0x00011948, 0x00011c46,
+    0x00011c68, 0x00012060, 0x00012748, 0x00012760, 0x00012788, 0x000127a0, 0x00012828, 0x00012920, 0x000129a8, 0x000129c0, 0x00012a28, 0x00012b00, 0x00012c48, 0x00012c80, 0x00013028, 0x00013040,
+    0x00013788, 0x000137a0, 0x00013828, 0x000138a0, 0x000139a8, 0x000139c0, 0x00013c48, 0x00013c80, 0x00013e45, 0x00013e80, 0x00013f65, 0x00013f80, 0x00013fc8, 0x00013fe0, 0x00014028, 0x00014060,
+    0x00014788, 0x000147a0, 0x00014828, 0x00014860, 0x000148e8, 0x00014920, 0x00014968, 0x000149c0, 0x00014a28, 0x00014a40, 0x00014e08, 0x00014e40, 0x00014ea8, 0x00014ec0, 0x00015028, 0x00015060,
+    0x00015788, 0x000157a0, 0x00015828, 0x000158c0, 0x000158e8, 0x00015920, 0x000159a8, 0x000159c0, 0x00015c48, 0x00015c80, 0x00015e25, 0x00015e40, 0x00015f48, 0x00016000, 0x00016028, 0x00016040,
+    0x00016788, 0x000167a0, 0x000167e8, 0x00016800, 0x00016828, 0x000168a0, 0x000169a8, 0x000169c0, 0x00016aa8, 0x00016ae0, 0x00016c48, 0x00016c80, 0x00017048, 0x00017060, 0x00017808, 0x00017820,
+    0x000179a8, 0x000179c0, 0x00017e6d, 0x00017f25, 0x00017f4d, 0x00017f60, 0x00018008, 0x00018020, 0x00018088, 0x000180a0, 0x00018788, 0x000187a0, 0x000187c8, 0x00018820, 0x000188c8, 0x00018920,
+    0x00018948, 0x000189c0, 0x00018aa8, 0x00018ae0, 0x00018c48, 0x00018c80, 0x00018f0d, 0x00018fe0, 0x00019028, 0x00019040, 0x00019788, 0x000197a0, 0x00019988, 0x000199c0, 0x00019c48, 0x00019c80,
+    0x0001a008, 0x0001a040, 0x0001a768, 0x0001a7a0, 0x0001a828, 0x0001a8a0, 0x0001a9a8, 0x0001a9c0, 0x0001ac48, 0x0001ac80, 0x0001b028, 0x0001b040, 0x0001b948, 0x0001b960, 0x0001ba48, 0x0001baa0,
+    0x0001bac8, 0x0001bae0, 0x0001c628, 0x0001c640, 0x0001c688, 0x0001c760, 0x0001c7e5, 0x0001c800, 0x0001c8e8, 0x0001c9e0, 0x0001d628, 0x0001d640, 0x0001d688, 0x0001d7a0, 0x0001d908, 0x0001d9c0,
+    0x0001e308, 0x0001e340, 0x0001e6a8, 0x0001e6c0, 0x0001e6e8, 0x0001e700, 0x0001e728, 0x0001e74d, 0x0001e7c0, 0x0001ee28, 0x0001efe0, 0x0001f008, 0x0001f0a0, 0x0001f0c8, 0x0001f100, 0x0001f1a8,
+    0x0001f300, 0x0001f328, 0x0001f7a0, 0x0001f8c8, 0x0001f8e0, 0x000205a8, 0x00020620, 0x00020648, 0x00020700, 0x00020728, 0x00020760, 0x000207a8, 0x000207e0, 0x00020b08, 0x00020b40, 0x00020bc8,
+    0x00020c20, 0x00020e28, 0x00020ea0, 0x00021048, 0x00021060, 0x000210a8, 0x000210e0, 0x000211a8, 0x000211c0, 0x000213a8, 0x000213c0, 0x00026ba8, 0x00026c00, 0x0002720d, 0x00027340, 0x0002800d,
+    0x00028020, 0x0002d00c, 0x0002d020, 0x0002d36d, 0x0002d3a0, 0x0002e248, 0x0002e2a0, 0x0002e648, 0x0002e680, 0x0002ea48, 0x0002ea80, 0x0002ee48, 0x0002ee80, 0x0002f688, 0x0002f6c0, 0x0002f6e8,
+    0x0002f7c0, 0x0002f8c8, 0x0002f8e0, 0x0002f928, 0x0002fa80, 0x0002fb65, 0x0002fb80, 0x0002fba8, 0x0002fbc0, 0x0002fe0d, 0x0002ff40, 0x0003000d, 0x00030168, 0x000301c9, 0x000301e8, 0x00030200,
+    0x000310a8, 0x000310e0, 0x00031528, 0x00031540, 0x00032408, 0x00032460, 0x000324e8, 0x00032520, 0x00032648, 0x00032660, 0x00032728, 0x00032780, 0x0003280d, 0x00032820, 0x0003288d, 0x000328c0,
+    0x00033bcd, 0x00034000, 0x000342e8, 0x00034320, 0x00034368, 0x00034380, 0x00034ac8, 0x00034ae0, 0x00034b08, 0x00034be0, 0x00034c08, 0x00034c20, 0x00034c48, 0x00034c60, 0x00034ca8, 0x00034da0,
+    0x00034e68, 0x00034fa0, 0x00034fe8, 0x00035000, 0x00035608, 0x000359e0, 0x00036008, 0x00036080, 0x00036688, 0x000366a0, 0x000366c8, 0x00036760, 0x00036788, 0x000367a0, 0x00036848, 0x00036860,
+    0x00036d68, 0x00036e80, 0x00037008, 0x00037040, 0x00037448, 0x000374c0, 0x00037508, 0x00037540, 0x00037568, 0x000375c0, 0x00037cc8, 0x00037ce0, 0x00037d08, 0x00037d40, 0x00037da8, 0x00037dc0,
+    0x00037de8, 0x00037e40, 0x00038588, 0x00038680, 0x000386c8, 0x00038700, 0x00039a08, 0x00039a60, 0x00039a88, 0x00039c20, 0x00039c48, 0x00039d20, 0x00039da8, 0x00039dc0, 0x00039e88, 0x00039ea0,
+    0x00039f08, 0x00039f40, 0x0003b808, 0x0003c000, 0x0003f7ad, 0x0003f7c0, 0x0003f7ed, 0x0003f840, 0x0003f9ad, 0x0003fa00, 0x0003fbad, 0x0003fc00, 0x0003fdad, 0x0003fe00, 0x0003ffad, 0x0003ffe0,
+    0x0004000c, 0x00040169, 0x000401c0, 0x000401e1, 0x0004020d, 0x0004050c, 0x0004052a, 0x0004054e, 0x00040570, 0x00040592, 0x000405af, 0x000405d1, 0x000405e7, 0x00040605, 0x000406ad, 0x00040887,
+    0x000408ad, 0x00040bec, 0x00040c09, 0x00040cd3, 0x00040cf4, 0x00040d15, 0x00040d36, 0x00040d49, 0x00040e03, 0x00040e20, 0x00040e83, 0x00040f44, 0x00040f8d, 0x00040fe0, 0x00041003, 0x00041144,
+    0x0004118d, 0x000411e0, 0x00041405, 0x00041a08, 0x00041e20, 0x0004200d, 0x00042040, 0x0004206d, 0x000420e0, 0x0004210d, 0x00042140, 0x0004228d, 0x000422a0, 0x000422cd, 0x00042320, 0x000423cd,
+    0x00042480, 0x000424ad, 0x000424c0, 0x000424ed, 0x00042500, 0x0004252d, 0x00042540, 0x000425c5, 0x000425e0, 0x0004274d, 0x00042780, 0x0004280d, 0x000428a0, 0x0004294d, 0x000429c0, 0x00042a0d,
+    0x00042c00, 0x0004312d, 0x00043180, 0x0004320d, 0x00044244, 0x00044265, 0x0004428d, 0x000466c0, 0x00046f6d, 0x000472a0, 0x000472cd, 0x000484e0, 0x0004880d, 0x00048960, 0x00048c0d, 0x00049103,
+    0x00049380, 0x00049d4d, 0x0004d580, 0x0004d5ad, 0x00050000, 0x0005200d, 0x00056e80, 0x00056ecd, 0x000572c0, 0x000572ed, 0x00058000, 0x00059cad, 0x00059d60, 0x00059de8, 0x00059e40, 0x00059f2d,
+    0x0005a000, 0x0005afe8, 0x0005b000, 0x0005bc08, 0x0005c00d, 0x0005cbc0, 0x0005d00d, 0x0005d340, 0x0005d36d, 0x0005de80, 0x0005e00d, 0x0005fac0, 0x0005fe0d, 0x0005ff80, 0x0006000c, 0x0006002d,
+    0x000600a0, 0x0006010d, 0x00060420, 0x00060548, 0x000605c0, 0x0006060d, 0x00060620, 0x000606cd, 0x00060700, 0x000607ad, 0x00060800, 0x00061328, 0x0006136d, 0x000613a0, 0x0006140d, 0x00061420,
+    0x00061f6d, 0x00061f80, 0x0006380d, 0x00063c80, 0x000643ad, 0x000643e0, 0x00064a0d, 0x00064c00, 0x00064f8d, 0x00064fe0, 0x0006562d, 0x00065800, 0x0006598d, 0x00065a00, 0x00066eed, 0x00066f60,
+    0x00067bcd, 0x00067c00, 0x00067fed, 0x00068000, 0x0009b80d, 0x0009c000, 0x0014920d, 0x001498e0, 0x0014c1ad, 0x0014c200, 0x0014cde8, 0x0014ce6d, 0x0014ce88, 0x0014cfcd, 0x0014d000, 0x0014d3c8,
+    0x0014d400, 0x0014de08, 0x0014de40, 0x0014e00d, 0x0014e440, 0x0014f10d, 0x0014f120, 0x00150048, 0x00150060, 0x001500c8, 0x001500e0, 0x00150168, 0x00150180, 0x001504a8, 0x001504e0, 0x0015050d,
+    0x00150588, 0x001505a0, 0x00150705, 0x00150740, 0x00150e8d, 0x00150f00, 0x00151888, 0x001518c0, 0x00151c08, 0x00151e40, 0x00151fe8, 0x00152000, 0x001524c8, 0x001525c0, 0x001528e8, 0x00152a40,
+    0x00153008, 0x00153060, 0x00153668, 0x00153680, 0x001536c8, 0x00153740, 0x00153788, 0x001537c0, 0x00153ca8, 0x00153cc0, 0x00154528, 0x001545e0, 0x00154628, 0x00154660, 0x001546a8, 0x001546e0,
+    0x00154868, 0x00154880, 0x00154988, 0x001549a0, 0x00154f88, 0x00154fa0, 0x00155608, 0x00155620, 0x00155648, 0x001556a0, 0x001556e8, 0x00155720, 0x001557c8, 0x00155800, 0x00155828, 0x00155840,
+    0x00155d88, 0x00155dc0, 0x00155ec8, 0x00155ee0, 0x00156d4d, 0x00156d80, 0x00157ca8, 0x00157cc0, 0x00157d08, 0x00157d20, 0x00157da8, 0x00157dc0, 0x001f63a1, 0x001f63c8, 0x001f63e1, 0x001f6524,
+    0x001f6541, 0x001f6a02, 0x001fa7cd, 0x001faa02, 0x001fb9ed, 0x001fba09, 0x001fbe02, 0x001fbfad, 0x001fc008, 0x001fc20d, 0x001fc340, 0x001fc408, 0x001fc60d, 0x001fca07, 0x001fca2d, 0x001fca47,
+    0x001fca60, 0x001fca8d, 0x001fcaa7, 0x001fcacd, 0x001fcbe5, 0x001fcc0d, 0x001fcc44, 0x001fcc8d, 0x001fcce0, 0x001fcd0d, 0x001fcd25, 0x001fcd6d, 0x001fcd80, 0x001fce02, 0x001fdfe9, 0x001fe000,
+    0x001fe02d, 0x001fe065, 0x001fe0cd, 0x001fe164, 0x001fe187, 0x001fe1a4, 0x001fe1c7, 0x001fe203, 0x001fe347, 0x001fe36d, 0x001fe420, 0x001fe76d, 0x001fe820, 0x001feb6d, 0x001fecc0, 0x001ffc05,
+    0x001ffc4d, 0x001ffca5, 0x001ffce0, 0x001ffd0d, 0x001ffde0, 0x001ffe09, 0x001fff2d, 0x001fffc9, 0x00200000, 0x0020202d, 0x00202040, 0x0020280d, 0x002031a0, 0x0020320d, 0x002033a0, 0x0020340d,
+    0x00203420, 0x00203fa8, 0x00203fc0, 0x00205c08, 0x00205c23, 0x00205f80, 0x00206ec8, 0x00206f60, 0x00210001, 0x002123ed, 0x00212401, 0x00214028, 0x00214081, 0x002140a8, 0x002140e1, 0x00214188,
+    0x00214201, 0x00214708, 0x00214761, 0x002147e8, 0x00214801, 0x00215ca8, 0x00215ce1, 0x0021672d, 0x00216801, 0x0021a002, 0x0021a488, 0x0021a502, 0x0021a606, 0x0021a742, 0x0021a801, 0x0021cc06,
+    0x0021cfe1, 0x0021d568, 0x0021d5a1, 0x0021d802, 0x0021e001, 0x0021e602, 0x0021e8c8, 0x0021ea22, 0x0021ee01, 0x0021f048, 0x0021f0c1, 0x00220000, 0x00220028, 0x00220040, 0x00220708, 0x002208e0,
+    0x00220a4d, 0x00220cc0, 0x00220e08, 0x00220e20, 0x00220e68, 0x00220ea0, 0x00220fe8, 0x00221040, 0x00221668, 0x002216e0, 0x00221728, 0x00221760, 0x00221848, 0x00221860, 0x00222008, 0x00222060,
+    0x002224e8, 0x00222580, 0x002225a8, 0x002226a0, 0x00222e68, 0x00222e80, 0x00223008, 0x00223040, 0x002236c8, 0x002237e0, 0x00223928, 0x002239a0, 0x002239e8, 0x00223a00, 0x002245e8, 0x00224640,
+    0x00224688, 0x002246a0, 0x002246c8, 0x00224700, 0x002247c8, 0x002247e0, 0x00225be8, 0x00225c00, 0x00225c68, 0x00225d60, 0x00226008, 0x00226040, 0x00226768, 0x002267a0, 0x00226808, 0x00226820,
+    0x00226cc8, 0x00226da0, 0x00226e08, 0x00226ea0, 0x00228708, 0x00228800, 0x00228848, 0x002288a0, 0x002288c8, 0x002288e0, 0x00228bc8, 0x00228be0, 0x00229668, 0x00229720, 0x00229748, 0x00229760,
+    0x002297e8, 0x00229820, 0x00229848, 0x00229880, 0x0022b648, 0x0022b6c0, 0x0022b788, 0x0022b7c0, 0x0022b7e8, 0x0022b820, 0x0022bb88, 0x0022bbc0, 0x0022c668, 0x0022c760, 0x0022c7a8, 0x0022c7c0,
+    0x0022c7e8, 0x0022c820, 0x0022cc0d, 0x0022cda0, 0x0022d568, 0x0022d580, 0x0022d5a8, 0x0022d5c0, 0x0022d608, 0x0022d6c0, 0x0022d6e8, 0x0022d700, 0x0022e3a8, 0x0022e400, 0x0022e448, 0x0022e4c0,
+    0x0022e4e8, 0x0022e580, 0x002305e8, 0x00230700, 0x00230728, 0x00230760, 0x00232768, 0x002327a0, 0x002327c8, 0x002327e0, 0x00232868, 0x00232880, 0x00233a88, 0x00233b00, 0x00233b48, 0x00233b80,
+    0x00233c08, 0x00233c20, 0x00234028, 0x002340e0, 0x00234128, 0x00234160, 0x00234668, 0x00234720, 0x00234768, 0x002347e0, 0x002348e8, 0x00234900, 0x00234a28, 0x00234ae0, 0x00234b28, 0x00234b80,
+    0x00235148, 0x002352e0, 0x00235308, 0x00235340, 0x00238608, 0x002386e0, 0x00238708, 0x002387c0, 0x00239248, 0x00239500, 0x00239548, 0x00239620, 0x00239648, 0x00239680, 0x002396a8, 0x002396e0,
+    0x0023a628, 0x0023a6e0, 0x0023a748, 0x0023a760, 0x0023a788, 0x0023a7c0, 0x0023a7e8, 0x0023a8c0, 0x0023a8e8, 0x0023a900, 0x0023b208, 0x0023b240, 0x0023b2a8, 0x0023b2c0, 0x0023b2e8, 0x0023b300,
+    0x0023de68, 0x0023dea0, 0x0023faad, 0x0023fba5, 0x0023fc2d, 0x0023fe40, 0x002d5e08, 0x002d5ea0, 0x002d6608, 0x002d66e0, 0x002de9e8, 0x002dea00, 0x002df1e8, 0x002df260, 0x002dfc4d, 0x002dfc60,
+    0x002dfc88, 0x002dfca0, 0x003793a8, 0x003793e0, 0x00379409, 0x00379480, 0x0039e008, 0x0039e5c0, 0x0039e608, 0x0039e8e0, 0x003a2ce8, 0x003a2d40, 0x003a2e69, 0x003a2f68, 0x003a3060, 0x003a30a8,
+    0x003a3180, 0x003a3548, 0x003a35c0, 0x003a3d2d, 0x003a3d60, 0x003a400d, 0x003a4848, 0x003a48ad, 0x003a48c0, 0x003a600d, 0x003a6ae0, 0x003adb6d, 0x003adb80, 0x003ae2ad, 0x003ae2c0, 0x003ae9ed,
+    0x003aea00, 0x003af12d, 0x003af140, 0x003af86d, 0x003af880, 0x003af9c3, 0x003b0000, 0x003b4008, 0x003b46e0, 0x003b4768, 0x003b4da0, 0x003b4ea8, 0x003b4ec0, 0x003b5088, 0x003b50a0, 0x003b5368,
+    0x003b5400, 0x003b5428, 0x003b5600, 0x003c0008, 0x003c00e0, 0x003c0108, 0x003c0320, 0x003c0368, 0x003c0440, 0x003c0468, 0x003c04a0, 0x003c04c8, 0x003c0560, 0x003c2608, 0x003c26e0, 0x003c55c8,
+    0x003c55e0, 0x003c5d88, 0x003c5e00, 0x003c5fe5, 0x003c6000, 0x003d0001, 0x003d1a08, 0x003d1ae1, 0x003d2888, 0x003d2961, 0x003d8e02, 0x003d9801, 0x003da002, 0x003daa01, 0x003dc002, 0x003dde0d,
+    0x003dde42, 0x003de001, 0x003e000d, 0x003e0580, 0x003e060d, 0x003e1280, 0x003e140d, 0x003e15e0, 0x003e162d, 0x003e1800, 0x003e182d, 0x003e1a00, 0x003e1a2d, 0x003e1ec0, 0x003e2003, 0x003e216d,
+    0x003e2200, 0x003e25ed, 0x003e2600, 0x003e2d4d, 0x003e2e00, 0x003e35ad, 0x003e35c0, 0x003e4c0d, 0x003e4cc0, 0x003e600d, 0x003edb00, 0x003edbad, 0x003edda0, 0x003ede0d, 0x003edfa0, 0x003ee00d,
+    0x003eee80, 0x003ef00d, 0x003efb20, 0x003efc0d, 0x003efd80, 0x003efe0d, 0x003efe20, 0x003f000d, 0x003f0180, 0x003f020d, 0x003f0900, 0x003f0a0d, 0x003f0b40, 0x003f0c0d, 0x003f1100, 0x003f120d,
+    0x003f15c0, 0x003f160d, 0x003f1640, 0x003f200d, 0x003f4a80, 0x003f4c0d, 0x003f4dc0, 0x003f4e0d, 0x003f4ea0, 0x003f4f0d, 0x003f4fa0, 0x003f500d, 0x003f50e0, 0x003f520d, 0x003f55a0, 0x003f560d,
+    0x003f5760, 0x003f580d, 0x003f58c0, 0x003f5a0d, 0x003f5b40, 0x003f5c0d, 0x003f5d00, 0x003f5e0d, 0x003f5ee0, 0x003f600d, 0x003f7260, 0x003f728d, 0x003f7960, 0x003f7e03, 0x003f7f40, 0x003fffc9,
+    0x00400000, 0x005fffc9, 0x00600000, 0x007fffc9, 0x00800000, 0x009fffc9, 0x00a00000, 0x00bfffc9, 0x00c00000, 0x00dfffc9, 0x00e00000, 0x00ffffc9, 0x01000000, 0x011fffc9, 0x01200000, 0x013fffc9,
+    0x01400000, 0x015fffc9, 0x01600000, 0x017fffc9, 0x01800000, 0x019fffc9, 0x01a00000, 0x01bfffc9, 0x01c02008, 0x01c03e09, 0x01c20000, 0x01dfffc9, 0x01e00000, 0x01ffffc9, 0x02000000, 0x021fffc9,
+];
+#[rustfmt::skip]
+const BIDI_MIRRORS: [(u32, u32); 342] = [
+    (0x000028, 0x200029), (0x000029, 0x400028), (0x00003c, 0x00003e), (0x00003e, 0x00003c), (0x00005b, 0x20005d), (0x00005d, 0x40005b), (0x00007b, 0x20007d), (0x00007d, 0x40007b),
+    (0x0000ab, 0x0000bb), (0x0000bb, 0x0000ab), (0x000f3a, 0x200f3b), (0x000f3b, 0x400f3a), (0x000f3c, 0x200f3d), (0x000f3d, 0x400f3c), (0x00169b, 0x20169c), (0x00169c, 0x40169b),
+    (0x002039, 0x00203a), (0x00203a, 0x002039), (0x002045, 0x202046), (0x002046, 0x402045), (0x00207d, 0x20207e), (0x00207e, 0x40207d), (0x00208d, 0x20208e), (0x00208e, 0x40208d),
+    (0x002208, 0x00220b), (0x00220a, 0x00220d), (0x00220b, 0x002208), (0x00220d, 0x00220a), (0x002264, 0x002265), (0x002265, 0x002264), (0x002266, 0x002267), (0x002267, 0x002266),
+    (0x002268, 0x002269), (0x002269, 0x002268), (0x00226a, 0x00226b), (0x00226b, 0x00226a), (0x00226e, 0x00226f), (0x00226f, 0x00226e), (0x002270, 0x002271), (0x002271, 0x002270),
+    (0x002272, 0x002273), (0x002273, 0x002272), (0x002274, 0x002275), (0x002275, 0x002274), (0x002276, 0x002277), (0x002277, 0x002276), (0x002278, 0x002279), (0x002279, 0x002278),
+    (0x00227a, 0x00227b), (0x00227b, 0x00227a), (0x00227c, 0x00227d), (0x00227d, 0x00227c), (0x00227e, 0x00227f), (0x00227f, 0x00227e), (0x002280, 0x002281), (0x002281, 0x002280),
+    (0x002282, 0x002283), (0x002283, 0x002282), (0x002284, 0x002285), (0x002285, 0x002284), (0x002286, 0x002287), (0x002287, 0x002286), (0x002288, 0x002289), (0x002289, 0x002288),
+    (0x00228a, 0x00228b), (0x00228b, 0x00228a), (0x0022a2, 0x0022a3), (0x0022a3, 0x0022a2), (0x0022ab, 0x002ae5), (0x0022b0, 0x0022b1), (0x0022b1, 0x0022b0), (0x0022b2, 0x0022b3),
+    (0x0022b3, 0x0022b2), (0x0022b4, 0x0022b5), (0x0022b5, 0x0022b4), (0x0022c9, 0x0022ca), (0x0022ca, 0x0022c9), (0x0022cb, 0x0022cc), (0x0022cc, 0x0022cb), (0x0022d0, 0x0022d1),
+    (0x0022d1, 0x0022d0), (0x0022d6, 0x0022d7), (0x0022d7, 0x0022d6), (0x0022d8, 0x0022d9), (0x0022d9, 0x0022d8), (0x0022da, 0x0022db), (0x0022db, 0x0022da), (0x0022dc, 0x0022dd),
+    (0x0022dd, 0x0022dc), (0x0022de, 0x0022df), (0x0022df, 0x0022de), (0x0022e0, 0x0022e1), (0x0022e1, 0x0022e0), (0x0022e6, 0x0022e7), (0x0022e7, 0x0022e6), (0x0022e8, 0x0022e9),
+    (0x0022e9, 0x0022e8), (0x002308, 0x202309), (0x002309, 0x402308), (0x00230a, 0x20230b), (0x00230b, 0x40230a), (0x002329, 0x20232a), (0x00232a, 0x402329), (0x002768, 0x202769),
+    (0x002769, 0x402768), (0x00276a, 0x20276b), (0x00276b, 0x40276a), (0x00276c, 0x20276d), (0x00276d, 0x40276c), (0x00276e, 0x20276f), (0x00276f, 0x40276e), (0x002770, 0x202771),
+    (0x002771, 0x402770), (0x002772, 0x202773), (0x002773, 0x402772), (0x002774, 0x202775), (0x002775, 0x402774), (0x0027c3, 0x0027c4), (0x0027c4, 0x0027c3), (0x0027c5, 0x2027c6),
+    (0x0027c6, 0x4027c5), (0x0027d5, 0x0027d6), (0x0027d6, 0x0027d5), (0x0027dd, 0x0027de), (0x0027de, 0x0027dd), (0x0027e2, 0x0027e3), (0x0027e3, 0x0027e2), (0x0027e4, 0x0027e5),
+    (0x0027e5, 0x0027e4), (0x0027e6, 0x2027e7), (0x0027e7, 0x4027e6), (0x0027e8, 0x2027e9), (0x0027e9, 0x4027e8), (0x0027ea, 0x2027eb), (0x0027eb, 0x4027ea), (0x0027ec, 0x2027ed),
+    (0x0027ed, 0x4027ec), (0x0027ee, 0x2027ef), (0x0027ef, 0x4027ee), (0x002983, 0x202984), (0x002984, 0x402983), (0x002985, 0x202986), (0x002986, 0x402985), (0x002987, 0x202988),
+    (0x002988, 0x402987), (0x002989, 0x20298a), (0x00298a, 0x402989), (0x00298b, 0x20298c), (0x00298c, 0x40298b), (0x00298d, 0x202990), (0x00298e, 0x40298f), (0x00298f, 0x20298e),
+    (0x002990, 0x40298d), (0x002991, 0x202992), (0x002992, 0x402991), (0x002993, 0x202994), (0x002994, 0x402993), (0x002995, 0x202996), (0x002996, 0x402995), (0x002997, 0x202998),
+    (0x002998, 0x402997), (0x0029a8, 0x0029a9), (0x0029a9, 0x0029a8), (0x0029aa, 0x0029ab), (0x0029ab, 0x0029aa), (0x0029ac, 0x0029ad), (0x0029ad, 0x0029ac), (0x0029ae, 0x0029af),
+    (0x0029af, 0x0029ae), (0x0029c0, 0x0029c1), (0x0029c1, 0x0029c0), (0x0029d1, 0x0029d2), (0x0029d2, 0x0029d1), (0x0029d4, 0x0029d5), (0x0029d5, 0x0029d4), (0x0029d8, 0x2029d9),
+    (0x0029d9, 0x4029d8), (0x0029da, 0x2029db), (0x0029db, 0x4029da), (0x0029e8, 0x0029e9), (0x0029e9, 0x0029e8), (0x0029fc, 0x2029fd), (0x0029fd, 0x4029fc), (0x002a2d, 0x002a2e),
+    (0x002a2e, 0x002a2d), (0x002a34, 0x002a35), (0x002a35, 0x002a34), (0x002a79, 0x002a7a), (0x002a7a, 0x002a79), (0x002a7b, 0x002a7c), (0x002a7c, 0x002a7b), (0x002a7d, 0x002a7e),
+    (0x002a7e, 0x002a7d), (0x002a7f, 0x002a80), (0x002a80, 0x002a7f), (0x002a81, 0x002a82), (0x002a82, 0x002a81), (0x002a85, 0x002a86), (0x002a86, 0x002a85), (0x002a87, 0x002a88),
+    (0x002a88, 0x002a87), (0x002a89, 0x002a8a), (0x002a8a, 0x002a89), (0x002a8b, 0x002a8c), (0x002a8c, 0x002a8b), (0x002a8d, 0x002a8e), (0x002a8e, 0x002a8d), (0x002a8f, 0x002a90),
+    (0x002a90, 0x002a8f), (0x002a91, 0x002a92), (0x002a92, 0x002a91), (0x002a93, 0x002a94), (0x002a94, 0x002a93), (0x002a95, 0x002a96), (0x002a96, 0x002a95), (0x002a97, 0x002a98),
+    (0x002a98, 0x002a97), (0x002a99, 0x002a9a), (0x002a9a, 0x002a99), (0x002a9b, 0x002a9c), (0x002a9c, 0x002a9b), (0x002a9d, 0x002a9e), (0x002a9e, 0x002a9d), (0x002a9f, 0x002aa0),
+    (0x002aa0, 0x002a9f), (0x002aa1, 0x002aa2), (0x002aa2, 0x002aa1), (0x002aa6, 0x002aa7), (0x002aa7, 0x002aa6), (0x002aa8, 0x002aa9), (0x002aa9, 0x002aa8), (0x002aaf, 0x002ab0),
+    (0x002ab0, 0x002aaf), (0x002ab1, 0x002ab2), (0x002ab2, 0x002ab1), (0x002ab3, 0x002ab4), (0x002ab4, 0x002ab3), (0x002ab5, 0x002ab6), (0x002ab6, 0x002ab5), (0x002ab7, 0x002ab8),
+    (0x002ab8, 0x002ab7), (0x002ab9, 0x002aba), (0x002aba, 0x002ab9), (0x002abb, 0x002abc), (0x002abc, 0x002abb), (0x002abd, 0x002abe), (0x002abe, 0x002abd), (0x002abf, 0x002ac0),
+    (0x002ac0, 0x002abf), (0x002ac1, 0x002ac2), (0x002ac2, 0x002ac1), (0x002ac3, 0x002ac4), (0x002ac4, 0x002ac3), (0x002ac5, 0x002ac6), (0x002ac6, 0x002ac5), (0x002ac7, 0x002ac8),
+    (0x002ac8, 0x002ac7), (0x002ac9, 0x002aca), (0x002aca, 0x002ac9), (0x002acb, 0x002acc), (0x002acc, 0x002acb), (0x002acd, 0x002ace), (0x002ace, 0x002acd), (0x002acf, 0x002ad0),
+    (0x002ad0, 0x002acf), (0x002ad1, 0x002ad2), (0x002ad2, 0x002ad1), (0x002ad3, 0x002ad4), (0x002ad4, 0x002ad3), (0x002ad5, 0x002ad6), (0x002ad6, 0x002ad5), (0x002ae5, 0x0022ab),
+    (0x002af7, 0x002af8), (0x002af8, 0x002af7), (0x002af9, 0x002afa), (0x002afa, 0x002af9), (0x002e02, 0x002e03), (0x002e03, 0x002e02), (0x002e04, 0x002e05), (0x002e05, 0x002e04),
+    (0x002e09, 0x002e0a), (0x002e0a, 0x002e09), (0x002e0c, 0x002e0d), (0x002e0d, 0x002e0c), (0x002e1c, 0x002e1d), (0x002e1d, 0x002e1c), (0x002e20, 0x002e21), (0x002e21, 0x002e20),
+    (0x002e22, 0x202e23), (0x002e23, 0x402e22), (0x002e24, 0x202e25), (0x002e25, 0x402e24), (0x002e26, 0x202e27), (0x002e27, 0x402e26), (0x002e28, 0x202e29), (0x002e29, 0x402e28),
+    (0x002e55, 0x202e56), (0x002e56, 0x402e55), (0x002e57, 0x202e58), (0x002e58, 0x402e57), (0x002e59, 0x202e5a), (0x002e5a, 0x402e59), (0x002e5b, 0x202e5c), (0x002e5c, 0x402e5b),
+    (0x003008, 0x203009), (0x003009, 0x403008), (0x00300a, 0x20300b), (0x00300b, 0x40300a), (0x00300c, 0x20300d), (0x00300d, 0x40300c), (0x00300e, 0x20300f), (0x00300f, 0x40300e),
+    (0x003010, 0x203011), (0x003011, 0x403010), (0x003014, 0x203015), (0x003015, 0x403014), (0x003016, 0x203017), (0x003017, 0x403016), (0x003018, 0x203019), (0x003019, 0x403018),
+    (0x00301a, 0x20301b), (0x00301b, 0x40301a), (0x00fe59, 0x20fe5a), (0x00fe5a, 0x40fe59), (0x00fe5b, 0x20fe5c), (0x00fe5c, 0x40fe5b), (0x00fe5d, 0x20fe5e), (0x00fe5e, 0x40fe5d),
+    (0x00fe64, 0x00fe65), (0x00fe65, 0x00fe64), (0x00ff08, 0x20ff09), (0x00ff09, 0x40ff08), (0x00ff1c, 0x00ff1e), (0x00ff1e, 0x00ff1c), (0x00ff3b, 0x20ff3d), (0x00ff3d, 0x40ff3b),
+    (0x00ff5b, 0x20ff5d), (0x00ff5d, 0x40ff5b), (0x00ff5f, 0x20ff60), (0x00ff60, 0x40ff5f), (0x00ff62, 0x20ff63), (0x00ff63, 0x40ff62),
+];
+/// Returns the Bidi_Class of `cp` in the order of `BidiClass`.
+#[inline]
+pub fn ucd_bidi_class(cp: char) -> u8 {
+    let cp = cp as u32;
+    let i = BIDI_CLASS_RUNS.partition_point(|&r| (r >> 5) <= cp);
+    // The first run starts at U+0000, so `i` is at least 1.
+    (unsafe { *BIDI_CLASS_RUNS.get_unchecked(i - 1) } & 31) as u8
+}
+/// Returns the Bidi_Mirroring_Glyph of `cp` in the lower 21 bits
+/// and its Bidi_Paired_Bracket_Type in the 2 bits above (1 = open, 2 = close).
+/// Returns 0 if `cp` doesn't have a mirrored glyph.
+#[inline]
+pub fn ucd_bidi_mirror(cp: char) -> u32 {
+    let cp = cp as u32;
+    match BIDI_MIRRORS.binary_search_by_key(&cp, |&(c, _)| c) {
+        Ok(i) => BIDI_MIRRORS[i].1,
+        Err(_) => 0,
+    }
+}
+// END: Generated by grapheme-table-gen
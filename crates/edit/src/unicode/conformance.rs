@@ -0,0 +1,384 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Runs the official `GraphemeBreakTest.txt` and `LineBreakTest.txt` conformance suites
+//! against [`MeasurementConfig`].
+//!
+//! The full suites are read from `src/unicode/tables/<version>/`, next to the tables.
+//! `unicode-gen --tests=<dir>` downloads them there, and CI does the same before testing.
+//! Set `EDIT_UCD_TEST_DIR` to read them from elsewhere instead. Either way they must be of
+//! the same version as [`UNICODE_VERSION`]. Without them, only the excerpts at the end of
+//! this file are run.
+//!
+//! Our rules are tailored for use with lookup tables (see `rules.rs` in unicode-gen).
+//! Boundaries that the suites decide via a rule we don't implement as specified are skipped,
+//! as are line breaks next to classes that unicode-gen doesn't distinguish.
+//! See `GRAPHEME_TAILORED_RULES`, `LINE_BREAK_CLASSES` and `LINE_BREAK_SKIPPED_RULES`.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use super::tables::*;
+use super::{MeasurementConfig, UNICODE_VERSION};
+use crate::helpers::Point;
+
+// The values of `ClusterBreak` in unicode-gen.
+const CB_RI: usize = 5;
+const CB_INCB_LINKER: usize = 12;
+const CB_INCB_CONSONANT: usize = 13;
+const CB_EXT_PIC: usize = 14;
+const CB_ZWJ: usize = 15;
+
+/// GB9c, GB11, GB12 and GB13 are simplified to pairs of characters.
+const GRAPHEME_TAILORED_RULES: &[&str] = &["9.3", "11.0", "12.0", "13.0"];
+
+/// The line break classes that unicode-gen maps onto `LineBreak`. All others (AI, SA, CJ, CB,
+/// the Hangul and Brahmic classes, ...) are lumped together as "Other" instead of being
+/// resolved as in LB1 or handled by LB26 to LB28a, so boundaries next to them are skipped.
+const LINE_BREAK_CLASSES: &[&str] = &[
+    "WJ", "ZW", "GL", "SP", "BA", "BB", "HY", "CL", "CP", "EX", "IN", "NS", "OP", "QU", "IS", "NU",
+    "PO", "PR", "SY", "AL", "HL", "ID", "EB", "EM",
+];
+
+/// The line break rules that `JOIN_RULES_LINE_BREAK` simplifies or omits (see `rules.rs`).
+/// The suites number sub-rules as e.g. 12.1 for LB12a or 21.01 for the first part of LB21.
+/// Entries are matched as prefixes, so that e.g. "15." covers LB15 and all of its sub-rules.
+const LINE_BREAK_SKIPPED_RULES: &[(&str, &str)] = &[
+    ("8.", "ZW SP* ÷ is simplified to ZW ÷, and ZWJ only joins emoji (LB8a)"),
+    ("14.", "OP SP* × is simplified to OP ×"),
+    ("15.", "LB15a and LB15b are missing, LB15c and LB15d ignore the context around spaces"),
+    ("16.", "(CL | CP) SP* × NS is missing"),
+    ("19.", "quotation marks always join, regardless of Pi/Pf and East Asian context"),
+    ("20.1", "word-initial hyphens (LB20a) aren't special-cased"),
+    ("21.1", "Hebrew + Hyphen + non-Hebrew (LB21a) isn't special-cased"),
+    ("21.2", "SY × HL (LB21b) is missing"),
+    ("25.", "numbers are simplified to pairs of characters"),
+];
+
+struct TestCase {
+    chars: Vec<char>,
+    /// The property value of each character, as annotated in the comment, e.g. "AL".
+    classes: Vec<String>,
+    /// `marks[i]` is the expected boundary before `chars[i]`, for `i` in `1..chars.len()`:
+    /// Whether it's a break and the rule that decided it. `marks[0]` is unused.
+    marks: Vec<(bool, String)>,
+}
+
+/// Parses a line like `÷ 0020 × 0308 ÷ #  ÷ [0.2] SPACE (Other) × [9.0] ... ÷ [0.3]`.
+/// Returns `None` for empty lines, comments and cases that aren't valid UTF-8 (surrogates).
+fn parse_line(line: &str) -> Option<TestCase> {
+    let (data, comment) = line.split_once('#').unwrap_or((line, ""));
+    if data.trim().is_empty() {
+        return None;
+    }
+
+    let mut chars = Vec::new();
+    let mut breaks = Vec::new();
+    for token in data.split_whitespace() {
+        match token {
+            "÷" => breaks.push(true),
+            "×" => breaks.push(false),
+            _ => chars.push(char::from_u32(u32::from_str_radix(token, 16).unwrap())?),
+        }
+    }
+
+    // Each "[rule]" is followed by the name of the next character and its class in parentheses.
+    // Names may contain parentheses themselves, e.g. "<CARRIAGE RETURN (CR)> (CR)".
+    let mut rules = Vec::new();
+    let mut classes = Vec::new();
+    for segment in comment.split('[').skip(1) {
+        let (rule, rest) = segment.split_once(']').unwrap();
+        rules.push(rule);
+        if let Some(beg) = rest.rfind('(') {
+            let class = &rest[beg + 1..rest.rfind(')').unwrap()];
+            // Variants like "OP_OP30" or "CM1_CM" are reduced to the actual class.
+            classes.push(class.split('_').next().unwrap().to_string());
+        }
+    }
+    assert_eq!(breaks.len(), chars.len() + 1, "{line}");
+    assert_eq!(rules.len(), breaks.len(), "{line}");
+    assert_eq!(classes.len(), chars.len(), "{line}");
+
+    let marks = breaks.into_iter().zip(rules).map(|(b, r)| (b, r.to_string())).collect();
+    Some(TestCase { chars, classes, marks })
+}
+
+/// Returns the boundaries between grapheme clusters, as indices into `chars`.
+fn measure_clusters(chars: &[char]) -> Vec<usize> {
+    let text: String = chars.iter().collect();
+    let text = text.as_bytes();
+    let mut cfg = MeasurementConfig::new(&text);
+    let mut offsets = Vec::new();
+
+    loop {
+        let cursor = cfg.cursor();
+        let mut next =
+            cfg.goto_logical(Point { x: cursor.logical_pos.x + 1, y: cursor.logical_pos.y });
+        if next.offset == cursor.offset {
+            // Stopped in front of a newline.
+            next = cfg.goto_logical(Point { x: 0, y: cursor.logical_pos.y + 1 });
+        }
+        if next.offset == cursor.offset || next.offset >= text.len() {
+            break;
+        }
+        offsets.push(next.offset);
+    }
+
+    // Translate the byte offsets into indices into `chars`.
+    let mut offset = 0;
+    let mut indices = Vec::new();
+    for (i, ch) in chars.iter().enumerate() {
+        if offsets.contains(&offset) {
+            indices.push(i);
+        }
+        offset += ch.len_utf8();
+    }
+    indices
+}
+
+fn grapheme_tailored(lead: char, trail: char) -> bool {
+    let lead = ucd_grapheme_cluster_lookup(lead) & 31;
+    let trail = ucd_grapheme_cluster_lookup(trail) & 31;
+    matches!(
+        (lead, trail),
+        (CB_ZWJ, CB_EXT_PIC) | (CB_INCB_LINKER, CB_INCB_CONSONANT) | (CB_RI, CB_RI)
+    )
+}
+
+/// Checks a `GraphemeBreakTest.txt` case. Returns the number of checked boundaries.
+fn check_grapheme_breaks(case: &TestCase) -> Result<usize, String> {
+    let clusters = measure_clusters(&case.chars);
+    let mut checked = 0;
+
+    for i in 1..case.chars.len() {
+        let (expected, rule) = &case.marks[i];
+        if GRAPHEME_TAILORED_RULES.contains(&rule.as_str())
+            || grapheme_tailored(case.chars[i - 1], case.chars[i])
+        {
+            continue;
+        }
+        if clusters.contains(&i) != *expected {
+            return Err(format!("boundary before character {i} (rule {rule})"));
+        }
+        checked += 1;
+    }
+
+    Ok(checked)
+}
+
+/// Checks a `LineBreakTest.txt` case. Returns the number of checked boundaries.
+///
+/// Like `MeasurementConfig`, this never breaks within grapheme clusters and otherwise
+/// asks `ucd_line_break_joins` with the first character of each adjacent cluster.
+fn check_line_breaks(case: &TestCase) -> Result<usize, String> {
+    let clusters = measure_clusters(&case.chars);
+    let mut cluster_start = 0;
+    let mut checked = 0;
+
+    for i in 1..case.chars.len() {
+        let (expected, rule) = &case.marks[i];
+        let lead = &case.classes[cluster_start];
+        let trail = &case.classes[i];
+        let actual = clusters.contains(&i)
+            && !ucd_line_break_joins(
+                ucd_grapheme_cluster_lookup(case.chars[cluster_start]),
+                ucd_grapheme_cluster_lookup(case.chars[i]),
+            );
+        if clusters.contains(&i) {
+            cluster_start = i;
+        }
+
+        if LINE_BREAK_SKIPPED_RULES.iter().any(|&(skipped, _)| rule.starts_with(skipped))
+            || !LINE_BREAK_CLASSES.contains(&lead.as_str())
+            || !LINE_BREAK_CLASSES.contains(&trail.as_str())
+            // HY ÷ HY is added, because U+002D is often used as a dash.
+            || (lead == "HY" && trail == "HY")
+        {
+            continue;
+        }
+        if actual != *expected {
+            return Err(format!("boundary before character {i} (rule {rule})"));
+        }
+        checked += 1;
+    }
+
+    Ok(checked)
+}
+
+fn run_suite(name: &str, excerpt: &str, check: fn(&TestCase) -> Result<usize, String>) {
+    // An explicit `EDIT_UCD_TEST_DIR` must contain the files, the default one may lack them.
+    let (dir, required) = match std::env::var_os("EDIT_UCD_TEST_DIR") {
+        Some(dir) => (PathBuf::from(dir), true),
+        None => {
+            let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/unicode/tables");
+            (dir.join(UNICODE_VERSION), false)
+        }
+    };
+    let path = dir.join(name);
+
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => {
+            // The first line is e.g. "# GraphemeBreakTest-16.0.0.txt".
+            let header = contents.lines().next().unwrap_or_default();
+            assert!(
+                header.contains(&format!("-{UNICODE_VERSION}.")),
+                "{name} is \"{header}\", but the tables are for Unicode {UNICODE_VERSION}"
+            );
+            contents
+        }
+        Err(err) if required => panic!("{}: {err}", path.display()),
+        Err(_) => excerpt.to_string(),
+    };
+
+    let mut cases = 0;
+    let mut checked = 0;
+    let mut failures = String::new();
+    let mut failure_count = 0;
+
+    for line in contents.lines() {
+        let Some(case) = parse_line(line) else {
+            continue;
+        };
+        cases += 1;
+        match check(&case) {
+            Ok(n) => checked += n,
+            Err(err) => {
+                failure_count += 1;
+                if failure_count <= 20 {
+                    _ = writeln!(failures, "  {}\n    {err}", line.trim());
+                }
+            }
+        }
+    }
+
+    assert!(cases > 0, "{name} contains no test cases");
+    assert!(failure_count == 0, "{name}: {failure_count} of {cases} cases failed:\n{failures}");
+    assert!(checked > 0, "{name}: no boundaries were checked");
+}
+
+#[test]
+fn test_grapheme_break_conformance() {
+    run_suite("GraphemeBreakTest.txt", GRAPHEME_BREAK_TEST_EXCERPT, check_grapheme_breaks);
+}
+
+#[test]
+fn test_line_break_conformance() {
+    run_suite("LineBreakTest.txt", LINE_BREAK_TEST_EXCERPT, check_line_breaks);
+}
+
+const GRAPHEME_BREAK_TEST_EXCERPT: &str = "\
+÷ 0020 ÷ 0020 ÷	#  ÷ [0.2] SPACE (Other) ÷ [999.0] SPACE (Other) ÷ [0.3]
+÷ 0020 × 0308 ÷	#  ÷ [0.2] SPACE (Other) × [9.0] COMBINING DIAERESIS (Extend_ExtCccZwj) ÷ [0.3]
+÷ 000D × 000A ÷	#  ÷ [0.2] <CARRIAGE RETURN (CR)> (CR) × [3.0] <LINE FEED (LF)> (LF) ÷ [0.3]
+÷ 000A ÷ 0308 ÷	#  ÷ [0.2] <LINE FEED (LF)> (LF) ÷ [4.0] COMBINING DIAERESIS (Extend_ExtCccZwj) ÷ [0.3]
+÷ 0001 ÷ 0020 ÷	#  ÷ [0.2] <START OF HEADING> (Control) ÷ [4.0] SPACE (Other) ÷ [0.3]
+÷ 0020 ÷ 0001 ÷	#  ÷ [0.2] SPACE (Other) ÷ [5.0] <START OF HEADING> (Control) ÷ [0.3]
+÷ 1100 × 1161 ÷	#  ÷ [0.2] HANGUL CHOSEONG KIYEOK (L) × [6.0] HANGUL JUNGSEONG A (V) ÷ [0.3]
+÷ AC00 × 11A8 ÷	#  ÷ [0.2] HANGUL SYLLABLE GA (LV) × [7.0] HANGUL JONGSEONG KIYEOK (T) ÷ [0.3]
+÷ AC01 × 11A8 ÷	#  ÷ [0.2] HANGUL SYLLABLE GAG (LVT) × [8.0] HANGUL JONGSEONG KIYEOK (T) ÷ [0.3]
+÷ 0020 × 0903 ÷	#  ÷ [0.2] SPACE (Other) × [9.1] DEVANAGARI SIGN VISARGA (SpacingMark) ÷ [0.3]
+÷ 0600 × 0020 ÷	#  ÷ [0.2] ARABIC NUMBER SIGN (Prepend) × [9.2] SPACE (Other) ÷ [0.3]
+÷ 0061 × 200D ÷ 1F6D1 ÷	#  ÷ [0.2] LATIN SMALL LETTER A (Other) × [9.0] ZERO WIDTH JOINER (ZWJ_ExtCccZwj) ÷ [999.0] OCTAGONAL SIGN (ExtPict) ÷ [0.3]
+÷ 1F6D1 × 200D × 1F6D1 ÷	#  ÷ [0.2] OCTAGONAL SIGN (ExtPict) × [9.0] ZERO WIDTH JOINER (ZWJ_ExtCccZwj) × [11.0] OCTAGONAL SIGN (ExtPict) ÷ [0.3]
+÷ 1F476 × 1F3FF ÷ 1F476 ÷	#  ÷ [0.2] BABY (ExtPict) × [9.0] EMOJI MODIFIER FITZPATRICK TYPE-6 (Extend) ÷ [999.0] BABY (ExtPict) ÷ [0.3]
+÷ 1F1E6 × 1F1E7 ÷ 1F1E8 ÷	#  ÷ [0.2] REGIONAL INDICATOR SYMBOL LETTER A (RI) × [12.0] REGIONAL INDICATOR SYMBOL LETTER B (RI) ÷ [999.0] REGIONAL INDICATOR SYMBOL LETTER C (RI) ÷ [0.3]
+÷ 0915 × 094D × 0924 ÷	#  ÷ [0.2] DEVANAGARI LETTER KA (ConjunctLinkingScripts_LinkingConsonant) × [9.0] DEVANAGARI SIGN VIRAMA (Extend_ConjunctLinkingScripts_ConjunctLinker_ExtCccZwj) × [9.3] DEVANAGARI LETTER TA (ConjunctLinkingScripts_LinkingConsonant) ÷ [0.3]
+÷ 0061 ÷ 0062 ÷	#  ÷ [0.2] LATIN SMALL LETTER A (Other) ÷ [999.0] LATIN SMALL LETTER B (Other) ÷ [0.3]
+÷ 000D ÷ 0061 ÷	#  ÷ [0.2] <CARRIAGE RETURN (CR)> (CR) ÷ [4.0] LATIN SMALL LETTER A (Other) ÷ [0.3]
+÷ 0061 ÷ 000A ÷	#  ÷ [0.2] LATIN SMALL LETTER A (Other) ÷ [5.0] <LINE FEED (LF)> (LF) ÷ [0.3]
+÷ 000A ÷ 000D ÷	#  ÷ [0.2] <LINE FEED (LF)> (LF) ÷ [4.0] <CARRIAGE RETURN (CR)> (CR) ÷ [0.3]
+÷ 000D × 000A ÷ 0061 ÷ 000A ÷ 0308 ÷	#  ÷ [0.2] <CARRIAGE RETURN (CR)> (CR) × [3.0] <LINE FEED (LF)> (LF) ÷ [4.0] LATIN SMALL LETTER A (Other) ÷ [5.0] <LINE FEED (LF)> (LF) ÷ [4.0] COMBINING DIAERESIS (Extend_ExtCccZwj) ÷ [0.3]
+÷ 0001 ÷ 0308 ÷	#  ÷ [0.2] <START OF HEADING> (Control) ÷ [4.0] COMBINING DIAERESIS (Extend_ExtCccZwj) ÷ [0.3]
+÷ 1100 × 1100 ÷	#  ÷ [0.2] HANGUL CHOSEONG KIYEOK (L) × [6.0] HANGUL CHOSEONG KIYEOK (L) ÷ [0.3]
+÷ 1100 × AC00 ÷	#  ÷ [0.2] HANGUL CHOSEONG KIYEOK (L) × [6.0] HANGUL SYLLABLE GA (LV) ÷ [0.3]
+÷ 1100 × AC01 ÷	#  ÷ [0.2] HANGUL CHOSEONG KIYEOK (L) × [6.0] HANGUL SYLLABLE GAG (LVT) ÷ [0.3]
+÷ 1100 ÷ 11A8 ÷	#  ÷ [0.2] HANGUL CHOSEONG KIYEOK (L) ÷ [999.0] HANGUL JONGSEONG KIYEOK (T) ÷ [0.3]
+÷ 1161 × 1161 ÷	#  ÷ [0.2] HANGUL JUNGSEONG A (V) × [7.0] HANGUL JUNGSEONG A (V) ÷ [0.3]
+÷ 1161 × 11A8 ÷	#  ÷ [0.2] HANGUL JUNGSEONG A (V) × [7.0] HANGUL JONGSEONG KIYEOK (T) ÷ [0.3]
+÷ 1161 ÷ 1100 ÷	#  ÷ [0.2] HANGUL JUNGSEONG A (V) ÷ [999.0] HANGUL CHOSEONG KIYEOK (L) ÷ [0.3]
+÷ AC00 × 1161 ÷	#  ÷ [0.2] HANGUL SYLLABLE GA (LV) × [7.0] HANGUL JUNGSEONG A (V) ÷ [0.3]
+÷ AC00 ÷ 1100 ÷	#  ÷ [0.2] HANGUL SYLLABLE GA (LV) ÷ [999.0] HANGUL CHOSEONG KIYEOK (L) ÷ [0.3]
+÷ AC01 ÷ 1161 ÷	#  ÷ [0.2] HANGUL SYLLABLE GAG (LVT) ÷ [999.0] HANGUL JUNGSEONG A (V) ÷ [0.3]
+÷ 11A8 × 11A8 ÷	#  ÷ [0.2] HANGUL JONGSEONG KIYEOK (T) × [8.0] HANGUL JONGSEONG KIYEOK (T) ÷ [0.3]
+÷ 11A8 ÷ 1161 ÷	#  ÷ [0.2] HANGUL JONGSEONG KIYEOK (T) ÷ [999.0] HANGUL JUNGSEONG A (V) ÷ [0.3]
+÷ 0061 × 0308 ÷ 0062 ÷	#  ÷ [0.2] LATIN SMALL LETTER A (Other) × [9.0] COMBINING DIAERESIS (Extend_ExtCccZwj) ÷ [999.0] LATIN SMALL LETTER B (Other) ÷ [0.3]
+÷ 0308 ÷ 0061 ÷	#  ÷ [0.2] COMBINING DIAERESIS (Extend_ExtCccZwj) ÷ [999.0] LATIN SMALL LETTER A (Other) ÷ [0.3]
+÷ 0061 × 0903 ÷ 0062 ÷	#  ÷ [0.2] LATIN SMALL LETTER A (Other) × [9.1] DEVANAGARI SIGN VISARGA (SpacingMark) ÷ [999.0] LATIN SMALL LETTER B (Other) ÷ [0.3]
+÷ 0061 ÷ 0600 × 0062 ÷	#  ÷ [0.2] LATIN SMALL LETTER A (Other) ÷ [999.0] ARABIC NUMBER SIGN (Prepend) × [9.2] LATIN SMALL LETTER B (Other) ÷ [0.3]
+÷ 0600 ÷ 000A ÷	#  ÷ [0.2] ARABIC NUMBER SIGN (Prepend) ÷ [5.0] <LINE FEED (LF)> (LF) ÷ [0.3]
+÷ 0600 ÷ 0001 ÷	#  ÷ [0.2] ARABIC NUMBER SIGN (Prepend) ÷ [5.0] <START OF HEADING> (Control) ÷ [0.3]
+÷ 2764 × FE0F ÷	#  ÷ [0.2] HEAVY BLACK HEART (ExtPict) × [9.0] VARIATION SELECTOR-16 (Extend) ÷ [0.3]
+÷ 2764 × FE0E ÷	#  ÷ [0.2] HEAVY BLACK HEART (ExtPict) × [9.0] VARIATION SELECTOR-15 (Extend) ÷ [0.3]
+÷ 0061 ÷ 1F6D1 ÷	#  ÷ [0.2] LATIN SMALL LETTER A (Other) ÷ [999.0] OCTAGONAL SIGN (ExtPict) ÷ [0.3]
+÷ 1F476 × 1F3FF × 200D × 1F476 ÷	#  ÷ [0.2] BABY (ExtPict) × [9.0] EMOJI MODIFIER FITZPATRICK TYPE-6 (Extend) × [9.0] ZERO WIDTH JOINER (ZWJ_ExtCccZwj) × [11.0] BABY (ExtPict) ÷ [0.3]
+÷ 0061 × 200D ÷ 0062 ÷	#  ÷ [0.2] LATIN SMALL LETTER A (Other) × [9.0] ZERO WIDTH JOINER (ZWJ_ExtCccZwj) ÷ [999.0] LATIN SMALL LETTER B (Other) ÷ [0.3]
+÷ 0915 ÷ 0924 ÷	#  ÷ [0.2] DEVANAGARI LETTER KA (ConjunctLinkingScripts_LinkingConsonant) ÷ [999.0] DEVANAGARI LETTER TA (ConjunctLinkingScripts_LinkingConsonant) ÷ [0.3]
+÷ 0915 × 093F ÷ 0924 ÷	#  ÷ [0.2] DEVANAGARI LETTER KA (ConjunctLinkingScripts_LinkingConsonant) × [9.1] DEVANAGARI VOWEL SIGN I (SpacingMark) ÷ [999.0] DEVANAGARI LETTER TA (ConjunctLinkingScripts_LinkingConsonant) ÷ [0.3]
+÷ 4E00 ÷ 4E00 ÷	#  ÷ [0.2] <CJK Ideograph, First> (Other) ÷ [999.0] <CJK Ideograph, First> (Other) ÷ [0.3]
+÷ 0020 ÷ 1F1E6 × 1F1E7 ÷	#  ÷ [0.2] SPACE (Other) ÷ [999.0] REGIONAL INDICATOR SYMBOL LETTER A (RI) × [12.0] REGIONAL INDICATOR SYMBOL LETTER B (RI) ÷ [0.3]
+";
+
+const LINE_BREAK_TEST_EXCERPT: &str = "\
+× 0041 × 0020 ÷ 0042 ÷	#  × [0.3] LATIN CAPITAL LETTER A (AL) × [7.01] SPACE (SP) ÷ [18.0] LATIN CAPITAL LETTER B (AL) ÷ [0.3]
+× 0061 × 0308 × 0020 ÷ 0062 ÷	#  × [0.3] LATIN SMALL LETTER A (AL) × [9.0] COMBINING DIAERESIS (CM1_CM) × [7.01] SPACE (SP) ÷ [18.0] LATIN SMALL LETTER B (AL) ÷ [0.3]
+× 0061 × 2060 × 0062 ÷	#  × [0.3] LATIN SMALL LETTER A (AL) × [11.01] WORD JOINER (WJ) × [11.02] LATIN SMALL LETTER B (AL) ÷ [0.3]
+× 0061 × 00A0 × 0062 ÷	#  × [0.3] LATIN SMALL LETTER A (AL) × [12.1] NO-BREAK SPACE (GL) × [12.0] LATIN SMALL LETTER B (AL) ÷ [0.3]
+× 0061 × 0029 ÷	#  × [0.3] LATIN SMALL LETTER A (AL) × [13.02] RIGHT PARENTHESIS (CP_CP30) ÷ [0.3]
+× 0061 × 0021 ÷	#  × [0.3] LATIN SMALL LETTER A (AL) × [13.01] EXCLAMATION MARK (EX) ÷ [0.3]
+× 0028 × 0020 × 0061 ÷	#  × [0.3] LEFT PARENTHESIS (OP_OP30) × [7.01] SPACE (SP) × [14.0] LATIN SMALL LETTER A (AL) ÷ [0.3]
+× 0061 × 2026 ÷	#  × [0.3] LATIN SMALL LETTER A (AL) × [22.0] HORIZONTAL ELLIPSIS (IN) ÷ [0.3]
+× 0061 × 0031 ÷	#  × [0.3] LATIN SMALL LETTER A (AL) × [23.02] DIGIT ONE (NU) ÷ [0.3]
+× 0031 × 0061 ÷	#  × [0.3] DIGIT ONE (NU) × [23.03] LATIN SMALL LETTER A (AL) ÷ [0.3]
+× 0024 × 0061 ÷	#  × [0.3] DOLLAR SIGN (PR) × [24.02] LATIN SMALL LETTER A (AL) ÷ [0.3]
+× 0020 ÷ 4E00 ÷	#  × [0.3] SPACE (SP) ÷ [18.0] <CJK Ideograph, First> (ID) ÷ [0.3]
+× 0061 × 0062 ÷	#  × [0.3] LATIN SMALL LETTER A (AL) × [28.0] LATIN SMALL LETTER B (AL) ÷ [0.3]
+× 05D0 × 0061 ÷	#  × [0.3] HEBREW LETTER ALEF (HL) × [28.0] LATIN SMALL LETTER A (AL) ÷ [0.3]
+× 0061 ÷ 4E00 ÷	#  × [0.3] LATIN SMALL LETTER A (AL) ÷ [999.0] <CJK Ideograph, First> (ID) ÷ [0.3]
+× 4E00 ÷ 4E00 ÷	#  × [0.3] <CJK Ideograph, First> (ID) ÷ [999.0] <CJK Ideograph, First> (ID) ÷ [0.3]
+× 4E00 × 0025 ÷	#  × [0.3] <CJK Ideograph, First> (ID) × [23.13] PERCENT SIGN (PO) ÷ [0.3]
+× 0024 × 4E00 ÷	#  × [0.3] DOLLAR SIGN (PR) × [23.12] <CJK Ideograph, First> (ID) ÷ [0.3]
+× 4E00 ÷ 0031 ÷	#  × [0.3] <CJK Ideograph, First> (ID) ÷ [999.0] DIGIT ONE (NU) ÷ [0.3]
+× 2060 × 0020 ÷ 0061 ÷	#  × [0.3] WORD JOINER (WJ) × [7.01] SPACE (SP) ÷ [18.0] LATIN SMALL LETTER A (AL) ÷ [0.3]
+× 0061 × 200B ÷ 0062 ÷	#  × [0.3] LATIN SMALL LETTER A (AL) × [7.02] ZERO WIDTH SPACE (ZW) ÷ [8.0] LATIN SMALL LETTER B (AL) ÷ [0.3]
+× 00A0 × 0020 ÷ 0061 ÷	#  × [0.3] NO-BREAK SPACE (GL) × [7.01] SPACE (SP) ÷ [18.0] LATIN SMALL LETTER A (AL) ÷ [0.3]
+× 0020 ÷ 00A0 ÷	#  × [0.3] SPACE (SP) ÷ [18.0] NO-BREAK SPACE (GL) ÷ [0.3]
+× 0061 × 002D ÷ 00A0 ÷	#  × [0.3] LATIN SMALL LETTER A (AL) × [21.02] HYPHEN-MINUS (HY) ÷ [999.0] NO-BREAK SPACE (GL) ÷ [0.3]
+× 0061 × 007C ÷ 00A0 ÷	#  × [0.3] LATIN SMALL LETTER A (AL) × [21.01] VERTICAL LINE (BA) ÷ [999.0] NO-BREAK SPACE (GL) ÷ [0.3]
+× 0061 × 002D ÷ 0062 ÷	#  × [0.3] LATIN SMALL LETTER A (AL) × [21.02] HYPHEN-MINUS (HY) ÷ [999.0] LATIN SMALL LETTER B (AL) ÷ [0.3]
+× 0061 × 007C ÷ 0062 ÷	#  × [0.3] LATIN SMALL LETTER A (AL) × [21.01] VERTICAL LINE (BA) ÷ [999.0] LATIN SMALL LETTER B (AL) ÷ [0.3]
+× 00B4 × 0061 ÷	#  × [0.3] ACUTE ACCENT (BB) × [21.04] LATIN SMALL LETTER A (AL) ÷ [0.3]
+× 0061 × 203C ÷	#  × [0.3] LATIN SMALL LETTER A (AL) × [21.03] DOUBLE EXCLAMATION MARK (NS) ÷ [0.3]
+× 4E00 × 203C ÷	#  × [0.3] <CJK Ideograph, First> (ID) × [21.03] DOUBLE EXCLAMATION MARK (NS) ÷ [0.3]
+× 0061 × 002C × 0062 ÷	#  × [0.3] LATIN SMALL LETTER A (AL) × [15.4] COMMA (IS) × [29.0] LATIN SMALL LETTER B (AL) ÷ [0.3]
+× 0061 × 002F ÷ 0062 ÷	#  × [0.3] LATIN SMALL LETTER A (AL) × [13.04] SOLIDUS (SY) ÷ [999.0] LATIN SMALL LETTER B (AL) ÷ [0.3]
+× 0061 × 007D ÷ 0062 ÷	#  × [0.3] LATIN SMALL LETTER A (AL) × [13.02] RIGHT CURLY BRACKET (CL) ÷ [999.0] LATIN SMALL LETTER B (AL) ÷ [0.3]
+× 0029 × 0061 ÷	#  × [0.3] RIGHT PARENTHESIS (CP_CP30) × [30.02] LATIN SMALL LETTER A (AL) ÷ [0.3]
+× 0029 × 0031 ÷	#  × [0.3] RIGHT PARENTHESIS (CP_CP30) × [30.02] DIGIT ONE (NU) ÷ [0.3]
+× 0061 × 0028 × 0062 ÷	#  × [0.3] LATIN SMALL LETTER A (AL) × [30.01] LEFT PARENTHESIS (OP_OP30) × [14.0] LATIN SMALL LETTER B (AL) ÷ [0.3]
+× 0031 × 0028 ÷	#  × [0.3] DIGIT ONE (NU) × [30.01] LEFT PARENTHESIS (OP_OP30) ÷ [0.3]
+× 4E00 ÷ 0028 ÷	#  × [0.3] <CJK Ideograph, First> (ID) ÷ [999.0] LEFT PARENTHESIS (OP_OP30) ÷ [0.3]
+× 0061 ÷ 300C ÷	#  × [0.3] LATIN SMALL LETTER A (AL) ÷ [999.0] LEFT CORNER BRACKET (OP) ÷ [0.3]
+× 4E00 × 300D ÷ 4E00 ÷	#  × [0.3] <CJK Ideograph, First> (ID) × [13.02] RIGHT CORNER BRACKET (CL) ÷ [999.0] <CJK Ideograph, First> (ID) ÷ [0.3]
+× FF09 ÷ 0061 ÷	#  × [0.3] FULLWIDTH RIGHT PARENTHESIS (CP) ÷ [999.0] LATIN SMALL LETTER A (AL) ÷ [0.3]
+× 0025 × 0061 ÷	#  × [0.3] PERCENT SIGN (PO) × [24.02] LATIN SMALL LETTER A (AL) ÷ [0.3]
+× 0061 × 0025 ÷	#  × [0.3] LATIN SMALL LETTER A (AL) × [24.03] PERCENT SIGN (PO) ÷ [0.3]
+× 0061 × 0024 ÷	#  × [0.3] LATIN SMALL LETTER A (AL) × [24.03] DOLLAR SIGN (PR) ÷ [0.3]
+× 05D0 × 0031 ÷	#  × [0.3] HEBREW LETTER ALEF (HL) × [23.02] DIGIT ONE (NU) ÷ [0.3]
+× 0031 × 05D0 ÷	#  × [0.3] DIGIT ONE (NU) × [23.03] HEBREW LETTER ALEF (HL) ÷ [0.3]
+× 0031 × 002E × 0031 ÷	#  × [0.3] DIGIT ONE (NU) × [15.4] FULL STOP (IS) × [25.03] DIGIT ONE (NU) ÷ [0.3]
+× 2026 ÷ 0061 ÷	#  × [0.3] HORIZONTAL ELLIPSIS (IN) ÷ [999.0] LATIN SMALL LETTER A (AL) ÷ [0.3]
+× 2026 × 2026 ÷	#  × [0.3] HORIZONTAL ELLIPSIS (IN) × [22.0] HORIZONTAL ELLIPSIS (IN) ÷ [0.3]
+× 0021 ÷ 0061 ÷	#  × [0.3] EXCLAMATION MARK (EX) ÷ [999.0] LATIN SMALL LETTER A (AL) ÷ [0.3]
+× 0020 × 0020 ÷ 0061 ÷	#  × [0.3] SPACE (SP) × [7.01] SPACE (SP) ÷ [18.0] LATIN SMALL LETTER A (AL) ÷ [0.3]
+× 0061 × 0020 × 0029 ÷	#  × [0.3] LATIN SMALL LETTER A (AL) × [7.01] SPACE (SP) × [13.02] RIGHT PARENTHESIS (CP_CP30) ÷ [0.3]
+× 0061 × 0020 × 0021 ÷	#  × [0.3] LATIN SMALL LETTER A (AL) × [7.01] SPACE (SP) × [13.01] EXCLAMATION MARK (EX) ÷ [0.3]
+× 0061 × 0020 × 2060 ÷	#  × [0.3] LATIN SMALL LETTER A (AL) × [7.01] SPACE (SP) × [11.01] WORD JOINER (WJ) ÷ [0.3]
+× 0020 ÷ 2026 ÷	#  × [0.3] SPACE (SP) ÷ [18.0] HORIZONTAL ELLIPSIS (IN) ÷ [0.3]
+× 0020 ÷ 203C ÷	#  × [0.3] SPACE (SP) ÷ [18.0] DOUBLE EXCLAMATION MARK (NS) ÷ [0.3]
+× 0061 × 0301 ÷ 4E00 ÷	#  × [0.3] LATIN SMALL LETTER A (AL) × [9.0] COMBINING ACUTE ACCENT (CM1_CM) ÷ [999.0] <CJK Ideograph, First> (ID) ÷ [0.3]
+× 0031 × 0308 × 0061 ÷	#  × [0.3] DIGIT ONE (NU) × [9.0] COMBINING DIAERESIS (CM1_CM) × [23.03] LATIN SMALL LETTER A (AL) ÷ [0.3]
+× 0028 × 0022 ÷	#  × [0.3] LEFT PARENTHESIS (OP_OP30) × [14.0] QUOTATION MARK (QU) ÷ [0.3]
+× 0061 × 0022 × 0062 ÷	#  × [0.3] LATIN SMALL LETTER A (AL) × [19.01] QUOTATION MARK (QU) × [19.02] LATIN SMALL LETTER B (AL) ÷ [0.3]
+× 3042 ÷ 4E00 ÷	#  × [0.3] HIRAGANA LETTER A (ID) ÷ [999.0] <CJK Ideograph, First> (ID) ÷ [0.3]
+× 0E01 × 0E02 ÷	#  × [0.3] THAI CHARACTER KO KAI (SA_AL) × [28.0] THAI CHARACTER KHO KHAI (SA_AL) ÷ [0.3]
+";
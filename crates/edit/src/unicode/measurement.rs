@@ -27,6 +27,21 @@ fn ambiguous_width() -> usize {
     unsafe { AMBIGUOUS_WIDTH }
 }
 
+/// Invalid UTF-8 is displayed as one `<XX>` badge per byte. This is the width of such a badge.
+const INVALID_BYTE_WIDTH: CoordType = 4;
+
+/// Returns the width of the badges for `ch`, if it's a U+FFFD that replaced the invalid `bytes`.
+/// Returns 0 for all other characters, including a literal U+FFFD.
+#[inline]
+fn invalid_utf8_width(ch: char, bytes: &[u8]) -> CoordType {
+    if ch == '\u{FFFD}' && bytes != "\u{FFFD}".as_bytes() {
+        cold_path();
+        bytes.len() as CoordType * INVALID_BYTE_WIDTH
+    } else {
+        0
+    }
+}
+
 /// Stores a position inside a [`ReadableDocument`].
 ///
 /// The cursor tracks both the absolute byte-offset,
@@ -174,6 +189,7 @@ impl<'doc> MeasurementConfig<'doc> {
         let mut chunk_iter = Utf8Chars::new(b"", 0);
         let mut chunk_range = offset..offset;
         let mut props_next_cluster = ucd_start_of_text_properties();
+        let mut invalid_width_next_cluster = 0;
 
         loop {
             // Have we reached the target already? Stop.
@@ -189,6 +205,7 @@ impl<'doc> MeasurementConfig<'doc> {
             let mut offset_next_cluster;
             let mut state = 0;
             let mut width = 0;
+            let mut invalid_width = 0;
 
             // Since we want to measure the width of the current cluster,
             // by necessity we need to seek to the next cluster.
@@ -207,11 +224,17 @@ impl<'doc> MeasurementConfig<'doc> {
                 // Similar applies to the width.
                 props_last_char = props_next_cluster;
                 offset_next_cluster = chunk_range.start + chunk_iter.offset();
-                width += ucd_grapheme_cluster_character_width(props_next_cluster, ambiguous_width())
-                    as CoordType;
+                if invalid_width_next_cluster != 0 {
+                    invalid_width += invalid_width_next_cluster;
+                } else {
+                    width +=
+                        ucd_grapheme_cluster_character_width(props_next_cluster, ambiguous_width())
+                            as CoordType;
+                }
 
                 // The `Document::read_forward` interface promises us that it will not split
                 // grapheme clusters across chunks. Therefore, we can safely break here.
+                let ch_beg = chunk_iter.offset();
                 let ch = match chunk_iter.next() {
                     Some(ch) => ch,
                     None => break,
@@ -219,6 +242,8 @@ impl<'doc> MeasurementConfig<'doc> {
 
                 // Get the properties of the next cluster.
                 props_next_cluster = ucd_grapheme_cluster_lookup(ch);
+                invalid_width_next_cluster =
+                    invalid_utf8_width(ch, &chunk_iter.source()[ch_beg..chunk_iter.offset()]);
                 state = ucd_grapheme_cluster_joins(state, props_last_char, props_next_cluster);
 
                 // Stop if the next character does not join.
@@ -236,8 +261,11 @@ impl<'doc> MeasurementConfig<'doc> {
                 continue;
             }
 
-            // The max. width of a terminal cell is 2.
-            width = width.min(2);
+            // The max. width of a terminal cell is 2. Invalid bytes are shown as badges instead.
+            // A trailing VS15 requests the text presentation of an Emoji, which is narrow.
+            let max_width =
+                if props_last_char == ucd_text_presentation_properties() { 1 } else { 2 };
+            width = width.min(max_width) + invalid_width;
 
             // Tabs require special handling because they can have a variable width.
             if props_last_char == ucd_tab_properties() {
@@ -322,8 +350,27 @@ impl<'doc> MeasurementConfig<'doc> {
                     chunk_iter.seek(chunk_iter.len());
                     chunk_range = offset..offset;
                     props_next_cluster = ucd_start_of_text_properties();
+                    invalid_width_next_cluster = 0;
                     continue;
                 }
+
+                // Moving the word to the next line may not have made enough room for the current
+                // cluster, if it's e.g. a tab. Then it needs to be force-wrapped on its own as well.
+                if visual_pos_x > 0 && visual_pos_x + width > self.word_wrap_column {
+                    cold_path();
+
+                    wrap_opp_offset = offset;
+                    wrap_opp_logical_pos_x = logical_pos_x;
+                    wrap_opp_visual_pos_x = visual_pos_x;
+                    wrap_opp_column = column;
+                    visual_pos_x = 0;
+                    visual_pos_y += 1;
+                    visual_target_x = Self::calc_target_x(visual_target, visual_pos_y);
+
+                    if visual_pos_x == visual_target_x {
+                        break;
+                    }
+                }
             }
 
             offset = offset_next_cluster;
@@ -359,7 +406,9 @@ impl<'doc> MeasurementConfig<'doc> {
                 // Of course we only need to do this if the cursor isn't on a wrap opportunity already.
 
                 // The loop below should not modify the target we already found.
+                let mut offset_lookahead = offset;
                 let mut visual_pos_x_lookahead = visual_pos_x;
+                let mut column_lookahead = column;
 
                 loop {
                     let props_current_cluster = props_next_cluster;
@@ -367,6 +416,7 @@ impl<'doc> MeasurementConfig<'doc> {
                     let mut offset_next_cluster;
                     let mut state = 0;
                     let mut width = 0;
+                    let mut invalid_width = 0;
 
                     // Since we want to measure the width of the current cluster,
                     // by necessity we need to seek to the next cluster.
@@ -386,13 +436,18 @@ impl<'doc> MeasurementConfig<'doc> {
                         // Similar applies to the width.
                         props_last_char = props_next_cluster;
                         offset_next_cluster = chunk_range.start + chunk_iter.offset();
-                        width += ucd_grapheme_cluster_character_width(
-                            props_next_cluster,
-                            ambiguous_width(),
-                        ) as CoordType;
+                        if invalid_width_next_cluster != 0 {
+                            invalid_width += invalid_width_next_cluster;
+                        } else {
+                            width += ucd_grapheme_cluster_character_width(
+                                props_next_cluster,
+                                ambiguous_width(),
+                            ) as CoordType;
+                        }
 
                         // The `Document::read_forward` interface promises us that it will not split
                         // grapheme clusters across chunks. Therefore, we can safely break here.
+                        let ch_beg = chunk_iter.offset();
                         let ch = match chunk_iter.next() {
                             Some(ch) => ch,
                             None => break,
@@ -400,6 +455,10 @@ impl<'doc> MeasurementConfig<'doc> {
 
                         // Get the properties of the next cluster.
                         props_next_cluster = ucd_grapheme_cluster_lookup(ch);
+                        invalid_width_next_cluster = invalid_utf8_width(
+                            ch,
+                            &chunk_iter.source()[ch_beg..chunk_iter.offset()],
+                        );
                         state =
                             ucd_grapheme_cluster_joins(state, props_last_char, props_next_cluster);
 
@@ -409,7 +468,9 @@ impl<'doc> MeasurementConfig<'doc> {
                         }
                     }
 
-                    if offset_next_cluster == offset {
+                    // Unlike above, we must compare against the lookahead offset here. Otherwise,
+                    // we'd measure the last cluster a second time when reaching the end of the text.
+                    if offset_next_cluster == offset_lookahead {
                         // No advance and the iterator is empty? End of text reached.
                         if chunk_iter.is_empty() {
                             break;
@@ -418,15 +479,18 @@ impl<'doc> MeasurementConfig<'doc> {
                         continue;
                     }
 
-                    // The max. width of a terminal cell is 2.
-                    width = width.min(2);
+                    // The max. width of a terminal cell is 2. Invalid bytes are shown as badges instead.
+                    // A trailing VS15 requests the text presentation of an Emoji, which is narrow.
+                    let max_width =
+                        if props_last_char == ucd_text_presentation_properties() { 1 } else { 2 };
+                    width = width.min(max_width) + invalid_width;
 
                     // Tabs require special handling because they can have a variable width.
                     if props_last_char == ucd_tab_properties() {
                         // SAFETY: `self.tab_size` is clamped to >= 1 in `with_tab_size`.
                         // This assert ensures that Rust doesn't insert panicking null checks.
                         unsafe { std::hint::assert_unchecked(self.tab_size >= 1) };
-                        width = self.tab_size - (column % self.tab_size);
+                        width = self.tab_size - (column_lookahead % self.tab_size);
                     }
 
                     // Hard wrap: Both the logical and visual position advance by one line.
@@ -434,9 +498,14 @@ impl<'doc> MeasurementConfig<'doc> {
                         break;
                     }
 
+                    offset_lookahead = offset_next_cluster;
                     visual_pos_x_lookahead += width;
+                    column_lookahead += width;
 
                     if visual_pos_x_lookahead > self.word_wrap_column {
+                        // Like above, the word now starts the line and
+                        // there's no wrap opportunity before the cursor anymore.
+                        wrap_opp = false;
                         visual_pos_x -= wrap_opp_visual_pos_x;
                         visual_pos_y += 1;
                         break;
@@ -511,6 +580,8 @@ pub fn strip_newline(mut text: &[u8]) -> &[u8] {
 
 #[cfg(test)]
 mod test {
+    use proptest::prelude::*;
+
     use super::*;
 
     struct ChunkedDoc<'a>(&'a [&'a [u8]]);
@@ -553,6 +624,24 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_measure_forward_variation_selectors() {
+        // VS16 requests the emoji presentation (wide) and VS15 the text presentation (narrow).
+        for (text, width) in [
+            ("\u{231A}", 2),
+            ("\u{231A}\u{FE0E}", 1),
+            ("\u{231A}\u{FE0F}", 2),
+            ("\u{2764}", 1),
+            ("\u{2764}\u{FE0E}", 1),
+            ("\u{2764}\u{FE0F}", 2),
+        ] {
+            let cursor =
+                MeasurementConfig::new(&text.as_bytes()).goto_logical(Point { x: 1, y: 0 });
+            assert_eq!(cursor.offset, text.len(), "{text:?}");
+            assert_eq!(cursor.visual_pos.x, width, "{text:?}");
+        }
+    }
+
     #[test]
     fn test_measure_forward_clipped_wide_char() {
         let cursor = MeasurementConfig::new(&"a😶‍🌫️b".as_bytes()).goto_visual(Point { x: 2, y: 0 });
@@ -576,6 +665,7 @@ mod test {
         let text = "foo bar \nbaz".as_bytes();
 
         // Does hitting a logical target wrap the visual position along with the word?
+        // There's no wrap opportunity before the cursor on the new line.
         let mut cfg = MeasurementConfig::new(&text).with_word_wrap_column(6);
         let cursor = cfg.goto_logical(Point { x: 5, y: 0 });
         assert_eq!(
@@ -585,7 +675,7 @@ mod test {
                 logical_pos: Point { x: 5, y: 0 },
                 visual_pos: Point { x: 1, y: 1 },
                 column: 5,
-                wrap_opp: true,
+                wrap_opp: false,
             }
         );
 
@@ -1033,6 +1123,29 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_invalid_utf8() {
+        // "\xE2\x82" is a truncated sequence (1 cluster of 2 bytes), whereas "\xFF" is 1 byte.
+        // A literal U+FFFD on the other hand is just a regular, narrow character.
+        let text = b"a\xE2\x82b\xFF\xEF\xBF\xBDc".as_slice();
+        let cursor = MeasurementConfig::new(&text).goto_logical(Point { x: CoordType::MAX, y: 0 });
+        assert_eq!(
+            cursor,
+            Cursor {
+                offset: 9,
+                logical_pos: Point { x: 6, y: 0 },
+                visual_pos: Point { x: 1 + 8 + 1 + 4 + 1 + 1, y: 0 },
+                column: 16,
+                wrap_opp: false,
+            }
+        );
+
+        // Clicking into the middle of a badge lands in front of it.
+        let cursor = MeasurementConfig::new(&text).goto_visual(Point { x: 6, y: 0 });
+        assert_eq!(cursor.offset, 1);
+        assert_eq!(cursor.visual_pos, Point { x: 1, y: 0 });
+    }
+
     #[test]
     fn test_wrapped_cursor_can_seek_backward() {
         let bytes = "hello world".as_bytes();
@@ -1053,10 +1166,153 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_wrap_lookahead_end_of_text() {
+        // |bar_baz| <- "baz" ends exactly at the wrap column and must not wrap.
+        let bytes = "bar baz".as_bytes();
+        let mut cfg = MeasurementConfig::new(&bytes).with_word_wrap_column(7);
+
+        let cursor = cfg.goto_offset(5);
+        assert_eq!(cursor.visual_pos, Point { x: 5, y: 0 });
+    }
+
+    #[test]
+    fn test_wrap_lookahead_tab() {
+        // |_aa_| <- The tab is 1 column wide at column 3, so "aa\t" fits.
+        let bytes = " aa\t".as_bytes();
+        let mut cfg = MeasurementConfig::new(&bytes).with_word_wrap_column(4).with_tab_size(2);
+
+        let cursor = cfg.goto_offset(2);
+        assert_eq!(cursor.visual_pos, Point { x: 2, y: 0 });
+    }
+
+    #[test]
+    fn test_wrap_tab_overflow() {
+        // |bar_      |
+        // |bazfoo    | <- Moving "bazfoo" doesn't make room for the 5 column tab,
+        // |_____     |    so the tab gets force-wrapped on its own as well.
+        let bytes = "bar bazfoo\t".as_bytes();
+        let cfg = MeasurementConfig::new(&bytes).with_word_wrap_column(10).with_tab_size(5);
+
+        let cursor = cfg.clone().goto_offset(10);
+        assert_eq!(cursor.visual_pos, Point { x: 6, y: 1 });
+        let cursor = cfg.clone().goto_offset(11);
+        assert_eq!(cursor.visual_pos, Point { x: 5, y: 2 });
+        let cursor = cfg.clone().goto_visual(Point { x: CoordType::MAX, y: 1 });
+        assert_eq!(cursor.offset, 10);
+    }
+
     #[test]
     fn test_strip_newline() {
         assert_eq!(strip_newline(b"hello\n"), b"hello");
         assert_eq!(strip_newline(b"hello\r\n"), b"hello");
         assert_eq!(strip_newline(b"hello"), b"hello");
     }
+
+    /// Arbitrary text made of pieces that are interesting for measurement:
+    /// words, whitespace, tabs, CRLF, wide and zero-width characters, and invalid UTF-8.
+    fn arb_text() -> impl Strategy<Value = Vec<u8>> {
+        const PIECES: &[&[u8]] = &[
+            b"a",
+            b"foo",
+            b"bar baz",
+            b" ",
+            b"  ",
+            b"\t",
+            b"\n",
+            b"\r\n",
+            b"\r",
+            b"-",
+            b",",
+            "\u{e9}".as_bytes(),
+            "e\u{301}".as_bytes(),
+            "\u{6f22}\u{5b57}".as_bytes(),
+            "\u{1f636}\u{200d}\u{1f32b}\u{fe0f}".as_bytes(),
+            "\u{200b}".as_bytes(),
+            b"\x80",
+            b"\xff",
+            b"\xc3",
+            b"\xe6\xbc",
+            b"\x00",
+            b"\x1b",
+        ];
+        prop::collection::vec(prop::sample::select(PIECES), 0..48).prop_map(|p| p.concat())
+    }
+
+    /// The parts of a cursor that describe its position. `wrap_opp` is internal state.
+    fn pos(c: Cursor) -> (usize, Point, Point, CoordType) {
+        (c.offset, c.logical_pos, c.visual_pos, c.column)
+    }
+
+    proptest! {
+        #[test]
+        fn prop_goto_round_trip(
+            text in arb_text(),
+            tab_size in 1..9 as CoordType,
+            word_wrap_column in prop_oneof![Just(0), 1..16 as CoordType],
+        ) {
+            let doc = &text[..];
+            let cfg = || {
+                MeasurementConfig::new(&doc)
+                    .with_tab_size(tab_size)
+                    .with_word_wrap_column(word_wrap_column)
+            };
+
+            let mut last = Cursor::default();
+            let mut line_start = Cursor::default();
+
+            for off in 0..=text.len() {
+                let c = cfg().goto_offset(off);
+                prop_assert!(c.offset <= text.len());
+                prop_assert!(c.offset >= last.offset);
+                prop_assert!(c.logical_pos >= last.logical_pos);
+                prop_assert!(c.visual_pos >= last.visual_pos);
+
+                prop_assert_eq!(pos(cfg().goto_offset(c.offset)), pos(c));
+                prop_assert_eq!(pos(cfg().goto_logical(c.logical_pos)), pos(c));
+
+                // Zero-width clusters share their visual position with the preceding one.
+                let v = cfg().goto_visual(c.visual_pos);
+                prop_assert_eq!(v.visual_pos, c.visual_pos);
+                prop_assert!(v.offset <= c.offset);
+
+                // Measuring from the start of the line must yield the same result as from
+                // the start of the document. That's what reflowing after an edit relies on.
+                prop_assert_eq!(pos(cfg().with_cursor(line_start).goto_offset(off)), pos(c));
+                prop_assert_eq!(pos(cfg().with_cursor(last).goto_offset(off)), pos(c));
+                if c.logical_pos.x == 0 {
+                    line_start = c;
+                }
+
+                last = c;
+            }
+        }
+
+        #[test]
+        fn prop_goto_chunked(
+            text in arb_text(),
+            split in any::<prop::sample::Index>(),
+            word_wrap_column in prop_oneof![Just(0), 1..16 as CoordType],
+        ) {
+            // `read_forward` promises not to split grapheme clusters across chunks.
+            let doc = &text[..];
+            let mut boundaries: Vec<_> = (0..=text.len())
+                .map(|off| MeasurementConfig::new(&doc).goto_offset(off).offset)
+                .collect();
+            boundaries.dedup();
+            let split = boundaries[split.index(boundaries.len())];
+            let chunks = [&text[..split], &text[split..]];
+            let chunked = ChunkedDoc(&chunks);
+
+            for off in 0..=text.len() {
+                let expected = MeasurementConfig::new(&doc)
+                    .with_word_wrap_column(word_wrap_column)
+                    .goto_offset(off);
+                let actual = MeasurementConfig::new(&chunked)
+                    .with_word_wrap_column(word_wrap_column)
+                    .goto_offset(off);
+                prop_assert_eq!(actual, expected);
+            }
+        }
+    }
 }
@@ -3,9 +3,26 @@
 
 //! Everything related to Unicode lives here.
 
+mod bidi;
+mod bidi_tables;
+#[cfg(test)]
+mod conformance;
 mod measurement;
-mod tables;
 mod utf8;
 
+// The tables for the Unicode version selected via `EDIT_CFG_UNICODE_VERSION`. See `build/main.rs`.
+mod tables {
+    include!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/unicode/tables/",
+        env!("EDIT_CFG_UNICODE_VERSION"),
+        ".rs"
+    ));
+}
+
+pub use bidi::*;
 pub use measurement::*;
 pub use utf8::*;
+
+/// The Unicode version of the grapheme cluster, line break and width tables.
+pub const UNICODE_VERSION: &str = env!("EDIT_CFG_UNICODE_VERSION");
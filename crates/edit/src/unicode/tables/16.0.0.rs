@@ -1,7 +1,7 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-// BEGIN: Generated by grapheme-table-gen on 2025-06-03T13:50:48Z, from Unicode 16.0.0, with --lang=rust --extended --line-breaks, 17688 bytes
+// BEGIN: Generated by grapheme-table-gen on 2026-10-19T00:19:47Z, from Unicode 16.0.0, with --lang=rust --extended --line-breaks, 17688 bytes
 #[rustfmt::skip]
 const STAGE0: [u16; 544] = [
     0x0000, 0x0040, 0x007f, 0x00bf, 0x00ff, 0x013f, 0x017f, 0x0194, 0x0194, 0x01a6, 0x0194, 0x0194, 0x0194, 0x0194, 0x0194, 0x0194,
@@ -594,444 +594,444 @@ const STAGE2: [u16; 4079] = [
 ];
 #[rustfmt::skip]
 const STAGE3: [u16; 1752] = [
-    0x0803, 0x0803, 0x0803, 0x0803,
-    0x0803, 0x0803, 0x0803, 0x0803,
-    0x0803, 0x0963, 0x0802, 0x0803,
-    0x0803, 0x0801, 0x0803, 0x0803,
-    0x0803, 0x0803, 0x0803, 0x0803,
-    0x0803, 0x0803, 0x0803, 0x0803,
-    0x0803, 0x0803, 0x0803, 0x0803,
-    0x0803, 0x0803, 0x0803, 0x0803,
-    0x0900, 0x0ac0, 0x0c00, 0x0d80,
-    0x0d00, 0x0cc0, 0x0d80, 0x0c00,
-    0x0bc0, 0x0a80, 0x0d80, 0x0d00,
-    0x0c40, 0x09c0, 0x0c40, 0x0d40,
-    0x0c80, 0x0c80, 0x0c80, 0x0c80,
-    0x0c80, 0x0c80, 0x0c80, 0x0c80,
-    0x0c80, 0x0c80, 0x0c40, 0x0c40,
-    0x0d80, 0x0d80, 0x0d80, 0x0ac0,
-    0x0d80, 0x0d80, 0x0d80, 0x0d80,
-    0x0d80, 0x0d80, 0x0d80, 0x0d80,
-    0x0d80, 0x0d80, 0x0d80, 0x0d80,
-    0x0d80, 0x0d80, 0x0d80, 0x0d80,
-    0x0d80, 0x0d80, 0x0d80, 0x0d80,
-    0x0d80, 0x0d80, 0x0d80, 0x0d80,
-    0x0d80, 0x0d80, 0x0d80, 0x0bc0,
-    0x0d00, 0x0a80, 0x0d80, 0x0d80,
-    0x0d80, 0x0d80, 0x0d80, 0x0d80,
-    0x0d80, 0x0d80, 0x0d80, 0x0d80,
-    0x0d80, 0x0d80, 0x0d80, 0x0d80,
-    0x0d80, 0x0d80, 0x0d80, 0x0d80,
-    0x0d80, 0x0d80, 0x0d80, 0x0d80,
-    0x0d80, 0x0d80, 0x0d80, 0x0d80,
-    0x0d80, 0x0d80, 0x0d80, 0x0bc0,
-    0x0940, 0x0a00, 0x0d80, 0x0803,
-    0x08c0, 0x1bc0, 0x0cc0, 0x0d00,
-    0x1d00, 0x0d00, 0x0d80, 0x1800,
-    0x0d8e, 0x1800, 0x0c00, 0x0d80,
-    0x0944, 0x1d8e, 0x0d80, 0x1cc0,
-    0x1d00, 0x1800, 0x1800, 0x1980,
-    0x0d80, 0x1800, 0x1800, 0x1800,
-    0x0c00, 0x1800, 0x1800, 0x1800,
-    0x1bc0, 0x0d80, 0x0d80, 0x1d80,
-    0x0d80, 0x0d80, 0x0d80, 0x1800,
-    0x0d80, 0x0d80, 0x1d80, 0x1d80,
-    0x0d80, 0x0d80, 0x1d80, 0x1d80,
-    0x1d80, 0x0d80, 0x1d80, 0x1d80,
-    0x0d80, 0x1d80, 0x0d80, 0x1d80,
-    0x0d80, 0x0d80, 0x0d80, 0x1d80,
-    0x1d80, 0x1d80, 0x1d80, 0x0d80,
-    0x0d80, 0x1800, 0x0980, 0x1800,
-    0x1800, 0x1800, 0x0980, 0x1800,
-    0x0d80, 0x0d80, 0x0d80, 0x1800,
-    0x1800, 0x1800, 0x1800, 0x0d80,
-    0x1800, 0x0d80, 0x1980, 0x0004,
-    0x0004, 0x0004, 0x0004, 0x00c4,
-    0x00c4, 0x00c4, 0x00c4, 0x0004,
-    0x0800, 0x0800, 0x0d80, 0x0d80,
-    0x0c40, 0x0d80, 0x0800, 0x0800,
-    0x0800, 0x0800, 0x0d80, 0x0d80,
-    0x0d80, 0x0800, 0x0d80, 0x0d80,
-    0x1d80, 0x1d80, 0x0800, 0x1d80,
-    0x0d80, 0x0d80, 0x0d80, 0x0004,
-    0x0004, 0x0d80, 0x0d80, 0x0c40,
-    0x0940, 0x0800, 0x0d80, 0x0d80,
-    0x0d00, 0x0800, 0x0004, 0x0004,
-    0x0004, 0x0940, 0x0004, 0x0004,
-    0x0ac0, 0x0004, 0x0486, 0x0486,
-    0x0486, 0x0486, 0x0d80, 0x0d80,
-    0x0cc0, 0x0cc0, 0x0cc0, 0x0004,
-    0x0004, 0x0004, 0x0ac0, 0x0ac0,
-    0x0ac0, 0x0c80, 0x0c80, 0x0cc0,
-    0x0c80, 0x0d80, 0x0d80, 0x0d80,
-    0x0004, 0x0d80, 0x0d80, 0x0d80,
-    0x0ac0, 0x0d80, 0x0004, 0x0004,
-    0x0486, 0x0d80, 0x0004, 0x0d80,
-    0x0d80, 0x0004, 0x0d80, 0x0004,
-    0x0004, 0x0c80, 0x0c80, 0x0d80,
-    0x0d80, 0x0800, 0x0586, 0x0004,
-    0x0004, 0x0004, 0x0800, 0x0004,
-    0x0d80, 0x0800, 0x0800, 0x0c40,
-    0x0ac0, 0x0d80, 0x0800, 0x0004,
-    0x0d00, 0x0d00, 0x0004, 0x0004,
-    0x0d80, 0x0004, 0x0004, 0x0004,
-    0x0800, 0x0800, 0x0d80, 0x0800,
-    0x0486, 0x0486, 0x0800, 0x0800,
-    0x0800, 0x0004, 0x0004, 0x0486,
-    0x0004, 0x0004, 0x0004, 0x0804,
-    0x0d80, 0x0d8d, 0x0d8d, 0x0d8d,
-    0x0d8d, 0x0004, 0x0804, 0x0004,
-    0x0d80, 0x0804, 0x0804, 0x0004,
-    0x0004, 0x0004, 0x0804, 0x0804,
-    0x0804, 0x000c, 0x0804, 0x0804,
-    0x0940, 0x0940, 0x0c80, 0x0c80,
-    0x0d80, 0x0004, 0x0804, 0x0804,
-    0x0d80, 0x0800, 0x0800, 0x0d80,
-    0x0d8d, 0x0800, 0x0d8d, 0x0d8d,
-    0x0800, 0x0d8d, 0x0800, 0x0800,
-    0x0d8d, 0x0d8d, 0x0800, 0x0800,
-    0x0004, 0x0800, 0x0800, 0x0804,
-    0x0800, 0x0800, 0x0804, 0x000c,
-    0x0d80, 0x0800, 0x0800, 0x0800,
-    0x0804, 0x0800, 0x0800, 0x0c80,
-    0x0c80, 0x0d8d, 0x0d8d, 0x0cc0,
-    0x0cc0, 0x0d80, 0x0cc0, 0x0d80,
-    0x0d00, 0x0d80, 0x0d80, 0x0004,
-    0x0800, 0x0004, 0x0004, 0x0804,
-    0x0800, 0x0d80, 0x0d80, 0x0800,
-    0x0800, 0x0004, 0x0800, 0x0804,
-    0x0804, 0x0004, 0x0004, 0x0800,
-    0x0800, 0x0004, 0x0d80, 0x0800,
-    0x0d80, 0x0800, 0x0d80, 0x0004,
-    0x0d80, 0x0800, 0x0d8d, 0x0d8d,
-    0x0d8d, 0x0004, 0x0804, 0x0800,
-    0x0804, 0x000c, 0x0800, 0x0800,
-    0x0d80, 0x0d00, 0x0800, 0x0800,
-    0x0d8d, 0x0004, 0x0004, 0x0800,
-    0x0004, 0x0804, 0x0804, 0x0004,
-    0x0d80, 0x0804, 0x0004, 0x0d80,
-    0x0d8d, 0x0d80, 0x0d80, 0x0800,
-    0x0800, 0x0804, 0x0804, 0x0004,
-    0x0804, 0x0804, 0x0800, 0x0804,
-    0x0804, 0x0004, 0x0800, 0x0800,
-    0x0d80, 0x0d00, 0x0d80, 0x0800,
-    0x0804, 0x0800, 0x0004, 0x0004,
-    0x000c, 0x0800, 0x0800, 0x0004,
-    0x0004, 0x0800, 0x0d8d, 0x0d8d,
-    0x0d8d, 0x0800, 0x0d80, 0x0800,
-    0x0800, 0x0800, 0x0980, 0x0d80,
-    0x0d80, 0x0d80, 0x0804, 0x0804,
-    0x0804, 0x0804, 0x0800, 0x0004,
-    0x0804, 0x0800, 0x0804, 0x0804,
-    0x0800, 0x0d80, 0x0d80, 0x0804,
-    0x000c, 0x0d86, 0x0d80, 0x0cc0,
-    0x0d80, 0x0d80, 0x0004, 0x0800,
-    0x0004, 0x0800, 0x0800, 0x0800,
-    0x0d00, 0x0004, 0x0004, 0x0004,
-    0x0d80, 0x0c80, 0x0c80, 0x0940,
-    0x0940, 0x0c80, 0x0c80, 0x0800,
-    0x0800, 0x0d80, 0x0980, 0x0980,
-    0x0980, 0x0d80, 0x0980, 0x0980,
-    0x08c0, 0x0980, 0x0980, 0x0940,
-    0x08c0, 0x0ac0, 0x0ac0, 0x0ac0,
-    0x08c0, 0x0d80, 0x0940, 0x0004,
-    0x0d80, 0x0004, 0x0bc0, 0x0a00,
-    0x0804, 0x0804, 0x0004, 0x0004,
-    0x0004, 0x0944, 0x0004, 0x0800,
-    0x0940, 0x0940, 0x0980, 0x0980,
-    0x0940, 0x0980, 0x0d80, 0x08c0,
-    0x08c0, 0x0800, 0x0004, 0x0804,
-    0x0004, 0x0004, 0x1007, 0x1007,
-    0x1007, 0x1007, 0x0808, 0x0808,
-    0x0808, 0x0808, 0x0809, 0x0809,
-    0x0809, 0x0809, 0x0d80, 0x0940,
-    0x0d80, 0x0d80, 0x0d80, 0x0a00,
-    0x0800, 0x0800, 0x0800, 0x0d80,
-    0x0d80, 0x0d80, 0x0940, 0x0940,
-    0x0d80, 0x0d80, 0x0004, 0x0804,
-    0x0800, 0x0800, 0x0804, 0x0940,
-    0x0940, 0x0800, 0x0d80, 0x0800,
-    0x0004, 0x0004, 0x0804, 0x0004,
-    0x0940, 0x0940, 0x0b40, 0x0800,
-    0x0940, 0x0d80, 0x0940, 0x0d00,
-    0x0d80, 0x0d80, 0x0ac0, 0x0ac0,
-    0x0940, 0x0940, 0x0980, 0x0d80,
-    0x0ac0, 0x0ac0, 0x0d80, 0x0004,
-    0x0004, 0x00c4, 0x0004, 0x0804,
-    0x0804, 0x0804, 0x0004, 0x0c80,
-    0x0c80, 0x0c80, 0x0800, 0x0804,
-    0x0004, 0x0804, 0x0800, 0x0800,
-    0x0800, 0x0940, 0x0940, 0x0dc0,
-    0x0940, 0x0940, 0x0940, 0x0dc0,
-    0x0dc0, 0x0dc0, 0x0dc0, 0x0004,
-    0x0d80, 0x0804, 0x0004, 0x0004,
-    0x0800, 0x0800, 0x0004, 0x0804,
-    0x0004, 0x0804, 0x0004, 0x0940,
-    0x0940, 0x0940, 0x0940, 0x0004,
-    0x0d80, 0x0d80, 0x0804, 0x0004,
-    0x0004, 0x0d80, 0x0800, 0x0004,
-    0x00c4, 0x0004, 0x0004, 0x0004,
-    0x0d80, 0x0980, 0x0d80, 0x0800,
-    0x0940, 0x0940, 0x0940, 0x08c0,
-    0x0940, 0x0940, 0x0940, 0x0084,
+    0x1003, 0x1003, 0x1003, 0x1003,
+    0x1003, 0x1003, 0x1003, 0x1003,
+    0x1003, 0x12a3, 0x1002, 0x1003,
+    0x1003, 0x1001, 0x1003, 0x1003,
+    0x1003, 0x1003, 0x1003, 0x1003,
+    0x1003, 0x1003, 0x1003, 0x1003,
+    0x1003, 0x1003, 0x1003, 0x1003,
+    0x1003, 0x1003, 0x1003, 0x1003,
+    0x1200, 0x1580, 0x1800, 0x1b00,
+    0x1a00, 0x1980, 0x1b00, 0x1800,
+    0x1780, 0x1500, 0x1b00, 0x1a00,
+    0x1880, 0x1380, 0x1880, 0x1a80,
+    0x1900, 0x1900, 0x1900, 0x1900,
+    0x1900, 0x1900, 0x1900, 0x1900,
+    0x1900, 0x1900, 0x1880, 0x1880,
+    0x1b00, 0x1b00, 0x1b00, 0x1580,
+    0x1b00, 0x1b00, 0x1b00, 0x1b00,
+    0x1b00, 0x1b00, 0x1b00, 0x1b00,
+    0x1b00, 0x1b00, 0x1b00, 0x1b00,
+    0x1b00, 0x1b00, 0x1b00, 0x1b00,
+    0x1b00, 0x1b00, 0x1b00, 0x1b00,
+    0x1b00, 0x1b00, 0x1b00, 0x1b00,
+    0x1b00, 0x1b00, 0x1b00, 0x1780,
+    0x1a00, 0x1500, 0x1b00, 0x1b00,
+    0x1b00, 0x1b00, 0x1b00, 0x1b00,
+    0x1b00, 0x1b00, 0x1b00, 0x1b00,
+    0x1b00, 0x1b00, 0x1b00, 0x1b00,
+    0x1b00, 0x1b00, 0x1b00, 0x1b00,
+    0x1b00, 0x1b00, 0x1b00, 0x1b00,
+    0x1b00, 0x1b00, 0x1b00, 0x1b00,
+    0x1b00, 0x1b00, 0x1b00, 0x1780,
+    0x1280, 0x1400, 0x1b00, 0x1003,
+    0x1180, 0x3780, 0x1980, 0x1a00,
+    0x3a00, 0x1a00, 0x1b00, 0x3000,
+    0x1b0e, 0x3000, 0x1800, 0x1b00,
+    0x1284, 0x3b0e, 0x1b00, 0x3980,
+    0x3a00, 0x3000, 0x3000, 0x3300,
+    0x1b00, 0x3000, 0x3000, 0x3000,
+    0x1800, 0x3000, 0x3000, 0x3000,
+    0x3780, 0x1b00, 0x1b00, 0x3b00,
+    0x1b00, 0x1b00, 0x1b00, 0x3000,
+    0x1b00, 0x1b00, 0x3b00, 0x3b00,
+    0x1b00, 0x1b00, 0x3b00, 0x3b00,
+    0x3b00, 0x1b00, 0x3b00, 0x3b00,
+    0x1b00, 0x3b00, 0x1b00, 0x3b00,
+    0x1b00, 0x1b00, 0x1b00, 0x3b00,
+    0x3b00, 0x3b00, 0x3b00, 0x1b00,
+    0x1b00, 0x3000, 0x1300, 0x3000,
+    0x3000, 0x3000, 0x1300, 0x3000,
+    0x1b00, 0x1b00, 0x1b00, 0x3000,
+    0x3000, 0x3000, 0x3000, 0x1b00,
+    0x3000, 0x1b00, 0x3300, 0x0004,
+    0x0004, 0x0004, 0x0004, 0x0184,
+    0x0184, 0x0184, 0x0184, 0x0004,
+    0x1000, 0x1000, 0x1b00, 0x1b00,
+    0x1880, 0x1b00, 0x1000, 0x1000,
+    0x1000, 0x1000, 0x1b00, 0x1b00,
+    0x1b00, 0x1000, 0x1b00, 0x1b00,
+    0x3b00, 0x3b00, 0x1000, 0x3b00,
+    0x1b00, 0x1b00, 0x1b00, 0x0004,
+    0x0004, 0x1b00, 0x1b00, 0x1880,
+    0x1280, 0x1000, 0x1b00, 0x1b00,
+    0x1a00, 0x1000, 0x0004, 0x0004,
+    0x0004, 0x1280, 0x0004, 0x0004,
+    0x1580, 0x0004, 0x0906, 0x0906,
+    0x0906, 0x0906, 0x1b00, 0x1b00,
+    0x1980, 0x1980, 0x1980, 0x0004,
+    0x0004, 0x0004, 0x1580, 0x1580,
+    0x1580, 0x1900, 0x1900, 0x1980,
+    0x1900, 0x1b00, 0x1b00, 0x1b00,
+    0x0004, 0x1b00, 0x1b00, 0x1b00,
+    0x1580, 0x1b00, 0x0004, 0x0004,
+    0x0906, 0x1b00, 0x0004, 0x1b00,
+    0x1b00, 0x0004, 0x1b00, 0x0004,
+    0x0004, 0x1900, 0x1900, 0x1b00,
+    0x1b00, 0x1000, 0x0b06, 0x0004,
+    0x0004, 0x0004, 0x1000, 0x0004,
+    0x1b00, 0x1000, 0x1000, 0x1880,
+    0x1580, 0x1b00, 0x1000, 0x0004,
+    0x1a00, 0x1a00, 0x0004, 0x0004,
+    0x1b00, 0x0004, 0x0004, 0x0004,
+    0x1000, 0x1000, 0x1b00, 0x1000,
+    0x0906, 0x0906, 0x1000, 0x1000,
+    0x1000, 0x0004, 0x0004, 0x0906,
+    0x0004, 0x0004, 0x0004, 0x1004,
+    0x1b00, 0x1b0d, 0x1b0d, 0x1b0d,
+    0x1b0d, 0x0004, 0x1004, 0x0004,
+    0x1b00, 0x1004, 0x1004, 0x0004,
+    0x0004, 0x0004, 0x1004, 0x1004,
+    0x1004, 0x000c, 0x1004, 0x1004,
+    0x1280, 0x1280, 0x1900, 0x1900,
+    0x1b00, 0x0004, 0x1004, 0x1004,
+    0x1b00, 0x1000, 0x1000, 0x1b00,
+    0x1b0d, 0x1000, 0x1b0d, 0x1b0d,
+    0x1000, 0x1b0d, 0x1000, 0x1000,
+    0x1b0d, 0x1b0d, 0x1000, 0x1000,
+    0x0004, 0x1000, 0x1000, 0x1004,
+    0x1000, 0x1000, 0x1004, 0x000c,
+    0x1b00, 0x1000, 0x1000, 0x1000,
+    0x1004, 0x1000, 0x1000, 0x1900,
+    0x1900, 0x1b0d, 0x1b0d, 0x1980,
+    0x1980, 0x1b00, 0x1980, 0x1b00,
+    0x1a00, 0x1b00, 0x1b00, 0x0004,
+    0x1000, 0x0004, 0x0004, 0x1004,
+    0x1000, 0x1b00, 0x1b00, 0x1000,
+    0x1000, 0x0004, 0x1000, 0x1004,
+    0x1004, 0x0004, 0x0004, 0x1000,
+    0x1000, 0x0004, 0x1b00, 0x1000,
+    0x1b00, 0x1000, 0x1b00, 0x0004,
+    0x1b00, 0x1000, 0x1b0d, 0x1b0d,
+    0x1b0d, 0x0004, 0x1004, 0x1000,
+    0x1004, 0x000c, 0x1000, 0x1000,
+    0x1b00, 0x1a00, 0x1000, 0x1000,
+    0x1b0d, 0x0004, 0x0004, 0x1000,
+    0x0004, 0x1004, 0x1004, 0x0004,
+    0x1b00, 0x1004, 0x0004, 0x1b00,
+    0x1b0d, 0x1b00, 0x1b00, 0x1000,
+    0x1000, 0x1004, 0x1004, 0x0004,
+    0x1004, 0x1004, 0x1000, 0x1004,
+    0x1004, 0x0004, 0x1000, 0x1000,
+    0x1b00, 0x1a00, 0x1b00, 0x1000,
+    0x1004, 0x1000, 0x0004, 0x0004,
+    0x000c, 0x1000, 0x1000, 0x0004,
+    0x0004, 0x1000, 0x1b0d, 0x1b0d,
+    0x1b0d, 0x1000, 0x1b00, 0x1000,
+    0x1000, 0x1000, 0x1300, 0x1b00,
+    0x1b00, 0x1b00, 0x1004, 0x1004,
+    0x1004, 0x1004, 0x1000, 0x0004,
+    0x1004, 0x1000, 0x1004, 0x1004,
+    0x1000, 0x1b00, 0x1b00, 0x1004,
+    0x000c, 0x1b06, 0x1b00, 0x1980,
+    0x1b00, 0x1b00, 0x0004, 0x1000,
+    0x0004, 0x1000, 0x1000, 0x1000,
+    0x1a00, 0x0004, 0x0004, 0x0004,
+    0x1b00, 0x1900, 0x1900, 0x1280,
+    0x1280, 0x1900, 0x1900, 0x1000,
+    0x1000, 0x1b00, 0x1300, 0x1300,
+    0x1300, 0x1b00, 0x1300, 0x1300,
+    0x1180, 0x1300, 0x1300, 0x1280,
+    0x1180, 0x1580, 0x1580, 0x1580,
+    0x1180, 0x1b00, 0x1280, 0x0004,
+    0x1b00, 0x0004, 0x1780, 0x1400,
+    0x1004, 0x1004, 0x0004, 0x0004,
+    0x0004, 0x1284, 0x0004, 0x1000,
+    0x1280, 0x1280, 0x1300, 0x1300,
+    0x1280, 0x1300, 0x1b00, 0x1180,
+    0x1180, 0x1000, 0x0004, 0x1004,
+    0x0004, 0x0004, 0x2007, 0x2007,
+    0x2007, 0x2007, 0x1008, 0x1008,
+    0x1008, 0x1008, 0x1009, 0x1009,
+    0x1009, 0x1009, 0x1b00, 0x1280,
+    0x1b00, 0x1b00, 0x1b00, 0x1400,
+    0x1000, 0x1000, 0x1000, 0x1b00,
+    0x1b00, 0x1b00, 0x1280, 0x1280,
+    0x1b00, 0x1b00, 0x0004, 0x1004,
+    0x1000, 0x1000, 0x1004, 0x1280,
+    0x1280, 0x1000, 0x1b00, 0x1000,
+    0x0004, 0x0004, 0x1004, 0x0004,
+    0x1280, 0x1280, 0x1680, 0x1000,
+    0x1280, 0x1b00, 0x1280, 0x1a00,
+    0x1b00, 0x1b00, 0x1580, 0x1580,
+    0x1280, 0x1280, 0x1300, 0x1b00,
+    0x1580, 0x1580, 0x1b00, 0x0004,
+    0x0004, 0x0184, 0x0004, 0x1004,
+    0x1004, 0x1004, 0x0004, 0x1900,
+    0x1900, 0x1900, 0x1000, 0x1004,
+    0x0004, 0x1004, 0x1000, 0x1000,
+    0x1000, 0x1280, 0x1280, 0x1b80,
+    0x1280, 0x1280, 0x1280, 0x1b80,
+    0x1b80, 0x1b80, 0x1b80, 0x0004,
+    0x1b00, 0x1004, 0x0004, 0x0004,
+    0x1000, 0x1000, 0x0004, 0x1004,
+    0x0004, 0x1004, 0x0004, 0x1280,
+    0x1280, 0x1280, 0x1280, 0x0004,
+    0x1b00, 0x1b00, 0x1004, 0x0004,
+    0x0004, 0x1b00, 0x1000, 0x0004,
+    0x0184, 0x0004, 0x0004, 0x0004,
+    0x1b00, 0x1300, 0x1b00, 0x1000,
+    0x1280, 0x1280, 0x1280, 0x1180,
+    0x1280, 0x1280, 0x1280, 0x0104,
     0x0004, 0x000f, 0x0004, 0x0004,
-    0x1940, 0x08c0, 0x0940, 0x1940,
-    0x1c00, 0x1c00, 0x0bc0, 0x0c00,
-    0x1800, 0x1800, 0x1d80, 0x0d80,
-    0x1b00, 0x1b00, 0x1b00, 0x1940,
-    0x0803, 0x0803, 0x0004, 0x0004,
-    0x0004, 0x08c0, 0x1cc0, 0x0cc0,
-    0x1cc0, 0x1cc0, 0x0cc0, 0x1cc0,
-    0x0cc0, 0x0cc0, 0x0d80, 0x0c00,
-    0x0c00, 0x1800, 0x0b4e, 0x0b40,
-    0x1d80, 0x0d80, 0x0c40, 0x0bc0,
-    0x0a00, 0x0b40, 0x0b4e, 0x0d80,
-    0x0d80, 0x0940, 0x0cc0, 0x0d80,
-    0x0940, 0x0940, 0x0940, 0x0044,
-    0x0584, 0x0584, 0x0584, 0x0803,
-    0x0004, 0x0004, 0x0d80, 0x0bc0,
-    0x0a00, 0x1800, 0x0d80, 0x0bc0,
-    0x0a00, 0x0800, 0x0d00, 0x0d00,
-    0x0d00, 0x0d00, 0x0cc0, 0x1d00,
-    0x0d00, 0x0d00, 0x0d00, 0x0cc0,
-    0x0d00, 0x0d00, 0x0d00, 0x0d80,
-    0x0d80, 0x0d80, 0x1cc0, 0x0d80,
-    0x0d80, 0x1d00, 0x0d80, 0x1800,
-    0x180e, 0x0d80, 0x0d8e, 0x0d80,
-    0x0d80, 0x0800, 0x0800, 0x0800,
-    0x1800, 0x0800, 0x0800, 0x0800,
-    0x1800, 0x1800, 0x0d80, 0x0d80,
-    0x180e, 0x180e, 0x180e, 0x180e,
-    0x0d80, 0x0d80, 0x0d8e, 0x0d8e,
-    0x0d80, 0x1800, 0x0d80, 0x1800,
-    0x1800, 0x0d80, 0x0d80, 0x1800,
-    0x0d00, 0x0d00, 0x0d80, 0x0d80,
-    0x0d80, 0x0b00, 0x0bc0, 0x0a00,
-    0x0bc0, 0x0a00, 0x0d80, 0x0d80,
-    0x15ce, 0x15ce, 0x0d8e, 0x1380,
-    0x1200, 0x0d80, 0x0d8e, 0x0d80,
-    0x0d80, 0x0d80, 0x0d8e, 0x0d80,
-    0x158e, 0x158e, 0x158e, 0x0d8e,
-    0x0d8e, 0x0d8e, 0x15ce, 0x0dce,
-    0x0dce, 0x15ce, 0x0d8e, 0x0d8e,
-    0x0d8e, 0x0d80, 0x1800, 0x1800,
-    0x180e, 0x1800, 0x1800, 0x0800,
-    0x1800, 0x1800, 0x1800, 0x1d80,
-    0x1800, 0x1800, 0x0d8e, 0x0d8e,
-    0x0d80, 0x0d80, 0x180e, 0x1800,
-    0x0d80, 0x0d80, 0x0d8e, 0x158e,
-    0x158e, 0x0d80, 0x0dce, 0x0dce,
-    0x0dce, 0x0dce, 0x0d8e, 0x180e,
-    0x1800, 0x0d8e, 0x180e, 0x0d8e,
-    0x0d8e, 0x180e, 0x180e, 0x15ce,
-    0x15ce, 0x080e, 0x080e, 0x0dce,
-    0x0d8e, 0x0dce, 0x0dce, 0x1dce,
-    0x0dce, 0x1dce, 0x0dce, 0x0d8e,
-    0x0d8e, 0x0d8e, 0x0d8e, 0x158e,
-    0x158e, 0x158e, 0x158e, 0x0d8e,
-    0x0dce, 0x0dce, 0x0dce, 0x180e,
-    0x0d8e, 0x180e, 0x0d8e, 0x180e,
-    0x180e, 0x0d8e, 0x180e, 0x1dce,
-    0x180e, 0x180e, 0x0d8e, 0x0d80,
-    0x0d80, 0x1580, 0x1580, 0x1580,
-    0x1580, 0x0d8e, 0x158e, 0x0d8e,
-    0x0d8e, 0x15ce, 0x15ce, 0x1dce,
-    0x1dce, 0x180e, 0x180e, 0x180e,
-    0x1dce, 0x158e, 0x1dce, 0x1dce,
-    0x180e, 0x1dce, 0x15ce, 0x180e,
-    0x180e, 0x180e, 0x1dce, 0x180e,
-    0x180e, 0x1dce, 0x1dce, 0x0d8e,
-    0x180e, 0x180e, 0x15ce, 0x180e,
-    0x1dce, 0x15ce, 0x15ce, 0x1dce,
-    0x15ce, 0x180e, 0x1dce, 0x1dce,
-    0x15ce, 0x180e, 0x15ce, 0x1dce,
-    0x1dce, 0x0dce, 0x158e, 0x0d80,
-    0x0d80, 0x0dce, 0x0dce, 0x15ce,
-    0x15ce, 0x0dce, 0x0dce, 0x0d8e,
-    0x0d8e, 0x0d80, 0x0d8e, 0x0d80,
-    0x158e, 0x0d80, 0x0d80, 0x0d80,
-    0x0d8e, 0x0d80, 0x0d80, 0x0d8e,
-    0x158e, 0x0d80, 0x158e, 0x0d80,
-    0x0d80, 0x0d80, 0x158e, 0x158e,
-    0x0d80, 0x100e, 0x0d80, 0x0d80,
-    0x0d80, 0x0c00, 0x0c00, 0x0c00,
-    0x0c00, 0x0d80, 0x0ac0, 0x0ace,
-    0x0bc0, 0x0a00, 0x1800, 0x1800,
-    0x0d80, 0x0bc0, 0x0a00, 0x0d80,
-    0x0d80, 0x0bc0, 0x0a00, 0x0bc0,
-    0x0a00, 0x0bc0, 0x0a00, 0x0d80,
-    0x0d80, 0x0d80, 0x0d8e, 0x0d8e,
-    0x0d8e, 0x0d80, 0x100e, 0x1800,
-    0x1800, 0x0800, 0x0ac0, 0x0940,
-    0x0940, 0x0d80, 0x0ac0, 0x0940,
-    0x0800, 0x0800, 0x0800, 0x0c00,
-    0x0c00, 0x0940, 0x0940, 0x0d80,
-    0x0940, 0x0bc0, 0x0940, 0x0d80,
-    0x0d80, 0x0c00, 0x0c00, 0x0d80,
-    0x0d80, 0x0c00, 0x0c00, 0x0bc0,
-    0x0a00, 0x0940, 0x0940, 0x0ac0,
-    0x0d80, 0x0940, 0x0940, 0x0940,
-    0x0d80, 0x0940, 0x0940, 0x0bc0,
-    0x0940, 0x0ac0, 0x0bc0, 0x0a80,
-    0x0bc0, 0x0a80, 0x0bc0, 0x0a80,
-    0x0940, 0x0800, 0x0800, 0x15c0,
-    0x15c0, 0x15c0, 0x15c0, 0x0800,
-    0x15c0, 0x15c0, 0x0800, 0x0800,
-    0x1140, 0x1200, 0x1200, 0x15c0,
-    0x1340, 0x15c0, 0x15c0, 0x1380,
-    0x1200, 0x1380, 0x1200, 0x15c0,
-    0x15c0, 0x1340, 0x1380, 0x1200,
-    0x1200, 0x15c0, 0x15c0, 0x0004,
-    0x0004, 0x1004, 0x1004, 0x15ce,
-    0x15c0, 0x15c0, 0x15c0, 0x1000,
-    0x15c0, 0x15c0, 0x15c0, 0x1340,
-    0x15ce, 0x15c0, 0x0dc0, 0x0800,
-    0x1000, 0x15c0, 0x1000, 0x15c0,
-    0x1000, 0x1000, 0x0800, 0x0004,
-    0x0004, 0x1340, 0x1340, 0x1340,
-    0x15c0, 0x1340, 0x1000, 0x15c0,
-    0x1000, 0x1000, 0x15c0, 0x1000,
-    0x1340, 0x1340, 0x15c0, 0x0800,
-    0x0800, 0x0800, 0x15c0, 0x1000,
-    0x1000, 0x1000, 0x1000, 0x15c0,
-    0x15c0, 0x15c0, 0x15ce, 0x15c0,
-    0x15c0, 0x0d80, 0x0940, 0x0ac0,
-    0x0940, 0x0004, 0x0004, 0x0d80,
-    0x0940, 0x0804, 0x0004, 0x0004,
-    0x0804, 0x0cc0, 0x0d80, 0x0800,
-    0x0800, 0x0980, 0x0980, 0x0ac0,
-    0x0ac0, 0x0804, 0x0804, 0x0d80,
-    0x0d80, 0x0980, 0x0d80, 0x0d80,
-    0x0004, 0x0004, 0x0940, 0x0940,
-    0x1007, 0x0800, 0x0800, 0x0800,
-    0x0804, 0x0dc0, 0x0dc0, 0x0dc0,
-    0x0940, 0x0dc0, 0x0dc0, 0x0800,
-    0x0940, 0x0800, 0x0800, 0x0dc0,
-    0x0dc0, 0x0d80, 0x0804, 0x0004,
-    0x0800, 0x0004, 0x0804, 0x0804,
-    0x0940, 0x100a, 0x100b, 0x100b,
-    0x100b, 0x100b, 0x0808, 0x0808,
-    0x0808, 0x0800, 0x0800, 0x0800,
-    0x0809, 0x0d80, 0x0d80, 0x0a00,
-    0x0bc0, 0x0cc0, 0x0d80, 0x0d80,
-    0x0d80, 0x0004, 0x0004, 0x0004,
-    0x1004, 0x1200, 0x1200, 0x1200,
-    0x1340, 0x12c0, 0x12c0, 0x1380,
-    0x1200, 0x1300, 0x0800, 0x0800,
-    0x00c4, 0x0004, 0x00c4, 0x0004,
-    0x00c4, 0x00c4, 0x0004, 0x1200,
-    0x1380, 0x1200, 0x1380, 0x1200,
-    0x15c0, 0x15c0, 0x1380, 0x1200,
-    0x15c0, 0x15c0, 0x15c0, 0x1200,
-    0x15c0, 0x1200, 0x0800, 0x1340,
-    0x1340, 0x12c0, 0x12c0, 0x15c0,
-    0x1500, 0x14c0, 0x15c0, 0x0d80,
-    0x0800, 0x0800, 0x0044, 0x0800,
-    0x12c0, 0x15c0, 0x15c0, 0x1500,
-    0x14c0, 0x15c0, 0x15c0, 0x1200,
-    0x15c0, 0x1200, 0x15c0, 0x15c0,
-    0x1340, 0x1340, 0x15c0, 0x15c0,
-    0x15c0, 0x12c0, 0x15c0, 0x15c0,
-    0x15c0, 0x1380, 0x15c0, 0x1200,
-    0x15c0, 0x1380, 0x1200, 0x0a00,
-    0x0b80, 0x0a00, 0x0b40, 0x0dc0,
-    0x0800, 0x0dc0, 0x0dc0, 0x0dc0,
-    0x0b44, 0x0b44, 0x0dc0, 0x0dc0,
-    0x0dc0, 0x0800, 0x0800, 0x0800,
-    0x14c0, 0x1500, 0x15c0, 0x15c0,
-    0x1500, 0x1500, 0x0800, 0x0940,
-    0x0940, 0x0940, 0x0800, 0x0d80,
-    0x0004, 0x0800, 0x0800, 0x0d80,
-    0x0d80, 0x0800, 0x0940, 0x0d80,
-    0x0004, 0x0004, 0x0800, 0x0940,
-    0x0940, 0x0b00, 0x0800, 0x0004,
-    0x0004, 0x0940, 0x0d80, 0x0d80,
-    0x0800, 0x0004, 0x0940, 0x0800,
-    0x0800, 0x0800, 0x00c4, 0x0d80,
-    0x0486, 0x0940, 0x0940, 0x0004,
-    0x0800, 0x0486, 0x0800, 0x0800,
-    0x0004, 0x0800, 0x0c80, 0x0c80,
-    0x0d80, 0x0804, 0x0804, 0x0d80,
-    0x0d86, 0x0d86, 0x0940, 0x0004,
-    0x0004, 0x0004, 0x0c80, 0x0c80,
-    0x0d80, 0x0980, 0x0940, 0x0d80,
-    0x0004, 0x0d80, 0x0940, 0x0800,
-    0x0800, 0x0004, 0x0940, 0x0804,
-    0x0804, 0x0800, 0x0800, 0x0800,
-    0x0dc0, 0x0004, 0x0800, 0x0804,
-    0x0800, 0x0804, 0x0004, 0x0806,
-    0x0004, 0x0dc0, 0x0dc0, 0x0800,
-    0x0dc0, 0x0004, 0x0980, 0x0940,
-    0x0940, 0x0ac0, 0x0ac0, 0x0d80,
-    0x0d80, 0x0004, 0x0940, 0x0940,
-    0x0d80, 0x0980, 0x0980, 0x0980,
-    0x0980, 0x0800, 0x0800, 0x0800,
-    0x0804, 0x0800, 0x0800, 0x0004,
-    0x0804, 0x0004, 0x0806, 0x0804,
-    0x0806, 0x0804, 0x0004, 0x0804,
-    0x0d86, 0x0004, 0x0004, 0x0004,
-    0x0980, 0x0940, 0x0980, 0x0d80,
-    0x0004, 0x0d86, 0x0d86, 0x0d86,
-    0x0d86, 0x0004, 0x0004, 0x0980,
-    0x0940, 0x0940, 0x0800, 0x0800,
-    0x0980, 0x0ac0, 0x0d80, 0x0d80,
-    0x0800, 0x0804, 0x0004, 0x0004,
-    0x0d86, 0x0004, 0x0004, 0x0800,
-    0x0804, 0x0800, 0x0800, 0x0940,
-    0x0004, 0x0004, 0x0806, 0x0804,
-    0x0bc0, 0x0bc0, 0x0bc0, 0x0a00,
-    0x0a00, 0x0d80, 0x0d80, 0x0a00,
-    0x0d80, 0x0bc0, 0x0a00, 0x0a00,
-    0x00c4, 0x00c4, 0x00c4, 0x03c4,
-    0x0204, 0x00c4, 0x00c4, 0x00c4,
-    0x03c4, 0x0204, 0x03c4, 0x0204,
-    0x0940, 0x0d80, 0x0800, 0x0800,
-    0x0c80, 0x0c80, 0x0800, 0x0d80,
-    0x0d80, 0x0d80, 0x0d88, 0x0d88,
-    0x0d88, 0x0d80, 0x1340, 0x1340,
-    0x1340, 0x1340, 0x00c4, 0x0800,
-    0x0800, 0x0800, 0x1004, 0x1004,
-    0x0800, 0x0800, 0x1580, 0x1580,
-    0x0800, 0x0800, 0x0800, 0x1580,
-    0x1580, 0x1580, 0x0800, 0x0800,
-    0x1000, 0x0800, 0x1000, 0x1000,
-    0x1000, 0x0800, 0x1000, 0x0800,
-    0x0800, 0x1580, 0x1580, 0x1580,
-    0x0d80, 0x0004, 0x0d80, 0x0d80,
-    0x0940, 0x0d80, 0x0c80, 0x0c80,
-    0x0c80, 0x0800, 0x0800, 0x0bc0,
-    0x0bc0, 0x15ce, 0x0dce, 0x0dce,
-    0x0dce, 0x15ce, 0x1800, 0x1800,
-    0x1800, 0x0800, 0x0d8e, 0x0d8e,
-    0x0d8e, 0x1800, 0x1800, 0x0d80,
-    0x0d8e, 0x180e, 0x180e, 0x1800,
-    0x1800, 0x180e, 0x180e, 0x1800,
-    0x1800, 0x100e, 0x1800, 0x100e,
-    0x100e, 0x100e, 0x100e, 0x1800,
-    0x0d8e, 0x0dce, 0x0dce, 0x0805,
-    0x0805, 0x0805, 0x0805, 0x15c0,
-    0x15ce, 0x15ce, 0x0dce, 0x15c0,
-    0x15c0, 0x15ce, 0x15ce, 0x15ce,
-    0x15ce, 0x15c0, 0x0dce, 0x0dce,
-    0x0dce, 0x15ce, 0x15ce, 0x15ce,
-    0x0dce, 0x15ce, 0x15ce, 0x0d8e,
-    0x0d8e, 0x0dce, 0x0dce, 0x15ce,
-    0x158e, 0x158e, 0x15ce, 0x15ce,
-    0x15ce, 0x15c4, 0x15c4, 0x15c4,
-    0x15c4, 0x158e, 0x15ce, 0x158e,
-    0x15ce, 0x15ce, 0x15ce, 0x158e,
-    0x158e, 0x158e, 0x15ce, 0x158e,
-    0x158e, 0x0d80, 0x0d80, 0x0d8e,
-    0x0d8e, 0x0dce, 0x15ce, 0x0dce,
-    0x0dce, 0x15ce, 0x0dce, 0x0c00,
-    0x0b40, 0x0b40, 0x0b40, 0x080e,
-    0x080e, 0x080e, 0x080e, 0x0d80,
-    0x0d80, 0x080e, 0x080e, 0x0d8e,
-    0x0d8e, 0x080e, 0x080e, 0x15ce,
-    0x15ce, 0x15ce, 0x0dc0, 0x15ce,
-    0x0dce, 0x0dce, 0x0800, 0x0800,
-    0x0803, 0x0004, 0x0803, 0x0803,
-    0x1800, 0x1800, 0x0800, 0x0800,
+    0x3280, 0x1180, 0x1280, 0x3280,
+    0x3800, 0x3800, 0x1780, 0x1800,
+    0x3000, 0x3000, 0x3b00, 0x1b00,
+    0x3600, 0x3600, 0x3600, 0x3280,
+    0x1003, 0x1003, 0x0004, 0x0004,
+    0x0004, 0x1180, 0x3980, 0x1980,
+    0x3980, 0x3980, 0x1980, 0x3980,
+    0x1980, 0x1980, 0x1b00, 0x1800,
+    0x1800, 0x3000, 0x168e, 0x1680,
+    0x3b00, 0x1b00, 0x1880, 0x1780,
+    0x1400, 0x1680, 0x168e, 0x1b00,
+    0x1b00, 0x1280, 0x1980, 0x1b00,
+    0x1280, 0x1280, 0x1280, 0x0084,
+    0x0b04, 0x0b04, 0x0b04, 0x1003,
+    0x0004, 0x0004, 0x1b00, 0x1780,
+    0x1400, 0x3000, 0x1b00, 0x1780,
+    0x1400, 0x1000, 0x1a00, 0x1a00,
+    0x1a00, 0x1a00, 0x1980, 0x3a00,
+    0x1a00, 0x1a00, 0x1a00, 0x1980,
+    0x1a00, 0x1a00, 0x1a00, 0x1b00,
+    0x1b00, 0x1b00, 0x3980, 0x1b00,
+    0x1b00, 0x3a00, 0x1b00, 0x3000,
+    0x300e, 0x1b00, 0x1b0e, 0x1b00,
+    0x1b00, 0x1000, 0x1000, 0x1000,
+    0x3000, 0x1000, 0x1000, 0x1000,
+    0x3000, 0x3000, 0x1b00, 0x1b00,
+    0x300e, 0x300e, 0x300e, 0x300e,
+    0x1b00, 0x1b00, 0x1b0e, 0x1b0e,
+    0x1b00, 0x3000, 0x1b00, 0x3000,
+    0x3000, 0x1b00, 0x1b00, 0x3000,
+    0x1a00, 0x1a00, 0x1b00, 0x1b00,
+    0x1b00, 0x1600, 0x1780, 0x1400,
+    0x1780, 0x1400, 0x1b00, 0x1b00,
+    0x2b8e, 0x2b8e, 0x1b0e, 0x2700,
+    0x2400, 0x1b00, 0x1b0e, 0x1b00,
+    0x1b00, 0x1b00, 0x1b0e, 0x1b00,
+    0x2b0e, 0x2b0e, 0x2b0e, 0x1b0e,
+    0x1b0e, 0x1b0e, 0x2b8e, 0x1b8e,
+    0x1b8e, 0x2b8e, 0x1b0e, 0x1b0e,
+    0x1b0e, 0x1b00, 0x3000, 0x3000,
+    0x300e, 0x3000, 0x3000, 0x1000,
+    0x3000, 0x3000, 0x3000, 0x3b00,
+    0x3000, 0x3000, 0x1b0e, 0x1b0e,
+    0x1b00, 0x1b00, 0x300e, 0x3000,
+    0x1b00, 0x1b00, 0x1b0e, 0x2b0e,
+    0x2b0e, 0x1b00, 0x1b8e, 0x1b8e,
+    0x1b8e, 0x1b8e, 0x1b0e, 0x300e,
+    0x3000, 0x1b0e, 0x300e, 0x1b0e,
+    0x1b0e, 0x300e, 0x300e, 0x2b8e,
+    0x2b8e, 0x100e, 0x100e, 0x1b8e,
+    0x1b0e, 0x1b8e, 0x1b8e, 0x3b8e,
+    0x1b8e, 0x3b8e, 0x1b8e, 0x1b0e,
+    0x1b0e, 0x1b0e, 0x1b0e, 0x2b0e,
+    0x2b0e, 0x2b0e, 0x2b0e, 0x1b0e,
+    0x1b8e, 0x1b8e, 0x1b8e, 0x300e,
+    0x1b0e, 0x300e, 0x1b0e, 0x300e,
+    0x300e, 0x1b0e, 0x300e, 0x3b8e,
+    0x300e, 0x300e, 0x1b0e, 0x1b00,
+    0x1b00, 0x2b00, 0x2b00, 0x2b00,
+    0x2b00, 0x1b0e, 0x2b0e, 0x1b0e,
+    0x1b0e, 0x2b8e, 0x2b8e, 0x3b8e,
+    0x3b8e, 0x300e, 0x300e, 0x300e,
+    0x3b8e, 0x2b0e, 0x3b8e, 0x3b8e,
+    0x300e, 0x3b8e, 0x2b8e, 0x300e,
+    0x300e, 0x300e, 0x3b8e, 0x300e,
+    0x300e, 0x3b8e, 0x3b8e, 0x1b0e,
+    0x300e, 0x300e, 0x2b8e, 0x300e,
+    0x3b8e, 0x2b8e, 0x2b8e, 0x3b8e,
+    0x2b8e, 0x300e, 0x3b8e, 0x3b8e,
+    0x2b8e, 0x300e, 0x2b8e, 0x3b8e,
+    0x3b8e, 0x1b8e, 0x2b0e, 0x1b00,
+    0x1b00, 0x1b8e, 0x1b8e, 0x2b8e,
+    0x2b8e, 0x1b8e, 0x1b8e, 0x1b0e,
+    0x1b0e, 0x1b00, 0x1b0e, 0x1b00,
+    0x2b0e, 0x1b00, 0x1b00, 0x1b00,
+    0x1b0e, 0x1b00, 0x1b00, 0x1b0e,
+    0x2b0e, 0x1b00, 0x2b0e, 0x1b00,
+    0x1b00, 0x1b00, 0x2b0e, 0x2b0e,
+    0x1b00, 0x200e, 0x1b00, 0x1b00,
+    0x1b00, 0x1800, 0x1800, 0x1800,
+    0x1800, 0x1b00, 0x1580, 0x158e,
+    0x1780, 0x1400, 0x3000, 0x3000,
+    0x1b00, 0x1780, 0x1400, 0x1b00,
+    0x1b00, 0x1780, 0x1400, 0x1780,
+    0x1400, 0x1780, 0x1400, 0x1b00,
+    0x1b00, 0x1b00, 0x1b0e, 0x1b0e,
+    0x1b0e, 0x1b00, 0x200e, 0x3000,
+    0x3000, 0x1000, 0x1580, 0x1280,
+    0x1280, 0x1b00, 0x1580, 0x1280,
+    0x1000, 0x1000, 0x1000, 0x1800,
+    0x1800, 0x1280, 0x1280, 0x1b00,
+    0x1280, 0x1780, 0x1280, 0x1b00,
+    0x1b00, 0x1800, 0x1800, 0x1b00,
+    0x1b00, 0x1800, 0x1800, 0x1780,
+    0x1400, 0x1280, 0x1280, 0x1580,
+    0x1b00, 0x1280, 0x1280, 0x1280,
+    0x1b00, 0x1280, 0x1280, 0x1780,
+    0x1280, 0x1580, 0x1780, 0x1500,
+    0x1780, 0x1500, 0x1780, 0x1500,
+    0x1280, 0x1000, 0x1000, 0x2b80,
+    0x2b80, 0x2b80, 0x2b80, 0x1000,
+    0x2b80, 0x2b80, 0x1000, 0x1000,
+    0x2280, 0x2400, 0x2400, 0x2b80,
+    0x2680, 0x2b80, 0x2b80, 0x2700,
+    0x2400, 0x2700, 0x2400, 0x2b80,
+    0x2b80, 0x2680, 0x2700, 0x2400,
+    0x2400, 0x2b80, 0x2b80, 0x0004,
+    0x0004, 0x2004, 0x2004, 0x2b8e,
+    0x2b80, 0x2b80, 0x2b80, 0x2000,
+    0x2b80, 0x2b80, 0x2b80, 0x2680,
+    0x2b8e, 0x2b80, 0x1b80, 0x1000,
+    0x2000, 0x2b80, 0x2000, 0x2b80,
+    0x2000, 0x2000, 0x1000, 0x0004,
+    0x0004, 0x2680, 0x2680, 0x2680,
+    0x2b80, 0x2680, 0x2000, 0x2b80,
+    0x2000, 0x2000, 0x2b80, 0x2000,
+    0x2680, 0x2680, 0x2b80, 0x1000,
+    0x1000, 0x1000, 0x2b80, 0x2000,
+    0x2000, 0x2000, 0x2000, 0x2b80,
+    0x2b80, 0x2b80, 0x2b8e, 0x2b80,
+    0x2b80, 0x1b00, 0x1280, 0x1580,
+    0x1280, 0x0004, 0x0004, 0x1b00,
+    0x1280, 0x1004, 0x0004, 0x0004,
+    0x1004, 0x1980, 0x1b00, 0x1000,
+    0x1000, 0x1300, 0x1300, 0x1580,
+    0x1580, 0x1004, 0x1004, 0x1b00,
+    0x1b00, 0x1300, 0x1b00, 0x1b00,
+    0x0004, 0x0004, 0x1280, 0x1280,
+    0x2007, 0x1000, 0x1000, 0x1000,
+    0x1004, 0x1b80, 0x1b80, 0x1b80,
+    0x1280, 0x1b80, 0x1b80, 0x1000,
+    0x1280, 0x1000, 0x1000, 0x1b80,
+    0x1b80, 0x1b00, 0x1004, 0x0004,
+    0x1000, 0x0004, 0x1004, 0x1004,
+    0x1280, 0x200a, 0x200b, 0x200b,
+    0x200b, 0x200b, 0x1008, 0x1008,
+    0x1008, 0x1000, 0x1000, 0x1000,
+    0x1009, 0x1b00, 0x1b00, 0x1400,
+    0x1780, 0x1980, 0x1b00, 0x1b00,
+    0x1b00, 0x0004, 0x0004, 0x0044,
+    0x2004, 0x2400, 0x2400, 0x2400,
+    0x2680, 0x2580, 0x2580, 0x2700,
+    0x2400, 0x2600, 0x1000, 0x1000,
+    0x0184, 0x0004, 0x0184, 0x0004,
+    0x0184, 0x0184, 0x0004, 0x2400,
+    0x2700, 0x2400, 0x2700, 0x2400,
+    0x2b80, 0x2b80, 0x2700, 0x2400,
+    0x2b80, 0x2b80, 0x2b80, 0x2400,
+    0x2b80, 0x2400, 0x1000, 0x2680,
+    0x2680, 0x2580, 0x2580, 0x2b80,
+    0x2a00, 0x2980, 0x2b80, 0x1b00,
+    0x1000, 0x1000, 0x0084, 0x1000,
+    0x2580, 0x2b80, 0x2b80, 0x2a00,
+    0x2980, 0x2b80, 0x2b80, 0x2400,
+    0x2b80, 0x2400, 0x2b80, 0x2b80,
+    0x2680, 0x2680, 0x2b80, 0x2b80,
+    0x2b80, 0x2580, 0x2b80, 0x2b80,
+    0x2b80, 0x2700, 0x2b80, 0x2400,
+    0x2b80, 0x2700, 0x2400, 0x1400,
+    0x1700, 0x1400, 0x1680, 0x1b80,
+    0x1000, 0x1b80, 0x1b80, 0x1b80,
+    0x1684, 0x1684, 0x1b80, 0x1b80,
+    0x1b80, 0x1000, 0x1000, 0x1000,
+    0x2980, 0x2a00, 0x2b80, 0x2b80,
+    0x2a00, 0x2a00, 0x1000, 0x1280,
+    0x1280, 0x1280, 0x1000, 0x1b00,
+    0x0004, 0x1000, 0x1000, 0x1b00,
+    0x1b00, 0x1000, 0x1280, 0x1b00,
+    0x0004, 0x0004, 0x1000, 0x1280,
+    0x1280, 0x1600, 0x1000, 0x0004,
+    0x0004, 0x1280, 0x1b00, 0x1b00,
+    0x1000, 0x0004, 0x1280, 0x1000,
+    0x1000, 0x1000, 0x0184, 0x1b00,
+    0x0906, 0x1280, 0x1280, 0x0004,
+    0x1000, 0x0906, 0x1000, 0x1000,
+    0x0004, 0x1000, 0x1900, 0x1900,
+    0x1b00, 0x1004, 0x1004, 0x1b00,
+    0x1b06, 0x1b06, 0x1280, 0x0004,
+    0x0004, 0x0004, 0x1900, 0x1900,
+    0x1b00, 0x1300, 0x1280, 0x1b00,
+    0x0004, 0x1b00, 0x1280, 0x1000,
+    0x1000, 0x0004, 0x1280, 0x1004,
+    0x1004, 0x1000, 0x1000, 0x1000,
+    0x1b80, 0x0004, 0x1000, 0x1004,
+    0x1000, 0x1004, 0x0004, 0x1006,
+    0x0004, 0x1b80, 0x1b80, 0x1000,
+    0x1b80, 0x0004, 0x1300, 0x1280,
+    0x1280, 0x1580, 0x1580, 0x1b00,
+    0x1b00, 0x0004, 0x1280, 0x1280,
+    0x1b00, 0x1300, 0x1300, 0x1300,
+    0x1300, 0x1000, 0x1000, 0x1000,
+    0x1004, 0x1000, 0x1000, 0x0004,
+    0x1004, 0x0004, 0x1006, 0x1004,
+    0x1006, 0x1004, 0x0004, 0x1004,
+    0x1b06, 0x0004, 0x0004, 0x0004,
+    0x1300, 0x1280, 0x1300, 0x1b00,
+    0x0004, 0x1b06, 0x1b06, 0x1b06,
+    0x1b06, 0x0004, 0x0004, 0x1300,
+    0x1280, 0x1280, 0x1000, 0x1000,
+    0x1300, 0x1580, 0x1b00, 0x1b00,
+    0x1000, 0x1004, 0x0004, 0x0004,
+    0x1b06, 0x0004, 0x0004, 0x1000,
+    0x1004, 0x1000, 0x1000, 0x1280,
+    0x0004, 0x0004, 0x1006, 0x1004,
+    0x1780, 0x1780, 0x1780, 0x1400,
+    0x1400, 0x1b00, 0x1b00, 0x1400,
+    0x1b00, 0x1780, 0x1400, 0x1400,
+    0x0184, 0x0184, 0x0184, 0x0784,
+    0x0404, 0x0184, 0x0184, 0x0184,
+    0x0784, 0x0404, 0x0784, 0x0404,
+    0x1280, 0x1b00, 0x1000, 0x1000,
+    0x1900, 0x1900, 0x1000, 0x1b00,
+    0x1b00, 0x1b00, 0x1b08, 0x1b08,
+    0x1b08, 0x1b00, 0x2680, 0x2680,
+    0x2680, 0x2680, 0x0184, 0x1000,
+    0x1000, 0x1000, 0x2004, 0x2004,
+    0x1000, 0x1000, 0x2b00, 0x2b00,
+    0x1000, 0x1000, 0x1000, 0x2b00,
+    0x2b00, 0x2b00, 0x1000, 0x1000,
+    0x2000, 0x1000, 0x2000, 0x2000,
+    0x2000, 0x1000, 0x2000, 0x1000,
+    0x1000, 0x2b00, 0x2b00, 0x2b00,
+    0x1b00, 0x0004, 0x1b00, 0x1b00,
+    0x1280, 0x1b00, 0x1900, 0x1900,
+    0x1900, 0x1000, 0x1000, 0x1780,
+    0x1780, 0x2b8e, 0x1b8e, 0x1b8e,
+    0x1b8e, 0x2b8e, 0x3000, 0x3000,
+    0x3000, 0x1000, 0x1b0e, 0x1b0e,
+    0x1b0e, 0x3000, 0x3000, 0x1b00,
+    0x1b0e, 0x300e, 0x300e, 0x3000,
+    0x3000, 0x300e, 0x300e, 0x3000,
+    0x3000, 0x200e, 0x3000, 0x200e,
+    0x200e, 0x200e, 0x200e, 0x3000,
+    0x1b0e, 0x1b8e, 0x1b8e, 0x1005,
+    0x1005, 0x1005, 0x1005, 0x2b80,
+    0x2b8e, 0x2b8e, 0x1b8e, 0x2b80,
+    0x2b80, 0x2b8e, 0x2b8e, 0x2b8e,
+    0x2b8e, 0x2b80, 0x1b8e, 0x1b8e,
+    0x1b8e, 0x2b8e, 0x2b8e, 0x2b8e,
+    0x1b8e, 0x2b8e, 0x2b8e, 0x1b0e,
+    0x1b0e, 0x1b8e, 0x1b8e, 0x2b8e,
+    0x2b0e, 0x2b0e, 0x2b8e, 0x2b8e,
+    0x2b8e, 0x2b84, 0x2b84, 0x2b84,
+    0x2b84, 0x2b0e, 0x2b8e, 0x2b0e,
+    0x2b8e, 0x2b8e, 0x2b8e, 0x2b0e,
+    0x2b0e, 0x2b0e, 0x2b8e, 0x2b0e,
+    0x2b0e, 0x1b00, 0x1b00, 0x1b0e,
+    0x1b0e, 0x1b8e, 0x2b8e, 0x1b8e,
+    0x1b8e, 0x2b8e, 0x1b8e, 0x1800,
+    0x1680, 0x1680, 0x1680, 0x100e,
+    0x100e, 0x100e, 0x100e, 0x1b00,
+    0x1b00, 0x100e, 0x100e, 0x1b0e,
+    0x1b0e, 0x100e, 0x100e, 0x2b8e,
+    0x2b8e, 0x2b8e, 0x1b80, 0x2b8e,
+    0x1b8e, 0x1b8e, 0x1000, 0x1000,
+    0x1003, 0x0004, 0x1003, 0x1003,
+    0x3000, 0x3000, 0x1000, 0x1000,
 ];
 #[rustfmt::skip]
 const GRAPHEME_JOIN_RULES: [[u32; 16]; 2] = [
@@ -1128,7 +1128,7 @@ pub fn ucd_grapheme_cluster_joins_done(state: u32) -> bool {
 }
 #[inline(always)]
 pub fn ucd_grapheme_cluster_character_width(val: usize, ambiguous_width: usize) -> usize {
-    let mut w = val >> 11;
+    let mut w = val >> 12;
     if w > 2 {
         cold_path();
         w = ambiguous_width;
@@ -1138,23 +1138,27 @@ pub fn ucd_grapheme_cluster_character_width(val: usize, ambiguous_width: usize)
 #[inline(always)]
 pub fn ucd_line_break_joins(lead: usize, trail: usize) -> bool {
     unsafe {
-        let l = (lead >> 6) & 31;
-        let t = (trail >> 6) & 31;
+        let l = (lead >> 7) & 31;
+        let t = (trail >> 7) & 31;
         let s = *LINE_BREAK_JOIN_RULES.get_unchecked(l);
         ((s >> t) & 1) != 0
     }
 }
 #[inline(always)]
 pub fn ucd_start_of_text_properties() -> usize {
-    0x603
+    0xc03
 }
 #[inline(always)]
 pub fn ucd_tab_properties() -> usize {
-    0x963
+    0x12a3
 }
 #[inline(always)]
 pub fn ucd_linefeed_properties() -> usize {
-    0x802
+    0x1002
+}
+#[inline(always)]
+pub fn ucd_text_presentation_properties() -> usize {
+    0x44
 }
 #[cold]
 #[inline(always)]
@@ -69,6 +69,8 @@ pub struct Parser {
     // Csi is not part of State, because it allows us
     // to more quickly erase and reuse the struct.
     csi: Csi,
+    // True while skipping a `:` separated sub-parameter.
+    csi_subparam: bool,
 }
 
 impl Parser {
@@ -76,6 +78,7 @@ impl Parser {
         Self {
             state: State::Ground,
             csi: Csi { params: [0; 32], param_count: 0, private_byte: '\0', final_byte: '\0' },
+            csi_subparam: false,
         }
     }
 
@@ -191,6 +194,7 @@ impl<'input> Stream<'_, 'input> {
                         self.parser.state = State::Csi;
                         self.parser.csi.private_byte = '\0';
                         self.parser.csi.final_byte = '\0';
+                        self.parser.csi_subparam = false;
                         while self.parser.csi.param_count > 0 {
                             self.parser.csi.param_count -= 1;
                             self.parser.csi.params[self.parser.csi.param_count] = 0;
@@ -217,7 +221,10 @@ impl<'input> Stream<'_, 'input> {
                 State::Csi => {
                     loop {
                         // If we still have slots left, parse the parameter.
-                        if self.parser.csi.param_count < self.parser.csi.params.len() {
+                        // Sub-parameters (as in `CSI 97:65;5u`) aren't needed by anyone so far.
+                        if self.parser.csi.param_count < self.parser.csi.params.len()
+                            && !self.parser.csi_subparam
+                        {
                             let dst = &mut self.parser.csi.params[self.parser.csi.param_count];
                             while self.off < bytes.len() && bytes[self.off].is_ascii_digit() {
                                 let add = bytes[self.off] as u32 - b'0' as u32;
@@ -251,7 +258,11 @@ impl<'input> Stream<'_, 'input> {
                                 }
                                 return Some(Token::Csi(&self.parser.csi));
                             }
-                            b';' => self.parser.csi.param_count += 1,
+                            b';' => {
+                                self.parser.csi.param_count += 1;
+                                self.parser.csi_subparam = false;
+                            }
+                            b':' => self.parser.csi_subparam = true,
                             b'<'..=b'?' => self.parser.csi.private_byte = c as char,
                             _ => {}
                         }
@@ -0,0 +1,85 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use std::fs;
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+fn edit() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_edit"))
+}
+
+/// A scratch directory that's removed again when the test ends, even if it fails.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("edit-batch-{}-{name}", std::process::id()));
+        _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+
+    fn file(&self, name: &str, contents: &[u8]) -> PathBuf {
+        let path = self.0.join(name);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+fn test_replace_and_normalize_eol() {
+    let dir = TempDir::new("replace");
+    let path = dir.file("replace.txt", b"foo bar\r\nfoo\n");
+    let status = edit()
+        .args(["--replace", "f(o+)", "x$1", "--regex", "--normalize-eol", "lf"])
+        .arg(&path)
+        .status()
+        .unwrap();
+    assert_eq!(status.code(), Some(0));
+    assert_eq!(fs::read(&path).unwrap(), b"xoo bar\nxoo\n");
+}
+
+#[test]
+fn test_convert_encoding() {
+    let dir = TempDir::new("encoding");
+    let path = dir.file("encoding.txt", b"ab\n");
+    let status = edit().args(["--convert-encoding", "utf-16le"]).arg(&path).status().unwrap();
+    assert_eq!(status.code(), Some(0));
+    assert_eq!(fs::read(&path).unwrap(), b"\xff\xfea\0b\0\n\0");
+}
+
+#[test]
+fn test_filter_stdin() {
+    let mut child = edit()
+        .args(["--replace", "hello", "bye"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"hello world\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(output.stdout, b"bye world\n");
+}
+
+#[test]
+fn test_exit_codes() {
+    let status = edit().args(["--normalize-eol", "cr"]).status().unwrap();
+    assert_eq!(status.code(), Some(2));
+
+    let status = edit().args(["--regex", "file.txt"]).status().unwrap();
+    assert_eq!(status.code(), Some(2));
+
+    let dir = TempDir::new("exit-codes");
+    let path = dir.0.join("does-not-exist.txt");
+    let status = edit().args(["--replace", "a", "b"]).arg(&path).status().unwrap();
+    assert_eq!(status.code(), Some(1));
+}
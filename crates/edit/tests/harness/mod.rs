@@ -0,0 +1,142 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! A headless test harness for the TUI.
+//!
+//! It drives [`Tui`] with scripted [`Input`] events the same way the editor's main loop does,
+//! renders the result into the framebuffer and compares [`Tui::snapshot()`] against
+//! golden files in `tests/snapshots/`.
+//!
+//! Run the tests with `EDIT_UPDATE_SNAPSHOTS=1` to create or update the golden files.
+
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::PathBuf;
+
+use edit::framebuffer::IndexedColor;
+use edit::helpers::{Point, Size};
+use edit::input::{Input, InputKey, InputKeyMod, InputMouse, InputMouseState, kbmod};
+use edit::theme::Theme;
+use edit::tui::{Context, Tui};
+use stdext::arena::scratch_arena;
+
+pub struct Harness {
+    tui: Tui,
+}
+
+impl Harness {
+    /// Creates a new harness with a viewport of the given size.
+    ///
+    /// The floater and modal colors are set up the same way as in the editor.
+    pub fn new(size: Size) -> Self {
+        let mut tui = Tui::new().unwrap();
+
+        let floater_bg = tui
+            .indexed_alpha(IndexedColor::Background, 2, 3)
+            .oklab_blend(tui.indexed_alpha(IndexedColor::Foreground, 1, 3));
+        let floater_fg = tui.contrasted(floater_bg);
+        tui.set_floater_default_bg(floater_bg);
+        tui.set_floater_default_fg(floater_fg);
+        tui.set_modal_default_bg(floater_bg);
+        tui.set_modal_default_fg(floater_fg);
+
+        tui.create_context(Some(Input::Resize(size)));
+        Self { tui }
+    }
+
+    /// Sets the theme that the following frames are styled with.
+    pub fn set_theme(&mut self, source: &str) {
+        self.tui.set_theme(Theme::parse(source).unwrap());
+    }
+
+    /// Processes a single input event and draws frames until the layout has settled.
+    ///
+    /// `draw` is called once per frame and must build the same UI each time.
+    pub fn input(&mut self, input: Option<Input>, draw: &mut dyn FnMut(&mut Context)) {
+        {
+            let mut ctx = self.tui.create_context(input);
+            draw(&mut ctx);
+        }
+        while self.tui.needs_settling() {
+            let mut ctx = self.tui.create_context(None);
+            draw(&mut ctx);
+        }
+    }
+
+    /// Draws the UI without any input.
+    pub fn draw(&mut self, draw: &mut dyn FnMut(&mut Context)) {
+        self.input(None, draw);
+    }
+
+    /// Types the given text.
+    pub fn text(&mut self, text: &str, draw: &mut dyn FnMut(&mut Context)) {
+        self.input(Some(Input::Text(text)), draw);
+    }
+
+    /// Presses the given key.
+    pub fn key(&mut self, key: InputKey, draw: &mut dyn FnMut(&mut Context)) {
+        self.input(Some(Input::Keyboard(key)), draw);
+    }
+
+    /// Clicks the left mouse button at the given position.
+    pub fn click(&mut self, position: Point, draw: &mut dyn FnMut(&mut Context)) {
+        self.mouse(InputMouseState::Left, kbmod::NONE, position, draw);
+        self.mouse(InputMouseState::None, kbmod::NONE, position, draw);
+    }
+
+    /// Sends a single mouse event.
+    pub fn mouse(
+        &mut self,
+        state: InputMouseState,
+        modifiers: InputKeyMod,
+        position: Point,
+        draw: &mut dyn FnMut(&mut Context),
+    ) {
+        let mouse = InputMouse { state, modifiers, position, scroll: Point::default() };
+        self.input(Some(Input::Mouse(mouse)), draw);
+    }
+
+    /// Renders the last frame and returns its snapshot.
+    pub fn render(&mut self) -> String {
+        let scratch = scratch_arena(None);
+        self.tui.render(&scratch);
+        self.tui.snapshot()
+    }
+
+    /// Renders the last frame and compares it against `tests/snapshots/{name}.txt`.
+    pub fn assert_snapshot(&mut self, name: &str) {
+        let actual = self.render();
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("snapshots")
+            .join(format!("{name}.txt"));
+
+        if std::env::var_os("EDIT_UPDATE_SNAPSHOTS").is_some() {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, &actual).unwrap();
+            return;
+        }
+
+        let Ok(expected) = fs::read_to_string(&path) else {
+            panic!(
+                "missing snapshot {}, run with EDIT_UPDATE_SNAPSHOTS=1 to create it:\n{actual}",
+                path.display()
+            );
+        };
+        // Git may have converted the line endings on checkout.
+        let expected = expected.replace("\r\n", "\n");
+        if expected != actual {
+            let mut diff = String::new();
+            for (i, (e, a)) in expected.lines().zip(actual.lines()).enumerate() {
+                if e != a {
+                    diff.push_str(&format!("{:4} - {e}\n{:4} + {a}\n", i + 1, i + 1));
+                }
+            }
+            panic!(
+                "snapshot {} doesn't match, run with EDIT_UPDATE_SNAPSHOTS=1 to update it:\n{diff}\nactual:\n{actual}",
+                path.display()
+            );
+        }
+    }
+}
@@ -0,0 +1,257 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+mod harness;
+
+use std::cell::Cell;
+
+use edit::buffer::{RcTextBuffer, TextBuffer};
+use edit::helpers::{Point, Rect, Size};
+use edit::input::{kbmod, vk};
+use edit::tui::{ButtonStyle, Context, ListSelection, Position, TreeAction, TreeItemState};
+use harness::Harness;
+
+fn draw_menubar(ctx: &mut Context, clicked: &mut Option<&'static str>) {
+    ctx.menubar_begin();
+    if ctx.menubar_menu_begin("File", 'F') {
+        if ctx.menubar_menu_button("New File", 'N', kbmod::CTRL | vk::N) {
+            *clicked = Some("new");
+        }
+        if ctx.menubar_menu_button("Save", 'S', kbmod::CTRL | vk::S) {
+            *clicked = Some("save");
+        }
+        ctx.menubar_menu_end();
+    }
+    if ctx.menubar_menu_begin("Edit", 'E') {
+        ctx.menubar_menu_button("Undo", 'U', kbmod::CTRL | vk::Z);
+        ctx.menubar_menu_end();
+    }
+    ctx.menubar_end();
+}
+
+#[test]
+fn test_menubar() {
+    let mut harness = Harness::new(Size { width: 30, height: 8 });
+    let mut clicked = None;
+    let mut draw = |ctx: &mut Context| draw_menubar(ctx, &mut clicked);
+
+    harness.draw(&mut draw);
+    harness.assert_snapshot("menubar_closed");
+
+    harness.click(Point { x: 1, y: 0 }, &mut draw);
+    harness.assert_snapshot("menubar_file_open");
+
+    // The first Down moves the focus from the menubar into the flyout.
+    harness.key(vk::DOWN, &mut draw);
+    harness.key(vk::DOWN, &mut draw);
+    harness.assert_snapshot("menubar_file_second_item");
+
+    harness.key(vk::RETURN, &mut draw);
+    assert_eq!(clicked, Some("save"));
+}
+
+#[test]
+fn test_modal() {
+    let mut harness = Harness::new(Size { width: 40, height: 10 });
+    let mut choice = None;
+    let mut draw = |ctx: &mut Context| {
+        ctx.modal_begin("unsaved-changes", "Unsaved Changes");
+        {
+            ctx.label("description", "Do you want to save the changes?");
+            ctx.attr_padding(Rect::three(1, 2, 1));
+
+            ctx.table_begin("choices");
+            ctx.inherit_focus();
+            ctx.attr_padding(Rect::three(0, 2, 1));
+            ctx.attr_position(Position::Center);
+            ctx.table_set_cell_gap(Size { width: 2, height: 0 });
+            {
+                ctx.table_next_row();
+                ctx.inherit_focus();
+
+                if ctx.button("yes", "Save", ButtonStyle::default().accelerator('S')) {
+                    choice = Some("yes");
+                }
+                ctx.inherit_focus();
+                if ctx.button("no", "Don't Save", ButtonStyle::default().accelerator('N')) {
+                    choice = Some("no");
+                }
+            }
+            ctx.table_end();
+        }
+        ctx.modal_end();
+    };
+
+    harness.draw(&mut draw);
+    harness.assert_snapshot("modal");
+
+    harness.key(vk::RIGHT, &mut draw);
+    harness.assert_snapshot("modal_second_button");
+
+    harness.key(vk::RETURN, &mut draw);
+    assert_eq!(choice, Some("no"));
+}
+
+#[test]
+fn test_themed_button() {
+    let mut harness = Harness::new(Size { width: 20, height: 3 });
+    harness
+        .set_theme("button:focused { background: #ff0000; foreground: #00ff00; reverse: false; }");
+    let mut draw = |ctx: &mut Context| {
+        ctx.button("ok", "OK", ButtonStyle::default());
+        ctx.inherit_focus();
+        ctx.button("cancel", "Cancel", ButtonStyle::default());
+    };
+
+    harness.draw(&mut draw);
+    let snapshot = harness.render();
+    assert!(snapshot.contains("bg=#ff0000ff fg=#00ff00ff"), "{snapshot}");
+}
+
+#[test]
+fn test_completion_popup() {
+    let tb: RcTextBuffer = TextBuffer::new_rc(true).unwrap();
+    tb.borrow_mut().write_canon(b"hello helium help\n");
+
+    let mut harness = Harness::new(Size { width: 40, height: 10 });
+    let mut draw = |ctx: &mut Context| {
+        ctx.textarea("textarea", tb.clone());
+        ctx.inherit_focus();
+        ctx.attr_intrinsic_size(Size { width: 0, height: 10 });
+    };
+
+    harness.draw(&mut draw);
+    harness.text("he", &mut draw);
+    harness.assert_snapshot("completion_popup");
+
+    harness.key(vk::DOWN, &mut draw);
+    harness.assert_snapshot("completion_popup_second_item");
+
+    harness.key(vk::ESCAPE, &mut draw);
+    harness.assert_snapshot("completion_popup_closed");
+}
+
+#[test]
+fn test_tree() {
+    // (depth, name, is_dir)
+    let mut items = vec![(0, "src", true), (0, "README.md", false)];
+    let mut expanded = false;
+    let selected = Cell::new(0);
+
+    let mut harness = Harness::new(Size { width: 20, height: 6 });
+    let mut draw = |ctx: &mut Context| {
+        let mut toggle = false;
+
+        ctx.tree_begin("tree");
+        ctx.inherit_focus();
+        for (idx, &(depth, name, is_dir)) in items.iter().enumerate() {
+            let state = match (is_dir, expanded) {
+                (false, _) => TreeItemState::Leaf,
+                (true, false) => TreeItemState::Collapsed,
+                (true, true) => TreeItemState::Expanded,
+            };
+            match ctx.tree_item(false, depth, state, name) {
+                TreeAction::Unchanged => {}
+                TreeAction::Selected => selected.set(idx),
+                TreeAction::Activated => toggle = is_dir,
+                TreeAction::Expand | TreeAction::Collapse => toggle = true,
+            }
+        }
+        ctx.tree_end();
+
+        // Children are "loaded" lazily, once the directory is expanded.
+        if toggle {
+            expanded = !expanded;
+            if expanded {
+                items.splice(1..1, [(1, "main.rs", false), (1, "lib.rs", false)]);
+            } else {
+                items.drain(1..3);
+            }
+            ctx.needs_rerender();
+        }
+    };
+
+    harness.draw(&mut draw);
+    harness.assert_snapshot("tree_collapsed");
+
+    harness.key(vk::RIGHT, &mut draw);
+    harness.assert_snapshot("tree_expanded");
+
+    // Right on an expanded item moves to its first child, Left back to the parent.
+    harness.key(vk::RIGHT, &mut draw);
+    assert_eq!(selected.get(), 1);
+    harness.key(vk::DOWN, &mut draw);
+    assert_eq!(selected.get(), 2);
+    harness.key(vk::LEFT, &mut draw);
+    assert_eq!(selected.get(), 0);
+
+    harness.key(vk::LEFT, &mut draw);
+    harness.assert_snapshot("tree_collapsed");
+}
+
+#[test]
+fn test_tree_empty_dir() {
+    let expanded = Cell::new(false);
+    let selected = Cell::new(0);
+
+    let mut harness = Harness::new(Size { width: 20, height: 6 });
+    let mut draw = |ctx: &mut Context| {
+        let state = if expanded.get() { TreeItemState::Expanded } else { TreeItemState::Collapsed };
+
+        ctx.tree_begin("tree");
+        ctx.inherit_focus();
+        for (idx, (state, name)) in
+            [(state, "empty"), (TreeItemState::Leaf, "README.md")].into_iter().enumerate()
+        {
+            match ctx.tree_item(false, 0, state, name) {
+                TreeAction::Selected => selected.set(idx),
+                TreeAction::Expand => expanded.set(true),
+                _ => {}
+            }
+        }
+        ctx.tree_end();
+    };
+
+    harness.draw(&mut draw);
+    harness.key(vk::RIGHT, &mut draw);
+    assert!(expanded.get());
+
+    // There's no child to move to, so Right must not select the next sibling.
+    harness.key(vk::RIGHT, &mut draw);
+    assert_eq!(selected.get(), 0);
+}
+
+#[test]
+fn test_list_with_columns() {
+    let rows = [("rw-", "12K", "a.txt"), ("r--", "3", "b.txt")];
+    let mut activated = None;
+
+    let mut harness = Harness::new(Size { width: 20, height: 3 });
+    let mut draw = |ctx: &mut Context| {
+        ctx.list_begin("files");
+        ctx.inherit_focus();
+        for (idx, &(permissions, size, name)) in rows.iter().enumerate() {
+            ctx.list_item_begin();
+            {
+                ctx.table_begin("columns");
+                ctx.table_set_columns(&[5, 4, 10]);
+                ctx.table_next_row();
+                ctx.label("permissions", &format!("  {permissions}"));
+                ctx.label("size", size);
+                ctx.label("name", name);
+                ctx.table_end();
+            }
+            if ctx.list_item_end(false) == ListSelection::Activated {
+                activated = Some(idx);
+            }
+        }
+        ctx.list_end();
+    };
+
+    harness.draw(&mut draw);
+    harness.key(vk::DOWN, &mut draw);
+    harness.assert_snapshot("list_with_columns");
+
+    harness.key(vk::RETURN, &mut draw);
+    assert_eq!(activated, Some(1));
+}
@@ -0,0 +1,282 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Bidi_Class, Bidi_Mirroring_Glyph and Bidi_Paired_Bracket_Type tables
+//! for the Unicode Bidirectional Algorithm (UAX #9).
+
+use std::fmt::Write as FmtWrite;
+
+use anyhow::{Context, bail};
+use indoc::writedoc;
+
+use crate::{Language, extract_range};
+
+// `BidiClass` is 5 bits.
+// NOTE: The order of these items must match `unicode::BidiClass` in the editor.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
+enum BidiClass {
+    L,
+    R,
+    AL,
+    EN,
+    ES,
+    ET,
+    AN,
+    CS,
+    NSM,
+    BN,
+    B,
+    S,
+    WS,
+    ON,
+    LRE,
+    LRO,
+    RLE,
+    RLO,
+    PDF,
+    LRI,
+    RLI,
+    FSI,
+    PDI,
+}
+
+// `BracketType` is 2 bits and stored above the 21 bits of the mirrored codepoint.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum BracketType {
+    None,
+    Open,
+    Close,
+}
+
+pub struct Bidi {
+    description: String,
+    /// Each run is `(first codepoint << 5) | class` and lasts until the next one.
+    runs: Vec<u32>,
+    /// `(codepoint, mirrored codepoint | bracket type << 21)`, sorted by codepoint.
+    mirrors: Vec<(u32, u32)>,
+}
+
+impl Bidi {
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn total_size(&self) -> usize {
+        self.runs.len() * 4 + self.mirrors.len() * 8
+    }
+}
+
+pub fn extract_bidi_from_ucd(doc: &roxmltree::Document) -> anyhow::Result<Bidi> {
+    let mut classes = vec![BidiClass::L; 1114112];
+    let mut mirrors = Vec::new();
+
+    let ns = "http://www.unicode.org/ns/2003/ucd/1.0";
+    let root = doc.root_element();
+    let description = root
+        .children()
+        .find(|n| n.has_tag_name((ns, "description")))
+        .context("missing ucd description")?;
+    let repertoire = root
+        .children()
+        .find(|n| n.has_tag_name((ns, "repertoire")))
+        .context("missing ucd repertoire")?;
+    let description = description.text().unwrap_or_default().to_string();
+
+    for group in repertoire.children().filter(|n| n.is_element()) {
+        let group_bc = group.attribute("bc").unwrap_or("L");
+        let group_bmg = group.attribute("bmg").unwrap_or("");
+        let group_bpt = group.attribute("bpt").unwrap_or("n");
+
+        for char in group.children().filter(|n| n.is_element()) {
+            let range = extract_range(&char);
+            let bc = char.attribute("bc").unwrap_or(group_bc);
+            let bmg = char.attribute("bmg").unwrap_or(group_bmg);
+            let bpt = char.attribute("bpt").unwrap_or(group_bpt);
+
+            let class = match bc {
+                "L" => BidiClass::L,
+                "R" => BidiClass::R,
+                "AL" => BidiClass::AL,
+                "EN" => BidiClass::EN,
+                "ES" => BidiClass::ES,
+                "ET" => BidiClass::ET,
+                "AN" => BidiClass::AN,
+                "CS" => BidiClass::CS,
+                "NSM" => BidiClass::NSM,
+                "BN" => BidiClass::BN,
+                "B" => BidiClass::B,
+                "S" => BidiClass::S,
+                "WS" => BidiClass::WS,
+                "ON" => BidiClass::ON,
+                "LRE" => BidiClass::LRE,
+                "LRO" => BidiClass::LRO,
+                "RLE" => BidiClass::RLE,
+                "RLO" => BidiClass::RLO,
+                "PDF" => BidiClass::PDF,
+                "LRI" => BidiClass::LRI,
+                "RLI" => BidiClass::RLI,
+                "FSI" => BidiClass::FSI,
+                "PDI" => BidiClass::PDI,
+                _ => bail!(
+                    "Unrecognized bc={} for U+{:04X} to U+{:04X}",
+                    bc,
+                    range.start(),
+                    range.end()
+                ),
+            };
+            classes[range.clone()].fill(class);
+
+            // "#" refers to the character itself, which is the same as having no mirror.
+            if matches!(bmg, "" | "#") {
+                continue;
+            }
+
+            let bracket = match bpt {
+                "n" => BracketType::None,
+                "o" => BracketType::Open,
+                "c" => BracketType::Close,
+                _ => bail!(
+                    "Unrecognized bpt={} for U+{:04X} to U+{:04X}",
+                    bpt,
+                    range.start(),
+                    range.end()
+                ),
+            };
+            let mirror = u32::from_str_radix(bmg, 16)?;
+            for cp in range {
+                mirrors.push((cp as u32, mirror | ((bracket as u32) << 21)));
+            }
+        }
+    }
+
+    mirrors.sort_unstable();
+
+    let mut runs = Vec::new();
+    let mut prev = None;
+    for (cp, &class) in classes.iter().enumerate() {
+        if prev != Some(class) {
+            runs.push(((cp as u32) << 5) | class as u32);
+            prev = Some(class);
+        }
+    }
+
+    Ok(Bidi { description, runs, mirrors })
+}
+
+pub fn generate_bidi(lang: Language, bidi: &Bidi, header: &str) -> String {
+    let mut buf = String::new();
+    match lang {
+        Language::C => generate_bidi_c(&mut buf, bidi, header),
+        Language::Rust => generate_bidi_rust(&mut buf, bidi, header),
+    }
+    buf
+}
+
+fn generate_bidi_c(buf: &mut String, bidi: &Bidi, header: &str) {
+    _ = writeln!(buf, "{header}\n// clang-format off");
+
+    _ = write!(buf, "static const uint32_t s_bidi_class_runs[] = {{");
+    for (i, r) in bidi.runs.iter().enumerate() {
+        if i % 16 == 0 {
+            buf.push_str("\n   ");
+        }
+        _ = write!(buf, " 0x{r:08x},");
+    }
+    buf.push_str("\n};\n");
+
+    _ = writeln!(buf, "static const uint32_t s_bidi_mirrors[][2] = {{");
+    for &(cp, mirror) in &bidi.mirrors {
+        _ = writeln!(buf, "    {{ 0x{cp:06x}, 0x{mirror:06x} }},");
+    }
+    buf.push_str("};\n");
+
+    _ = writedoc!(
+        buf,
+        "
+        inline int ucd_bidi_class(const uint32_t cp)
+        {{
+            size_t lo = 0;
+            size_t hi = sizeof(s_bidi_class_runs) / sizeof(s_bidi_class_runs[0]);
+            while (hi - lo > 1) {{
+                const size_t mid = (lo + hi) / 2;
+                if ((s_bidi_class_runs[mid] >> 5) <= cp) {{
+                    lo = mid;
+                }} else {{
+                    hi = mid;
+                }}
+            }}
+            return s_bidi_class_runs[lo] & 31;
+        }}
+        inline uint32_t ucd_bidi_mirror(const uint32_t cp)
+        {{
+            size_t lo = 0;
+            size_t hi = sizeof(s_bidi_mirrors) / sizeof(s_bidi_mirrors[0]);
+            while (lo < hi) {{
+                const size_t mid = (lo + hi) / 2;
+                if (s_bidi_mirrors[mid][0] < cp) {{
+                    lo = mid + 1;
+                }} else {{
+                    hi = mid;
+                }}
+            }}
+            return lo < sizeof(s_bidi_mirrors) / sizeof(s_bidi_mirrors[0]) && s_bidi_mirrors[lo][0] == cp ? s_bidi_mirrors[lo][1] : 0;
+        }}
+        "
+    );
+
+    buf.push_str("// clang-format on\n// END: Generated by grapheme-table-gen\n");
+}
+
+fn generate_bidi_rust(buf: &mut String, bidi: &Bidi, header: &str) {
+    _ = writeln!(buf, "{header}");
+
+    _ = write!(buf, "#[rustfmt::skip]\nconst BIDI_CLASS_RUNS: [u32; {}] = [", bidi.runs.len());
+    for (i, r) in bidi.runs.iter().enumerate() {
+        if i % 16 == 0 {
+            buf.push_str("\n   ");
+        }
+        _ = write!(buf, " 0x{r:08x},");
+    }
+    buf.push_str("\n];\n");
+
+    _ = write!(
+        buf,
+        "#[rustfmt::skip]\nconst BIDI_MIRRORS: [(u32, u32); {}] = [",
+        bidi.mirrors.len()
+    );
+    for (i, &(cp, mirror)) in bidi.mirrors.iter().enumerate() {
+        if i % 8 == 0 {
+            buf.push_str("\n   ");
+        }
+        _ = write!(buf, " (0x{cp:06x}, 0x{mirror:06x}),");
+    }
+    buf.push_str("\n];\n");
+
+    _ = writedoc!(
+        buf,
+        "
+        /// Returns the Bidi_Class of `cp` in the order of `BidiClass`.
+        #[inline]
+        pub fn ucd_bidi_class(cp: char) -> u8 {{
+            let cp = cp as u32;
+            let i = BIDI_CLASS_RUNS.partition_point(|&r| (r >> 5) <= cp);
+            // The first run starts at U+0000, so `i` is at least 1.
+            (unsafe {{ *BIDI_CLASS_RUNS.get_unchecked(i - 1) }} & 31) as u8
+        }}
+        /// Returns the Bidi_Mirroring_Glyph of `cp` in the lower 21 bits
+        /// and its Bidi_Paired_Bracket_Type in the 2 bits above (1 = open, 2 = close).
+        /// Returns 0 if `cp` doesn't have a mirrored glyph.
+        #[inline]
+        pub fn ucd_bidi_mirror(cp: char) -> u32 {{
+            let cp = cp as u32;
+            match BIDI_MIRRORS.binary_search_by_key(&cp, |&(c, _)| c) {{
+                Ok(i) => BIDI_MIRRORS[i].1,
+                Err(_) => 0,
+            }}
+        }}
+        "
+    );
+
+    buf.push_str("// END: Generated by grapheme-table-gen\n");
+}
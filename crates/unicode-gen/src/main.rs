@@ -1,13 +1,15 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
+mod bidi;
 mod rules;
 
 use std::collections::HashMap;
 use std::fmt::Write as FmtWrite;
 use std::io::Write as IoWrite;
 use std::ops::RangeInclusive;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
 use anyhow::{Context, bail};
 use indoc::writedoc;
@@ -48,11 +50,12 @@ enum ClusterBreak {
 }
 
 // Extended information for each `ClusterBreak` via --extended.
-// Currently only used for storing the subtype "tab" for `ClusterBreak::Control`.
-// As such, this is 1 bit.
+// Currently only used for storing the subtypes "tab" for `ClusterBreak::Control`
+// and "text presentation selector" for `ClusterBreak::Extend`. As such, this is 2 bits.
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum ClusterBreakExt {
     ControlTab = 1,
+    ExtendTextPresentation = 2,
 }
 
 // `LineBreak` is 5 bits.
@@ -158,7 +161,7 @@ struct BitPacking {
 impl BitPacking {
     fn new(line_breaks: bool, extended: bool) -> Self {
         let cb_width: u32 = if extended { 5 } else { 4 };
-        let cb_ext_width: u32 = if extended { 1 } else { 0 };
+        let cb_ext_width: u32 = if extended { 2 } else { 0 };
         let lb_width: u32 = if line_breaks { 5 } else { 0 };
         let cw_width: u32 = 3;
 
@@ -211,6 +214,7 @@ struct Output {
     arg_extended: bool,
     arg_no_ambiguous: bool,
     arg_line_breaks: bool,
+    arg_bidi: bool,
 
     ucd: Ucd,
     trie: Trie,
@@ -235,6 +239,9 @@ impl Output {
         if self.arg_line_breaks {
             buf.push_str(" --line-breaks")
         }
+        if self.arg_bidi {
+            buf.push_str(" --bidi")
+        }
         buf
     }
 }
@@ -244,9 +251,12 @@ Usage: grapheme-table-gen [options...] <ucd.nounihan.grouped.xml>
   -h, --help            Prints help information
   --lang=<c|rust>       Output language (default: c)
   --extended            Expose a start-of-text property for kick-starting the segmentation
-                        Expose tab and linefeed as grapheme cluster properties
+                        Expose tab, linefeed and VS15 as grapheme cluster properties
   --no-ambiguous        Treat all ambiguous characters as narrow
   --line-breaks         Store and expose line break information
+  --bidi                Generate the bidi class and mirroring tables instead
+  --tests=<dir>         Download the GraphemeBreakTest.txt and LineBreakTest.txt
+                        conformance tests of the same Unicode version into <dir>
 
 Download ucd.nounihan.grouped.xml at:
   https://www.unicode.org/Public/UCD/latest/ucdxml/ucd.nounihan.grouped.zip
@@ -268,8 +278,10 @@ fn main() -> anyhow::Result<()> {
         arg_extended: args.contains("--extended"),
         arg_no_ambiguous: args.contains("--no-ambiguous"),
         arg_line_breaks: args.contains("--line-breaks"),
+        arg_bidi: args.contains("--bidi"),
         ..Default::default()
     };
+    let arg_tests: Option<PathBuf> = args.opt_value_from_str("--tests")?;
     let arg_input = args.free_from_os_str(|s| -> Result<PathBuf, &'static str> { Ok(s.into()) })?;
     let arg_remaining = args.finish();
     if !arg_remaining.is_empty() {
@@ -278,6 +290,26 @@ fn main() -> anyhow::Result<()> {
 
     let input = std::fs::read_to_string(arg_input)?;
     let doc = roxmltree::Document::parse(&input)?;
+
+    if let Some(dir) = &arg_tests {
+        fetch_tests(&doc, dir)?;
+    }
+
+    // The bidi tables are only needed for rendering and go into a separate file.
+    if out.arg_bidi {
+        let bidi = bidi::extract_bidi_from_ucd(&doc)?;
+        let header = format!(
+            "// BEGIN: Generated by grapheme-table-gen on {}, from {}, with {}, {} bytes",
+            chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            bidi.description(),
+            out.args(),
+            bidi.total_size(),
+        );
+        let buf = bidi::generate_bidi(out.arg_lang, &bidi, &header);
+        std::io::stdout().write_all(buf.as_bytes())?;
+        return Ok(());
+    }
+
     out.ucd = extract_values_from_ucd(&doc, &out)?;
 
     // Find the best trie configuration over the given block sizes (2^2 - 2^8) and stages (4).
@@ -334,6 +366,43 @@ fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+// The conformance tests aren't part of the XML, so they're fetched from the same release instead.
+// edit's conformance tests pick them up if they're placed next to the tables.
+fn fetch_tests(doc: &roxmltree::Document, dir: &Path) -> anyhow::Result<()> {
+    let ns = "http://www.unicode.org/ns/2003/ucd/1.0";
+    // The description is e.g. "Unicode 16.0.0".
+    let description = doc
+        .root_element()
+        .children()
+        .find(|n| n.has_tag_name((ns, "description")))
+        .and_then(|n| n.text())
+        .context("missing ucd description")?;
+    let version = description
+        .split_whitespace()
+        .last()
+        .filter(|v| v.split('.').all(|v| v.parse::<u32>().is_ok()))
+        .with_context(|| format!("no version in ucd description \"{description}\""))?;
+
+    std::fs::create_dir_all(dir)?;
+
+    for name in ["GraphemeBreakTest.txt", "LineBreakTest.txt"] {
+        let url = format!("https://www.unicode.org/Public/{version}/ucd/auxiliary/{name}");
+        let path = dir.join(name);
+        let status = Command::new("curl")
+            .args(["--fail", "--silent", "--show-error", "--location", "--output"])
+            .arg(&path)
+            .arg(&url)
+            .status()
+            .context("failed to run curl")?;
+        if !status.success() {
+            bail!("failed to download {url}: {status}");
+        }
+        eprintln!("Downloaded {}", path.display());
+    }
+
+    Ok(())
+}
+
 fn generate_c(out: Output) -> String {
     let mut buf = String::new();
 
@@ -509,6 +578,10 @@ fn generate_c(out: Output) -> String {
             {{
                 return {:#x};
             }}
+            inline int ucd_text_presentation_properties()
+            {{
+                return {:#x};
+            }}
             ",
             TrieType::new(
                 &out.ucd.packing,
@@ -521,6 +594,7 @@ fn generate_c(out: Output) -> String {
             .value(),
             out.ucd.values['\t' as usize].value(),
             out.ucd.values['\n' as usize].value(),
+            out.ucd.values[0xFE0E].value(),
         );
     }
 
@@ -719,6 +793,10 @@ fn generate_rust(out: Output) -> String {
             pub fn ucd_linefeed_properties() -> usize {{
                 {:#x}
             }}
+            #[inline(always)]
+            pub fn ucd_text_presentation_properties() -> usize {{
+                {:#x}
+            }}
             ",
             TrieType::new(
                 &out.ucd.packing,
@@ -731,6 +809,7 @@ fn generate_rust(out: Output) -> String {
             .value(),
             out.ucd.values['\t' as usize].value(),
             out.ucd.values['\n' as usize].value(),
+            out.ucd.values[0xFE0E].value(),
         );
     }
 
@@ -960,6 +1039,15 @@ fn extract_values_from_ucd(doc: &roxmltree::Document, out: &Output) -> anyhow::R
     // but for us that's equivalent to Other.
     values[0xFE0F].change_width(&packing, CharacterWidth::Wide);
 
+    // U+FE0E Variation Selector-15 is the opposite: It requests the text presentation of
+    // an Emoji, which terminals draw narrow even if the Emoji is wide by default (e.g. U+231A).
+    // It can't be expressed as a width, so --extended gives it a subtype of Extend instead,
+    // and the caller clamps the width of clusters ending in it to 1.
+    // Its width stays zero, like that of all other variation selectors.
+    if out.arg_extended {
+        values[0xFE0E].change_cluster_break_ext(&packing, ClusterBreakExt::ExtendTextPresentation);
+    }
+
     Ok(Ucd { description, values, packing })
 }
 